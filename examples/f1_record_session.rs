@@ -0,0 +1,28 @@
+//! Record the F1 datagrams of a session to a file, each prefixed by its length as a little
+//! endian `u32`, until the game stops sending for 5 seconds.
+
+use simetry::f1;
+use std::io::Write;
+use std::time::Duration;
+use tokio::net::UdpSocket;
+
+#[tokio::main]
+async fn main() {
+    let path = std::env::args()
+        .nth(1)
+        .expect("Usage: f1_record_session <output file>");
+    let mut file = std::io::BufWriter::new(std::fs::File::create(path).unwrap());
+    let socket = UdpSocket::bind(f1::Client::DEFAULT_URI).await.unwrap();
+    let mut buffer = [0u8; 2048];
+    let mut count = 0;
+    while let Ok(res) =
+        tokio::time::timeout(Duration::from_secs(5), socket.recv_from(&mut buffer)).await
+    {
+        let (size, _origin) = res.unwrap();
+        file.write_all(&(size as u32).to_le_bytes()).unwrap();
+        file.write_all(&buffer[..size]).unwrap();
+        count += 1;
+    }
+    file.flush().unwrap();
+    println!("Recorded {count} datagrams");
+}
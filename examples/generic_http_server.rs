@@ -0,0 +1,14 @@
+use simetry::generic_http::{GenericHttpServer, DEFAULT_ADDRESS};
+
+#[tokio::main]
+async fn main() {
+    let server = GenericHttpServer::bind(DEFAULT_ADDRESS).await.unwrap();
+    println!("Listening on {}", server.local_addr());
+    loop {
+        println!("Starting connection...");
+        let client = simetry::connect().await;
+        println!("Serving {}", client.name());
+        server.run(client).await;
+        println!("Connection finished!");
+    }
+}
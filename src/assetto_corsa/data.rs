@@ -1,4 +1,6 @@
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Penalty {
     None,
     DriveThroughCutting,
@@ -24,7 +26,7 @@ pub enum Penalty {
     DisqualifiedExceededDriverStintLimit,
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Status {
     Off,
     Replay,
@@ -32,7 +34,7 @@ pub enum Status {
     Pause,
 }
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum SessionType {
     Unknown,
     Practice,
@@ -46,7 +48,7 @@ pub enum SessionType {
     HotlapSuperPole,
 }
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum FlagType {
     None,
     Blue,
@@ -59,7 +61,7 @@ pub enum FlagType {
     Orange,
 }
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct Physics {
     pub packet_id: i32,
     pub gas: f32,
@@ -148,7 +150,7 @@ pub struct Physics {
     pub abs_vibrations: f32,
 }
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct Graphics {
     pub packet_id: i32,
     pub status: Status,
@@ -218,7 +220,7 @@ pub struct Graphics {
 }
 
 /// Data that never changes during a session
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct StaticData {
     pub sm_version: String,
     pub ac_version: String,
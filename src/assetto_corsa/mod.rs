@@ -2,15 +2,17 @@ pub use crate::assetto_corsa::data::{
     FlagType, Graphics, Penalty, Physics, SessionType, StaticData, Status,
 };
 use crate::assetto_corsa::shared_memory_data::{PageFileGraphics, PageFilePhysics, PageFileStatic};
-use crate::{Moment, RacingFlags, Simetry};
+use crate::{Moment, RacingFlags, Session, SessionPhase, Simetry};
 use std::borrow::Cow;
 use uom::si::angular_velocity::revolution_per_minute;
-use uom::si::f64::{AngularVelocity, Velocity};
+use uom::si::f64::{AngularVelocity, Time, Velocity};
+use uom::si::time::millisecond;
 use uom::si::velocity::kilometer_per_hour;
 
 mod conversions;
 mod data;
 mod shared_memory_data;
+pub mod udp;
 pub(crate) mod util;
 
 #[derive(Clone, Debug, Default)]
@@ -133,4 +135,49 @@ impl Moment for SimState {
     fn is_starter_on(&self) -> Option<bool> {
         Some(self.physics.starter_engine_on != 0)
     }
+
+    fn session(&self) -> Option<Session> {
+        let session_type = match self.graphics.session {
+            SessionType::Unknown => None,
+            SessionType::Practice => Some(crate::SessionType::Practice),
+            SessionType::Qualify => Some(crate::SessionType::Qualifying),
+            SessionType::Race => Some(crate::SessionType::Race),
+            SessionType::Hotlap
+            | SessionType::TimeAttack
+            | SessionType::HotStint
+            | SessionType::HotlapSuperPole => Some(crate::SessionType::Hotlap),
+            SessionType::Drift | SessionType::Drag => Some(crate::SessionType::Other),
+        };
+        let phase = match (self.graphics.status, &self.graphics.flag) {
+            (Status::Off, _) => None,
+            (Status::Pause, _) => Some(SessionPhase::Stopped),
+            (_, FlagType::Checkered) => Some(SessionPhase::Checkered),
+            (_, _) => Some(SessionPhase::Green),
+        };
+        let time_remaining = Some(self.graphics.session_time_left)
+            .filter(|v| *v >= 0.0)
+            .map(|v| Time::new::<millisecond>(v as f64));
+        let total_laps = u32::try_from(self.graphics.number_of_laps)
+            .ok()
+            .filter(|v| *v > 0);
+        let completed_laps = u32::try_from(self.graphics.completed_laps).unwrap_or_default();
+        let laps_remaining = total_laps.map(|v| v.saturating_sub(completed_laps));
+        let track_id = if self.static_data.track_configuration.is_empty() {
+            self.static_data.track.clone()
+        } else {
+            format!(
+                "{}|{}",
+                self.static_data.track, self.static_data.track_configuration
+            )
+        };
+        Some(Session {
+            session_type,
+            phase,
+            time_remaining,
+            laps_remaining,
+            total_laps,
+            track_id: Some(track_id),
+            is_replay: Some(self.graphics.status == Status::Replay),
+        })
+    }
 }
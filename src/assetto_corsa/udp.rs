@@ -0,0 +1,420 @@
+//! Support for the UDP remote telemetry of Assetto Corsa.
+//!
+//! The game listens on port 9996 for clients, which works from other machines too.
+//! After a handshake, the client subscribes to [`CarInfo`] updates sent every physics step,
+//! or to [`Lap`] spot events sent whenever a car completes a lap.
+
+use crate::{Moment, Pedals, Session, Simetry};
+use anyhow::{bail, Context, Result};
+use byteorder::{LittleEndian, ReadBytesExt};
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::io::Read;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::net::UdpSocket;
+use uom::si::angular_velocity::revolution_per_minute;
+use uom::si::f64::{AngularVelocity, Velocity};
+use uom::si::velocity::kilometer_per_hour;
+
+/// Operations sent from the client to the game.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum Operation {
+    Handshake,
+    SubscribeUpdate,
+    SubscribeSpot,
+    Dismiss,
+}
+
+impl Operation {
+    /// Encode the operation as a handshaker message.
+    pub fn encode(self) -> [u8; 12] {
+        let operation_id: i32 = match self {
+            Self::Handshake => 0,
+            Self::SubscribeUpdate => 1,
+            Self::SubscribeSpot => 2,
+            Self::Dismiss => 3,
+        };
+        let mut out = [0u8; 12];
+        out[0..4].copy_from_slice(&DEVICE_IDENTIFIER.to_le_bytes());
+        out[4..8].copy_from_slice(&PROTOCOL_VERSION.to_le_bytes());
+        out[8..12].copy_from_slice(&operation_id.to_le_bytes());
+        out
+    }
+}
+
+const DEVICE_IDENTIFIER: i32 = 1;
+const PROTOCOL_VERSION: i32 = 1;
+
+#[derive(Debug)]
+pub struct Client {
+    socket: UdpSocket,
+    handshake: Arc<HandshakeResponse>,
+    last_lap: Option<Arc<Lap>>,
+}
+
+impl Client {
+    /// Address of the game, when running on the same machine.
+    pub const DEFAULT_URI: &'static str = "127.0.0.1:9996";
+
+    /// Connect to the game at `uri`, like [`Client::DEFAULT_URI`], and subscribe to updates.
+    pub async fn connect(uri: &str, retry_delay: Duration) -> Self {
+        loop {
+            if let Ok(client) = Self::try_connect(uri).await {
+                return client;
+            }
+            tokio::time::sleep(retry_delay).await;
+        }
+    }
+
+    pub async fn try_connect(uri: &str) -> Result<Self> {
+        let socket = UdpSocket::bind("0.0.0.0:0").await?;
+        socket.connect(uri).await?;
+        socket.send(&Operation::Handshake.encode()).await?;
+        let mut buffer = [0u8; PACKET_BUFFER_SIZE];
+        let size = tokio::time::timeout(HANDSHAKE_TIMEOUT, socket.recv(&mut buffer))
+            .await
+            .context("Game did not respond to the handshake")??;
+        let handshake = Arc::new(HandshakeResponse::parse(&buffer[..size])?);
+        let slf = Self {
+            socket,
+            handshake,
+            last_lap: None,
+        };
+        slf.send(Operation::SubscribeUpdate).await?;
+        Ok(slf)
+    }
+
+    pub async fn send(&self, operation: Operation) -> Result<()> {
+        self.socket.send(&operation.encode()).await?;
+        Ok(())
+    }
+
+    /// Tell the game to stop sending data to this client.
+    pub async fn dismiss(self) -> Result<()> {
+        self.send(Operation::Dismiss).await
+    }
+
+    pub fn handshake(&self) -> &HandshakeResponse {
+        &self.handshake
+    }
+
+    /// Wait for the next car info update.
+    ///
+    /// Lap events received in the meantime are kept in [`SimState::last_lap`].
+    /// Datagrams that fail to parse are logged and skipped.
+    pub async fn next_sim_state(&mut self) -> Result<SimState> {
+        let mut buffer = [0u8; PACKET_BUFFER_SIZE];
+        loop {
+            let size = self.socket.recv(&mut buffer).await?;
+            match Packet::parse(&buffer[..size]) {
+                Ok(Packet::CarInfo(car_info)) => {
+                    return Ok(SimState {
+                        handshake: Arc::clone(&self.handshake),
+                        car_info: *car_info,
+                        last_lap: self.last_lap.clone(),
+                    });
+                }
+                Ok(Packet::Lap(lap)) => self.last_lap = Some(Arc::new(lap)),
+                Ok(Packet::HandshakeResponse(_)) => {}
+                Err(err) => log::warn!("Skipping Assetto Corsa datagram: {err}"),
+            }
+        }
+    }
+}
+
+const PACKET_BUFFER_SIZE: usize = 1024;
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(1);
+
+#[async_trait::async_trait]
+impl Simetry for Client {
+    fn name(&self) -> &str {
+        "AssettoCorsa"
+    }
+
+    async fn next_moment(&mut self) -> Option<Box<dyn Moment + Send + Sync + 'static>> {
+        Some(Box::new(self.next_sim_state().await.ok()?))
+    }
+}
+
+/// Any datagram sent by the game, told apart by their size.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Packet {
+    HandshakeResponse(HandshakeResponse),
+    CarInfo(Box<CarInfo>),
+    Lap(Lap),
+}
+
+impl Packet {
+    pub fn parse(datagram: &[u8]) -> Result<Self> {
+        Ok(match datagram.len() {
+            HandshakeResponse::SIZE => Self::HandshakeResponse(HandshakeResponse::parse(datagram)?),
+            CarInfo::SIZE => Self::CarInfo(Box::new(CarInfo::parse(datagram)?)),
+            Lap::SIZE => Self::Lap(Lap::parse(datagram)?),
+            size => bail!("Unexpected datagram of {size} bytes"),
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct HandshakeResponse {
+    pub car_name: String,
+    pub driver_name: String,
+    pub identifier: i32,
+    pub version: i32,
+    pub track_name: String,
+    pub track_config: String,
+}
+
+impl HandshakeResponse {
+    pub const SIZE: usize = 408;
+
+    pub fn parse(datagram: &[u8]) -> Result<Self> {
+        check_size("Handshake response", datagram, Self::SIZE)?;
+        let data = &mut &datagram[..];
+        Ok(Self {
+            car_name: read_string(data)?,
+            driver_name: read_string(data)?,
+            identifier: data.read_i32::<LittleEndian>()?,
+            version: data.read_i32::<LittleEndian>()?,
+            track_name: read_string(data)?,
+            track_config: read_string(data)?,
+        })
+    }
+}
+
+/// Physics of the player's car.
+///
+/// Wheel arrays are in front left, front right, rear left, rear right order.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CarInfo {
+    /// Always `'a'`
+    pub identifier: u8,
+    pub size: i32,
+    pub speed_kmh: f32,
+    pub speed_mph: f32,
+    pub speed_ms: f32,
+    pub is_abs_enabled: bool,
+    pub is_abs_in_action: bool,
+    pub is_tc_in_action: bool,
+    pub is_tc_enabled: bool,
+    pub is_in_pit: bool,
+    pub is_engine_limiter_on: bool,
+    pub acc_g_vertical: f32,
+    pub acc_g_horizontal: f32,
+    pub acc_g_frontal: f32,
+    /// Current lap time in milliseconds
+    pub lap_time: i32,
+    /// Last lap time in milliseconds
+    pub last_lap: i32,
+    /// Best lap time in milliseconds
+    pub best_lap: i32,
+    pub lap_count: i32,
+    pub gas: f32,
+    pub brake: f32,
+    pub clutch: f32,
+    pub engine_rpm: f32,
+    pub steer: f32,
+    /// 0 = reverse, 1 = neutral, 2 = first gear...
+    pub gear: i32,
+    pub cg_height: f32,
+    pub wheel_angular_speed: [f32; 4],
+    pub slip_angle: [f32; 4],
+    pub slip_angle_contact_patch: [f32; 4],
+    pub slip_ratio: [f32; 4],
+    pub tyre_slip: [f32; 4],
+    pub nd_slip: [f32; 4],
+    pub load: [f32; 4],
+    pub dy: [f32; 4],
+    pub mz: [f32; 4],
+    pub tyre_dirty_level: [f32; 4],
+    pub camber_rad: [f32; 4],
+    pub tyre_radius: [f32; 4],
+    pub tyre_loaded_radius: [f32; 4],
+    pub suspension_height: [f32; 4],
+    /// Position on the track in 0-1 range
+    pub car_position_normalized: f32,
+    pub car_slope: f32,
+    pub car_coordinates: [f32; 3],
+}
+
+impl CarInfo {
+    pub const SIZE: usize = 328;
+
+    pub fn parse(datagram: &[u8]) -> Result<Self> {
+        check_size("Car info", datagram, Self::SIZE)?;
+        let data = &mut &datagram[..];
+        let identifier = data.read_u8()?;
+        // Padding
+        read_bytes::<3>(data)?;
+        let size = data.read_i32::<LittleEndian>()?;
+        let speed_kmh = data.read_f32::<LittleEndian>()?;
+        let speed_mph = data.read_f32::<LittleEndian>()?;
+        let speed_ms = data.read_f32::<LittleEndian>()?;
+        // Six flags followed by padding
+        let flags = read_bytes::<8>(data)?.map(|v| v != 0);
+        Ok(Self {
+            identifier,
+            size,
+            speed_kmh,
+            speed_mph,
+            speed_ms,
+            is_abs_enabled: flags[0],
+            is_abs_in_action: flags[1],
+            is_tc_in_action: flags[2],
+            is_tc_enabled: flags[3],
+            is_in_pit: flags[4],
+            is_engine_limiter_on: flags[5],
+            acc_g_vertical: data.read_f32::<LittleEndian>()?,
+            acc_g_horizontal: data.read_f32::<LittleEndian>()?,
+            acc_g_frontal: data.read_f32::<LittleEndian>()?,
+            lap_time: data.read_i32::<LittleEndian>()?,
+            last_lap: data.read_i32::<LittleEndian>()?,
+            best_lap: data.read_i32::<LittleEndian>()?,
+            lap_count: data.read_i32::<LittleEndian>()?,
+            gas: data.read_f32::<LittleEndian>()?,
+            brake: data.read_f32::<LittleEndian>()?,
+            clutch: data.read_f32::<LittleEndian>()?,
+            engine_rpm: data.read_f32::<LittleEndian>()?,
+            steer: data.read_f32::<LittleEndian>()?,
+            gear: data.read_i32::<LittleEndian>()?,
+            cg_height: data.read_f32::<LittleEndian>()?,
+            wheel_angular_speed: read_f32s(data)?,
+            slip_angle: read_f32s(data)?,
+            slip_angle_contact_patch: read_f32s(data)?,
+            slip_ratio: read_f32s(data)?,
+            tyre_slip: read_f32s(data)?,
+            nd_slip: read_f32s(data)?,
+            load: read_f32s(data)?,
+            dy: read_f32s(data)?,
+            mz: read_f32s(data)?,
+            tyre_dirty_level: read_f32s(data)?,
+            camber_rad: read_f32s(data)?,
+            tyre_radius: read_f32s(data)?,
+            tyre_loaded_radius: read_f32s(data)?,
+            suspension_height: read_f32s(data)?,
+            car_position_normalized: data.read_f32::<LittleEndian>()?,
+            car_slope: data.read_f32::<LittleEndian>()?,
+            car_coordinates: read_f32s(data)?,
+        })
+    }
+}
+
+/// Lap completed by any car in the session.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Lap {
+    pub car_identifier_number: i32,
+    pub lap: i32,
+    pub driver_name: String,
+    pub car_name: String,
+    /// Lap time in milliseconds
+    pub time: i32,
+}
+
+impl Lap {
+    pub const SIZE: usize = 212;
+
+    pub fn parse(datagram: &[u8]) -> Result<Self> {
+        check_size("Lap", datagram, Self::SIZE)?;
+        let data = &mut &datagram[..];
+        Ok(Self {
+            car_identifier_number: data.read_i32::<LittleEndian>()?,
+            lap: data.read_i32::<LittleEndian>()?,
+            driver_name: read_string(data)?,
+            car_name: read_string(data)?,
+            time: data.read_i32::<LittleEndian>()?,
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SimState {
+    pub handshake: Arc<HandshakeResponse>,
+    pub car_info: CarInfo,
+    /// Latest lap event, if subscribed to them with [`Operation::SubscribeSpot`]
+    pub last_lap: Option<Arc<Lap>>,
+}
+
+impl Moment for SimState {
+    fn vehicle_gear(&self) -> Option<i8> {
+        Some((self.car_info.gear - 1) as i8)
+    }
+
+    fn vehicle_velocity(&self) -> Option<Velocity> {
+        Some(Velocity::new::<kilometer_per_hour>(
+            self.car_info.speed_kmh as f64,
+        ))
+    }
+
+    fn vehicle_engine_rotation_speed(&self) -> Option<AngularVelocity> {
+        Some(AngularVelocity::new::<revolution_per_minute>(
+            self.car_info.engine_rpm as f64,
+        ))
+    }
+
+    fn is_vehicle_in_pit_lane(&self) -> Option<bool> {
+        Some(self.car_info.is_in_pit)
+    }
+
+    fn vehicle_unique_id(&self) -> Option<Cow<str>> {
+        Some(self.handshake.car_name.as_str().into())
+    }
+
+    fn pedals(&self) -> Option<Pedals> {
+        Some(Pedals {
+            throttle: self.car_info.gas as f64,
+            brake: self.car_info.brake as f64,
+            clutch: self.car_info.clutch as f64,
+        })
+    }
+
+    fn session(&self) -> Option<Session> {
+        let track_id = if self.handshake.track_config.is_empty() {
+            self.handshake.track_name.clone()
+        } else {
+            format!(
+                "{}|{}",
+                self.handshake.track_name, self.handshake.track_config
+            )
+        };
+        Some(Session {
+            track_id: Some(track_id),
+            ..Session::default()
+        })
+    }
+}
+
+fn check_size(name: &str, datagram: &[u8], size: usize) -> Result<()> {
+    if datagram.len() != size {
+        bail!(
+            "{name} datagram should have {size} bytes, got {}",
+            datagram.len()
+        );
+    }
+    Ok(())
+}
+
+fn read_bytes<const N: usize>(data: &mut &[u8]) -> Result<[u8; N]> {
+    let mut out = [0; N];
+    data.read_exact(&mut out)?;
+    Ok(out)
+}
+
+fn read_f32s<const N: usize>(data: &mut &[u8]) -> Result<[f32; N]> {
+    let mut out = [0.0; N];
+    data.read_f32_into::<LittleEndian>(&mut out)?;
+    Ok(out)
+}
+
+/// Read a UTF-16 string of 50 characters.
+///
+/// The game terminates strings with `%` instead of a null character in some versions.
+fn read_string(data: &mut &[u8]) -> Result<String> {
+    let mut chars = [0u16; 50];
+    data.read_u16_into::<LittleEndian>(&mut chars)?;
+    let length = chars
+        .iter()
+        .position(|v| *v == 0 || *v == b'%' as u16)
+        .unwrap_or(chars.len());
+    Ok(String::from_utf16_lossy(&chars[..length]))
+}
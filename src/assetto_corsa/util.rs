@@ -3,6 +3,7 @@ use crate::assetto_corsa::shared_memory_data::StatusRaw;
 use crate::assetto_corsa::Status;
 use crate::windows_util::SharedMemory;
 use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
 use std::fmt::Debug;
 use std::sync::Arc;
 use std::time::Duration;
@@ -45,7 +46,11 @@ pub trait AcApiVersion: Debug {
     type DataGraphics: Clone + WithPacketId + Debug;
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(bound(
+    serialize = "Version::DataStatic: Serialize, Version::DataPhysics: Serialize, Version::DataGraphics: Serialize",
+    deserialize = "Version::DataStatic: Deserialize<'de>, Version::DataPhysics: Deserialize<'de>, Version::DataGraphics: Deserialize<'de>",
+))]
 pub struct SimState<Version: AcApiVersion> {
     pub static_data: Arc<Version::DataStatic>,
     pub physics: Arc<Version::DataPhysics>,
@@ -0,0 +1,965 @@
+//! Support for the UDP broadcasting protocol of Assetto Corsa Competizione.
+//!
+//! Unlike the shared memory, it provides data of all cars in the session, and works from
+//! another machine too. The port and passwords are set in
+//! `Documents/Assetto Corsa Competizione/Config/broadcasting.json`, where `udpListenerPort`
+//! has to be set to a non-zero value, like 9000.
+
+use crate::{Moment, Session, Simetry};
+use anyhow::{bail, Context, Result};
+use byteorder::{LittleEndian, ReadBytesExt};
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::io::Read;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::net::UdpSocket;
+use uom::si::f64::{Time, Velocity};
+use uom::si::time::millisecond;
+use uom::si::velocity::kilometer_per_hour;
+
+pub const BROADCASTING_PROTOCOL_VERSION: u8 = 4;
+
+/// Registration of the client, matching the settings in `broadcasting.json`.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Registration {
+    /// Name shown in the game for this client
+    pub display_name: String,
+    /// `connectionPassword` from `broadcasting.json`
+    pub connection_password: String,
+    /// `commandPassword` from `broadcasting.json`, needed for sending commands
+    pub command_password: String,
+    /// Interval between realtime updates sent by the game
+    pub update_interval: Duration,
+}
+
+impl Default for Registration {
+    fn default() -> Self {
+        Self {
+            display_name: "simetry".to_string(),
+            connection_password: String::new(),
+            command_password: String::new(),
+            update_interval: Duration::from_millis(250),
+        }
+    }
+}
+
+pub struct Client {
+    socket: UdpSocket,
+    state: SimState,
+    last_entry_list_request: Instant,
+}
+
+impl Client {
+    /// Address of the game, when running on the same machine with port 9000.
+    pub const DEFAULT_URI: &'static str = "127.0.0.1:9000";
+
+    pub async fn connect(uri: &str, registration: &Registration, retry_delay: Duration) -> Self {
+        loop {
+            if let Ok(client) = Self::try_connect(uri, registration).await {
+                return client;
+            }
+            tokio::time::sleep(retry_delay).await;
+        }
+    }
+
+    /// Register with the game at `uri`, and request the entry list and track data.
+    ///
+    /// Fails if the game rejects the registration, like when the password is wrong.
+    pub async fn try_connect(uri: &str, registration: &Registration) -> Result<Self> {
+        let socket = UdpSocket::bind("0.0.0.0:0").await?;
+        socket.connect(uri).await?;
+        let register = OutboundMessage::RegisterCommandApplication {
+            display_name: registration.display_name.clone(),
+            connection_password: registration.connection_password.clone(),
+            update_interval_ms: registration.update_interval.as_millis() as i32,
+            command_password: registration.command_password.clone(),
+        };
+        socket.send(&register.encode()).await?;
+        let mut buffer = [0u8; PACKET_BUFFER_SIZE];
+        let result = loop {
+            let size = tokio::time::timeout(REGISTRATION_TIMEOUT, socket.recv(&mut buffer))
+                .await
+                .context("Game did not respond to the registration")??;
+            if let Ok(InboundMessage::RegistrationResult(result)) =
+                InboundMessage::parse(&buffer[..size])
+            {
+                break result;
+            }
+        };
+        if !result.success {
+            bail!("Registration rejected: {}", result.error_message);
+        }
+        let mut slf = Self {
+            socket,
+            state: SimState::default(),
+            last_entry_list_request: Instant::now(),
+        };
+        slf.state.update(InboundMessage::RegistrationResult(result));
+        slf.request_entry_list().await?;
+        slf.request_track_data().await?;
+        Ok(slf)
+    }
+
+    /// Wait for the next valid message, and return the state with that message merged into it.
+    ///
+    /// The entry list gets requested again whenever an unknown car shows up.
+    /// Datagrams that fail to parse are logged and skipped.
+    pub async fn next_sim_state(&mut self) -> Result<SimState> {
+        let mut buffer = [0u8; PACKET_BUFFER_SIZE];
+        loop {
+            let size = self.socket.recv(&mut buffer).await?;
+            match InboundMessage::parse(&buffer[..size]) {
+                Ok(message) => {
+                    let is_unknown_car = match &message {
+                        InboundMessage::RealtimeCarUpdate(v) => {
+                            !self.state.entry_list.contains_key(&v.car_index)
+                        }
+                        _ => false,
+                    };
+                    self.state.update(message);
+                    if is_unknown_car
+                        && self.last_entry_list_request.elapsed() > ENTRY_LIST_REQUEST_INTERVAL
+                    {
+                        self.request_entry_list().await?;
+                    }
+                    return Ok(self.state.clone());
+                }
+                Err(err) => log::warn!("Skipping ACC broadcasting datagram: {err}"),
+            }
+        }
+    }
+
+    pub async fn send(&self, message: &OutboundMessage) -> Result<()> {
+        self.socket.send(&message.encode()).await?;
+        Ok(())
+    }
+
+    pub async fn request_entry_list(&mut self) -> Result<()> {
+        self.last_entry_list_request = Instant::now();
+        self.send(&OutboundMessage::RequestEntryList {
+            connection_id: self.state.connection_id,
+        })
+        .await
+    }
+
+    pub async fn request_track_data(&self) -> Result<()> {
+        self.send(&OutboundMessage::RequestTrackData {
+            connection_id: self.state.connection_id,
+        })
+        .await
+    }
+
+    /// Focus the camera on another car, and optionally switch to another camera.
+    pub async fn change_focus(&self, car_index: Option<u16>, camera: Option<Camera>) -> Result<()> {
+        self.send(&OutboundMessage::ChangeFocus {
+            connection_id: self.state.connection_id,
+            car_index,
+            camera,
+        })
+        .await
+    }
+
+    /// Switch to another camera, with camera sets and cameras listed in [`TrackData`].
+    pub async fn set_camera(&self, camera_set: &str, camera: &str) -> Result<()> {
+        self.change_focus(
+            None,
+            Some(Camera {
+                camera_set: camera_set.to_string(),
+                camera: camera.to_string(),
+            }),
+        )
+        .await
+    }
+
+    /// Switch to another HUD page, with pages listed in [`TrackData`].
+    pub async fn change_hud_page(&self, hud_page: &str) -> Result<()> {
+        self.send(&OutboundMessage::ChangeHudPage {
+            connection_id: self.state.connection_id,
+            hud_page: hud_page.to_string(),
+        })
+        .await
+    }
+
+    /// Start an instant replay from `start_session_time` milliseconds of the session.
+    pub async fn request_instant_replay(
+        &self,
+        start_session_time: f32,
+        duration_ms: f32,
+        initial_focused_car_index: Option<u16>,
+        camera: Option<Camera>,
+    ) -> Result<()> {
+        let camera = camera.unwrap_or_default();
+        self.send(&OutboundMessage::InstantReplayRequest {
+            connection_id: self.state.connection_id,
+            start_session_time,
+            duration_ms,
+            initial_focused_car_index: initial_focused_car_index.map_or(-1, |v| v as i32),
+            initial_camera_set: camera.camera_set,
+            initial_camera: camera.camera,
+        })
+        .await
+    }
+
+    /// Tell the game to stop sending data to this client.
+    pub async fn unregister(self) -> Result<()> {
+        self.send(&OutboundMessage::UnregisterCommandApplication {
+            connection_id: self.state.connection_id,
+        })
+        .await
+    }
+}
+
+const PACKET_BUFFER_SIZE: usize = 32 * 1024;
+const REGISTRATION_TIMEOUT: Duration = Duration::from_secs(2);
+const ENTRY_LIST_REQUEST_INTERVAL: Duration = Duration::from_secs(1);
+
+#[async_trait::async_trait]
+impl Simetry for Client {
+    fn name(&self) -> &str {
+        "AssettoCorsaCompetizione"
+    }
+
+    async fn next_moment(&mut self) -> Option<Box<dyn Moment + Send + Sync + 'static>> {
+        Some(Box::new(self.next_sim_state().await.ok()?))
+    }
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Camera {
+    pub camera_set: String,
+    pub camera: String,
+}
+
+/// Messages sent from the client to the game.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum OutboundMessage {
+    RegisterCommandApplication {
+        display_name: String,
+        connection_password: String,
+        update_interval_ms: i32,
+        command_password: String,
+    },
+    UnregisterCommandApplication {
+        connection_id: i32,
+    },
+    RequestEntryList {
+        connection_id: i32,
+    },
+    RequestTrackData {
+        connection_id: i32,
+    },
+    ChangeHudPage {
+        connection_id: i32,
+        hud_page: String,
+    },
+    ChangeFocus {
+        connection_id: i32,
+        car_index: Option<u16>,
+        camera: Option<Camera>,
+    },
+    InstantReplayRequest {
+        connection_id: i32,
+        start_session_time: f32,
+        duration_ms: f32,
+        /// -1 to keep the current focus
+        initial_focused_car_index: i32,
+        initial_camera_set: String,
+        initial_camera: String,
+    },
+}
+
+impl OutboundMessage {
+    pub fn encode(&self) -> Vec<u8> {
+        let mut out = vec![];
+        match self {
+            Self::RegisterCommandApplication {
+                display_name,
+                connection_password,
+                update_interval_ms,
+                command_password,
+            } => {
+                out.push(1);
+                out.push(BROADCASTING_PROTOCOL_VERSION);
+                write_string(&mut out, display_name);
+                write_string(&mut out, connection_password);
+                out.extend(update_interval_ms.to_le_bytes());
+                write_string(&mut out, command_password);
+            }
+            Self::UnregisterCommandApplication { connection_id } => {
+                out.push(9);
+                out.extend(connection_id.to_le_bytes());
+            }
+            Self::RequestEntryList { connection_id } => {
+                out.push(10);
+                out.extend(connection_id.to_le_bytes());
+            }
+            Self::RequestTrackData { connection_id } => {
+                out.push(11);
+                out.extend(connection_id.to_le_bytes());
+            }
+            Self::ChangeHudPage {
+                connection_id,
+                hud_page,
+            } => {
+                out.push(49);
+                out.extend(connection_id.to_le_bytes());
+                write_string(&mut out, hud_page);
+            }
+            Self::ChangeFocus {
+                connection_id,
+                car_index,
+                camera,
+            } => {
+                out.push(50);
+                out.extend(connection_id.to_le_bytes());
+                match car_index {
+                    None => out.push(0),
+                    Some(car_index) => {
+                        out.push(1);
+                        out.extend(car_index.to_le_bytes());
+                    }
+                }
+                match camera {
+                    None => out.push(0),
+                    Some(camera) => {
+                        out.push(1);
+                        write_string(&mut out, &camera.camera_set);
+                        write_string(&mut out, &camera.camera);
+                    }
+                }
+            }
+            Self::InstantReplayRequest {
+                connection_id,
+                start_session_time,
+                duration_ms,
+                initial_focused_car_index,
+                initial_camera_set,
+                initial_camera,
+            } => {
+                out.push(51);
+                out.extend(connection_id.to_le_bytes());
+                out.extend(start_session_time.to_le_bytes());
+                out.extend(duration_ms.to_le_bytes());
+                out.extend(initial_focused_car_index.to_le_bytes());
+                write_string(&mut out, initial_camera_set);
+                write_string(&mut out, initial_camera);
+            }
+        }
+        out
+    }
+}
+
+/// Messages sent from the game to the client.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum InboundMessage {
+    RegistrationResult(RegistrationResult),
+    RealtimeUpdate(RealtimeUpdate),
+    RealtimeCarUpdate(RealtimeCarUpdate),
+    EntryList(EntryList),
+    EntryListCar(CarInfo),
+    TrackData(TrackData),
+    BroadcastingEvent(BroadcastingEvent),
+}
+
+impl InboundMessage {
+    pub fn parse(datagram: &[u8]) -> Result<Self> {
+        let data = &mut &datagram[..];
+        let message_type = data.read_u8().context("Empty datagram")?;
+        Ok(match message_type {
+            1 => Self::RegistrationResult(RegistrationResult {
+                connection_id: data.read_i32::<LittleEndian>()?,
+                success: data.read_u8()? > 0,
+                is_read_only: data.read_u8()? == 0,
+                error_message: read_string(data)?,
+            }),
+            2 => Self::RealtimeUpdate(read_realtime_update(data)?),
+            3 => Self::RealtimeCarUpdate(read_realtime_car_update(data)?),
+            4 => {
+                let connection_id = data.read_i32::<LittleEndian>()?;
+                let count = data.read_u16::<LittleEndian>()?;
+                Self::EntryList(EntryList {
+                    connection_id,
+                    car_indices: (0..count)
+                        .map(|_| data.read_u16::<LittleEndian>())
+                        .collect::<std::io::Result<_>>()?,
+                })
+            }
+            5 => Self::TrackData(read_track_data(data)?),
+            6 => Self::EntryListCar(read_car_info(data)?),
+            7 => Self::BroadcastingEvent(BroadcastingEvent {
+                event_type: data.read_u8()?.into(),
+                message: read_string(data)?,
+                time_ms: data.read_i32::<LittleEndian>()?,
+                car_index: data.read_i32::<LittleEndian>()?,
+            }),
+            v => bail!("Unknown message type {v}"),
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RegistrationResult {
+    pub connection_id: i32,
+    pub success: bool,
+    /// Set if the command password was wrong, so commands are ignored
+    pub is_read_only: bool,
+    pub error_message: String,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum RaceSessionType {
+    Practice,
+    Qualifying,
+    Superpole,
+    Race,
+    Hotlap,
+    Hotstint,
+    HotlapSuperpole,
+    Replay,
+    Unknown,
+}
+
+impl From<u8> for RaceSessionType {
+    fn from(v: u8) -> Self {
+        match v {
+            0 => Self::Practice,
+            4 => Self::Qualifying,
+            9 => Self::Superpole,
+            10 => Self::Race,
+            11 => Self::Hotlap,
+            12 => Self::Hotstint,
+            13 => Self::HotlapSuperpole,
+            14 => Self::Replay,
+            _ => Self::Unknown,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum SessionPhase {
+    None,
+    Starting,
+    PreFormation,
+    FormationLap,
+    PreSession,
+    Session,
+    SessionOver,
+    PostSession,
+    ResultUi,
+}
+
+impl From<u8> for SessionPhase {
+    fn from(v: u8) -> Self {
+        match v {
+            1 => Self::Starting,
+            2 => Self::PreFormation,
+            3 => Self::FormationLap,
+            4 => Self::PreSession,
+            5 => Self::Session,
+            6 => Self::SessionOver,
+            7 => Self::PostSession,
+            8 => Self::ResultUi,
+            _ => Self::None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum CarLocation {
+    None,
+    Track,
+    Pitlane,
+    PitEntry,
+    PitExit,
+}
+
+impl From<u8> for CarLocation {
+    fn from(v: u8) -> Self {
+        match v {
+            1 => Self::Track,
+            2 => Self::Pitlane,
+            3 => Self::PitEntry,
+            4 => Self::PitExit,
+            _ => Self::None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum LapType {
+    Outlap,
+    Regular,
+    Inlap,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum DriverCategory {
+    Bronze,
+    Silver,
+    Gold,
+    Platinum,
+    Unknown,
+}
+
+impl From<u8> for DriverCategory {
+    fn from(v: u8) -> Self {
+        match v {
+            0 => Self::Bronze,
+            1 => Self::Silver,
+            2 => Self::Gold,
+            3 => Self::Platinum,
+            _ => Self::Unknown,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum BroadcastingEventType {
+    None,
+    GreenFlag,
+    SessionOver,
+    PenaltyCommMsg,
+    Accident,
+    LapCompleted,
+    BestSessionLap,
+    BestPersonalLap,
+}
+
+impl From<u8> for BroadcastingEventType {
+    fn from(v: u8) -> Self {
+        match v {
+            1 => Self::GreenFlag,
+            2 => Self::SessionOver,
+            3 => Self::PenaltyCommMsg,
+            4 => Self::Accident,
+            5 => Self::LapCompleted,
+            6 => Self::BestSessionLap,
+            7 => Self::BestPersonalLap,
+            _ => Self::None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct LapInfo {
+    /// Lap time in milliseconds, `None` if not set
+    pub lap_time_ms: Option<i32>,
+    pub car_index: u16,
+    pub driver_index: u16,
+    /// Sector times in milliseconds, `None` for sectors not driven yet
+    pub splits: Vec<Option<i32>>,
+    pub is_invalid: bool,
+    pub is_valid_for_best: bool,
+    pub lap_type: LapType,
+}
+
+/// State of the session, sent every update interval.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RealtimeUpdate {
+    pub event_index: u16,
+    pub session_index: u16,
+    pub session_type: RaceSessionType,
+    pub phase: SessionPhase,
+    /// Time since the session start in milliseconds
+    pub session_time: f32,
+    /// Time remaining in the session in milliseconds
+    pub session_end_time: f32,
+    pub focused_car_index: i32,
+    pub active_camera_set: String,
+    pub active_camera: String,
+    pub current_hud_page: String,
+    pub is_replay_playing: bool,
+    /// Set if a replay is playing
+    pub replay_session_time: Option<f32>,
+    /// Set if a replay is playing
+    pub replay_remaining_time: Option<f32>,
+    /// Time of day in milliseconds
+    pub time_of_day: f32,
+    pub ambient_temp: u8,
+    pub track_temp: u8,
+    /// Clouds in 0-1 range
+    pub clouds: f32,
+    /// Rain level in 0-1 range
+    pub rain_level: f32,
+    /// Wetness in 0-1 range
+    pub wetness: f32,
+    pub best_session_lap: LapInfo,
+}
+
+/// State of a single car, sent for each car every update interval.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RealtimeCarUpdate {
+    pub car_index: u16,
+    pub driver_index: u16,
+    pub driver_count: u8,
+    /// -1 = reverse, 0 = neutral, 1 and up forward gears
+    pub gear: i8,
+    pub world_pos_x: f32,
+    pub world_pos_y: f32,
+    pub yaw: f32,
+    pub car_location: CarLocation,
+    pub kmh: u16,
+    pub position: u16,
+    pub cup_position: u16,
+    pub track_position: u16,
+    /// Position on the track in 0-1 range
+    pub spline_position: f32,
+    pub laps: u16,
+    /// Delta to the best session lap in milliseconds
+    pub delta: i32,
+    pub best_session_lap: LapInfo,
+    pub last_lap: LapInfo,
+    pub current_lap: LapInfo,
+}
+
+/// Indices of the cars in the session.
+///
+/// Details of each car follow in separate [`InboundMessage::EntryListCar`] messages.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct EntryList {
+    pub connection_id: i32,
+    pub car_indices: Vec<u16>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CarInfo {
+    pub car_index: u16,
+    pub car_model_type: u8,
+    pub team_name: String,
+    pub race_number: i32,
+    /// 0 = overall or pro, 1 = pro-am, 2 = am, 3 = silver, 4 = national
+    pub cup_category: u8,
+    pub current_driver_index: u8,
+    pub nationality: u16,
+    pub drivers: Vec<DriverInfo>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DriverInfo {
+    pub first_name: String,
+    pub last_name: String,
+    pub short_name: String,
+    pub category: DriverCategory,
+    pub nationality: u16,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TrackData {
+    pub connection_id: i32,
+    pub track_name: String,
+    pub track_id: i32,
+    pub track_meters: i32,
+    /// Camera names by camera set name
+    pub camera_sets: BTreeMap<String, Vec<String>>,
+    pub hud_pages: Vec<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct BroadcastingEvent {
+    pub event_type: BroadcastingEventType,
+    pub message: String,
+    pub time_ms: i32,
+    pub car_index: i32,
+}
+
+/// Everything received from the game since the registration.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct SimState {
+    pub connection_id: i32,
+    pub is_read_only: bool,
+    pub realtime_update: Option<Arc<RealtimeUpdate>>,
+    pub track_data: Option<Arc<TrackData>>,
+    /// Details of each car by car index
+    pub entry_list: BTreeMap<u16, Arc<CarInfo>>,
+    /// Latest update of each car by car index
+    pub cars: BTreeMap<u16, Arc<RealtimeCarUpdate>>,
+    pub last_event: Option<Arc<BroadcastingEvent>>,
+}
+
+impl SimState {
+    /// Merge the message into the state.
+    ///
+    /// A new entry list drops all cars that are not part of it anymore.
+    pub fn update(&mut self, message: InboundMessage) {
+        match message {
+            InboundMessage::RegistrationResult(v) => {
+                self.connection_id = v.connection_id;
+                self.is_read_only = v.is_read_only;
+            }
+            InboundMessage::RealtimeUpdate(v) => self.realtime_update = Some(Arc::new(v)),
+            InboundMessage::RealtimeCarUpdate(v) => {
+                self.cars.insert(v.car_index, Arc::new(v));
+            }
+            InboundMessage::EntryList(v) => {
+                self.entry_list.retain(|k, _| v.car_indices.contains(k));
+                self.cars.retain(|k, _| v.car_indices.contains(k));
+            }
+            InboundMessage::EntryListCar(v) => {
+                self.entry_list.insert(v.car_index, Arc::new(v));
+            }
+            InboundMessage::TrackData(v) => self.track_data = Some(Arc::new(v)),
+            InboundMessage::BroadcastingEvent(v) => self.last_event = Some(Arc::new(v)),
+        }
+    }
+
+    /// Index of the car the camera is focused on.
+    pub fn focused_car_index(&self) -> Option<u16> {
+        u16::try_from(self.realtime_update.as_ref()?.focused_car_index).ok()
+    }
+
+    pub fn focused_car(&self) -> Option<&RealtimeCarUpdate> {
+        self.cars
+            .get(&self.focused_car_index()?)
+            .map(|v| v.as_ref())
+    }
+
+    pub fn focused_car_info(&self) -> Option<&CarInfo> {
+        self.entry_list
+            .get(&self.focused_car_index()?)
+            .map(|v| v.as_ref())
+    }
+
+    /// Latest updates of all cars ordered by their position.
+    pub fn standings(&self) -> Vec<&RealtimeCarUpdate> {
+        let mut standings = self.cars.values().map(|v| v.as_ref()).collect::<Vec<_>>();
+        standings.sort_by_key(|v| v.position);
+        standings
+    }
+}
+
+impl Moment for SimState {
+    fn vehicle_gear(&self) -> Option<i8> {
+        Some(self.focused_car()?.gear)
+    }
+
+    fn vehicle_velocity(&self) -> Option<Velocity> {
+        Some(Velocity::new::<kilometer_per_hour>(
+            self.focused_car()?.kmh as f64,
+        ))
+    }
+
+    fn is_vehicle_in_pit_lane(&self) -> Option<bool> {
+        Some(matches!(
+            self.focused_car()?.car_location,
+            CarLocation::Pitlane | CarLocation::PitEntry | CarLocation::PitExit
+        ))
+    }
+
+    fn vehicle_model_id(&self) -> Option<Cow<str>> {
+        Some(self.focused_car_info()?.car_model_type.to_string().into())
+    }
+
+    fn vehicle_unique_id(&self) -> Option<Cow<str>> {
+        self.vehicle_model_id()
+    }
+
+    fn session(&self) -> Option<Session> {
+        let realtime_update = self.realtime_update.as_ref()?;
+        let session_type = match realtime_update.session_type {
+            RaceSessionType::Practice => Some(crate::SessionType::Practice),
+            RaceSessionType::Qualifying | RaceSessionType::Superpole => {
+                Some(crate::SessionType::Qualifying)
+            }
+            RaceSessionType::Race => Some(crate::SessionType::Race),
+            RaceSessionType::Hotlap
+            | RaceSessionType::Hotstint
+            | RaceSessionType::HotlapSuperpole => Some(crate::SessionType::Hotlap),
+            RaceSessionType::Replay | RaceSessionType::Unknown => None,
+        };
+        let phase = match realtime_update.phase {
+            SessionPhase::None => None,
+            SessionPhase::Starting | SessionPhase::PreFormation => {
+                Some(crate::SessionPhase::Waiting)
+            }
+            SessionPhase::FormationLap => Some(crate::SessionPhase::Formation),
+            SessionPhase::PreSession => Some(crate::SessionPhase::Countdown),
+            SessionPhase::Session => Some(crate::SessionPhase::Green),
+            SessionPhase::SessionOver => Some(crate::SessionPhase::Checkered),
+            SessionPhase::PostSession | SessionPhase::ResultUi => {
+                Some(crate::SessionPhase::Finished)
+            }
+        };
+        let time_remaining = Some(realtime_update.session_end_time)
+            .filter(|v| *v >= 0.0)
+            .map(|v| Time::new::<millisecond>(v as f64));
+        Some(Session {
+            session_type,
+            phase,
+            time_remaining,
+            laps_remaining: None,
+            total_laps: None,
+            track_id: self.track_data.as_ref().map(|v| v.track_name.clone()),
+            is_replay: Some(realtime_update.is_replay_playing),
+        })
+    }
+}
+
+fn write_string(out: &mut Vec<u8>, value: &str) {
+    out.extend((value.len() as u16).to_le_bytes());
+    out.extend(value.as_bytes());
+}
+
+fn read_string(data: &mut &[u8]) -> Result<String> {
+    let length = data.read_u16::<LittleEndian>()? as usize;
+    let mut bytes = vec![0; length];
+    data.read_exact(&mut bytes)?;
+    Ok(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+fn read_lap_info(data: &mut &[u8]) -> Result<LapInfo> {
+    let lap_time_ms = Some(data.read_i32::<LittleEndian>()?).filter(|v| *v != i32::MAX);
+    let car_index = data.read_u16::<LittleEndian>()?;
+    let driver_index = data.read_u16::<LittleEndian>()?;
+    let split_count = data.read_u8()?;
+    let mut splits = (0..split_count)
+        .map(|_| Ok(Some(data.read_i32::<LittleEndian>()?).filter(|v| *v != i32::MAX)))
+        .collect::<Result<Vec<_>>>()?;
+    if splits.len() < 3 {
+        splits.resize(3, None);
+    }
+    let is_invalid = data.read_u8()? > 0;
+    let is_valid_for_best = data.read_u8()? > 0;
+    let is_outlap = data.read_u8()? > 0;
+    let is_inlap = data.read_u8()? > 0;
+    Ok(LapInfo {
+        lap_time_ms,
+        car_index,
+        driver_index,
+        splits,
+        is_invalid,
+        is_valid_for_best,
+        lap_type: match (is_outlap, is_inlap) {
+            (true, _) => LapType::Outlap,
+            (_, true) => LapType::Inlap,
+            _ => LapType::Regular,
+        },
+    })
+}
+
+fn read_realtime_update(data: &mut &[u8]) -> Result<RealtimeUpdate> {
+    let event_index = data.read_u16::<LittleEndian>()?;
+    let session_index = data.read_u16::<LittleEndian>()?;
+    let session_type = data.read_u8()?.into();
+    let phase = data.read_u8()?.into();
+    let session_time = data.read_f32::<LittleEndian>()?;
+    let session_end_time = data.read_f32::<LittleEndian>()?;
+    let focused_car_index = data.read_i32::<LittleEndian>()?;
+    let active_camera_set = read_string(data)?;
+    let active_camera = read_string(data)?;
+    let current_hud_page = read_string(data)?;
+    let is_replay_playing = data.read_u8()? > 0;
+    let (replay_session_time, replay_remaining_time) = match is_replay_playing {
+        true => (
+            Some(data.read_f32::<LittleEndian>()?),
+            Some(data.read_f32::<LittleEndian>()?),
+        ),
+        false => (None, None),
+    };
+    Ok(RealtimeUpdate {
+        event_index,
+        session_index,
+        session_type,
+        phase,
+        session_time,
+        session_end_time,
+        focused_car_index,
+        active_camera_set,
+        active_camera,
+        current_hud_page,
+        is_replay_playing,
+        replay_session_time,
+        replay_remaining_time,
+        time_of_day: data.read_f32::<LittleEndian>()?,
+        ambient_temp: data.read_u8()?,
+        track_temp: data.read_u8()?,
+        clouds: data.read_u8()? as f32 / 10.0,
+        rain_level: data.read_u8()? as f32 / 10.0,
+        wetness: data.read_u8()? as f32 / 10.0,
+        best_session_lap: read_lap_info(data)?,
+    })
+}
+
+fn read_realtime_car_update(data: &mut &[u8]) -> Result<RealtimeCarUpdate> {
+    Ok(RealtimeCarUpdate {
+        car_index: data.read_u16::<LittleEndian>()?,
+        driver_index: data.read_u16::<LittleEndian>()?,
+        driver_count: data.read_u8()?,
+        gear: (data.read_u8()? as i16 - 2) as i8,
+        world_pos_x: data.read_f32::<LittleEndian>()?,
+        world_pos_y: data.read_f32::<LittleEndian>()?,
+        yaw: data.read_f32::<LittleEndian>()?,
+        car_location: data.read_u8()?.into(),
+        kmh: data.read_u16::<LittleEndian>()?,
+        position: data.read_u16::<LittleEndian>()?,
+        cup_position: data.read_u16::<LittleEndian>()?,
+        track_position: data.read_u16::<LittleEndian>()?,
+        spline_position: data.read_f32::<LittleEndian>()?,
+        laps: data.read_u16::<LittleEndian>()?,
+        delta: data.read_i32::<LittleEndian>()?,
+        best_session_lap: read_lap_info(data)?,
+        last_lap: read_lap_info(data)?,
+        current_lap: read_lap_info(data)?,
+    })
+}
+
+fn read_car_info(data: &mut &[u8]) -> Result<CarInfo> {
+    let car_index = data.read_u16::<LittleEndian>()?;
+    let car_model_type = data.read_u8()?;
+    let team_name = read_string(data)?;
+    let race_number = data.read_i32::<LittleEndian>()?;
+    let cup_category = data.read_u8()?;
+    let current_driver_index = data.read_u8()?;
+    let nationality = data.read_u16::<LittleEndian>()?;
+    let driver_count = data.read_u8()?;
+    let drivers = (0..driver_count)
+        .map(|_| {
+            Ok(DriverInfo {
+                first_name: read_string(data)?,
+                last_name: read_string(data)?,
+                short_name: read_string(data)?,
+                category: data.read_u8()?.into(),
+                nationality: data.read_u16::<LittleEndian>()?,
+            })
+        })
+        .collect::<Result<_>>()?;
+    Ok(CarInfo {
+        car_index,
+        car_model_type,
+        team_name,
+        race_number,
+        cup_category,
+        current_driver_index,
+        nationality,
+        drivers,
+    })
+}
+
+fn read_track_data(data: &mut &[u8]) -> Result<TrackData> {
+    let connection_id = data.read_i32::<LittleEndian>()?;
+    let track_name = read_string(data)?;
+    let track_id = data.read_i32::<LittleEndian>()?;
+    let track_meters = data.read_i32::<LittleEndian>()?;
+    let camera_set_count = data.read_u8()?;
+    let mut camera_sets = BTreeMap::new();
+    for _ in 0..camera_set_count {
+        let name = read_string(data)?;
+        let camera_count = data.read_u8()?;
+        let cameras = (0..camera_count)
+            .map(|_| read_string(data))
+            .collect::<Result<_>>()?;
+        camera_sets.insert(name, cameras);
+    }
+    let hud_page_count = data.read_u8()?;
+    Ok(TrackData {
+        connection_id,
+        track_name,
+        track_id,
+        track_meters,
+        camera_sets,
+        hud_pages: (0..hud_page_count)
+            .map(|_| read_string(data))
+            .collect::<Result<_>>()?,
+    })
+}
@@ -0,0 +1,376 @@
+//! Support for the legacy UDP telemetry of Codemasters titles, like DiRT Rally, DiRT 4 and
+//! GRID Autosport.
+//!
+//! Telemetry is enabled in
+//! `Documents/My Games/<title>/hardwaresettings/hardware_settings_config.xml` by setting
+//! `<udp enabled="true" extradata="3" ip="127.0.0.1" port="20777" delay="1" />`.
+//! The `extradata` level decides how many fields get sent, and is detected from the datagram
+//! length.
+
+use crate::{Moment, Pedals, Session, Simetry};
+use anyhow::{bail, Result};
+use byteorder::{LittleEndian, ReadBytesExt};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tokio::net::UdpSocket;
+use uom::si::angular_velocity::revolution_per_minute;
+use uom::si::f64::{AngularVelocity, Velocity};
+use uom::si::velocity::meter_per_second;
+
+/// Title sending the telemetry, which can't be told apart from the data itself.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum Title {
+    DirtRally,
+    Dirt4,
+    DirtRally2,
+    GridAutosport,
+}
+
+impl Title {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::DirtRally => "DirtRally",
+            Self::Dirt4 => "Dirt4",
+            Self::DirtRally2 => "DirtRally2",
+            Self::GridAutosport => "GridAutosport",
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Client {
+    socket: UdpSocket,
+    title: Title,
+}
+
+impl Client {
+    pub const DEFAULT_URI: &'static str = "127.0.0.1:20777";
+
+    pub async fn connect(uri: &str, title: Title, retry_delay: Duration) -> Self {
+        loop {
+            if let Ok(client) = Self::try_connect(uri, title).await {
+                return client;
+            }
+            tokio::time::sleep(retry_delay).await;
+        }
+    }
+
+    pub async fn try_connect(uri: &str, title: Title) -> Result<Self> {
+        let slf = Self {
+            socket: UdpSocket::bind(uri).await?,
+            title,
+        };
+        slf.next_sim_state().await?;
+        Ok(slf)
+    }
+
+    pub fn title(&self) -> Title {
+        self.title
+    }
+
+    /// Wait for the next valid datagram.
+    ///
+    /// Datagrams not matching any extradata level are logged and skipped.
+    pub async fn next_sim_state(&self) -> Result<SimState> {
+        let mut buffer = [0u8; PACKET_BUFFER_SIZE];
+        loop {
+            let size = self.socket.recv(&mut buffer).await?;
+            match SimState::parse(&buffer[..size]) {
+                Ok(state) => return Ok(state),
+                Err(err) => log::warn!("Skipping Codemasters datagram: {err}"),
+            }
+        }
+    }
+}
+
+const PACKET_BUFFER_SIZE: usize = 1024;
+
+#[async_trait::async_trait]
+impl Simetry for Client {
+    fn name(&self) -> &str {
+        self.title.name()
+    }
+
+    async fn next_moment(&mut self) -> Option<Box<dyn Moment + Send + Sync + 'static>> {
+        Some(Box::new(self.next_sim_state().await.ok()?))
+    }
+}
+
+/// Value of the `extradata` setting, each level extending the previous one.
+#[derive(Clone, Copy, Debug, Eq, Ord, PartialEq, PartialOrd, Serialize, Deserialize)]
+pub enum ExtraData {
+    Level0,
+    Level1,
+    Level2,
+    Level3,
+}
+
+impl ExtraData {
+    pub const ALL: [Self; 4] = [Self::Level0, Self::Level1, Self::Level2, Self::Level3];
+
+    /// Number of `f32` fields sent.
+    pub fn field_count(self) -> usize {
+        match self {
+            Self::Level0 => 5,
+            Self::Level1 => 38,
+            Self::Level2 => 64,
+            Self::Level3 => 66,
+        }
+    }
+
+    /// Size of the datagram in bytes.
+    pub fn size(self) -> usize {
+        self.field_count() * 4
+    }
+
+    pub fn from_size(size: usize) -> Option<Self> {
+        Self::ALL.into_iter().find(|v| v.size() == size)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SimState {
+    pub extra_data: ExtraData,
+    pub base: Base,
+    /// Sent with extradata 1 and up
+    pub motion: Option<MotionData>,
+    /// Sent with extradata 2 and up
+    pub race: Option<RaceData>,
+    /// Sent with extradata 3
+    pub engine: Option<EngineData>,
+}
+
+impl SimState {
+    /// Parse the datagram, picking the extradata level from its length.
+    pub fn parse(datagram: &[u8]) -> Result<Self> {
+        let Some(extra_data) = ExtraData::from_size(datagram.len()) else {
+            bail!("No extradata level sends {} bytes", datagram.len());
+        };
+        let mut fields = vec![0.0; extra_data.field_count()];
+        (&mut &datagram[..]).read_f32_into::<LittleEndian>(&mut fields)?;
+        Ok(Self {
+            extra_data,
+            base: Base::from_fields(&fields),
+            motion: (extra_data >= ExtraData::Level1).then(|| MotionData::from_fields(&fields)),
+            race: (extra_data >= ExtraData::Level2).then(|| RaceData::from_fields(&fields)),
+            engine: (extra_data >= ExtraData::Level3).then(|| EngineData::from_fields(&fields)),
+        })
+    }
+}
+
+/// Four consecutive wheel values, starting at the index.
+fn wheels(fields: &[f32], start: usize) -> [f32; 4] {
+    [
+        fields[start],
+        fields[start + 1],
+        fields[start + 2],
+        fields[start + 3],
+    ]
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Base {
+    /// Seconds since the session start
+    pub time: f32,
+    /// Seconds since the lap start
+    pub lap_time: f32,
+    /// Meters driven on the lap
+    pub lap_distance: f32,
+    /// Meters driven in the session
+    pub total_distance: f32,
+    pub position_x: f32,
+}
+
+impl Base {
+    /// Read from the fields of a datagram, which must include the fields of its level.
+    pub(crate) fn from_fields(f: &[f32]) -> Self {
+        Self {
+            time: f[0],
+            lap_time: f[1],
+            lap_distance: f[2],
+            total_distance: f[3],
+            position_x: f[4],
+        }
+    }
+}
+
+/// Wheel values are in order rear left, rear right, front left, front right.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct MotionData {
+    pub position_y: f32,
+    pub position_z: f32,
+    /// Speed in m/s
+    pub speed: f32,
+    pub velocity: [f32; 3],
+    pub roll_vector: [f32; 3],
+    pub pitch_vector: [f32; 3],
+    pub suspension_position: [f32; 4],
+    pub suspension_velocity: [f32; 4],
+    /// Wheel speeds in m/s
+    pub wheel_speed: [f32; 4],
+    pub throttle: f32,
+    pub steer: f32,
+    pub brake: f32,
+    pub clutch: f32,
+    /// 0 = neutral, 10 = reverse in rally titles, -1 = reverse in others
+    pub gear: f32,
+    pub g_force_lateral: f32,
+    pub g_force_longitudinal: f32,
+    pub lap: f32,
+    /// Engine speed in RPM divided by 10
+    pub engine_rate: f32,
+}
+
+impl MotionData {
+    /// Read from the fields of a datagram, which must include the fields of its level.
+    pub(crate) fn from_fields(f: &[f32]) -> Self {
+        Self {
+            position_y: f[5],
+            position_z: f[6],
+            speed: f[7],
+            velocity: [f[8], f[9], f[10]],
+            roll_vector: [f[11], f[12], f[13]],
+            pitch_vector: [f[14], f[15], f[16]],
+            suspension_position: wheels(f, 17),
+            suspension_velocity: wheels(f, 21),
+            wheel_speed: wheels(f, 25),
+            throttle: f[29],
+            steer: f[30],
+            brake: f[31],
+            clutch: f[32],
+            gear: f[33],
+            g_force_lateral: f[34],
+            g_force_longitudinal: f[35],
+            lap: f[36],
+            engine_rate: f[37],
+        }
+    }
+}
+
+/// Wheel values are in order rear left, rear right, front left, front right.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RaceData {
+    pub sli_pro_native_support: f32,
+    pub race_position: f32,
+    pub kers_level: f32,
+    pub kers_max_level: f32,
+    pub drs: f32,
+    pub traction_control: f32,
+    pub anti_lock_brakes: f32,
+    pub fuel_in_tank: f32,
+    pub fuel_capacity: f32,
+    pub in_pits: f32,
+    /// Zero based sector index
+    pub sector: f32,
+    /// Seconds
+    pub sector_1_time: f32,
+    /// Seconds
+    pub sector_2_time: f32,
+    /// Brake temperatures in Celsius
+    pub brake_temperature: [f32; 4],
+    /// Tyre pressures in PSI
+    pub tyre_pressure: [f32; 4],
+    pub laps_completed: f32,
+    pub total_laps: f32,
+    /// Meters
+    pub track_length: f32,
+    /// Seconds
+    pub last_lap_time: f32,
+    /// Maximum engine speed in RPM divided by 10
+    pub max_rpm: f32,
+}
+
+impl RaceData {
+    /// Read from the fields of a datagram, which must include the fields of its level.
+    pub(crate) fn from_fields(f: &[f32]) -> Self {
+        Self {
+            sli_pro_native_support: f[38],
+            race_position: f[39],
+            kers_level: f[40],
+            kers_max_level: f[41],
+            drs: f[42],
+            traction_control: f[43],
+            anti_lock_brakes: f[44],
+            fuel_in_tank: f[45],
+            fuel_capacity: f[46],
+            in_pits: f[47],
+            sector: f[48],
+            sector_1_time: f[49],
+            sector_2_time: f[50],
+            brake_temperature: wheels(f, 51),
+            tyre_pressure: wheels(f, 55),
+            laps_completed: f[59],
+            total_laps: f[60],
+            track_length: f[61],
+            last_lap_time: f[62],
+            max_rpm: f[63],
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct EngineData {
+    /// Idle engine speed in RPM divided by 10
+    pub idle_rpm: f32,
+    pub max_gears: f32,
+}
+
+impl EngineData {
+    /// Read from the fields of a datagram, which must include the fields of its level.
+    pub(crate) fn from_fields(f: &[f32]) -> Self {
+        Self {
+            idle_rpm: f[64],
+            max_gears: f[65],
+        }
+    }
+}
+
+impl Moment for SimState {
+    fn vehicle_gear(&self) -> Option<i8> {
+        let gear = self.motion.as_ref()?.gear as i8;
+        Some(if gear == 10 { -1 } else { gear })
+    }
+
+    fn vehicle_velocity(&self) -> Option<Velocity> {
+        Some(Velocity::new::<meter_per_second>(
+            self.motion.as_ref()?.speed as f64,
+        ))
+    }
+
+    fn vehicle_engine_rotation_speed(&self) -> Option<AngularVelocity> {
+        Some(AngularVelocity::new::<revolution_per_minute>(
+            self.motion.as_ref()?.engine_rate as f64 * 10.0,
+        ))
+    }
+
+    fn vehicle_max_engine_rotation_speed(&self) -> Option<AngularVelocity> {
+        Some(AngularVelocity::new::<revolution_per_minute>(
+            self.race.as_ref()?.max_rpm as f64 * 10.0,
+        ))
+    }
+
+    fn is_vehicle_in_pit_lane(&self) -> Option<bool> {
+        Some(self.race.as_ref()?.in_pits != 0.0)
+    }
+
+    fn pedals(&self) -> Option<Pedals> {
+        let motion = self.motion.as_ref()?;
+        Some(Pedals {
+            throttle: motion.throttle as f64,
+            brake: motion.brake as f64,
+            clutch: motion.clutch as f64,
+        })
+    }
+
+    fn session(&self) -> Option<Session> {
+        let race = self.race.as_ref()?;
+        let total_laps = Some(race.total_laps as u32).filter(|v| *v > 0);
+        Some(Session {
+            laps_remaining: total_laps.map(|v| v.saturating_sub(race.laps_completed as u32)),
+            total_laps,
+            // No track ID is sent, but the track length is unique enough to tell tracks apart.
+            track_id: Some(format!("{:.0}", race.track_length)),
+            ..Session::default()
+        })
+    }
+}
@@ -1,6 +1,8 @@
-use crate::{Moment, Simetry};
+use crate::codemasters;
+use crate::{Moment, Pedals, Session, Simetry};
 use anyhow::Result;
-use std::mem::transmute;
+use byteorder::{ByteOrder, LittleEndian};
+use serde::{Deserialize, Serialize};
 use std::time::Duration;
 use tokio::net::UdpSocket;
 use uom::si::angular_velocity::revolution_per_minute;
@@ -32,17 +34,41 @@ impl Client {
         Ok(slf)
     }
 
+    /// Wait for the next valid datagram.
+    ///
+    /// Datagrams that fail to parse are logged and skipped.
     pub async fn next_sim_state(&self) -> Result<SimState> {
         let mut buffer = [0u8; PACKET_BUFFER_SIZE];
-        let (_bytes, _origin) = self.socket.recv_from(&mut buffer).await?;
-        Ok(unsafe { transmute(buffer) })
+        loop {
+            let size = self.socket.recv(&mut buffer).await?;
+            match SimState::parse(&buffer[..size]) {
+                Ok(state) => return Ok(state),
+                Err(err) => log::warn!("Skipping DiRT Rally 2.0 datagram: {err}"),
+            }
+        }
+    }
+}
+
+const PACKET_BUFFER_SIZE: usize = 1024;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ParseError {
+    WrongSize { expected: usize, actual: usize },
+}
+
+impl std::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::WrongSize { expected, actual } => {
+                write!(f, "Expected {expected} bytes, but got {actual} bytes")
+            }
+        }
     }
 }
 
-const PACKET_BUFFER_SIZE: usize = 264;
+impl std::error::Error for ParseError {}
 
-#[repr(C, packed(4))]
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
 pub struct SimState {
     pub time: f32,
     pub time_of_current_lap: f32,
@@ -82,17 +108,248 @@ pub struct SimState {
     pub g_force_longitudinal: f32,
     pub current_lap: f32,
     pub speed_of_engine_rpm_div_10: f32,
-    unused1: [f32; 13],
+    pub sli_pro_native_support: f32,
+    pub race_position: f32,
+    pub kers_level: f32,
+    pub kers_max_level: f32,
+    pub drs: f32,
+    pub traction_control: f32,
+    pub anti_lock_brakes: f32,
+    /// Liters
+    pub fuel_in_tank: f32,
+    /// Liters
+    pub fuel_capacity: f32,
+    /// Non-zero while in the pits or the service area
+    pub in_pits: f32,
+    /// Zero based sector index
+    pub sector: f32,
+    /// Seconds
+    pub time_of_sector_1: f32,
+    /// Seconds
+    pub time_of_sector_2: f32,
     pub temperature_brake_rear_left: f32,
     pub temperature_brake_rear_right: f32,
     pub temperature_brake_front_left: f32,
     pub temperature_brake_front_right: f32,
-    unused2: [f32; 5],
+    pub pressure_tyre_rear_left: f32,
+    pub pressure_tyre_rear_right: f32,
+    pub pressure_tyre_front_left: f32,
+    pub pressure_tyre_front_right: f32,
+    pub number_of_laps_completed: f32,
     pub number_of_laps_in_total: f32,
     pub length_of_track_in_total: f32,
-    unused3: [f32; 1],
+    /// Seconds
+    pub time_of_last_lap: f32,
     pub maximum_rpm_div_10: f32,
-    unused4: [u8; 8],
+    pub idle_rpm_div_10: f32,
+    pub maximum_gears: f32,
+}
+
+impl SimState {
+    pub const SIZE: usize = 264;
+
+    pub fn parse(datagram: &[u8]) -> Result<Self, ParseError> {
+        if datagram.len() != Self::SIZE {
+            return Err(ParseError::WrongSize {
+                expected: Self::SIZE,
+                actual: datagram.len(),
+            });
+        }
+        let mut f = [0.0; Self::SIZE / 4];
+        for (value, bytes) in f.iter_mut().zip(datagram.chunks_exact(4)) {
+            *value = LittleEndian::read_f32(bytes);
+        }
+        // Same layout as the extradata 3 of the other Codemasters titles.
+        let base = codemasters::Base::from_fields(&f);
+        let motion = codemasters::MotionData::from_fields(&f);
+        let race = codemasters::RaceData::from_fields(&f);
+        let engine = codemasters::EngineData::from_fields(&f);
+        Ok(Self {
+            time: base.time,
+            time_of_current_lap: base.lap_time,
+            distance_driven_on_current_lap: base.lap_distance,
+            distance_driven_overall: base.total_distance,
+            position_x: base.position_x,
+            position_y: motion.position_y,
+            position_z: motion.position_z,
+            velocity_ms: motion.speed,
+            velocity_x: motion.velocity[0],
+            velocity_y: motion.velocity[1],
+            velocity_z: motion.velocity[2],
+            roll_vector_x: motion.roll_vector[0],
+            roll_vector_y: motion.roll_vector[1],
+            roll_vector_z: motion.roll_vector[2],
+            pitch_vector_x: motion.pitch_vector[0],
+            pitch_vector_y: motion.pitch_vector[1],
+            pitch_vector_z: motion.pitch_vector[2],
+            position_of_suspension_rear_left: motion.suspension_position[0],
+            position_of_suspension_rear_right: motion.suspension_position[1],
+            position_of_suspension_front_left: motion.suspension_position[2],
+            position_of_suspension_front_right: motion.suspension_position[3],
+            velocity_of_suspension_rear_left: motion.suspension_velocity[0],
+            velocity_of_suspension_rear_right: motion.suspension_velocity[1],
+            velocity_of_suspension_front_left: motion.suspension_velocity[2],
+            velocity_of_suspension_front_right: motion.suspension_velocity[3],
+            velocity_of_wheel_rear_left: motion.wheel_speed[0],
+            velocity_of_wheel_rear_right: motion.wheel_speed[1],
+            velocity_of_wheel_front_left: motion.wheel_speed[2],
+            velocity_of_wheel_front_right: motion.wheel_speed[3],
+            position_throttle: motion.throttle,
+            position_steer: motion.steer,
+            position_brake: motion.brake,
+            position_clutch: motion.clutch,
+            gear: motion.gear,
+            g_force_lateral: motion.g_force_lateral,
+            g_force_longitudinal: motion.g_force_longitudinal,
+            current_lap: motion.lap,
+            speed_of_engine_rpm_div_10: motion.engine_rate,
+            sli_pro_native_support: race.sli_pro_native_support,
+            race_position: race.race_position,
+            kers_level: race.kers_level,
+            kers_max_level: race.kers_max_level,
+            drs: race.drs,
+            traction_control: race.traction_control,
+            anti_lock_brakes: race.anti_lock_brakes,
+            fuel_in_tank: race.fuel_in_tank,
+            fuel_capacity: race.fuel_capacity,
+            in_pits: race.in_pits,
+            sector: race.sector,
+            time_of_sector_1: race.sector_1_time,
+            time_of_sector_2: race.sector_2_time,
+            temperature_brake_rear_left: race.brake_temperature[0],
+            temperature_brake_rear_right: race.brake_temperature[1],
+            temperature_brake_front_left: race.brake_temperature[2],
+            temperature_brake_front_right: race.brake_temperature[3],
+            pressure_tyre_rear_left: race.tyre_pressure[0],
+            pressure_tyre_rear_right: race.tyre_pressure[1],
+            pressure_tyre_front_left: race.tyre_pressure[2],
+            pressure_tyre_front_right: race.tyre_pressure[3],
+            number_of_laps_completed: race.laps_completed,
+            number_of_laps_in_total: race.total_laps,
+            length_of_track_in_total: race.track_length,
+            time_of_last_lap: race.last_lap_time,
+            maximum_rpm_div_10: race.max_rpm,
+            idle_rpm_div_10: engine.idle_rpm,
+            maximum_gears: engine.max_gears,
+        })
+    }
+
+    /// Session time in seconds when the current lap or stage started.
+    pub fn time_of_lap_start(&self) -> f32 {
+        self.time - self.time_of_current_lap
+    }
+
+    /// Encode the state into a datagram, the inverse of [`SimState::parse`].
+    pub fn encode(&self) -> Vec<u8> {
+        let fields = [
+            self.time,
+            self.time_of_current_lap,
+            self.distance_driven_on_current_lap,
+            self.distance_driven_overall,
+            self.position_x,
+            self.position_y,
+            self.position_z,
+            self.velocity_ms,
+            self.velocity_x,
+            self.velocity_y,
+            self.velocity_z,
+            self.roll_vector_x,
+            self.roll_vector_y,
+            self.roll_vector_z,
+            self.pitch_vector_x,
+            self.pitch_vector_y,
+            self.pitch_vector_z,
+            self.position_of_suspension_rear_left,
+            self.position_of_suspension_rear_right,
+            self.position_of_suspension_front_left,
+            self.position_of_suspension_front_right,
+            self.velocity_of_suspension_rear_left,
+            self.velocity_of_suspension_rear_right,
+            self.velocity_of_suspension_front_left,
+            self.velocity_of_suspension_front_right,
+            self.velocity_of_wheel_rear_left,
+            self.velocity_of_wheel_rear_right,
+            self.velocity_of_wheel_front_left,
+            self.velocity_of_wheel_front_right,
+            self.position_throttle,
+            self.position_steer,
+            self.position_brake,
+            self.position_clutch,
+            self.gear,
+            self.g_force_lateral,
+            self.g_force_longitudinal,
+            self.current_lap,
+            self.speed_of_engine_rpm_div_10,
+            self.sli_pro_native_support,
+            self.race_position,
+            self.kers_level,
+            self.kers_max_level,
+            self.drs,
+            self.traction_control,
+            self.anti_lock_brakes,
+            self.fuel_in_tank,
+            self.fuel_capacity,
+            self.in_pits,
+            self.sector,
+            self.time_of_sector_1,
+            self.time_of_sector_2,
+            self.temperature_brake_rear_left,
+            self.temperature_brake_rear_right,
+            self.temperature_brake_front_left,
+            self.temperature_brake_front_right,
+            self.pressure_tyre_rear_left,
+            self.pressure_tyre_rear_right,
+            self.pressure_tyre_front_left,
+            self.pressure_tyre_front_right,
+            self.number_of_laps_completed,
+            self.number_of_laps_in_total,
+            self.length_of_track_in_total,
+            self.time_of_last_lap,
+            self.maximum_rpm_div_10,
+            self.idle_rpm_div_10,
+            self.maximum_gears,
+        ];
+        let mut datagram = vec![0; Self::SIZE];
+        LittleEndian::write_f32_into(&fields, &mut datagram);
+        datagram
+    }
+
+    /// Fill the fields that [`Moment`] exposes, for sending data of other sims to software that
+    /// understands this format. Other fields are zero, and `time` is left to the caller.
+    pub fn from_moment(moment: &dyn Moment) -> Self {
+        let pedals = moment.pedals();
+        let session = moment.session();
+        let total_laps = session.as_ref().and_then(|v| v.total_laps);
+        let laps_remaining = session.as_ref().and_then(|v| v.laps_remaining);
+        Self {
+            velocity_ms: moment
+                .vehicle_velocity()
+                .map_or(0.0, |v| v.get::<meter_per_second>() as f32),
+            position_throttle: pedals.as_ref().map_or(0.0, |v| v.throttle as f32),
+            position_brake: pedals.as_ref().map_or(0.0, |v| v.brake as f32),
+            position_clutch: pedals.as_ref().map_or(0.0, |v| v.clutch as f32),
+            gear: match moment.vehicle_gear() {
+                Some(-1) => 10.0,
+                gear => gear.unwrap_or(0) as f32,
+            },
+            current_lap: match (total_laps, laps_remaining) {
+                (Some(total), Some(remaining)) => total.saturating_sub(remaining) as f32,
+                _ => 0.0,
+            },
+            speed_of_engine_rpm_div_10: rpm_div_10(moment.vehicle_engine_rotation_speed()),
+            in_pits: match moment.is_vehicle_in_pit_lane() {
+                Some(true) => 1.0,
+                _ => 0.0,
+            },
+            number_of_laps_in_total: total_laps.unwrap_or(0) as f32,
+            // Round trips the track ID of this format, other IDs aren't lengths.
+            length_of_track_in_total: session
+                .and_then(|v| v.track_id?.parse().ok())
+                .unwrap_or(0.0),
+            maximum_rpm_div_10: rpm_div_10(moment.vehicle_max_engine_rotation_speed()),
+            ..Self::default()
+        }
+    }
 }
 
 #[async_trait::async_trait]
@@ -130,4 +387,31 @@ impl Moment for SimState {
             self.maximum_rpm_div_10 as f64 * 10.0,
         ))
     }
+
+    fn is_vehicle_in_pit_lane(&self) -> Option<bool> {
+        Some(self.in_pits != 0.0)
+    }
+
+    fn pedals(&self) -> Option<Pedals> {
+        Some(Pedals {
+            throttle: self.position_throttle as f64,
+            brake: self.position_brake as f64,
+            clutch: self.position_clutch as f64,
+        })
+    }
+
+    fn session(&self) -> Option<Session> {
+        let total_laps = Some(self.number_of_laps_in_total as u32).filter(|v| *v > 0);
+        Some(Session {
+            laps_remaining: total_laps.map(|v| v.saturating_sub(self.current_lap as u32)),
+            total_laps,
+            // No track ID is sent, but the track length is unique enough to tell tracks apart.
+            track_id: Some(format!("{:.0}", self.length_of_track_in_total)),
+            ..Session::default()
+        })
+    }
+}
+
+fn rpm_div_10(rpm: Option<AngularVelocity>) -> f32 {
+    rpm.map_or(0.0, |v| (v.get::<revolution_per_minute>() / 10.0) as f32)
 }
@@ -0,0 +1,434 @@
+//! Support for the UDP telemetry of EA Sports WRC.
+//!
+//! The game sends packets laid out by JSON definitions found in
+//! `Documents/My Games/WRC/telemetry`. The channels with their types are defined in
+//! `readme/channels.json`, and each structure in `udp/<structure>.json` lists the channels of
+//! each packet. Since the structures can be customised, they are loaded at runtime into a
+//! [`Layout`], which is then used to decode the datagrams by channel name.
+
+use crate::{Moment, Pedals, Simetry};
+use anyhow::{bail, Context, Result};
+use byteorder::{LittleEndian, ReadBytesExt};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::net::UdpSocket;
+use uom::si::angular_velocity::revolution_per_minute;
+use uom::si::f64::{AngularVelocity, Velocity};
+use uom::si::velocity::meter_per_second;
+
+pub struct Client {
+    socket: UdpSocket,
+    layout: Arc<Layout>,
+    state: SimState,
+}
+
+impl Client {
+    pub const DEFAULT_URI: &'static str = "127.0.0.1:20777";
+
+    pub async fn connect(uri: &str, layout: Arc<Layout>, retry_delay: Duration) -> Self {
+        loop {
+            if let Ok(client) = Self::try_connect(uri, layout.clone()).await {
+                return client;
+            }
+            tokio::time::sleep(retry_delay).await;
+        }
+    }
+
+    pub async fn try_connect(uri: &str, layout: Arc<Layout>) -> Result<Self> {
+        let mut slf = Self {
+            socket: UdpSocket::bind(uri).await?,
+            layout,
+            state: SimState::default(),
+        };
+        slf.next_sim_state().await?;
+        Ok(slf)
+    }
+
+    /// Wait for the next packet, and return the state with that packet merged into it.
+    ///
+    /// Datagrams that don't match the layout are logged and skipped.
+    pub async fn next_sim_state(&mut self) -> Result<SimState> {
+        let mut buffer = [0u8; PACKET_BUFFER_SIZE];
+        loop {
+            let size = self.socket.recv(&mut buffer).await?;
+            match self.layout.decode(&buffer[..size]) {
+                Ok(packet) => {
+                    self.state.update(packet);
+                    return Ok(self.state.clone());
+                }
+                Err(err) => log::warn!("Skipping EA WRC datagram: {err}"),
+            }
+        }
+    }
+}
+
+const PACKET_BUFFER_SIZE: usize = 2048;
+
+#[async_trait::async_trait]
+impl Simetry for Client {
+    fn name(&self) -> &str {
+        "EaWrc"
+    }
+
+    async fn next_moment(&mut self) -> Option<Box<dyn Moment + Send + Sync + 'static>> {
+        Some(Box::new(self.next_sim_state().await.ok()?))
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChannelType {
+    Boolean,
+    Uint8,
+    Uint16,
+    Uint32,
+    Uint64,
+    Int8,
+    Int16,
+    Int32,
+    Int64,
+    Float32,
+    Float64,
+    Fourcc,
+}
+
+impl ChannelType {
+    /// Size of the value in bytes.
+    pub fn size(self) -> usize {
+        match self {
+            Self::Boolean | Self::Uint8 | Self::Int8 => 1,
+            Self::Uint16 | Self::Int16 => 2,
+            Self::Uint32 | Self::Int32 | Self::Float32 | Self::Fourcc => 4,
+            Self::Uint64 | Self::Int64 | Self::Float64 => 8,
+        }
+    }
+
+    fn read(self, data: &mut &[u8]) -> Result<Value> {
+        Ok(match self {
+            Self::Boolean => Value::Boolean(data.read_u8()? > 0),
+            Self::Uint8 => Value::Unsigned(data.read_u8()? as u64),
+            Self::Uint16 => Value::Unsigned(data.read_u16::<LittleEndian>()? as u64),
+            Self::Uint32 => Value::Unsigned(data.read_u32::<LittleEndian>()? as u64),
+            Self::Uint64 => Value::Unsigned(data.read_u64::<LittleEndian>()?),
+            Self::Int8 => Value::Signed(data.read_i8()? as i64),
+            Self::Int16 => Value::Signed(data.read_i16::<LittleEndian>()? as i64),
+            Self::Int32 => Value::Signed(data.read_i32::<LittleEndian>()? as i64),
+            Self::Int64 => Value::Signed(data.read_i64::<LittleEndian>()?),
+            Self::Float32 => Value::Float(data.read_f32::<LittleEndian>()? as f64),
+            Self::Float64 => Value::Float(data.read_f64::<LittleEndian>()?),
+            Self::Fourcc => {
+                let mut code = [0u8; 4];
+                std::io::Read::read_exact(data, &mut code)?;
+                Value::FourCc(String::from_utf8_lossy(&code).into_owned())
+            }
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Value {
+    Boolean(bool),
+    Unsigned(u64),
+    Signed(i64),
+    Float(f64),
+    FourCc(String),
+}
+
+impl Value {
+    /// Numeric value of the channel, `None` for four character codes.
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            Self::Boolean(v) => Some(*v as u8 as f64),
+            Self::Unsigned(v) => Some(*v as f64),
+            Self::Signed(v) => Some(*v as f64),
+            Self::Float(v) => Some(*v),
+            Self::FourCc(_) => None,
+        }
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            Self::Boolean(v) => Some(*v),
+            Self::FourCc(_) => None,
+            v => Some(v.as_f64()? != 0.0),
+        }
+    }
+}
+
+/// Channel definition from `readme/channels.json`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Channel {
+    pub id: String,
+    #[serde(rename = "type")]
+    pub channel_type: ChannelType,
+    #[serde(default)]
+    pub units: String,
+    #[serde(default)]
+    pub description: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ChannelsFile {
+    pub channels: Vec<Channel>,
+}
+
+/// Structure definition from `udp/<structure>.json`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct StructureFile {
+    pub id: String,
+    pub packets: Vec<PacketStructure>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PacketStructure {
+    pub id: String,
+    #[serde(default)]
+    pub header: PacketHeader,
+    #[serde(default)]
+    pub channels: Vec<String>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct PacketHeader {
+    #[serde(default)]
+    pub channels: Vec<String>,
+}
+
+/// Layout of all packets in a structure, with the type of each channel resolved.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Layout {
+    pub structure_id: String,
+    pub packets: Vec<PacketLayout>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct PacketLayout {
+    pub id: String,
+    /// Four character code sent in the `packet_4cc` channel, if known for this packet
+    pub four_cc: Option<&'static str>,
+    /// Header channels followed by the packet channels, in the order they are sent
+    pub channels: Vec<(String, ChannelType)>,
+}
+
+impl PacketLayout {
+    /// Size of the packet in bytes.
+    pub fn size(&self) -> usize {
+        self.channels.iter().map(|(_, v)| v.size()).sum()
+    }
+}
+
+impl Layout {
+    /// Load the channels and the structure with the given id from the telemetry directory,
+    /// usually `Documents/My Games/WRC/telemetry`.
+    pub fn load(telemetry_dir: impl AsRef<Path>, structure_id: &str) -> Result<Self> {
+        let telemetry_dir = telemetry_dir.as_ref();
+        let channels_path = telemetry_dir.join("readme").join("channels.json");
+        let structure_path = telemetry_dir
+            .join("udp")
+            .join(format!("{structure_id}.json"));
+        let channels = std::fs::read_to_string(&channels_path)
+            .with_context(|| format!("Failed to read {}", channels_path.display()))?;
+        let structure = std::fs::read_to_string(&structure_path)
+            .with_context(|| format!("Failed to read {}", structure_path.display()))?;
+        Self::from_json(&channels, &structure)
+    }
+
+    pub fn from_json(channels: &str, structure: &str) -> Result<Self> {
+        let channels: ChannelsFile = serde_json::from_str(channels)?;
+        let structure: StructureFile = serde_json::from_str(structure)?;
+        Self::new(&channels, &structure)
+    }
+
+    pub fn new(channels: &ChannelsFile, structure: &StructureFile) -> Result<Self> {
+        let types = channels
+            .channels
+            .iter()
+            .map(|v| (v.id.as_str(), v.channel_type))
+            .collect::<HashMap<_, _>>();
+        let packets = structure
+            .packets
+            .iter()
+            .map(|packet| {
+                let channels = packet
+                    .header
+                    .channels
+                    .iter()
+                    .chain(&packet.channels)
+                    .map(|id| match types.get(id.as_str()) {
+                        Some(channel_type) => Ok((id.clone(), *channel_type)),
+                        None => bail!("Unknown channel {id} in packet {}", packet.id),
+                    })
+                    .collect::<Result<_>>()?;
+                Ok(PacketLayout {
+                    id: packet.id.clone(),
+                    four_cc: known_four_cc(&packet.id),
+                    channels,
+                })
+            })
+            .collect::<Result<_>>()?;
+        Ok(Self {
+            structure_id: structure.id.clone(),
+            packets,
+        })
+    }
+
+    /// Decode the datagram with the packet layout it belongs to.
+    ///
+    /// The packet is picked by the `packet_4cc` channel when the structure sends it,
+    /// otherwise by the size of the datagram.
+    pub fn decode(&self, datagram: &[u8]) -> Result<Packet> {
+        let four_cc = datagram.get(..4).map(String::from_utf8_lossy);
+        let layout = self
+            .packets
+            .iter()
+            .filter(|v| v.size() == datagram.len())
+            .find(|v| match (v.four_cc, v.channels.first(), &four_cc) {
+                (Some(expected), Some((id, _)), Some(actual)) if id == "packet_4cc" => {
+                    expected == actual
+                }
+                _ => true,
+            })
+            .with_context(|| format!("No packet matches {} bytes", datagram.len()))?;
+        let data = &mut &datagram[..];
+        let values = layout
+            .channels
+            .iter()
+            .map(|(id, channel_type)| Ok((id.clone(), channel_type.read(data)?)))
+            .collect::<Result<_>>()?;
+        Ok(Packet {
+            id: layout.id.clone(),
+            values,
+        })
+    }
+}
+
+fn known_four_cc(packet_id: &str) -> Option<&'static str> {
+    Some(match packet_id {
+        "session_start" => "sess",
+        "session_update" => "sesu",
+        "session_end" => "sese",
+        "session_pause" => "sesp",
+        "session_resume" => "sesr",
+        _ => return None,
+    })
+}
+
+/// Decoded packet with the values of its channels by channel id.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Packet {
+    pub id: String,
+    pub values: HashMap<String, Value>,
+}
+
+impl Packet {
+    pub fn get(&self, channel: &str) -> Option<&Value> {
+        self.values.get(channel)
+    }
+
+    pub fn get_f64(&self, channel: &str) -> Option<f64> {
+        self.get(channel)?.as_f64()
+    }
+
+    pub fn get_bool(&self, channel: &str) -> Option<bool> {
+        self.get(channel)?.as_bool()
+    }
+}
+
+/// Latest packet of each kind received.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct SimState {
+    pub packets: HashMap<String, Arc<Packet>>,
+    pub is_paused: bool,
+}
+
+impl SimState {
+    pub fn update(&mut self, packet: Packet) {
+        match packet.id.as_str() {
+            "session_start" | "session_resume" => self.is_paused = false,
+            "session_pause" => self.is_paused = true,
+            _ => {}
+        }
+        if packet.id == "session_start" {
+            self.packets.clear();
+        }
+        self.packets.insert(packet.id.clone(), Arc::new(packet));
+    }
+
+    pub fn session_update(&self) -> Option<&Packet> {
+        self.packets.get("session_update").map(|v| v.as_ref())
+    }
+
+    /// Numeric value of the channel from the latest session update.
+    pub fn channel(&self, channel: &str) -> Option<f64> {
+        self.session_update()?.get_f64(channel)
+    }
+
+    /// Progress through the stage in 0-1 range.
+    pub fn stage_progress(&self) -> Option<f64> {
+        self.channel("stage_progress")
+    }
+
+    /// Values of a wheel channel, like `vehicle_brake_temperature`, in order
+    /// front left, front right, rear left, rear right.
+    pub fn wheels(&self, channel: &str) -> Option<[f64; 4]> {
+        let wheel = |suffix: &str| self.channel(&format!("{channel}_{suffix}"));
+        Some([wheel("fl")?, wheel("fr")?, wheel("bl")?, wheel("br")?])
+    }
+
+    /// Fraction of the shift lights lit, in 0-1 range.
+    pub fn shift_lights_fraction(&self) -> Option<f64> {
+        self.channel("shiftlights_fraction")
+    }
+}
+
+impl Moment for SimState {
+    fn vehicle_gear(&self) -> Option<i8> {
+        let gear = self.channel("vehicle_gear_index")?;
+        if Some(gear) == self.channel("vehicle_gear_index_neutral") {
+            Some(0)
+        } else if Some(gear) == self.channel("vehicle_gear_index_reverse") {
+            Some(-1)
+        } else {
+            Some(gear as i8)
+        }
+    }
+
+    fn vehicle_velocity(&self) -> Option<Velocity> {
+        Some(Velocity::new::<meter_per_second>(
+            self.channel("vehicle_speed")?,
+        ))
+    }
+
+    fn vehicle_engine_rotation_speed(&self) -> Option<AngularVelocity> {
+        Some(AngularVelocity::new::<revolution_per_minute>(
+            self.channel("vehicle_engine_rpm_current")?,
+        ))
+    }
+
+    fn vehicle_max_engine_rotation_speed(&self) -> Option<AngularVelocity> {
+        Some(AngularVelocity::new::<revolution_per_minute>(
+            self.channel("vehicle_engine_rpm_max")?,
+        ))
+    }
+
+    fn shift_point(&self) -> Option<AngularVelocity> {
+        let update = self.session_update()?;
+        if !update.get_bool("shiftlights_rpm_valid")? {
+            return None;
+        }
+        Some(AngularVelocity::new::<revolution_per_minute>(
+            update.get_f64("shiftlights_rpm_end")?,
+        ))
+    }
+
+    fn pedals(&self) -> Option<Pedals> {
+        Some(Pedals {
+            throttle: self.channel("vehicle_throttle")?,
+            brake: self.channel("vehicle_brake")?,
+            clutch: self.channel("vehicle_clutch")?,
+        })
+    }
+}
@@ -0,0 +1,110 @@
+//! Re-broadcast data of any sim in the UDP formats of other sims.
+//!
+//! Dashboards and motion platforms often understand a single UDP format. The [`Emitter`]
+//! encodes [`Moment`]s of any sim in those formats, so sims read through shared memory can
+//! drive them as well. Only the data a [`Moment`] exposes is sent, other fields are zero.
+
+use crate::{dirt_rally_2, outgauge, Moment, Simetry};
+use anyhow::Result;
+use std::net::SocketAddr;
+use std::time::{Duration, Instant};
+use tokio::net::UdpSocket;
+use tokio::time::MissedTickBehavior;
+
+/// Format of the emitted datagrams.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Protocol {
+    /// Codemasters extradata 3 layout, as parsed by [`dirt_rally_2::SimState`].
+    DirtRally2,
+    /// Live for Speed OutGauge, as parsed by [`outgauge::OutGauge`].
+    OutGauge,
+}
+
+impl Protocol {
+    /// Encode the moment, with `time` since the start of the emission.
+    pub fn encode(self, moment: &dyn Moment, time: Duration) -> Vec<u8> {
+        match self {
+            Self::DirtRally2 => dirt_rally_2::SimState {
+                time: time.as_secs_f32(),
+                ..dirt_rally_2::SimState::from_moment(moment)
+            }
+            .encode(),
+            Self::OutGauge => outgauge::OutGauge {
+                time_ms: time.as_millis() as u32,
+                ..outgauge::OutGauge::from_moment(moment)
+            }
+            .encode(),
+        }
+    }
+}
+
+/// Destination and format of emitted datagrams.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Target {
+    pub protocol: Protocol,
+    pub destination: SocketAddr,
+}
+
+#[derive(Debug)]
+pub struct Emitter {
+    socket: UdpSocket,
+    targets: Vec<Target>,
+    started: Instant,
+}
+
+impl Emitter {
+    /// Bind `uri` to send from, usually `127.0.0.1:0`.
+    pub async fn bind(uri: &str, targets: Vec<Target>) -> Result<Self> {
+        Ok(Self {
+            socket: UdpSocket::bind(uri).await?,
+            targets,
+            started: Instant::now(),
+        })
+    }
+
+    pub fn targets(&self) -> &[Target] {
+        &self.targets
+    }
+
+    /// Send the moment to every target.
+    ///
+    /// Failures to reach a target are logged, so one missing target doesn't stop the others.
+    pub async fn emit(&self, moment: &dyn Moment) {
+        let time = self.started.elapsed();
+        for target in &self.targets {
+            let datagram = target.protocol.encode(moment, time);
+            if let Err(err) = self.socket.send_to(&datagram, target.destination).await {
+                log::warn!("Failed to emit datagram to {}: {err}", target.destination);
+            }
+        }
+    }
+
+    /// Emit the latest moment of the client every `period`, until the client disconnects.
+    ///
+    /// Nothing is sent before the first moment arrives. If the client is slower than `period`,
+    /// the latest moment is sent again, since hardware usually expects a steady stream.
+    pub async fn run<S: Simetry + ?Sized>(&self, client: &mut S, period: Duration) {
+        let mut interval = tokio::time::interval(period);
+        interval.set_missed_tick_behavior(MissedTickBehavior::Skip);
+        let mut latest: Option<Box<dyn Moment + Send + Sync>> = None;
+        loop {
+            // Keep waiting on the same future across ticks, since clients may lose progress
+            // when a pending moment is dropped.
+            let mut next_moment = client.next_moment();
+            let moment = loop {
+                tokio::select! {
+                    moment = &mut next_moment => break moment,
+                    _ = interval.tick() => {
+                        if let Some(moment) = &latest {
+                            self.emit(moment.as_ref()).await;
+                        }
+                    }
+                }
+            };
+            match moment {
+                Some(moment) => latest = Some(moment),
+                None => return,
+            }
+        }
+    }
+}
@@ -0,0 +1,238 @@
+//! Support for the UDP telemetry of F1 23 and F1 24.
+//!
+//! The telemetry has to be enabled in the game settings.
+//! The game sends it to port 20777 by default, which is also the default port of
+//! DiRT Rally 2.0, so [`Client::DEFAULT_URI`] uses port 20778 to let both be detected by
+//! [`crate::connect`]. Change the port in the game, or pass a different URI to the client.
+
+use crate::{Moment, Pedals, RacingFlags, Session, SessionPhase, Simetry};
+use anyhow::Result;
+use packets::{
+    CarDamagePacket, CarSetupsPacket, CarStatusPacket, CarTelemetryPacket, EventPacket,
+    FinalClassificationPacket, LapDataPacket, MotionPacket, Packet, ParticipantsPacket,
+    SessionPacket, TyreSetsPacket,
+};
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::net::UdpSocket;
+use uom::si::angular_velocity::revolution_per_minute;
+use uom::si::f64::{AngularVelocity, Time, Velocity};
+use uom::si::time::second;
+use uom::si::velocity::kilometer_per_hour;
+
+pub mod packets;
+
+#[derive(Debug)]
+pub struct Client {
+    socket: UdpSocket,
+    state: SimState,
+}
+
+impl Client {
+    pub const DEFAULT_URI: &'static str = "127.0.0.1:20778";
+
+    pub async fn connect(uri: &str, retry_delay: Duration) -> Self {
+        loop {
+            if let Ok(client) = Self::try_connect(uri).await {
+                return client;
+            }
+            tokio::time::sleep(retry_delay).await;
+        }
+    }
+
+    pub async fn try_connect(uri: &str) -> Result<Self> {
+        let mut slf = Self {
+            socket: UdpSocket::bind(uri).await?,
+            state: SimState::default(),
+        };
+        slf.next_sim_state().await?;
+        Ok(slf)
+    }
+
+    /// Wait for the next valid packet, and return the state with that packet merged into it.
+    ///
+    /// Datagrams that fail to parse are logged and skipped.
+    pub async fn next_sim_state(&mut self) -> Result<SimState> {
+        let mut buffer = [0u8; PACKET_BUFFER_SIZE];
+        loop {
+            let (size, _origin) = self.socket.recv_from(&mut buffer).await?;
+            match Packet::parse(&buffer[..size]) {
+                Ok(packet) => {
+                    self.state.update(packet);
+                    return Ok(self.state.clone());
+                }
+                Err(err) => log::warn!("Skipping F1 datagram: {err}"),
+            }
+        }
+    }
+}
+
+const PACKET_BUFFER_SIZE: usize = 2048;
+
+#[async_trait::async_trait]
+impl Simetry for Client {
+    fn name(&self) -> &str {
+        "F1"
+    }
+
+    async fn next_moment(&mut self) -> Option<Box<dyn Moment + Send + Sync + 'static>> {
+        Some(Box::new(self.next_sim_state().await.ok()?))
+    }
+}
+
+/// Latest packet of each type received during the current session.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct SimState {
+    pub session_uid: u64,
+    pub player_car_index: u8,
+    pub motion: Option<Arc<MotionPacket>>,
+    pub session: Option<Arc<SessionPacket>>,
+    pub lap_data: Option<Arc<LapDataPacket>>,
+    pub last_event: Option<Arc<EventPacket>>,
+    pub participants: Option<Arc<ParticipantsPacket>>,
+    pub car_setups: Option<Arc<CarSetupsPacket>>,
+    pub car_telemetry: Option<Arc<CarTelemetryPacket>>,
+    pub car_status: Option<Arc<CarStatusPacket>>,
+    pub final_classification: Option<Arc<FinalClassificationPacket>>,
+    pub car_damage: Option<Arc<CarDamagePacket>>,
+    pub tyre_sets: Option<Arc<TyreSetsPacket>>,
+}
+
+impl SimState {
+    /// Merge the packet into the state, dropping all data of the previous session if the packet
+    /// comes from a new one.
+    pub fn update(&mut self, packet: Packet) {
+        let header = packet.header();
+        if header.session_uid != self.session_uid {
+            *self = Self {
+                session_uid: header.session_uid,
+                ..Self::default()
+            };
+        }
+        self.player_car_index = header.player_car_index;
+        match packet {
+            Packet::Motion(v) => self.motion = Some(Arc::new(v)),
+            Packet::Session(v) => self.session = Some(Arc::new(v)),
+            Packet::LapData(v) => self.lap_data = Some(Arc::new(v)),
+            Packet::Event(v) => self.last_event = Some(Arc::new(v)),
+            Packet::Participants(v) => self.participants = Some(Arc::new(v)),
+            Packet::CarSetups(v) => self.car_setups = Some(Arc::new(v)),
+            Packet::CarTelemetry(v) => self.car_telemetry = Some(Arc::new(v)),
+            Packet::CarStatus(v) => self.car_status = Some(Arc::new(v)),
+            Packet::FinalClassification(v) => self.final_classification = Some(Arc::new(v)),
+            Packet::CarDamage(v) => self.car_damage = Some(Arc::new(v)),
+            Packet::TyreSets(v) => self.tyre_sets = Some(Arc::new(v)),
+            Packet::Other(_) => {}
+        }
+    }
+
+    fn player<'a, T>(&self, cars: Option<&'a Vec<T>>) -> Option<&'a T> {
+        cars?.get(self.player_car_index as usize)
+    }
+
+    fn player_telemetry(&self) -> Option<&packets::CarTelemetry> {
+        self.player(self.car_telemetry.as_ref().map(|v| &v.car_telemetry))
+    }
+
+    fn player_status(&self) -> Option<&packets::CarStatus> {
+        self.player(self.car_status.as_ref().map(|v| &v.car_status))
+    }
+
+    fn player_lap_data(&self) -> Option<&packets::LapData> {
+        self.player(self.lap_data.as_ref().map(|v| &v.lap_data))
+    }
+
+    fn player_participant(&self) -> Option<&packets::Participant> {
+        self.player(self.participants.as_ref().map(|v| &v.participants))
+    }
+}
+
+impl Moment for SimState {
+    fn vehicle_gear(&self) -> Option<i8> {
+        Some(self.player_telemetry()?.gear)
+    }
+
+    fn vehicle_velocity(&self) -> Option<Velocity> {
+        Some(Velocity::new::<kilometer_per_hour>(
+            self.player_telemetry()?.speed as f64,
+        ))
+    }
+
+    fn vehicle_engine_rotation_speed(&self) -> Option<AngularVelocity> {
+        Some(AngularVelocity::new::<revolution_per_minute>(
+            self.player_telemetry()?.engine_rpm as f64,
+        ))
+    }
+
+    fn vehicle_max_engine_rotation_speed(&self) -> Option<AngularVelocity> {
+        Some(AngularVelocity::new::<revolution_per_minute>(
+            self.player_status()?.max_rpm as f64,
+        ))
+    }
+
+    fn is_pit_limiter_engaged(&self) -> Option<bool> {
+        Some(self.player_status()?.pit_limiter_status != 0)
+    }
+
+    fn is_vehicle_in_pit_lane(&self) -> Option<bool> {
+        Some(self.player_lap_data()?.pit_status != 0)
+    }
+
+    fn flags(&self) -> Option<RacingFlags> {
+        let fia_flags = self.player_status()?.vehicle_fia_flags;
+        if fia_flags < 0 {
+            return None;
+        }
+        Some(RacingFlags {
+            green: fia_flags == 1,
+            blue: fia_flags == 2,
+            yellow: fia_flags == 3,
+            ..RacingFlags::default()
+        })
+    }
+
+    fn vehicle_brand_id(&self) -> Option<Cow<str>> {
+        Some(self.player_participant()?.team_id.to_string().into())
+    }
+
+    fn pedals(&self) -> Option<Pedals> {
+        let telemetry = self.player_telemetry()?;
+        Some(Pedals {
+            throttle: telemetry.throttle as f64,
+            brake: telemetry.brake as f64,
+            clutch: telemetry.clutch as f64 / 100.0,
+        })
+    }
+
+    fn session(&self) -> Option<Session> {
+        let session = self.session.as_ref()?;
+        let phase = if self.final_classification.is_some() {
+            SessionPhase::Finished
+        } else if session.game_paused != 0 {
+            SessionPhase::Stopped
+        } else {
+            match session.safety_car_status {
+                1 | 2 => SessionPhase::FullCourseYellow,
+                3 => SessionPhase::Formation,
+                _ => SessionPhase::Green,
+            }
+        };
+        let total_laps = Some(session.total_laps as u32).filter(|v| *v > 0);
+        let current_lap = self.player_lap_data().map(|v| v.current_lap_num as u32);
+        Some(Session {
+            session_type: session.session_type(),
+            phase: Some(phase),
+            time_remaining: Some(Time::new::<second>(session.session_time_left as f64)),
+            laps_remaining: total_laps
+                .zip(current_lap)
+                .map(|(total, current)| total.saturating_sub(current.saturating_sub(1))),
+            total_laps,
+            track_id: Some(session.track_id)
+                .filter(|v| *v >= 0)
+                .map(|v| v.to_string()),
+            is_replay: Some(session.is_spectating != 0),
+        })
+    }
+}
@@ -0,0 +1,1261 @@
+use anyhow::{bail, Result};
+use byteorder::{LittleEndian, ReadBytesExt};
+use serde::{Deserialize, Serialize};
+use std::io::Read;
+
+/// Number of car slots sent in every packet with per-car data.
+pub const MAX_CARS: usize = 22;
+/// Size of the [`PacketHeader`] in bytes.
+pub const HEADER_SIZE: usize = 29;
+
+/// Packet formats of F1 23 and F1 24, which are supported.
+pub const SUPPORTED_PACKET_FORMATS: [u16; 2] = [2023, 2024];
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PacketHeader {
+    /// Year of the game the packet comes from, like 2023
+    pub packet_format: u16,
+    /// Last two digits of the game year, like 23
+    pub game_year: u8,
+    pub game_major_version: u8,
+    pub game_minor_version: u8,
+    /// Version of this packet type, all start from 1
+    pub packet_version: u8,
+    /// Identifier for the packet type
+    pub packet_id: u8,
+    /// Unique identifier for the session
+    pub session_uid: u64,
+    /// Session timestamp
+    pub session_time: f32,
+    /// Identifier for the frame the data was retrieved on
+    pub frame_identifier: u32,
+    /// Overall identifier for the frame, that doesn't go back after flashbacks
+    pub overall_frame_identifier: u32,
+    /// Index of player's car in the array
+    pub player_car_index: u8,
+    /// Index of secondary player's car in the array in split-screen, 255 if no second player
+    pub secondary_player_car_index: u8,
+}
+
+/// Any packet sent by the game.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Packet {
+    Motion(MotionPacket),
+    Session(SessionPacket),
+    LapData(LapDataPacket),
+    Event(EventPacket),
+    Participants(ParticipantsPacket),
+    CarSetups(CarSetupsPacket),
+    CarTelemetry(CarTelemetryPacket),
+    CarStatus(CarStatusPacket),
+    FinalClassification(FinalClassificationPacket),
+    CarDamage(CarDamagePacket),
+    TyreSets(TyreSetsPacket),
+    /// Packet types that are not parsed, like lobby info, session history or extended motion
+    Other(PacketHeader),
+}
+
+impl Packet {
+    /// Parse a whole datagram sent by the game.
+    ///
+    /// Fails if the packet format is not supported or if the datagram size doesn't match the
+    /// size expected for the packet type.
+    pub fn parse(datagram: &[u8]) -> Result<Self> {
+        if datagram.len() < HEADER_SIZE {
+            bail!(
+                "Datagram of {} bytes is too short for a packet header",
+                datagram.len()
+            );
+        }
+        let mut data = datagram;
+        let header = read_header(&mut data)?;
+        if !SUPPORTED_PACKET_FORMATS.contains(&header.packet_format) {
+            bail!("Packet format {} is not supported", header.packet_format);
+        }
+        let Some(expected_size) = packet_size(header.packet_format, header.packet_id) else {
+            return Ok(Self::Other(header));
+        };
+        if datagram.len() != expected_size {
+            bail!(
+                "Packet {} of format {} should have {} bytes, got {}",
+                header.packet_id,
+                header.packet_format,
+                expected_size,
+                datagram.len(),
+            );
+        }
+        let format = header.packet_format;
+        let data = &mut data;
+        Ok(match header.packet_id {
+            0 => Self::Motion(MotionPacket {
+                car_motion: read_cars(data, read_car_motion)?,
+                header,
+            }),
+            1 => Self::Session(read_session(header, data)?),
+            2 => Self::LapData(LapDataPacket {
+                lap_data: read_cars(data, |data| read_lap_data(format, data))?,
+                time_trial_pb_car_index: data.read_u8()?,
+                time_trial_rival_car_index: data.read_u8()?,
+                header,
+            }),
+            3 => Self::Event(EventPacket {
+                event: read_event(format, data)?,
+                header,
+            }),
+            4 => Self::Participants(ParticipantsPacket {
+                num_active_cars: data.read_u8()?,
+                participants: read_cars(data, |data| read_participant(format, data))?,
+                header,
+            }),
+            5 => Self::CarSetups(CarSetupsPacket {
+                car_setups: read_cars(data, |data| read_car_setup(format, data))?,
+                next_front_wing_value: match format {
+                    2023 => None,
+                    _ => Some(data.read_f32::<LittleEndian>()?),
+                },
+                header,
+            }),
+            6 => Self::CarTelemetry(CarTelemetryPacket {
+                car_telemetry: read_cars(data, read_car_telemetry)?,
+                mfd_panel_index: data.read_u8()?,
+                mfd_panel_index_secondary_player: data.read_u8()?,
+                suggested_gear: data.read_i8()?,
+                header,
+            }),
+            7 => Self::CarStatus(CarStatusPacket {
+                car_status: read_cars(data, read_car_status)?,
+                header,
+            }),
+            8 => Self::FinalClassification(FinalClassificationPacket {
+                num_cars: data.read_u8()?,
+                classification: read_cars(data, read_final_classification)?,
+                header,
+            }),
+            10 => Self::CarDamage(CarDamagePacket {
+                car_damage: read_cars(data, read_car_damage)?,
+                header,
+            }),
+            12 => Self::TyreSets(TyreSetsPacket {
+                car_index: data.read_u8()?,
+                tyre_sets: (0..NUM_TYRE_SETS)
+                    .map(|_| read_tyre_set(data))
+                    .collect::<Result<_>>()?,
+                fitted_index: data.read_u8()?,
+                header,
+            }),
+            _ => Self::Other(header),
+        })
+    }
+
+    pub fn header(&self) -> &PacketHeader {
+        match self {
+            Self::Motion(v) => &v.header,
+            Self::Session(v) => &v.header,
+            Self::LapData(v) => &v.header,
+            Self::Event(v) => &v.header,
+            Self::Participants(v) => &v.header,
+            Self::CarSetups(v) => &v.header,
+            Self::CarTelemetry(v) => &v.header,
+            Self::CarStatus(v) => &v.header,
+            Self::FinalClassification(v) => &v.header,
+            Self::CarDamage(v) => &v.header,
+            Self::TyreSets(v) => &v.header,
+            Self::Other(header) => header,
+        }
+    }
+}
+
+/// Size in bytes of the whole datagram for the packet types that get parsed.
+pub fn packet_size(packet_format: u16, packet_id: u8) -> Option<usize> {
+    Some(match (packet_format, packet_id) {
+        (_, 0) => 1349,
+        (2023, 1) => 644,
+        (_, 1) => 753,
+        (2023, 2) => 1131,
+        (_, 2) => 1285,
+        (_, 3) => 45,
+        (2023, 4) => 1306,
+        (_, 4) => 1350,
+        (2023, 5) => 1107,
+        (_, 5) => 1133,
+        (_, 6) => 1352,
+        (_, 7) => 1239,
+        (_, 8) => 1020,
+        (_, 10) => 953,
+        (_, 12) => 231,
+        _ => return None,
+    })
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct MotionPacket {
+    pub header: PacketHeader,
+    pub car_motion: Vec<CarMotion>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CarMotion {
+    /// World space position in meters
+    pub world_position: [f32; 3],
+    /// Velocity in world space in meters per second
+    pub world_velocity: [f32; 3],
+    /// World space forward direction, normalized to 32767
+    pub world_forward_dir: [i16; 3],
+    /// World space right direction, normalized to 32767
+    pub world_right_dir: [i16; 3],
+    pub g_force_lateral: f32,
+    pub g_force_longitudinal: f32,
+    pub g_force_vertical: f32,
+    /// Yaw angle in radians
+    pub yaw: f32,
+    /// Pitch angle in radians
+    pub pitch: f32,
+    /// Roll angle in radians
+    pub roll: f32,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SessionPacket {
+    pub header: PacketHeader,
+    /// 0 = clear, 1 = light cloud, 2 = overcast, 3 = light rain, 4 = heavy rain, 5 = storm
+    pub weather: u8,
+    /// Track temperature in degrees Celsius
+    pub track_temperature: i8,
+    /// Air temperature in degrees Celsius
+    pub air_temperature: i8,
+    /// Total number of laps in this race
+    pub total_laps: u8,
+    /// Track length in meters
+    pub track_length: u16,
+    /// Type of the session, with values depending on the packet format
+    ///
+    /// Use [`SessionPacket::session_type`] for the generic session type.
+    pub session_type: u8,
+    /// -1 for unknown
+    pub track_id: i8,
+    /// 0 = F1 Modern, 1 = F1 Classic, 2 = F2, 3 = F1 Generic, 4 = Beta, 6 = Esports, ...
+    pub formula: u8,
+    /// Time left in session in seconds
+    pub session_time_left: u16,
+    /// Session duration in seconds
+    pub session_duration: u16,
+    /// Pit speed limit in kilometers per hour
+    pub pit_speed_limit: u8,
+    pub game_paused: u8,
+    pub is_spectating: u8,
+    pub spectator_car_index: u8,
+    pub sli_pro_native_support: u8,
+    pub marshal_zones: Vec<MarshalZone>,
+    /// 0 = no safety car, 1 = full, 2 = virtual, 3 = formation lap
+    pub safety_car_status: u8,
+    pub network_game: u8,
+    pub weather_forecast_samples: Vec<WeatherForecastSample>,
+    /// 0 = perfect, 1 = approximate
+    pub forecast_accuracy: u8,
+    /// AI difficulty rating in 0-110 range
+    pub ai_difficulty: u8,
+    pub season_link_identifier: u32,
+    pub weekend_link_identifier: u32,
+    pub session_link_identifier: u32,
+    pub pit_stop_window_ideal_lap: u8,
+    pub pit_stop_window_latest_lap: u8,
+    pub pit_stop_rejoin_position: u8,
+    pub steering_assist: u8,
+    pub braking_assist: u8,
+    pub gearbox_assist: u8,
+    pub pit_assist: u8,
+    pub pit_release_assist: u8,
+    pub ers_assist: u8,
+    pub drs_assist: u8,
+    pub dynamic_racing_line: u8,
+    pub dynamic_racing_line_type: u8,
+    pub game_mode: u8,
+    pub rule_set: u8,
+    /// Local time of day in minutes since midnight
+    pub time_of_day: u32,
+    pub session_length: u8,
+    pub speed_units_lead_player: u8,
+    pub temperature_units_lead_player: u8,
+    pub speed_units_secondary_player: u8,
+    pub temperature_units_secondary_player: u8,
+    pub num_safety_car_periods: u8,
+    pub num_virtual_safety_car_periods: u8,
+    pub num_red_flag_periods: u8,
+    /// Session settings only sent by F1 24 and newer
+    pub settings: Option<SessionSettings>,
+}
+
+impl SessionPacket {
+    pub fn session_type(&self) -> Option<crate::SessionType> {
+        use crate::SessionType;
+        let race_start = match self.header.packet_format {
+            2023 => 10,
+            _ => 15,
+        };
+        Some(match self.session_type {
+            1..=4 => SessionType::Practice,
+            // Sprint shootout sessions in F1 24 are qualifying sessions for the sprint.
+            5..=9 => SessionType::Qualifying,
+            v if v < race_start && v > 9 => SessionType::Qualifying,
+            v if v == race_start + 3 => SessionType::Hotlap,
+            v if (race_start..race_start + 3).contains(&v) => SessionType::Race,
+            _ => return None,
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct MarshalZone {
+    /// Fraction (0..1) of way through the lap the marshal zone starts
+    pub zone_start: f32,
+    /// -1 = invalid/unknown, 0 = none, 1 = green, 2 = blue, 3 = yellow
+    pub zone_flag: i8,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct WeatherForecastSample {
+    pub session_type: u8,
+    /// Time in minutes the forecast is for
+    pub time_offset: u8,
+    pub weather: u8,
+    pub track_temperature: i8,
+    /// 0 = up, 1 = down, 2 = no change
+    pub track_temperature_change: i8,
+    pub air_temperature: i8,
+    /// 0 = up, 1 = down, 2 = no change
+    pub air_temperature_change: i8,
+    /// Rain percentage in 0-100 range
+    pub rain_percentage: u8,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SessionSettings {
+    pub equal_car_performance: u8,
+    pub recovery_mode: u8,
+    pub flashback_limit: u8,
+    pub surface_type: u8,
+    pub low_fuel_mode: u8,
+    pub race_starts: u8,
+    pub tyre_temperature: u8,
+    pub pit_lane_tyre_sim: u8,
+    pub car_damage: u8,
+    pub car_damage_rate: u8,
+    pub collisions: u8,
+    pub collisions_off_for_first_lap_only: u8,
+    pub mp_unsafe_pit_release: u8,
+    pub mp_off_for_griefing: u8,
+    pub corner_cutting_stringency: u8,
+    pub parc_ferme_rules: u8,
+    pub pit_stop_experience: u8,
+    pub safety_car: u8,
+    pub safety_car_experience: u8,
+    pub formation_lap: u8,
+    pub formation_lap_experience: u8,
+    pub red_flags: u8,
+    pub affects_licence_level_solo: u8,
+    pub affects_licence_level_mp: u8,
+    pub num_sessions_in_weekend: u8,
+    /// Session types in the weekend, only the first `num_sessions_in_weekend` are valid
+    pub weekend_structure: [u8; 12],
+    /// Distance in meters around the track where sector 2 starts
+    pub sector2_lap_distance_start: f32,
+    /// Distance in meters around the track where sector 3 starts
+    pub sector3_lap_distance_start: f32,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct LapDataPacket {
+    pub header: PacketHeader,
+    pub lap_data: Vec<LapData>,
+    /// Index of personal best car in time trial, 255 if invalid
+    pub time_trial_pb_car_index: u8,
+    /// Index of rival car in time trial, 255 if invalid
+    pub time_trial_rival_car_index: u8,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct LapData {
+    pub last_lap_time_ms: u32,
+    pub current_lap_time_ms: u32,
+    /// Sector 1 time in milliseconds, including the minutes part
+    pub sector1_time_ms: u32,
+    /// Sector 2 time in milliseconds, including the minutes part
+    pub sector2_time_ms: u32,
+    /// Time delta to car in front in milliseconds, including the minutes part
+    pub delta_to_car_in_front_ms: u32,
+    /// Time delta to race leader in milliseconds, including the minutes part
+    pub delta_to_race_leader_ms: u32,
+    /// Distance vehicle is around current lap in meters, could be negative before crossing the line
+    pub lap_distance: f32,
+    /// Total distance travelled in session in meters, could be negative before crossing the line
+    pub total_distance: f32,
+    /// Delta in seconds for safety car
+    pub safety_car_delta: f32,
+    pub car_position: u8,
+    pub current_lap_num: u8,
+    /// 0 = none, 1 = pitting, 2 = in pit area
+    pub pit_status: u8,
+    pub num_pit_stops: u8,
+    /// 0 = sector 1, 1 = sector 2, 2 = sector 3
+    pub sector: u8,
+    pub current_lap_invalid: u8,
+    /// Accumulated time penalties in seconds to be added
+    pub penalties: u8,
+    pub total_warnings: u8,
+    pub corner_cutting_warnings: u8,
+    pub num_unserved_drive_through_pens: u8,
+    pub num_unserved_stop_go_pens: u8,
+    pub grid_position: u8,
+    /// 0 = in garage, 1 = flying lap, 2 = in lap, 3 = out lap, 4 = on track
+    pub driver_status: u8,
+    /// 0 = invalid, 1 = inactive, 2 = active, 3 = finished, 4 = did not finish,
+    /// 5 = disqualified, 6 = not classified, 7 = retired
+    pub result_status: u8,
+    pub pit_lane_timer_active: u8,
+    pub pit_lane_time_in_lane_ms: u16,
+    pub pit_stop_timer_ms: u16,
+    pub pit_stop_should_serve_pen: u8,
+    /// Fastest speed through speed trap for this car in kilometers per hour, only sent by F1 24
+    pub speed_trap_fastest_speed: Option<f32>,
+    /// Lap number the fastest speed was achieved, 255 = not set, only sent by F1 24
+    pub speed_trap_fastest_lap: Option<u8>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct EventPacket {
+    pub header: PacketHeader,
+    pub event: Event,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Event {
+    SessionStarted,
+    SessionEnded,
+    FastestLap {
+        vehicle_index: u8,
+        /// Lap time in seconds
+        lap_time: f32,
+    },
+    Retirement {
+        vehicle_index: u8,
+        /// Only sent by F1 24
+        reason: Option<u8>,
+    },
+    DrsEnabled,
+    DrsDisabled {
+        /// Only sent by F1 24
+        reason: Option<u8>,
+    },
+    TeamMateInPits {
+        vehicle_index: u8,
+    },
+    ChequeredFlag,
+    RaceWinner {
+        vehicle_index: u8,
+    },
+    Penalty {
+        penalty_type: u8,
+        infringement_type: u8,
+        vehicle_index: u8,
+        other_vehicle_index: u8,
+        /// Time gained, or time spent doing action in seconds
+        time: u8,
+        lap_num: u8,
+        places_gained: u8,
+    },
+    SpeedTrap {
+        vehicle_index: u8,
+        /// Top speed achieved in kilometers per hour
+        speed: f32,
+        is_overall_fastest_in_session: u8,
+        is_driver_fastest_in_session: u8,
+        fastest_vehicle_index_in_session: u8,
+        fastest_speed_in_session: f32,
+    },
+    StartLights {
+        num_lights: u8,
+    },
+    LightsOut,
+    DriveThroughServed {
+        vehicle_index: u8,
+    },
+    StopGoServed {
+        vehicle_index: u8,
+    },
+    Flashback {
+        flashback_frame_identifier: u32,
+        flashback_session_time: f32,
+    },
+    Buttons {
+        /// Bit flags specifying which buttons are being pressed
+        button_status: u32,
+    },
+    RedFlag,
+    Overtake {
+        overtaking_vehicle_index: u8,
+        being_overtaken_vehicle_index: u8,
+    },
+    SafetyCar {
+        /// 0 = no safety car, 1 = full, 2 = virtual, 3 = formation lap
+        safety_car_type: u8,
+        /// 0 = deployed, 1 = returning, 2 = returned, 3 = resume race
+        event_type: u8,
+    },
+    Collision {
+        vehicle1_index: u8,
+        vehicle2_index: u8,
+    },
+    /// Event with a code that is not known
+    Other {
+        code: String,
+    },
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ParticipantsPacket {
+    pub header: PacketHeader,
+    /// Number of active cars in the data, should match number of cars on HUD
+    pub num_active_cars: u8,
+    pub participants: Vec<Participant>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Participant {
+    pub ai_controlled: u8,
+    /// 255 if network human
+    pub driver_id: u8,
+    pub network_id: u8,
+    pub team_id: u8,
+    pub my_team: u8,
+    pub race_number: u8,
+    pub nationality: u8,
+    pub name: String,
+    /// 0 = restricted, 1 = public
+    pub your_telemetry: u8,
+    pub show_online_names: u8,
+    /// Only sent by F1 24
+    pub tech_level: Option<u16>,
+    /// 1 = Steam, 3 = PlayStation, 4 = Xbox, 6 = Origin, 255 = unknown
+    pub platform: u8,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CarSetupsPacket {
+    pub header: PacketHeader,
+    pub car_setups: Vec<CarSetup>,
+    /// Value of front wing after next pit stop, only sent by F1 24
+    pub next_front_wing_value: Option<f32>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CarSetup {
+    pub front_wing: u8,
+    pub rear_wing: u8,
+    /// Differential adjustment on throttle in percent
+    pub on_throttle: u8,
+    /// Differential adjustment off throttle in percent
+    pub off_throttle: u8,
+    pub front_camber: f32,
+    pub rear_camber: f32,
+    pub front_toe: f32,
+    pub rear_toe: f32,
+    pub front_suspension: u8,
+    pub rear_suspension: u8,
+    pub front_anti_roll_bar: u8,
+    pub rear_anti_roll_bar: u8,
+    pub front_suspension_height: u8,
+    pub rear_suspension_height: u8,
+    /// Brake pressure in percent
+    pub brake_pressure: u8,
+    /// Brake bias in percent
+    pub brake_bias: u8,
+    /// Engine braking in percent, only sent by F1 24
+    pub engine_braking: Option<u8>,
+    /// Tyre pressures in PSI, in rear left, rear right, front left, front right order
+    pub tyre_pressures: [f32; 4],
+    pub ballast: u8,
+    pub fuel_load: f32,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CarTelemetryPacket {
+    pub header: PacketHeader,
+    pub car_telemetry: Vec<CarTelemetry>,
+    /// Index of MFD panel open, 255 = closed
+    pub mfd_panel_index: u8,
+    pub mfd_panel_index_secondary_player: u8,
+    /// Suggested gear for the player, 0 if no gear is suggested
+    pub suggested_gear: i8,
+}
+
+/// Wheel arrays are in rear left, rear right, front left, front right order.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CarTelemetry {
+    /// Speed of car in kilometers per hour
+    pub speed: u16,
+    /// Amount of throttle applied in 0-1 range
+    pub throttle: f32,
+    /// Steering in -1 (full lock left) to 1 (full lock right) range
+    pub steer: f32,
+    /// Amount of brake applied in 0-1 range
+    pub brake: f32,
+    /// Amount of clutch applied in 0-100 range
+    pub clutch: u8,
+    /// -1 = reverse, 0 = neutral, 1-8 forward gears
+    pub gear: i8,
+    pub engine_rpm: u16,
+    pub drs: u8,
+    pub rev_lights_percent: u8,
+    /// Bit 0 is the leftmost LED, bit 14 the rightmost
+    pub rev_lights_bit_value: u16,
+    /// Brake temperatures in degrees Celsius
+    pub brakes_temperature: [u16; 4],
+    /// Tyre surface temperatures in degrees Celsius
+    pub tyres_surface_temperature: [u8; 4],
+    /// Tyre inner temperatures in degrees Celsius
+    pub tyres_inner_temperature: [u8; 4],
+    /// Engine temperature in degrees Celsius
+    pub engine_temperature: u16,
+    /// Tyre pressures in PSI
+    pub tyres_pressure: [f32; 4],
+    /// Driving surface of each wheel
+    pub surface_type: [u8; 4],
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CarStatusPacket {
+    pub header: PacketHeader,
+    pub car_status: Vec<CarStatus>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CarStatus {
+    /// 0 = off, 1 = medium, 2 = full
+    pub traction_control: u8,
+    pub anti_lock_brakes: u8,
+    /// 0 = lean, 1 = standard, 2 = rich, 3 = max
+    pub fuel_mix: u8,
+    /// Front brake bias in percent
+    pub front_brake_bias: u8,
+    pub pit_limiter_status: u8,
+    /// Current fuel mass
+    pub fuel_in_tank: f32,
+    pub fuel_capacity: f32,
+    /// Fuel remaining in terms of laps, as shown on MFD
+    pub fuel_remaining_laps: f32,
+    /// Engine RPM where the rev limiter kicks in
+    pub max_rpm: u16,
+    pub idle_rpm: u16,
+    pub max_gears: u8,
+    pub drs_allowed: u8,
+    /// Distance in meters to where DRS will be available, 0 if not available
+    pub drs_activation_distance: u16,
+    pub actual_tyre_compound: u8,
+    pub visual_tyre_compound: u8,
+    pub tyres_age_laps: u8,
+    /// -1 = invalid/unknown, 0 = none, 1 = green, 2 = blue, 3 = yellow
+    pub vehicle_fia_flags: i8,
+    /// Engine power output of ICE in watts
+    pub engine_power_ice: f32,
+    /// Engine power output of MGU-K in watts
+    pub engine_power_mguk: f32,
+    /// ERS energy store in joules
+    pub ers_store_energy: f32,
+    /// 0 = none, 1 = medium, 2 = hotlap, 3 = overtake
+    pub ers_deploy_mode: u8,
+    pub ers_harvested_this_lap_mguk: f32,
+    pub ers_harvested_this_lap_mguh: f32,
+    pub ers_deployed_this_lap: f32,
+    pub network_paused: u8,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct FinalClassificationPacket {
+    pub header: PacketHeader,
+    pub num_cars: u8,
+    pub classification: Vec<FinalClassification>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct FinalClassification {
+    pub position: u8,
+    pub num_laps: u8,
+    pub grid_position: u8,
+    pub points: u8,
+    pub num_pit_stops: u8,
+    pub result_status: u8,
+    pub best_lap_time_ms: u32,
+    /// Total race time in seconds, without penalties
+    pub total_race_time: f64,
+    /// Total penalties accumulated in seconds
+    pub penalties_time: u8,
+    pub num_penalties: u8,
+    pub num_tyre_stints: u8,
+    pub tyre_stints_actual: [u8; 8],
+    pub tyre_stints_visual: [u8; 8],
+    pub tyre_stints_end_laps: [u8; 8],
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CarDamagePacket {
+    pub header: PacketHeader,
+    pub car_damage: Vec<CarDamage>,
+}
+
+/// Damage values are in percent, wheel arrays in rear left, rear right, front left, front right
+/// order.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CarDamage {
+    pub tyres_wear: [f32; 4],
+    pub tyres_damage: [u8; 4],
+    pub brakes_damage: [u8; 4],
+    pub front_left_wing_damage: u8,
+    pub front_right_wing_damage: u8,
+    pub rear_wing_damage: u8,
+    pub floor_damage: u8,
+    pub diffuser_damage: u8,
+    pub sidepod_damage: u8,
+    pub drs_fault: u8,
+    pub ers_fault: u8,
+    pub gear_box_damage: u8,
+    pub engine_damage: u8,
+    pub engine_mguh_wear: u8,
+    pub engine_es_wear: u8,
+    pub engine_ce_wear: u8,
+    pub engine_ice_wear: u8,
+    pub engine_mguk_wear: u8,
+    pub engine_tc_wear: u8,
+    pub engine_blown: u8,
+    pub engine_seized: u8,
+}
+
+const NUM_TYRE_SETS: usize = 20;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TyreSetsPacket {
+    pub header: PacketHeader,
+    /// Index of the car this data relates to
+    pub car_index: u8,
+    /// 13 dry and 7 wet tyre sets
+    pub tyre_sets: Vec<TyreSet>,
+    /// Index into the tyre sets of the fitted set
+    pub fitted_index: u8,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TyreSet {
+    pub actual_tyre_compound: u8,
+    pub visual_tyre_compound: u8,
+    /// Tyre wear in percent
+    pub wear: u8,
+    pub available: u8,
+    pub recommended_session: u8,
+    /// Laps left in this tyre set
+    pub life_span: u8,
+    /// Max number of laps recommended for this compound
+    pub usable_life: u8,
+    /// Lap delta time in milliseconds compared to fitted set
+    pub lap_delta_time: i16,
+    pub fitted: u8,
+}
+
+fn read_header(data: &mut &[u8]) -> Result<PacketHeader> {
+    Ok(PacketHeader {
+        packet_format: data.read_u16::<LittleEndian>()?,
+        game_year: data.read_u8()?,
+        game_major_version: data.read_u8()?,
+        game_minor_version: data.read_u8()?,
+        packet_version: data.read_u8()?,
+        packet_id: data.read_u8()?,
+        session_uid: data.read_u64::<LittleEndian>()?,
+        session_time: data.read_f32::<LittleEndian>()?,
+        frame_identifier: data.read_u32::<LittleEndian>()?,
+        overall_frame_identifier: data.read_u32::<LittleEndian>()?,
+        player_car_index: data.read_u8()?,
+        secondary_player_car_index: data.read_u8()?,
+    })
+}
+
+fn read_cars<T>(data: &mut &[u8], read: impl Fn(&mut &[u8]) -> Result<T>) -> Result<Vec<T>> {
+    (0..MAX_CARS).map(|_| read(data)).collect()
+}
+
+fn read_bytes<const N: usize>(data: &mut &[u8]) -> Result<[u8; N]> {
+    let mut bytes = [0u8; N];
+    data.read_exact(&mut bytes)?;
+    Ok(bytes)
+}
+
+fn read_f32s<const N: usize>(data: &mut &[u8]) -> Result<[f32; N]> {
+    let mut values = [0.0; N];
+    data.read_f32_into::<LittleEndian>(&mut values)?;
+    Ok(values)
+}
+
+fn read_i16s<const N: usize>(data: &mut &[u8]) -> Result<[i16; N]> {
+    let mut values = [0; N];
+    data.read_i16_into::<LittleEndian>(&mut values)?;
+    Ok(values)
+}
+
+fn read_u16s<const N: usize>(data: &mut &[u8]) -> Result<[u16; N]> {
+    let mut values = [0; N];
+    data.read_u16_into::<LittleEndian>(&mut values)?;
+    Ok(values)
+}
+
+fn read_car_motion(data: &mut &[u8]) -> Result<CarMotion> {
+    Ok(CarMotion {
+        world_position: read_f32s(data)?,
+        world_velocity: read_f32s(data)?,
+        world_forward_dir: read_i16s(data)?,
+        world_right_dir: read_i16s(data)?,
+        g_force_lateral: data.read_f32::<LittleEndian>()?,
+        g_force_longitudinal: data.read_f32::<LittleEndian>()?,
+        g_force_vertical: data.read_f32::<LittleEndian>()?,
+        yaw: data.read_f32::<LittleEndian>()?,
+        pitch: data.read_f32::<LittleEndian>()?,
+        roll: data.read_f32::<LittleEndian>()?,
+    })
+}
+
+fn read_session(header: PacketHeader, data: &mut &[u8]) -> Result<SessionPacket> {
+    let format = header.packet_format;
+    let weather = data.read_u8()?;
+    let track_temperature = data.read_i8()?;
+    let air_temperature = data.read_i8()?;
+    let total_laps = data.read_u8()?;
+    let track_length = data.read_u16::<LittleEndian>()?;
+    let session_type = data.read_u8()?;
+    let track_id = data.read_i8()?;
+    let formula = data.read_u8()?;
+    let session_time_left = data.read_u16::<LittleEndian>()?;
+    let session_duration = data.read_u16::<LittleEndian>()?;
+    let pit_speed_limit = data.read_u8()?;
+    let game_paused = data.read_u8()?;
+    let is_spectating = data.read_u8()?;
+    let spectator_car_index = data.read_u8()?;
+    let sli_pro_native_support = data.read_u8()?;
+    let num_marshal_zones = data.read_u8()? as usize;
+    let mut marshal_zones = (0..21)
+        .map(|_| {
+            Ok(MarshalZone {
+                zone_start: data.read_f32::<LittleEndian>()?,
+                zone_flag: data.read_i8()?,
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+    marshal_zones.truncate(num_marshal_zones);
+    let safety_car_status = data.read_u8()?;
+    let network_game = data.read_u8()?;
+    let num_weather_forecast_samples = data.read_u8()? as usize;
+    let max_weather_forecast_samples = match format {
+        2023 => 56,
+        _ => 64,
+    };
+    let mut weather_forecast_samples = (0..max_weather_forecast_samples)
+        .map(|_| {
+            Ok(WeatherForecastSample {
+                session_type: data.read_u8()?,
+                time_offset: data.read_u8()?,
+                weather: data.read_u8()?,
+                track_temperature: data.read_i8()?,
+                track_temperature_change: data.read_i8()?,
+                air_temperature: data.read_i8()?,
+                air_temperature_change: data.read_i8()?,
+                rain_percentage: data.read_u8()?,
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+    weather_forecast_samples.truncate(num_weather_forecast_samples);
+    Ok(SessionPacket {
+        header,
+        weather,
+        track_temperature,
+        air_temperature,
+        total_laps,
+        track_length,
+        session_type,
+        track_id,
+        formula,
+        session_time_left,
+        session_duration,
+        pit_speed_limit,
+        game_paused,
+        is_spectating,
+        spectator_car_index,
+        sli_pro_native_support,
+        marshal_zones,
+        safety_car_status,
+        network_game,
+        weather_forecast_samples,
+        forecast_accuracy: data.read_u8()?,
+        ai_difficulty: data.read_u8()?,
+        season_link_identifier: data.read_u32::<LittleEndian>()?,
+        weekend_link_identifier: data.read_u32::<LittleEndian>()?,
+        session_link_identifier: data.read_u32::<LittleEndian>()?,
+        pit_stop_window_ideal_lap: data.read_u8()?,
+        pit_stop_window_latest_lap: data.read_u8()?,
+        pit_stop_rejoin_position: data.read_u8()?,
+        steering_assist: data.read_u8()?,
+        braking_assist: data.read_u8()?,
+        gearbox_assist: data.read_u8()?,
+        pit_assist: data.read_u8()?,
+        pit_release_assist: data.read_u8()?,
+        ers_assist: data.read_u8()?,
+        drs_assist: data.read_u8()?,
+        dynamic_racing_line: data.read_u8()?,
+        dynamic_racing_line_type: data.read_u8()?,
+        game_mode: data.read_u8()?,
+        rule_set: data.read_u8()?,
+        time_of_day: data.read_u32::<LittleEndian>()?,
+        session_length: data.read_u8()?,
+        speed_units_lead_player: data.read_u8()?,
+        temperature_units_lead_player: data.read_u8()?,
+        speed_units_secondary_player: data.read_u8()?,
+        temperature_units_secondary_player: data.read_u8()?,
+        num_safety_car_periods: data.read_u8()?,
+        num_virtual_safety_car_periods: data.read_u8()?,
+        num_red_flag_periods: data.read_u8()?,
+        settings: match format {
+            2023 => None,
+            _ => Some(read_session_settings(data)?),
+        },
+    })
+}
+
+fn read_session_settings(data: &mut &[u8]) -> Result<SessionSettings> {
+    Ok(SessionSettings {
+        equal_car_performance: data.read_u8()?,
+        recovery_mode: data.read_u8()?,
+        flashback_limit: data.read_u8()?,
+        surface_type: data.read_u8()?,
+        low_fuel_mode: data.read_u8()?,
+        race_starts: data.read_u8()?,
+        tyre_temperature: data.read_u8()?,
+        pit_lane_tyre_sim: data.read_u8()?,
+        car_damage: data.read_u8()?,
+        car_damage_rate: data.read_u8()?,
+        collisions: data.read_u8()?,
+        collisions_off_for_first_lap_only: data.read_u8()?,
+        mp_unsafe_pit_release: data.read_u8()?,
+        mp_off_for_griefing: data.read_u8()?,
+        corner_cutting_stringency: data.read_u8()?,
+        parc_ferme_rules: data.read_u8()?,
+        pit_stop_experience: data.read_u8()?,
+        safety_car: data.read_u8()?,
+        safety_car_experience: data.read_u8()?,
+        formation_lap: data.read_u8()?,
+        formation_lap_experience: data.read_u8()?,
+        red_flags: data.read_u8()?,
+        affects_licence_level_solo: data.read_u8()?,
+        affects_licence_level_mp: data.read_u8()?,
+        num_sessions_in_weekend: data.read_u8()?,
+        weekend_structure: read_bytes(data)?,
+        sector2_lap_distance_start: data.read_f32::<LittleEndian>()?,
+        sector3_lap_distance_start: data.read_f32::<LittleEndian>()?,
+    })
+}
+
+/// Read a time split into milliseconds and minutes parts.
+fn read_split_time(data: &mut &[u8]) -> Result<u32> {
+    let millis = data.read_u16::<LittleEndian>()? as u32;
+    let minutes = data.read_u8()? as u32;
+    Ok(minutes * 60_000 + millis)
+}
+
+fn read_lap_data(format: u16, data: &mut &[u8]) -> Result<LapData> {
+    let last_lap_time_ms = data.read_u32::<LittleEndian>()?;
+    let current_lap_time_ms = data.read_u32::<LittleEndian>()?;
+    let sector1_time_ms = read_split_time(data)?;
+    let sector2_time_ms = read_split_time(data)?;
+    let (delta_to_car_in_front_ms, delta_to_race_leader_ms) = match format {
+        2023 => (
+            data.read_u16::<LittleEndian>()? as u32,
+            data.read_u16::<LittleEndian>()? as u32,
+        ),
+        _ => (read_split_time(data)?, read_split_time(data)?),
+    };
+    Ok(LapData {
+        last_lap_time_ms,
+        current_lap_time_ms,
+        sector1_time_ms,
+        sector2_time_ms,
+        delta_to_car_in_front_ms,
+        delta_to_race_leader_ms,
+        lap_distance: data.read_f32::<LittleEndian>()?,
+        total_distance: data.read_f32::<LittleEndian>()?,
+        safety_car_delta: data.read_f32::<LittleEndian>()?,
+        car_position: data.read_u8()?,
+        current_lap_num: data.read_u8()?,
+        pit_status: data.read_u8()?,
+        num_pit_stops: data.read_u8()?,
+        sector: data.read_u8()?,
+        current_lap_invalid: data.read_u8()?,
+        penalties: data.read_u8()?,
+        total_warnings: data.read_u8()?,
+        corner_cutting_warnings: data.read_u8()?,
+        num_unserved_drive_through_pens: data.read_u8()?,
+        num_unserved_stop_go_pens: data.read_u8()?,
+        grid_position: data.read_u8()?,
+        driver_status: data.read_u8()?,
+        result_status: data.read_u8()?,
+        pit_lane_timer_active: data.read_u8()?,
+        pit_lane_time_in_lane_ms: data.read_u16::<LittleEndian>()?,
+        pit_stop_timer_ms: data.read_u16::<LittleEndian>()?,
+        pit_stop_should_serve_pen: data.read_u8()?,
+        speed_trap_fastest_speed: match format {
+            2023 => None,
+            _ => Some(data.read_f32::<LittleEndian>()?),
+        },
+        speed_trap_fastest_lap: match format {
+            2023 => None,
+            _ => Some(data.read_u8()?),
+        },
+    })
+}
+
+fn read_event(format: u16, data: &mut &[u8]) -> Result<Event> {
+    let code = read_bytes::<4>(data)?;
+    let f1_24 = format != 2023;
+    Ok(match &code {
+        b"SSTA" => Event::SessionStarted,
+        b"SEND" => Event::SessionEnded,
+        b"FTLP" => Event::FastestLap {
+            vehicle_index: data.read_u8()?,
+            lap_time: data.read_f32::<LittleEndian>()?,
+        },
+        b"RTMT" => Event::Retirement {
+            vehicle_index: data.read_u8()?,
+            reason: f1_24.then(|| data.read_u8()).transpose()?,
+        },
+        b"DRSE" => Event::DrsEnabled,
+        b"DRSD" => Event::DrsDisabled {
+            reason: f1_24.then(|| data.read_u8()).transpose()?,
+        },
+        b"TMPT" => Event::TeamMateInPits {
+            vehicle_index: data.read_u8()?,
+        },
+        b"CHQF" => Event::ChequeredFlag,
+        b"RCWN" => Event::RaceWinner {
+            vehicle_index: data.read_u8()?,
+        },
+        b"PENA" => Event::Penalty {
+            penalty_type: data.read_u8()?,
+            infringement_type: data.read_u8()?,
+            vehicle_index: data.read_u8()?,
+            other_vehicle_index: data.read_u8()?,
+            time: data.read_u8()?,
+            lap_num: data.read_u8()?,
+            places_gained: data.read_u8()?,
+        },
+        b"SPTP" => Event::SpeedTrap {
+            vehicle_index: data.read_u8()?,
+            speed: data.read_f32::<LittleEndian>()?,
+            is_overall_fastest_in_session: data.read_u8()?,
+            is_driver_fastest_in_session: data.read_u8()?,
+            fastest_vehicle_index_in_session: data.read_u8()?,
+            fastest_speed_in_session: data.read_f32::<LittleEndian>()?,
+        },
+        b"STLG" => Event::StartLights {
+            num_lights: data.read_u8()?,
+        },
+        b"LGOT" => Event::LightsOut,
+        b"DTSV" => Event::DriveThroughServed {
+            vehicle_index: data.read_u8()?,
+        },
+        b"SGSV" => Event::StopGoServed {
+            vehicle_index: data.read_u8()?,
+        },
+        b"FLBK" => Event::Flashback {
+            flashback_frame_identifier: data.read_u32::<LittleEndian>()?,
+            flashback_session_time: data.read_f32::<LittleEndian>()?,
+        },
+        b"BUTN" => Event::Buttons {
+            button_status: data.read_u32::<LittleEndian>()?,
+        },
+        b"RDFL" => Event::RedFlag,
+        b"OVTK" => Event::Overtake {
+            overtaking_vehicle_index: data.read_u8()?,
+            being_overtaken_vehicle_index: data.read_u8()?,
+        },
+        b"SCAR" => Event::SafetyCar {
+            safety_car_type: data.read_u8()?,
+            event_type: data.read_u8()?,
+        },
+        b"COLL" => Event::Collision {
+            vehicle1_index: data.read_u8()?,
+            vehicle2_index: data.read_u8()?,
+        },
+        _ => Event::Other {
+            code: String::from_utf8_lossy(&code).into_owned(),
+        },
+    })
+}
+
+fn read_participant(format: u16, data: &mut &[u8]) -> Result<Participant> {
+    let ai_controlled = data.read_u8()?;
+    let driver_id = data.read_u8()?;
+    let network_id = data.read_u8()?;
+    let team_id = data.read_u8()?;
+    let my_team = data.read_u8()?;
+    let race_number = data.read_u8()?;
+    let nationality = data.read_u8()?;
+    let name = read_bytes::<48>(data)?;
+    let name_length = name.iter().position(|v| *v == 0).unwrap_or(name.len());
+    Ok(Participant {
+        ai_controlled,
+        driver_id,
+        network_id,
+        team_id,
+        my_team,
+        race_number,
+        nationality,
+        name: String::from_utf8_lossy(&name[..name_length]).into_owned(),
+        your_telemetry: data.read_u8()?,
+        show_online_names: data.read_u8()?,
+        tech_level: match format {
+            2023 => None,
+            _ => Some(data.read_u16::<LittleEndian>()?),
+        },
+        platform: data.read_u8()?,
+    })
+}
+
+fn read_car_setup(format: u16, data: &mut &[u8]) -> Result<CarSetup> {
+    Ok(CarSetup {
+        front_wing: data.read_u8()?,
+        rear_wing: data.read_u8()?,
+        on_throttle: data.read_u8()?,
+        off_throttle: data.read_u8()?,
+        front_camber: data.read_f32::<LittleEndian>()?,
+        rear_camber: data.read_f32::<LittleEndian>()?,
+        front_toe: data.read_f32::<LittleEndian>()?,
+        rear_toe: data.read_f32::<LittleEndian>()?,
+        front_suspension: data.read_u8()?,
+        rear_suspension: data.read_u8()?,
+        front_anti_roll_bar: data.read_u8()?,
+        rear_anti_roll_bar: data.read_u8()?,
+        front_suspension_height: data.read_u8()?,
+        rear_suspension_height: data.read_u8()?,
+        brake_pressure: data.read_u8()?,
+        brake_bias: data.read_u8()?,
+        engine_braking: match format {
+            2023 => None,
+            _ => Some(data.read_u8()?),
+        },
+        tyre_pressures: read_f32s(data)?,
+        ballast: data.read_u8()?,
+        fuel_load: data.read_f32::<LittleEndian>()?,
+    })
+}
+
+fn read_car_telemetry(data: &mut &[u8]) -> Result<CarTelemetry> {
+    Ok(CarTelemetry {
+        speed: data.read_u16::<LittleEndian>()?,
+        throttle: data.read_f32::<LittleEndian>()?,
+        steer: data.read_f32::<LittleEndian>()?,
+        brake: data.read_f32::<LittleEndian>()?,
+        clutch: data.read_u8()?,
+        gear: data.read_i8()?,
+        engine_rpm: data.read_u16::<LittleEndian>()?,
+        drs: data.read_u8()?,
+        rev_lights_percent: data.read_u8()?,
+        rev_lights_bit_value: data.read_u16::<LittleEndian>()?,
+        brakes_temperature: read_u16s(data)?,
+        tyres_surface_temperature: read_bytes(data)?,
+        tyres_inner_temperature: read_bytes(data)?,
+        engine_temperature: data.read_u16::<LittleEndian>()?,
+        tyres_pressure: read_f32s(data)?,
+        surface_type: read_bytes(data)?,
+    })
+}
+
+fn read_car_status(data: &mut &[u8]) -> Result<CarStatus> {
+    Ok(CarStatus {
+        traction_control: data.read_u8()?,
+        anti_lock_brakes: data.read_u8()?,
+        fuel_mix: data.read_u8()?,
+        front_brake_bias: data.read_u8()?,
+        pit_limiter_status: data.read_u8()?,
+        fuel_in_tank: data.read_f32::<LittleEndian>()?,
+        fuel_capacity: data.read_f32::<LittleEndian>()?,
+        fuel_remaining_laps: data.read_f32::<LittleEndian>()?,
+        max_rpm: data.read_u16::<LittleEndian>()?,
+        idle_rpm: data.read_u16::<LittleEndian>()?,
+        max_gears: data.read_u8()?,
+        drs_allowed: data.read_u8()?,
+        drs_activation_distance: data.read_u16::<LittleEndian>()?,
+        actual_tyre_compound: data.read_u8()?,
+        visual_tyre_compound: data.read_u8()?,
+        tyres_age_laps: data.read_u8()?,
+        vehicle_fia_flags: data.read_i8()?,
+        engine_power_ice: data.read_f32::<LittleEndian>()?,
+        engine_power_mguk: data.read_f32::<LittleEndian>()?,
+        ers_store_energy: data.read_f32::<LittleEndian>()?,
+        ers_deploy_mode: data.read_u8()?,
+        ers_harvested_this_lap_mguk: data.read_f32::<LittleEndian>()?,
+        ers_harvested_this_lap_mguh: data.read_f32::<LittleEndian>()?,
+        ers_deployed_this_lap: data.read_f32::<LittleEndian>()?,
+        network_paused: data.read_u8()?,
+    })
+}
+
+fn read_final_classification(data: &mut &[u8]) -> Result<FinalClassification> {
+    Ok(FinalClassification {
+        position: data.read_u8()?,
+        num_laps: data.read_u8()?,
+        grid_position: data.read_u8()?,
+        points: data.read_u8()?,
+        num_pit_stops: data.read_u8()?,
+        result_status: data.read_u8()?,
+        best_lap_time_ms: data.read_u32::<LittleEndian>()?,
+        total_race_time: data.read_f64::<LittleEndian>()?,
+        penalties_time: data.read_u8()?,
+        num_penalties: data.read_u8()?,
+        num_tyre_stints: data.read_u8()?,
+        tyre_stints_actual: read_bytes(data)?,
+        tyre_stints_visual: read_bytes(data)?,
+        tyre_stints_end_laps: read_bytes(data)?,
+    })
+}
+
+fn read_car_damage(data: &mut &[u8]) -> Result<CarDamage> {
+    Ok(CarDamage {
+        tyres_wear: read_f32s(data)?,
+        tyres_damage: read_bytes(data)?,
+        brakes_damage: read_bytes(data)?,
+        front_left_wing_damage: data.read_u8()?,
+        front_right_wing_damage: data.read_u8()?,
+        rear_wing_damage: data.read_u8()?,
+        floor_damage: data.read_u8()?,
+        diffuser_damage: data.read_u8()?,
+        sidepod_damage: data.read_u8()?,
+        drs_fault: data.read_u8()?,
+        ers_fault: data.read_u8()?,
+        gear_box_damage: data.read_u8()?,
+        engine_damage: data.read_u8()?,
+        engine_mguh_wear: data.read_u8()?,
+        engine_es_wear: data.read_u8()?,
+        engine_ce_wear: data.read_u8()?,
+        engine_ice_wear: data.read_u8()?,
+        engine_mguk_wear: data.read_u8()?,
+        engine_tc_wear: data.read_u8()?,
+        engine_blown: data.read_u8()?,
+        engine_seized: data.read_u8()?,
+    })
+}
+
+fn read_tyre_set(data: &mut &[u8]) -> Result<TyreSet> {
+    Ok(TyreSet {
+        actual_tyre_compound: data.read_u8()?,
+        visual_tyre_compound: data.read_u8()?,
+        wear: data.read_u8()?,
+        available: data.read_u8()?,
+        recommended_session: data.read_u8()?,
+        life_span: data.read_u8()?,
+        usable_life: data.read_u8()?,
+        lap_delta_time: data.read_i16::<LittleEndian>()?,
+        fitted: data.read_u8()?,
+    })
+}
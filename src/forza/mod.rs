@@ -0,0 +1,430 @@
+//! Support for the "Data Out" UDP telemetry of Forza Motorsport and Forza Horizon.
+//!
+//! Data Out has to be enabled in the HUD settings of the game, with the IP address and port
+//! matching the ones the [`Client`] is bound to.
+
+use crate::{Moment, Pedals, Simetry};
+use anyhow::{bail, Result};
+use byteorder::{LittleEndian, ReadBytesExt};
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::time::Duration;
+use tokio::net::UdpSocket;
+use uom::si::angular_velocity::revolution_per_minute;
+use uom::si::f64::{AngularVelocity, Velocity};
+use uom::si::velocity::meter_per_second;
+
+#[derive(Debug)]
+pub struct Client {
+    socket: UdpSocket,
+}
+
+impl Client {
+    pub const DEFAULT_URI: &'static str = "127.0.0.1:5300";
+
+    pub async fn connect(uri: &str, retry_delay: Duration) -> Self {
+        loop {
+            if let Ok(client) = Self::try_connect(uri).await {
+                return client;
+            }
+            tokio::time::sleep(retry_delay).await;
+        }
+    }
+
+    pub async fn try_connect(uri: &str) -> Result<Self> {
+        let slf = Self {
+            socket: UdpSocket::bind(uri).await?,
+        };
+        slf.next_sim_state().await?;
+        Ok(slf)
+    }
+
+    /// Wait for the next valid datagram.
+    ///
+    /// Datagrams that don't match any of the formats are logged and skipped.
+    pub async fn next_sim_state(&self) -> Result<SimState> {
+        let mut buffer = [0u8; PACKET_BUFFER_SIZE];
+        loop {
+            let (size, _origin) = self.socket.recv_from(&mut buffer).await?;
+            match SimState::parse(&buffer[..size]) {
+                Ok(sim_state) => return Ok(sim_state),
+                Err(err) => log::warn!("Skipping Forza datagram: {err}"),
+            }
+        }
+    }
+}
+
+const PACKET_BUFFER_SIZE: usize = 1024;
+
+#[async_trait::async_trait]
+impl Simetry for Client {
+    fn name(&self) -> &str {
+        "Forza"
+    }
+
+    async fn next_moment(&mut self) -> Option<Box<dyn Moment + Send + Sync + 'static>> {
+        Some(Box::new(self.next_sim_state().await.ok()?))
+    }
+}
+
+/// Layout of the datagrams, as selected in the game settings.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum Format {
+    /// Only the [`Sled`] data, sent by Forza Motorsport 7.
+    Sled,
+    /// [`Sled`] and [`Dash`] data, sent by Forza Motorsport 7.
+    Dash,
+    /// [`Sled`] and [`Dash`] data with extra padding, sent by Forza Horizon 4 and 5.
+    HorizonDash,
+    /// [`Sled`] and [`Dash`] data followed by [`MotorsportExtras`], sent by Forza Motorsport (2023).
+    MotorsportDash,
+}
+
+impl Format {
+    pub const SLED_SIZE: usize = 232;
+    pub const DASH_SIZE: usize = 311;
+    pub const HORIZON_DASH_SIZE: usize = 324;
+    pub const MOTORSPORT_DASH_SIZE: usize = 331;
+
+    /// Detect the format from the size of the datagram.
+    pub fn from_size(size: usize) -> Option<Self> {
+        Some(match size {
+            Self::SLED_SIZE => Self::Sled,
+            Self::DASH_SIZE => Self::Dash,
+            Self::HORIZON_DASH_SIZE => Self::HorizonDash,
+            Self::MOTORSPORT_DASH_SIZE => Self::MotorsportDash,
+            _ => return None,
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SimState {
+    pub format: Format,
+    pub sled: Sled,
+    /// Present for all formats except [`Format::Sled`].
+    pub dash: Option<Dash>,
+    /// Present only for [`Format::MotorsportDash`].
+    pub motorsport_extras: Option<MotorsportExtras>,
+}
+
+impl SimState {
+    /// Parse a datagram of any of the supported formats.
+    pub fn parse(datagram: &[u8]) -> Result<Self> {
+        let Some(format) = Format::from_size(datagram.len()) else {
+            bail!(
+                "Datagram of {} bytes doesn't match any format",
+                datagram.len()
+            );
+        };
+        let mut data = datagram;
+        let sled = read_sled(&mut data)?;
+        if format == Format::HorizonDash {
+            // Horizon sends car details between the sled and dash parts, which are not documented.
+            data = &data[12..];
+        }
+        let dash = match format {
+            Format::Sled => None,
+            _ => Some(read_dash(&mut data)?),
+        };
+        let motorsport_extras = match format {
+            Format::MotorsportDash => Some(MotorsportExtras {
+                tire_wear: read_wheels(&mut data, |data| data.read_f32::<LittleEndian>())?,
+                track_ordinal: data.read_i32::<LittleEndian>()?,
+            }),
+            _ => None,
+        };
+        Ok(Self {
+            format,
+            sled,
+            dash,
+            motorsport_extras,
+        })
+    }
+
+    /// Whether a race is on. In menus and while paused, the other values are all 0.
+    pub fn is_race_on(&self) -> bool {
+        self.sled.is_race_on != 0
+    }
+
+    fn race_dash(&self) -> Option<&Dash> {
+        self.dash.as_ref().filter(|_| self.is_race_on())
+    }
+
+    /// Class of the car, which the Horizon games divide differently from Motorsport.
+    pub fn car_class(&self) -> Option<CarClass> {
+        use CarClass::*;
+        let classes: &[CarClass] = match self.format {
+            Format::HorizonDash => &[D, C, B, A, S1, S2, X],
+            _ => &[D, C, B, A, S, R, P, X],
+        };
+        usize::try_from(self.sled.car_class)
+            .ok()
+            .and_then(|index| classes.get(index).copied())
+    }
+}
+
+/// Performance class of a car, from the slowest to the fastest.
+///
+/// The [`Moment`] has no counterpart to classes, so they're only available from
+/// [`SimState::car_class`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum CarClass {
+    D,
+    C,
+    B,
+    A,
+    /// Only in Forza Motorsport
+    S,
+    /// Only in Forza Motorsport
+    R,
+    /// Only in Forza Motorsport
+    P,
+    /// Only in Forza Horizon
+    S1,
+    /// Only in Forza Horizon
+    S2,
+    X,
+}
+
+/// Wheel arrays are in front left, front right, rear left, rear right order.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Sled {
+    /// 1 while racing, 0 in menus and while the race is stopped
+    pub is_race_on: i32,
+    /// Timestamp in milliseconds, which can overflow to 0
+    pub timestamp_ms: u32,
+    pub engine_max_rpm: f32,
+    pub engine_idle_rpm: f32,
+    pub current_engine_rpm: f32,
+    /// Acceleration in car local space in meters per second squared, X right, Y up, Z forward
+    pub acceleration: [f32; 3],
+    /// Velocity in car local space in meters per second, X right, Y up, Z forward
+    pub velocity: [f32; 3],
+    /// Angular velocity in car local space in radians per second, X pitch, Y yaw, Z roll
+    pub angular_velocity: [f32; 3],
+    pub yaw: f32,
+    pub pitch: f32,
+    pub roll: f32,
+    /// Suspension travel, 0 = max stretch, 1 = max compression
+    pub normalized_suspension_travel: [f32; 4],
+    /// Tire slip ratio, 0 = 100% grip, over 1 = loss of grip
+    pub tire_slip_ratio: [f32; 4],
+    /// Wheel rotation speed in radians per second
+    pub wheel_rotation_speed: [f32; 4],
+    /// 1 when the wheel is on a rumble strip
+    pub wheel_on_rumble_strip: [i32; 4],
+    /// Depth of the puddle the wheel is in, from 0 to 1
+    pub wheel_in_puddle_depth: [f32; 4],
+    /// Force feedback rumble caused by the surface
+    pub surface_rumble: [f32; 4],
+    /// Tire slip angle, 0 = 100% grip, over 1 = loss of grip
+    pub tire_slip_angle: [f32; 4],
+    /// Combined tire slip, 0 = 100% grip, over 1 = loss of grip
+    pub tire_combined_slip: [f32; 4],
+    /// Suspension travel in meters
+    pub suspension_travel_meters: [f32; 4],
+    /// Unique ID of the car model
+    pub car_ordinal: i32,
+    /// Class of the car, from 0 (D, the slowest cars) up to X, the fastest cars.
+    /// See [`SimState::car_class`] for the classes of each game.
+    pub car_class: i32,
+    /// Performance index of the car, from 100 to 999
+    pub car_performance_index: i32,
+    /// 0 = FWD, 1 = RWD, 2 = AWD
+    pub drivetrain_type: i32,
+    pub num_cylinders: i32,
+}
+
+/// Wheel arrays are in front left, front right, rear left, rear right order.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Dash {
+    /// Position in world space in meters
+    pub position: [f32; 3],
+    /// Speed in meters per second
+    pub speed: f32,
+    /// Power in watts
+    pub power: f32,
+    /// Torque in newton meters
+    pub torque: f32,
+    /// Tire temperatures in degrees Fahrenheit
+    pub tire_temp: [f32; 4],
+    pub boost: f32,
+    /// Fuel left in 0-1 range
+    pub fuel: f32,
+    /// Distance travelled in meters
+    pub distance_traveled: f32,
+    /// Best lap time in seconds
+    pub best_lap: f32,
+    /// Last lap time in seconds
+    pub last_lap: f32,
+    /// Current lap time in seconds
+    pub current_lap: f32,
+    /// Current race time in seconds
+    pub current_race_time: f32,
+    pub lap_number: u16,
+    pub race_position: u8,
+    /// Throttle in 0-255 range
+    pub accel: u8,
+    /// Brake in 0-255 range
+    pub brake: u8,
+    /// Clutch in 0-255 range
+    pub clutch: u8,
+    /// Handbrake in 0-255 range
+    pub hand_brake: u8,
+    /// 0 = reverse, 1 and up forward gears
+    pub gear: u8,
+    /// Steering in -127 (full lock left) to 127 (full lock right) range
+    pub steer: i8,
+    pub normalized_driving_line: i8,
+    pub normalized_ai_brake_difference: i8,
+}
+
+/// Wheel arrays are in front left, front right, rear left, rear right order.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct MotorsportExtras {
+    /// Tire wear in 0-1 range
+    pub tire_wear: [f32; 4],
+    /// Unique ID of the track
+    pub track_ordinal: i32,
+}
+
+// Values are zeroed while the race isn't on, so they're reported as unknown instead of as a car
+// standing still in reverse.
+impl Moment for SimState {
+    fn vehicle_gear(&self) -> Option<i8> {
+        let gear = self.race_dash()?.gear;
+        if gear == 0 {
+            return Some(-1);
+        }
+        Some(gear as i8)
+    }
+
+    fn vehicle_velocity(&self) -> Option<Velocity> {
+        if !self.is_race_on() {
+            return None;
+        }
+        let speed = match &self.dash {
+            Some(dash) => dash.speed,
+            None => self.sled.velocity.iter().map(|v| v * v).sum::<f32>().sqrt(),
+        };
+        Some(Velocity::new::<meter_per_second>(speed as f64))
+    }
+
+    fn vehicle_engine_rotation_speed(&self) -> Option<AngularVelocity> {
+        if !self.is_race_on() {
+            return None;
+        }
+        Some(AngularVelocity::new::<revolution_per_minute>(
+            self.sled.current_engine_rpm as f64,
+        ))
+    }
+
+    fn vehicle_max_engine_rotation_speed(&self) -> Option<AngularVelocity> {
+        if !self.is_race_on() {
+            return None;
+        }
+        Some(AngularVelocity::new::<revolution_per_minute>(
+            self.sled.engine_max_rpm as f64,
+        ))
+    }
+
+    fn vehicle_model_id(&self) -> Option<Cow<str>> {
+        if !self.is_race_on() {
+            return None;
+        }
+        Some(self.sled.car_ordinal.to_string().into())
+    }
+
+    fn vehicle_unique_id(&self) -> Option<Cow<str>> {
+        // The car ordinal is unique across brands, and no brand is sent.
+        self.vehicle_model_id()
+    }
+
+    fn pedals(&self) -> Option<Pedals> {
+        let dash = self.race_dash()?;
+        Some(Pedals {
+            throttle: dash.accel as f64 / 255.0,
+            brake: dash.brake as f64 / 255.0,
+            clutch: dash.clutch as f64 / 255.0,
+        })
+    }
+}
+
+fn read_wheels<T: Default + Copy, E>(
+    data: &mut &[u8],
+    read: impl Fn(&mut &[u8]) -> Result<T, E>,
+) -> Result<[T; 4], E> {
+    let mut values = [T::default(); 4];
+    for value in &mut values {
+        *value = read(data)?;
+    }
+    Ok(values)
+}
+
+fn read_vector(data: &mut &[u8]) -> std::io::Result<[f32; 3]> {
+    let mut values = [0.0; 3];
+    data.read_f32_into::<LittleEndian>(&mut values)?;
+    Ok(values)
+}
+
+fn read_f32_wheels(data: &mut &[u8]) -> std::io::Result<[f32; 4]> {
+    read_wheels(data, |data| data.read_f32::<LittleEndian>())
+}
+
+fn read_sled(data: &mut &[u8]) -> Result<Sled> {
+    Ok(Sled {
+        is_race_on: data.read_i32::<LittleEndian>()?,
+        timestamp_ms: data.read_u32::<LittleEndian>()?,
+        engine_max_rpm: data.read_f32::<LittleEndian>()?,
+        engine_idle_rpm: data.read_f32::<LittleEndian>()?,
+        current_engine_rpm: data.read_f32::<LittleEndian>()?,
+        acceleration: read_vector(data)?,
+        velocity: read_vector(data)?,
+        angular_velocity: read_vector(data)?,
+        yaw: data.read_f32::<LittleEndian>()?,
+        pitch: data.read_f32::<LittleEndian>()?,
+        roll: data.read_f32::<LittleEndian>()?,
+        normalized_suspension_travel: read_f32_wheels(data)?,
+        tire_slip_ratio: read_f32_wheels(data)?,
+        wheel_rotation_speed: read_f32_wheels(data)?,
+        wheel_on_rumble_strip: read_wheels(data, |data| data.read_i32::<LittleEndian>())?,
+        wheel_in_puddle_depth: read_f32_wheels(data)?,
+        surface_rumble: read_f32_wheels(data)?,
+        tire_slip_angle: read_f32_wheels(data)?,
+        tire_combined_slip: read_f32_wheels(data)?,
+        suspension_travel_meters: read_f32_wheels(data)?,
+        car_ordinal: data.read_i32::<LittleEndian>()?,
+        car_class: data.read_i32::<LittleEndian>()?,
+        car_performance_index: data.read_i32::<LittleEndian>()?,
+        drivetrain_type: data.read_i32::<LittleEndian>()?,
+        num_cylinders: data.read_i32::<LittleEndian>()?,
+    })
+}
+
+fn read_dash(data: &mut &[u8]) -> Result<Dash> {
+    Ok(Dash {
+        position: read_vector(data)?,
+        speed: data.read_f32::<LittleEndian>()?,
+        power: data.read_f32::<LittleEndian>()?,
+        torque: data.read_f32::<LittleEndian>()?,
+        tire_temp: read_f32_wheels(data)?,
+        boost: data.read_f32::<LittleEndian>()?,
+        fuel: data.read_f32::<LittleEndian>()?,
+        distance_traveled: data.read_f32::<LittleEndian>()?,
+        best_lap: data.read_f32::<LittleEndian>()?,
+        last_lap: data.read_f32::<LittleEndian>()?,
+        current_lap: data.read_f32::<LittleEndian>()?,
+        current_race_time: data.read_f32::<LittleEndian>()?,
+        lap_number: data.read_u16::<LittleEndian>()?,
+        race_position: data.read_u8()?,
+        accel: data.read_u8()?,
+        brake: data.read_u8()?,
+        clutch: data.read_u8()?,
+        hand_brake: data.read_u8()?,
+        gear: data.read_u8()?,
+        steer: data.read_i8()?,
+        normalized_driving_line: data.read_i8()?,
+        normalized_ai_brake_difference: data.read_i8()?,
+    })
+}
@@ -1,11 +1,16 @@
-use crate::{Moment, Pedals, RacingFlags, Simetry};
+mod server;
+mod stream;
+
+use crate::{Moment, MomentSnapshot, Pedals, RacingFlags, Session, Simetry};
 use anyhow::Result;
 use hyper::body::Buf;
 use hyper::client::HttpConnector;
 use hyper::{Client, Uri};
 use serde::{Deserialize, Serialize};
+pub use server::GenericHttpServer;
 use std::borrow::Cow;
 use std::time::Duration;
+pub use stream::{GenericStreamClient, StreamConfig, STREAM_PATH};
 use tokio::time::timeout;
 use uom::si::f64::{AngularVelocity, Velocity};
 
@@ -52,46 +57,18 @@ impl GenericHttpClient {
 pub struct SimState {
     #[serde(default)]
     pub name: String,
-    #[serde(default)]
-    pub vehicle_left: Option<bool>,
-    #[serde(default)]
-    pub vehicle_right: Option<bool>,
-    #[serde(default)]
-    pub gear: Option<i8>,
-    #[serde(default)]
-    pub speed: Option<Velocity>,
-    #[serde(default)]
-    pub engine_rotation_speed: Option<AngularVelocity>,
-    #[serde(default)]
-    pub max_engine_rotation_speed: Option<AngularVelocity>,
-    #[serde(default)]
-    pub pit_limiter_engaged: Option<bool>,
-    #[serde(default)]
-    pub in_pit_lane: Option<bool>,
-    #[serde(default)]
-    pub shift_point: Option<AngularVelocity>,
-    #[serde(default)]
-    pub flags: Option<RacingFlags>,
-    #[serde(default)]
-    pub vehicle_brand_id: Option<String>,
-    #[serde(default)]
-    pub vehicle_model_id: Option<String>,
-    #[serde(default)]
-    pub vehicle_unique_id: Option<String>,
-    #[serde(default)]
-    pub left_turn_indicator_on: Option<bool>,
-    #[serde(default)]
-    pub right_turn_indicator_on: Option<bool>,
-    #[serde(default)]
-    pub hazard_indicator_on: Option<bool>,
-    #[serde(default)]
-    pub ignition_on: Option<bool>,
-    #[serde(default)]
-    pub starter_on: Option<bool>,
-    #[serde(default)]
-    pub pedals: Option<Pedals>,
-    #[serde(default)]
-    pub pedals_raw: Option<Pedals>,
+    #[serde(flatten)]
+    pub moment: MomentSnapshot,
+}
+
+impl SimState {
+    /// Capture the moment of the named sim into a state that can be served to clients.
+    pub fn capture<M: Moment + ?Sized>(name: impl Into<String>, moment: &M) -> Self {
+        Self {
+            name: name.into(),
+            moment: MomentSnapshot::capture(moment),
+        }
+    }
 }
 
 #[async_trait::async_trait]
@@ -114,82 +91,86 @@ impl Simetry for GenericHttpClient {
 
 impl Moment for SimState {
     fn vehicle_gear(&self) -> Option<i8> {
-        self.gear
+        self.moment.vehicle_gear()
     }
 
     fn vehicle_velocity(&self) -> Option<Velocity> {
-        self.speed
+        self.moment.vehicle_velocity()
     }
 
     fn vehicle_engine_rotation_speed(&self) -> Option<AngularVelocity> {
-        self.engine_rotation_speed
+        self.moment.vehicle_engine_rotation_speed()
     }
 
     fn vehicle_max_engine_rotation_speed(&self) -> Option<AngularVelocity> {
-        self.max_engine_rotation_speed
+        self.moment.vehicle_max_engine_rotation_speed()
     }
 
     fn is_pit_limiter_engaged(&self) -> Option<bool> {
-        self.pit_limiter_engaged
+        self.moment.is_pit_limiter_engaged()
     }
 
     fn is_vehicle_in_pit_lane(&self) -> Option<bool> {
-        self.in_pit_lane
+        self.moment.is_vehicle_in_pit_lane()
     }
 
     fn is_vehicle_left(&self) -> Option<bool> {
-        self.vehicle_left
+        self.moment.is_vehicle_left()
     }
 
     fn is_vehicle_right(&self) -> Option<bool> {
-        self.vehicle_right
+        self.moment.is_vehicle_right()
     }
 
     fn shift_point(&self) -> Option<AngularVelocity> {
-        self.shift_point
+        self.moment.shift_point()
     }
 
     fn flags(&self) -> Option<RacingFlags> {
-        self.flags.clone()
+        self.moment.flags()
     }
 
     fn vehicle_brand_id(&self) -> Option<Cow<str>> {
-        Some(self.vehicle_brand_id.as_ref()?.into())
+        self.moment.vehicle_brand_id()
     }
 
     fn vehicle_model_id(&self) -> Option<Cow<str>> {
-        Some(self.vehicle_model_id.as_ref()?.into())
+        self.moment.vehicle_model_id()
     }
 
     fn vehicle_unique_id(&self) -> Option<Cow<str>> {
-        Some(self.vehicle_unique_id.as_ref()?.into())
+        self.moment.vehicle_unique_id()
     }
 
     fn is_left_turn_indicator_on(&self) -> Option<bool> {
-        self.left_turn_indicator_on
+        self.moment.is_left_turn_indicator_on()
     }
 
     fn is_right_turn_indicator_on(&self) -> Option<bool> {
-        self.right_turn_indicator_on
+        self.moment.is_right_turn_indicator_on()
     }
 
     fn is_hazard_indicator_on(&self) -> Option<bool> {
-        self.hazard_indicator_on
+        self.moment.is_hazard_indicator_on()
     }
 
     fn is_ignition_on(&self) -> Option<bool> {
-        self.ignition_on
+        self.moment.is_ignition_on()
     }
 
     fn is_starter_on(&self) -> Option<bool> {
-        self.starter_on
+        self.moment.is_starter_on()
     }
 
     fn pedals(&self) -> Option<Pedals> {
-        self.pedals.clone()
+        self.moment.pedals()
     }
 
     fn pedals_raw(&self) -> Option<Pedals> {
-        self.pedals_raw.clone()
+        self.moment.pedals_raw()
+    }
+
+    fn session(&self) -> Option<Session> {
+        self.moment.session()
     }
 }
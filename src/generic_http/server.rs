@@ -0,0 +1,106 @@
+use crate::generic_http::stream::{self, STREAM_PATH};
+use crate::generic_http::SimState;
+use crate::{MomentSnapshot, Simetry};
+use anyhow::Result;
+use hyper::header::CONTENT_TYPE;
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{Body, Request, Response, Server, StatusCode};
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use tokio::net::TcpListener;
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+
+/// Serves the latest moment of any sim in the format read by [`GenericHttpClient`].
+///
+/// Moments are also streamed as server-sent events on [`STREAM_PATH`],
+/// for the [`GenericStreamClient`].
+/// While no sim is running, requests are answered with `503 Service Unavailable`.
+///
+/// [`GenericHttpClient`]: crate::generic_http::GenericHttpClient
+/// [`GenericStreamClient`]: crate::generic_http::GenericStreamClient
+#[derive(Debug)]
+pub struct GenericHttpServer {
+    state: watch::Sender<Option<Arc<SimState>>>,
+    local_addr: SocketAddr,
+    task: JoinHandle<()>,
+}
+
+impl GenericHttpServer {
+    /// Start listening for requests on the address, like [`DEFAULT_ADDRESS`].
+    ///
+    /// [`DEFAULT_ADDRESS`]: crate::generic_http::DEFAULT_ADDRESS
+    pub async fn bind(address: &str) -> Result<Self> {
+        let listener = TcpListener::bind(address).await?;
+        let local_addr = listener.local_addr()?;
+        let (state, receiver) = watch::channel(None);
+        let make_service = make_service_fn(move |_| {
+            let receiver = receiver.clone();
+            async move {
+                Ok::<_, Infallible>(service_fn(move |request: Request<Body>| {
+                    let response = match request.uri().path() {
+                        STREAM_PATH => stream::respond(receiver.clone(), request.uri().query()),
+                        _ => respond(receiver.borrow().as_deref()),
+                    };
+                    async move { Ok::<_, Infallible>(response) }
+                }))
+            }
+        });
+        let server = Server::from_tcp(listener.into_std()?)?.serve(make_service);
+        let task = tokio::spawn(async move {
+            if let Err(err) = server.await {
+                log::error!("Generic HTTP server stopped: {err}");
+            }
+        });
+        Ok(Self {
+            state,
+            local_addr,
+            task,
+        })
+    }
+
+    /// Address the server is listening on.
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+
+    /// Serve moments from the sim until it disconnects.
+    pub async fn run(&self, mut sim: Box<dyn Simetry + Send + Sync>) {
+        let name = sim.name().to_string();
+        self.state.send_replace(Some(Arc::new(SimState {
+            name: name.clone(),
+            moment: MomentSnapshot::default(),
+        })));
+        while let Some(moment) = sim.next_moment().await {
+            let sim_state = SimState::capture(name.clone(), moment.as_ref());
+            self.state.send_replace(Some(Arc::new(sim_state)));
+        }
+        self.state.send_replace(None);
+    }
+}
+
+impl Drop for GenericHttpServer {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+fn respond(sim_state: Option<&SimState>) -> Response<Body> {
+    let Some(sim_state) = sim_state else {
+        return status_response(StatusCode::SERVICE_UNAVAILABLE);
+    };
+    match serde_json::to_vec(sim_state) {
+        Ok(body) => Response::builder()
+            .header(CONTENT_TYPE, "application/json")
+            .body(Body::from(body))
+            .unwrap_or_else(|_| status_response(StatusCode::INTERNAL_SERVER_ERROR)),
+        Err(_) => status_response(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+pub(super) fn status_response(status: StatusCode) -> Response<Body> {
+    let mut response = Response::new(Body::empty());
+    *response.status_mut() = status;
+    response
+}
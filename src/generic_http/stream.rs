@@ -0,0 +1,224 @@
+use crate::generic_http::SimState;
+use crate::{Moment, Simetry};
+use anyhow::{bail, Context, Result};
+use hyper::body::{Bytes, HttpBody};
+use hyper::client::HttpConnector;
+use hyper::header::{CACHE_CONTROL, CONTENT_TYPE};
+use hyper::{Body, Client, Response, StatusCode, Uri};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::watch;
+use tokio::time::Instant;
+
+/// Path on the [`GenericHttpServer`] which streams moments as server-sent events.
+///
+/// [`GenericHttpServer`]: crate::generic_http::GenericHttpServer
+pub const STREAM_PATH: &str = "/stream";
+
+/// Options for the stream requested by a [`GenericStreamClient`].
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct StreamConfig {
+    max_rate: Option<u32>,
+    fields: Option<Vec<String>>,
+}
+
+impl StreamConfig {
+    /// Limit the number of moments sent per second.
+    ///
+    /// When moments arrive faster, only the latest one is sent.
+    pub fn max_rate(mut self, moments_per_second: u32) -> Self {
+        self.max_rate = Some(moments_per_second).filter(|v| *v > 0);
+        self
+    }
+
+    /// Only send the listed fields of [`SimState`], with the rest being `None` in received moments.
+    ///
+    /// The sim name is always sent.
+    pub fn fields<I: IntoIterator<Item = S>, S: Into<String>>(mut self, fields: I) -> Self {
+        self.fields = Some(fields.into_iter().map(Into::into).collect());
+        self
+    }
+
+    fn to_query(&self) -> String {
+        let mut params = vec![];
+        if let Some(max_rate) = self.max_rate {
+            params.push(format!("max_rate={max_rate}"));
+        }
+        if let Some(fields) = &self.fields {
+            params.push(format!("fields={}", fields.join(",")));
+        }
+        params.join("&")
+    }
+
+    fn from_query(query: Option<&str>) -> Self {
+        let mut config = Self::default();
+        for (key, value) in query
+            .unwrap_or_default()
+            .split('&')
+            .filter_map(|v| v.split_once('='))
+        {
+            match key {
+                "max_rate" => {
+                    if let Ok(max_rate) = value.parse() {
+                        config = config.max_rate(max_rate);
+                    }
+                }
+                "fields" => config = config.fields(value.split(',').filter(|v| !v.is_empty())),
+                _ => {}
+            }
+        }
+        config
+    }
+
+    fn min_interval(&self) -> Option<Duration> {
+        Some(Duration::from_secs(1) / self.max_rate?)
+    }
+}
+
+/// Client for the moments streamed by a [`GenericHttpServer`].
+///
+/// Works as a replacement for [`GenericHttpClient`], which needs a request per moment.
+///
+/// [`GenericHttpServer`]: crate::generic_http::GenericHttpServer
+/// [`GenericHttpClient`]: crate::generic_http::GenericHttpClient
+#[derive(Debug)]
+pub struct GenericStreamClient {
+    name: String,
+    body: Body,
+    buffer: Vec<u8>,
+    first_moment: Option<SimState>,
+}
+
+impl GenericStreamClient {
+    pub async fn connect(uri: &str, config: StreamConfig, retry_delay: Duration) -> Self {
+        loop {
+            if let Ok(client) = Self::try_connect(uri, config.clone()).await {
+                return client;
+            }
+            tokio::time::sleep(retry_delay).await;
+        }
+    }
+
+    /// Connect to the server at the URI, like [`DEFAULT_URI`].
+    ///
+    /// [`DEFAULT_URI`]: crate::generic_http::DEFAULT_URI
+    pub async fn try_connect(uri: &str, config: StreamConfig) -> Result<Self> {
+        let uri: Uri = uri.parse()?;
+        let mut builder = Uri::builder();
+        if let Some(scheme) = uri.scheme() {
+            builder = builder.scheme(scheme.clone());
+        }
+        if let Some(authority) = uri.authority() {
+            builder = builder.authority(authority.clone());
+        }
+        let uri = builder
+            .path_and_query(format!("{STREAM_PATH}?{}", config.to_query()))
+            .build()?;
+        let client: Client<HttpConnector> = Client::new();
+        let response = client.get(uri).await?;
+        if response.status() != StatusCode::OK {
+            bail!("Server responded with status {}", response.status());
+        }
+        let mut slf = Self {
+            name: "".to_string(),
+            body: response.into_body(),
+            buffer: vec![],
+            first_moment: None,
+        };
+        let sim_state = slf
+            .next_sim_state()
+            .await?
+            .context("Stream ended before sending a moment")?;
+        slf.name = sim_state.name.clone();
+        slf.first_moment = Some(sim_state);
+        Ok(slf)
+    }
+
+    /// Wait for the next moment sent by the server, or `None` if the stream has ended.
+    pub async fn next_sim_state(&mut self) -> Result<Option<SimState>> {
+        if let Some(sim_state) = self.first_moment.take() {
+            return Ok(Some(sim_state));
+        }
+        loop {
+            if let Some(data) = take_event_data(&mut self.buffer) {
+                return Ok(Some(serde_json::from_str(&data)?));
+            }
+            match self.body.data().await {
+                Some(chunk) => self.buffer.extend_from_slice(&chunk?),
+                None => return Ok(None),
+            }
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl Simetry for GenericStreamClient {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn next_moment(&mut self) -> Option<Box<dyn Moment + Send + Sync + 'static>> {
+        let data = self.next_sim_state().await.ok()??;
+        if data.name != self.name {
+            return None;
+        }
+        Some(Box::new(data))
+    }
+}
+
+/// Remove the first complete event from the buffer, and return its data.
+fn take_event_data(buffer: &mut Vec<u8>) -> Option<String> {
+    let end = buffer.windows(2).position(|v| v == b"\n\n")?;
+    let event = buffer.drain(..end + 2).collect::<Vec<_>>();
+    let data = String::from_utf8_lossy(&event)
+        .lines()
+        .filter_map(|line| line.strip_prefix("data:"))
+        .map(|data| data.strip_prefix(' ').unwrap_or(data))
+        .collect::<Vec<_>>()
+        .join("\n");
+    Some(data)
+}
+
+/// Respond to a stream request, with the config taken from the query of the request.
+pub(super) fn respond(
+    mut receiver: watch::Receiver<Option<Arc<SimState>>>,
+    query: Option<&str>,
+) -> Response<Body> {
+    if receiver.borrow_and_update().is_none() {
+        return super::server::status_response(StatusCode::SERVICE_UNAVAILABLE);
+    }
+    let config = StreamConfig::from_query(query);
+    let (mut sender, body) = Body::channel();
+    tokio::spawn(async move {
+        let mut sim_state = receiver.borrow().clone();
+        while let Some(current) = sim_state {
+            let sent_at = Instant::now();
+            let Ok(event) = encode_event(&current, config.fields.as_deref()) else {
+                break;
+            };
+            if sender.send_data(event).await.is_err() {
+                break;
+            }
+            if let Some(min_interval) = config.min_interval() {
+                tokio::time::sleep_until(sent_at + min_interval).await;
+            }
+            if receiver.changed().await.is_err() {
+                break;
+            }
+            sim_state = receiver.borrow_and_update().clone();
+        }
+    });
+    Response::builder()
+        .header(CONTENT_TYPE, "text/event-stream")
+        .header(CACHE_CONTROL, "no-cache")
+        .body(body)
+        .unwrap_or_else(|_| super::server::status_response(StatusCode::INTERNAL_SERVER_ERROR))
+}
+
+fn encode_event(sim_state: &SimState, fields: Option<&[String]>) -> Result<Bytes> {
+    let mut value = serde_json::to_value(sim_state)?;
+    if let (Some(fields), Some(object)) = (fields, value.as_object_mut()) {
+        object.retain(|key, _| key == "name" || fields.iter().any(|field| field == key));
+    }
+    Ok(format!("data: {value}\n\n").into())
+}
@@ -0,0 +1,403 @@
+//! Support for the UDP telemetry of Gran Turismo 7.
+//!
+//! The console only sends telemetry to the address that keeps sending it heartbeats,
+//! so the [`Client`] needs the address of the PlayStation running the game.
+//! Packets are encrypted with Salsa20, and get decrypted with [`decrypt`].
+
+use crate::{Moment, Pedals, Session, SessionPhase, Simetry};
+use anyhow::{bail, Result};
+use byteorder::{LittleEndian, ReadBytesExt};
+use salsa20::cipher::{KeyIvInit, StreamCipher};
+use salsa20::Salsa20;
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::time::Duration;
+use tokio::net::UdpSocket;
+use uom::si::angular_velocity::revolution_per_minute;
+use uom::si::f64::{AngularVelocity, Velocity};
+use uom::si::velocity::meter_per_second;
+
+#[derive(Debug)]
+pub struct Client {
+    socket: UdpSocket,
+    console_uri: String,
+    packets_since_heartbeat: u32,
+}
+
+impl Client {
+    /// Local address the console sends the telemetry to.
+    pub const DEFAULT_URI: &'static str = "0.0.0.0:33740";
+    /// Port of the console that receives heartbeats.
+    pub const CONSOLE_PORT: u16 = 33739;
+
+    /// Connect to the console at `console_uri`, like `"192.168.1.20:33739"`,
+    /// receiving telemetry on `uri`, like [`Client::DEFAULT_URI`].
+    pub async fn connect(uri: &str, console_uri: &str, retry_delay: Duration) -> Self {
+        loop {
+            if let Ok(client) = Self::try_connect(uri, console_uri).await {
+                return client;
+            }
+            tokio::time::sleep(retry_delay).await;
+        }
+    }
+
+    pub async fn try_connect(uri: &str, console_uri: &str) -> Result<Self> {
+        let mut slf = Self {
+            socket: UdpSocket::bind(uri).await?,
+            console_uri: console_uri.to_string(),
+            packets_since_heartbeat: 0,
+        };
+        slf.send_heartbeat().await?;
+        slf.next_sim_state().await?;
+        Ok(slf)
+    }
+
+    /// Wait for the next valid packet.
+    ///
+    /// Heartbeats get sent periodically, and whenever the console stops sending packets.
+    /// Packets that fail to decrypt are logged and skipped.
+    pub async fn next_sim_state(&mut self) -> Result<SimState> {
+        let mut buffer = [0u8; PACKET_BUFFER_SIZE];
+        loop {
+            if self.packets_since_heartbeat >= HEARTBEAT_INTERVAL_PACKETS {
+                self.send_heartbeat().await?;
+            }
+            let received =
+                tokio::time::timeout(HEARTBEAT_TIMEOUT, self.socket.recv_from(&mut buffer)).await;
+            let Ok(received) = received else {
+                self.send_heartbeat().await?;
+                continue;
+            };
+            let (size, _origin) = received?;
+            self.packets_since_heartbeat += 1;
+            match decrypt(&buffer[..size]).and_then(|data| SimState::parse(&data)) {
+                Ok(sim_state) => return Ok(sim_state),
+                Err(err) => log::warn!("Skipping Gran Turismo 7 packet: {err}"),
+            }
+        }
+    }
+
+    async fn send_heartbeat(&mut self) -> Result<()> {
+        self.socket.send_to(HEARTBEAT, &self.console_uri).await?;
+        self.packets_since_heartbeat = 0;
+        Ok(())
+    }
+}
+
+const PACKET_BUFFER_SIZE: usize = 1024;
+const HEARTBEAT: &[u8] = b"A";
+const HEARTBEAT_INTERVAL_PACKETS: u32 = 100;
+const HEARTBEAT_TIMEOUT: Duration = Duration::from_secs(1);
+
+#[async_trait::async_trait]
+impl Simetry for Client {
+    fn name(&self) -> &str {
+        "GranTurismo7"
+    }
+
+    async fn next_moment(&mut self) -> Option<Box<dyn Moment + Send + Sync + 'static>> {
+        Some(Box::new(self.next_sim_state().await.ok()?))
+    }
+}
+
+/// Size of the packets sent by the game.
+pub const PACKET_SIZE: usize = 0x128;
+/// Magic number at the start of every decrypted packet, sent as "0S7G".
+pub const MAGIC: u32 = 0x4737_5330;
+
+const KEY: &[u8; 32] = b"Simulator Interface Packet GT7 v";
+const IV_OFFSET: usize = 0x40;
+const IV_MASK: u32 = 0xDEAD_BEAF;
+
+/// Decrypt a packet sent by the game.
+///
+/// The nonce is derived from the 4 bytes at offset `0x40`, which are sent unencrypted.
+/// Fails if the decrypted packet doesn't start with [`MAGIC`].
+pub fn decrypt(packet: &[u8]) -> Result<Vec<u8>> {
+    if packet.len() != PACKET_SIZE {
+        bail!(
+            "Packet should have {PACKET_SIZE} bytes, got {}",
+            packet.len()
+        );
+    }
+    let iv1 = (&packet[IV_OFFSET..]).read_u32::<LittleEndian>()?;
+    let iv2 = iv1 ^ IV_MASK;
+    let mut nonce = [0u8; 8];
+    nonce[..4].copy_from_slice(&iv2.to_le_bytes());
+    nonce[4..].copy_from_slice(&iv1.to_le_bytes());
+    let mut data = packet.to_vec();
+    Salsa20::new(KEY.into(), &nonce.into()).apply_keystream(&mut data);
+    let magic = (&data[..]).read_u32::<LittleEndian>()?;
+    if magic != MAGIC {
+        bail!("Decrypted packet has invalid magic number {magic:#010x}");
+    }
+    Ok(data)
+}
+
+/// Bits of [`SimState::flags`].
+pub mod flags {
+    pub const CAR_ON_TRACK: u16 = 0x0001;
+    pub const PAUSED: u16 = 0x0002;
+    pub const LOADING_OR_PROCESSING: u16 = 0x0004;
+    pub const IN_GEAR: u16 = 0x0008;
+    pub const HAS_TURBO: u16 = 0x0010;
+    pub const REV_LIMITER_BLINK_ALERT_ACTIVE: u16 = 0x0020;
+    pub const HAND_BRAKE_ACTIVE: u16 = 0x0040;
+    pub const LIGHTS_ACTIVE: u16 = 0x0080;
+    pub const HIGH_BEAM_ACTIVE: u16 = 0x0100;
+    pub const LOW_BEAM_ACTIVE: u16 = 0x0200;
+    pub const ASM_ACTIVE: u16 = 0x0400;
+    pub const TCS_ACTIVE: u16 = 0x0800;
+}
+
+/// Wheel arrays are in front left, front right, rear left, rear right order.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SimState {
+    /// Position in world space in meters
+    pub position: [f32; 3],
+    /// Velocity in world space in meters per second
+    pub velocity: [f32; 3],
+    /// Rotation as pitch, yaw and roll, in -1 to 1 range
+    pub rotation: [f32; 3],
+    /// Orientation relative to north, 1 is north and 0 is south
+    pub relative_orientation_to_north: f32,
+    /// Angular velocity in radians per second
+    pub angular_velocity: [f32; 3],
+    pub body_height: f32,
+    pub engine_rpm: f32,
+    /// Fuel level, or charge level of electric cars
+    pub gas_level: f32,
+    pub gas_capacity: f32,
+    /// Speed in meters per second
+    pub speed: f32,
+    pub turbo_boost: f32,
+    pub oil_pressure: f32,
+    /// Water temperature in degrees Celsius
+    pub water_temperature: f32,
+    /// Oil temperature in degrees Celsius
+    pub oil_temperature: f32,
+    /// Tire surface temperatures in degrees Celsius
+    pub tire_temperature: [f32; 4],
+    /// Increasing ID of the packet
+    pub packet_id: i32,
+    pub lap_count: i16,
+    /// Total laps of the race, 0 for time trials and free runs
+    pub laps_in_race: i16,
+    /// Best lap time in milliseconds, -1 if not set
+    pub best_lap_time_ms: i32,
+    /// Last lap time in milliseconds, -1 if not set
+    pub last_lap_time_ms: i32,
+    /// Time of day in the race in milliseconds
+    pub time_of_day_ms: i32,
+    /// Starting position before the race starts, -1 once it has started
+    pub pre_race_start_position: i16,
+    /// Number of cars before the race starts, -1 once it has started
+    pub pre_race_num_cars: i16,
+    /// RPM at which the shift light starts
+    pub min_alert_rpm: i16,
+    /// RPM at which the shift light flashes
+    pub max_alert_rpm: i16,
+    /// Top speed the car can reach with the current gear ratios, in kilometers per hour
+    pub calculated_max_speed: i16,
+    /// See [`flags`]
+    pub flags: u16,
+    /// 0 = reverse, 1 and up forward gears, 15 = neutral
+    pub current_gear: u8,
+    /// Gear suggested for the next corner, 15 if none
+    pub suggested_gear: u8,
+    /// Throttle in 0-255 range
+    pub throttle: u8,
+    /// Brake in 0-255 range
+    pub brake: u8,
+    pub road_plane: [f32; 3],
+    pub road_plane_distance: f32,
+    /// Wheel rotation speed in radians per second
+    pub wheel_rps: [f32; 4],
+    /// Tire radius in meters
+    pub tire_radius: [f32; 4],
+    pub suspension_height: [f32; 4],
+    /// Clutch pedal in 0-1 range
+    pub clutch_pedal: f32,
+    /// Clutch engagement in 0-1 range
+    pub clutch_engagement: f32,
+    pub rpm_from_clutch_to_gearbox: f32,
+    pub transmission_top_speed: f32,
+    /// Gear ratios of the first 8 gears, 0 for gears the car doesn't have
+    pub gear_ratios: [f32; 8],
+    /// Unique ID of the car model
+    pub car_code: i32,
+}
+
+impl SimState {
+    /// Parse a decrypted packet.
+    pub fn parse(data: &[u8]) -> Result<Self> {
+        if data.len() != PACKET_SIZE {
+            bail!("Packet should have {PACKET_SIZE} bytes, got {}", data.len());
+        }
+        let data = &mut &data[..];
+        let magic = data.read_u32::<LittleEndian>()?;
+        if magic != MAGIC {
+            bail!("Packet has invalid magic number {magic:#010x}");
+        }
+        let position = read_f32s(data)?;
+        let velocity = read_f32s(data)?;
+        let rotation = read_f32s(data)?;
+        let relative_orientation_to_north = data.read_f32::<LittleEndian>()?;
+        let angular_velocity = read_f32s(data)?;
+        let body_height = data.read_f32::<LittleEndian>()?;
+        let engine_rpm = data.read_f32::<LittleEndian>()?;
+        // Initialization vector of the encryption
+        *data = &data[4..];
+        let gas_level = data.read_f32::<LittleEndian>()?;
+        let gas_capacity = data.read_f32::<LittleEndian>()?;
+        let speed = data.read_f32::<LittleEndian>()?;
+        let turbo_boost = data.read_f32::<LittleEndian>()?;
+        let oil_pressure = data.read_f32::<LittleEndian>()?;
+        let water_temperature = data.read_f32::<LittleEndian>()?;
+        let oil_temperature = data.read_f32::<LittleEndian>()?;
+        let tire_temperature = read_f32s(data)?;
+        let packet_id = data.read_i32::<LittleEndian>()?;
+        let lap_count = data.read_i16::<LittleEndian>()?;
+        let laps_in_race = data.read_i16::<LittleEndian>()?;
+        let best_lap_time_ms = data.read_i32::<LittleEndian>()?;
+        let last_lap_time_ms = data.read_i32::<LittleEndian>()?;
+        let time_of_day_ms = data.read_i32::<LittleEndian>()?;
+        let pre_race_start_position = data.read_i16::<LittleEndian>()?;
+        let pre_race_num_cars = data.read_i16::<LittleEndian>()?;
+        let min_alert_rpm = data.read_i16::<LittleEndian>()?;
+        let max_alert_rpm = data.read_i16::<LittleEndian>()?;
+        let calculated_max_speed = data.read_i16::<LittleEndian>()?;
+        let flags = data.read_u16::<LittleEndian>()?;
+        let gears = data.read_u8()?;
+        let throttle = data.read_u8()?;
+        let brake = data.read_u8()?;
+        *data = &data[1..];
+        let road_plane = read_f32s(data)?;
+        let road_plane_distance = data.read_f32::<LittleEndian>()?;
+        let wheel_rps = read_f32s(data)?;
+        let tire_radius = read_f32s(data)?;
+        let suspension_height = read_f32s(data)?;
+        *data = &data[32..];
+        Ok(Self {
+            position,
+            velocity,
+            rotation,
+            relative_orientation_to_north,
+            angular_velocity,
+            body_height,
+            engine_rpm,
+            gas_level,
+            gas_capacity,
+            speed,
+            turbo_boost,
+            oil_pressure,
+            water_temperature,
+            oil_temperature,
+            tire_temperature,
+            packet_id,
+            lap_count,
+            laps_in_race,
+            best_lap_time_ms,
+            last_lap_time_ms,
+            time_of_day_ms,
+            pre_race_start_position,
+            pre_race_num_cars,
+            min_alert_rpm,
+            max_alert_rpm,
+            calculated_max_speed,
+            flags,
+            current_gear: gears & 0x0f,
+            suggested_gear: gears >> 4,
+            throttle,
+            brake,
+            road_plane,
+            road_plane_distance,
+            wheel_rps,
+            tire_radius,
+            suspension_height,
+            clutch_pedal: data.read_f32::<LittleEndian>()?,
+            clutch_engagement: data.read_f32::<LittleEndian>()?,
+            rpm_from_clutch_to_gearbox: data.read_f32::<LittleEndian>()?,
+            transmission_top_speed: data.read_f32::<LittleEndian>()?,
+            gear_ratios: read_f32s(data)?,
+            car_code: data.read_i32::<LittleEndian>()?,
+        })
+    }
+
+    fn has_flag(&self, flag: u16) -> bool {
+        self.flags & flag != 0
+    }
+}
+
+fn read_f32s<const N: usize>(data: &mut &[u8]) -> Result<[f32; N]> {
+    let mut values = [0.0; N];
+    data.read_f32_into::<LittleEndian>(&mut values)?;
+    Ok(values)
+}
+
+impl Moment for SimState {
+    fn vehicle_gear(&self) -> Option<i8> {
+        Some(match self.current_gear {
+            0 => -1,
+            15 => 0,
+            gear => gear as i8,
+        })
+    }
+
+    fn vehicle_velocity(&self) -> Option<Velocity> {
+        Some(Velocity::new::<meter_per_second>(self.speed as f64))
+    }
+
+    fn vehicle_engine_rotation_speed(&self) -> Option<AngularVelocity> {
+        Some(AngularVelocity::new::<revolution_per_minute>(
+            self.engine_rpm as f64,
+        ))
+    }
+
+    fn vehicle_max_engine_rotation_speed(&self) -> Option<AngularVelocity> {
+        Some(AngularVelocity::new::<revolution_per_minute>(
+            self.max_alert_rpm as f64,
+        ))
+    }
+
+    fn shift_point(&self) -> Option<AngularVelocity> {
+        Some(AngularVelocity::new::<revolution_per_minute>(
+            self.min_alert_rpm as f64,
+        ))
+    }
+
+    fn vehicle_model_id(&self) -> Option<Cow<str>> {
+        Some(self.car_code.to_string().into())
+    }
+
+    fn vehicle_unique_id(&self) -> Option<Cow<str>> {
+        // The car code is unique across brands, and no brand is sent.
+        self.vehicle_model_id()
+    }
+
+    fn pedals(&self) -> Option<Pedals> {
+        Some(Pedals {
+            throttle: self.throttle as f64 / 255.0,
+            brake: self.brake as f64 / 255.0,
+            clutch: self.clutch_pedal as f64,
+        })
+    }
+
+    fn session(&self) -> Option<Session> {
+        let phase = if self.has_flag(flags::PAUSED) {
+            SessionPhase::Stopped
+        } else if self.pre_race_start_position >= 0 {
+            SessionPhase::Waiting
+        } else {
+            SessionPhase::Green
+        };
+        let total_laps = Some(self.laps_in_race as u32).filter(|v| *v > 0);
+        Some(Session {
+            phase: Some(phase),
+            laps_remaining: total_laps
+                .map(|v| v.saturating_sub((self.lap_count.max(1) - 1) as u32)),
+            total_laps,
+            ..Session::default()
+        })
+    }
+}
@@ -1,13 +1,17 @@
 use crate::iracing::flags::{driver_black_flags, global_flags, start_flags};
 use crate::iracing::{
-    BitField, CarPositions, Header, Value, VarData, VarHeader, VarHeaders, VarType,
+    BitField, CarPositions, Header, Value, VarData, VarHeader, VarHeaders, VarType, UNLIMITED_LAPS,
+    UNLIMITED_TIME,
 };
-use crate::{Moment, Pedals, RacingFlags};
+use crate::{Moment, Pedals, RacingFlags, Session, SessionPhase, SessionType};
+use serde::ser::SerializeMap;
+use serde::{Serialize, Serializer};
 use std::borrow::Cow;
 use std::fmt::{Debug, Formatter};
 use std::sync::Arc;
 use uom::si::angular_velocity::revolution_per_minute;
-use uom::si::f64::{AngularVelocity, Velocity};
+use uom::si::f64::{AngularVelocity, Time, Velocity};
+use uom::si::time::second;
 use uom::si::velocity::meter_per_second;
 use yaml_rust::Yaml;
 
@@ -127,6 +131,74 @@ impl Moment for SimState {
             clutch: 1.0 - self.read_name::<f32>("ClutchRaw")? as f64,
         })
     }
+
+    fn session(&self) -> Option<Session> {
+        let session_num = self.read_name::<i32>("SessionNum")?;
+        let current_session = self.session_info()["SessionInfo"]["Sessions"]
+            .as_vec()
+            .and_then(|sessions| {
+                sessions
+                    .iter()
+                    .find(|session| session["SessionNum"].as_i64() == Some(session_num as i64))
+            });
+        let session_type = current_session
+            .and_then(|session| session["SessionType"].as_str())
+            .map(|session_type| {
+                let session_type = session_type.to_lowercase();
+                if session_type.contains("practice") || session_type.contains("testing") {
+                    SessionType::Practice
+                } else if session_type.contains("qualify") {
+                    SessionType::Qualifying
+                } else if session_type.contains("warmup") {
+                    SessionType::Warmup
+                } else if session_type.contains("race") {
+                    SessionType::Race
+                } else if session_type.contains("time trial") {
+                    SessionType::Hotlap
+                } else {
+                    SessionType::Other
+                }
+            });
+        let caution = self
+            .read_name::<BitField>("SessionFlags")
+            .is_some_and(|flags| {
+                flags.0 & (global_flags::CAUTION | global_flags::CAUTION_WAVING) != 0
+            });
+        let phase = match self.read_name::<i32>("SessionState") {
+            Some(1) | Some(2) => Some(SessionPhase::Waiting),
+            Some(3) => Some(SessionPhase::Formation),
+            Some(4) if caution => Some(SessionPhase::FullCourseYellow),
+            Some(4) => Some(SessionPhase::Green),
+            Some(5) => Some(SessionPhase::Checkered),
+            Some(6) => Some(SessionPhase::Finished),
+            _ => None,
+        };
+        let time_remaining = self
+            .read_name::<f64>("SessionTimeRemain")
+            .filter(|v| *v >= 0.0 && *v < UNLIMITED_TIME)
+            .map(Time::new::<second>);
+        let laps_remaining = self
+            .read_name::<i32>("SessionLapsRemainEx")
+            .filter(|v| *v != UNLIMITED_LAPS)
+            .and_then(|v| u32::try_from(v).ok());
+        let total_laps = self
+            .read_name::<i32>("SessionLapsTotal")
+            .or_else(|| current_session?["SessionLaps"].as_i64()?.try_into().ok())
+            .filter(|v| *v != UNLIMITED_LAPS)
+            .and_then(|v| u32::try_from(v).ok());
+        let track_id = self.session_info()["WeekendInfo"]["TrackName"]
+            .as_str()
+            .map(ToString::to_string);
+        Some(Session {
+            session_type,
+            phase,
+            time_remaining,
+            laps_remaining,
+            total_laps,
+            track_id,
+            is_replay: self.read_name("IsReplayPlaying"),
+        })
+    }
 }
 
 impl Debug for SimState {
@@ -139,6 +211,29 @@ impl Debug for SimState {
     }
 }
 
+/// Serializes as a map from variable name to value, or to a list of values for array variables.
+impl Serialize for SimState {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use itertools::Itertools;
+        let mut map = serializer.serialize_map(Some(self.variables.len()))?;
+        for header in self
+            .variables
+            .values()
+            .sorted_by_key(|header| header.offset)
+        {
+            if header.count == 1 {
+                map.serialize_entry(&header.name, &self.read::<Value>(header))?;
+            } else {
+                let values = (0..header.count)
+                    .map(|idx| self.read_at::<Value>(idx, header))
+                    .collect::<Vec<_>>();
+                map.serialize_entry(&header.name, &values)?;
+            }
+        }
+        map.end()
+    }
+}
+
 impl SimState {
     pub(super) fn new(
         header: Arc<Header>,
@@ -1,5 +1,6 @@
 use crate::iracing::header::{VarHeader, VarType};
 use byteorder::{LittleEndian, ReadBytesExt};
+use serde::Serialize;
 
 pub trait VarData: Sized {
     fn parse_from_raw(entry: usize, header: &VarHeader, data: &[u8]) -> Option<Self> {
@@ -19,7 +20,8 @@ pub trait VarData: Sized {
     fn parse(var_type: VarType, data: &[u8]) -> Option<Self>;
 }
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(untagged)]
 pub enum Value {
     Char(u8),
     Bool(bool),
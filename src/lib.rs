@@ -1,22 +1,38 @@
+pub use moment_snapshot::MomentSnapshot;
 pub use racing_flags::RacingFlags;
 use serde::{Deserialize, Serialize};
+pub use session::{Session, SessionPhase, SessionType};
 use std::borrow::Cow;
+#[cfg(feature = "with_r3e")]
+use std::path::PathBuf;
 use std::time::Duration;
 use tokio::select;
 use uom::si::f64::{AngularVelocity, Velocity};
 
 pub mod assetto_corsa;
 pub mod assetto_corsa_competizione;
+pub mod codemasters;
 pub mod dirt_rally_2;
+pub mod ea_wrc;
+pub mod emitter;
+pub mod f1;
+pub mod forza;
 #[cfg(feature = "unstable_generic_http_client")]
 pub mod generic_http;
+pub mod gran_turismo_7;
 pub mod iracing;
+pub mod madness_engine;
+mod moment_snapshot;
+pub mod outgauge;
 #[cfg(feature = "with_r3e")]
 pub mod raceroom_racing_experience;
 mod racing_flags;
 pub mod rfactor_2;
+pub mod richard_burns_rally;
+mod session;
 #[cfg(feature = "with_truck_simulator")]
 pub mod truck_simulator;
+pub mod udp_relay;
 mod windows_util;
 
 /// Sim that we can connect to via the common [`connect`] function.
@@ -36,6 +52,13 @@ pub struct SimetryConnectionBuilder {
     #[cfg(feature = "unstable_generic_http_client")]
     generic_http_uri: String,
     dirt_rally_2_uri: String,
+    f1_uri: String,
+    forza_uri: String,
+    outgauge_uri: String,
+    richard_burns_rally_uri: String,
+    rfactor_2_config: rfactor_2::Config,
+    #[cfg(feature = "with_r3e")]
+    raceroom_data_path: Option<PathBuf>,
     retry_delay: Duration,
 }
 
@@ -45,6 +68,13 @@ impl Default for SimetryConnectionBuilder {
             #[cfg(feature = "unstable_generic_http_client")]
             generic_http_uri: generic_http::DEFAULT_URI.to_string(),
             dirt_rally_2_uri: dirt_rally_2::Client::DEFAULT_URI.to_string(),
+            f1_uri: f1::Client::DEFAULT_URI.to_string(),
+            forza_uri: forza::Client::DEFAULT_URI.to_string(),
+            outgauge_uri: outgauge::Client::DEFAULT_URI.to_string(),
+            richard_burns_rally_uri: richard_burns_rally::Client::DEFAULT_URI.to_string(),
+            rfactor_2_config: rfactor_2::Config::default(),
+            #[cfg(feature = "with_r3e")]
+            raceroom_data_path: None,
             retry_delay: Duration::from_secs(5),
         }
     }
@@ -62,6 +92,39 @@ impl SimetryConnectionBuilder {
         self
     }
 
+    pub fn f1_uri(mut self, uri: String) -> Self {
+        self.f1_uri = uri;
+        self
+    }
+
+    pub fn forza_uri(mut self, uri: String) -> Self {
+        self.forza_uri = uri;
+        self
+    }
+
+    pub fn outgauge_uri(mut self, uri: String) -> Self {
+        self.outgauge_uri = uri;
+        self
+    }
+
+    pub fn richard_burns_rally_uri(mut self, uri: String) -> Self {
+        self.richard_burns_rally_uri = uri;
+        self
+    }
+
+    /// Configure the rFactor 2 connection, like for connecting to a dedicated server.
+    pub fn rfactor_2_config(mut self, config: rfactor_2::Config) -> Self {
+        self.rfactor_2_config = config;
+        self
+    }
+
+    /// Path to `r3e-data.json` of the RaceRoom installation, for resolving car and track names.
+    #[cfg(feature = "with_r3e")]
+    pub fn raceroom_data_path(mut self, path: PathBuf) -> Self {
+        self.raceroom_data_path = Some(path);
+        self
+    }
+
     pub fn retry_delay(mut self, delay: Duration) -> Self {
         self.retry_delay = delay;
         self
@@ -74,13 +137,39 @@ impl SimetryConnectionBuilder {
         let assetto_corsa_competizione_future =
             assetto_corsa_competizione::Client::connect(retry_delay);
         #[cfg(feature = "with_r3e")]
-            let raceroom_racing_experience_future =
-            raceroom_racing_experience::Client::connect(retry_delay);
+            let raceroom_racing_experience_future = async {
+            // Reading and parsing the file blocks, so it's done off the runtime while connecting.
+            let game_data = self.raceroom_data_path.clone().map(|path| {
+                tokio::task::spawn_blocking(move || {
+                    raceroom_racing_experience::GameData::load(path)
+                })
+            });
+            let client = raceroom_racing_experience::Client::connect(retry_delay).await;
+            let game_data = match game_data {
+                Some(task) => task
+                    .await
+                    .map_err(anyhow::Error::from)
+                    .and_then(|v| v)
+                    .map_err(|e| log::warn!("Failed to load RaceRoom data: {e:#}"))
+                    .ok(),
+                None => None,
+            };
+            match game_data {
+                Some(game_data) => client.with_game_data(game_data),
+                None => client,
+            }
+        };
         #[cfg(not(feature = "with_r3e"))]
             let raceroom_racing_experience_future = never_resolved();
-        let rfactor_2_future = rfactor_2::Client::connect();
+        let rfactor_2_future = rfactor_2::Client::connect_with_config(&self.rfactor_2_config);
+        let madness_engine_future = madness_engine::Client::connect(retry_delay);
         let dirt_rally_2_future =
             dirt_rally_2::Client::connect(&self.dirt_rally_2_uri, retry_delay);
+        let f1_future = f1::Client::connect(&self.f1_uri, retry_delay);
+        let forza_future = forza::Client::connect(&self.forza_uri, retry_delay);
+        let outgauge_future = outgauge::Client::connect(&self.outgauge_uri, retry_delay);
+        let richard_burns_rally_future =
+            richard_burns_rally::Client::connect(&self.richard_burns_rally_uri, retry_delay);
         #[cfg(feature = "unstable_generic_http_client")]
             let generic_http_future =
             generic_http::GenericHttpClient::connect(&self.generic_http_uri, retry_delay);
@@ -97,14 +186,23 @@ impl SimetryConnectionBuilder {
             x = assetto_corsa_competizione_future => Box::new(x),
             x = raceroom_racing_experience_future => Box::new(x),
             x = rfactor_2_future => Box::new(x),
+            x = madness_engine_future => Box::new(x),
             x = dirt_rally_2_future => Box::new(x),
+            x = f1_future => Box::new(x),
+            x = forza_future => Box::new(x),
+            x = outgauge_future => Box::new(x),
+            x = richard_burns_rally_future => Box::new(x),
             x = generic_http_future => Box::new(x),
             x = truck_simulator_future => Box::new(x),
         }
     }
 }
 
-#[cfg(not(feature = "unstable_generic_http_client"))]
+#[cfg(not(all(
+    feature = "unstable_generic_http_client",
+    feature = "with_r3e",
+    feature = "with_truck_simulator"
+)))]
 async fn never_resolved() -> iracing::Client {
     loop {
         tokio::time::sleep(Duration::from_secs(1_000_000_000)).await;
@@ -232,6 +330,11 @@ pub trait Moment {
     fn pedals_raw(&self) -> Option<Pedals> {
         self.pedals()
     }
+
+    /// Information about the currently running session.
+    fn session(&self) -> Option<Session> {
+        None
+    }
 }
 
 /// Percentage values of pedal inputs.
@@ -0,0 +1,105 @@
+use crate::madness_engine::shared_memory_data::{PageSharedMemory, SHARED_MEMORY_VERSION};
+use crate::madness_engine::{GameState, SimState};
+use crate::windows_util::SharedMemory;
+use anyhow::{bail, Result};
+use std::time::Duration;
+
+/// Delay between polls of the sequence number.
+const POLL_DELAY: Duration = Duration::from_millis(1);
+
+/// Number of attempts at copying a page the game is writing at the same time,
+/// before giving up until the next poll.
+const READ_ATTEMPTS: usize = 64;
+
+/// Client reading the `$pcars2$` shared memory page.
+pub struct Client {
+    shared_memory: SharedMemory,
+    last_sequence_number: u32,
+}
+
+impl Client {
+    pub async fn connect(retry_delay: Duration) -> Self {
+        loop {
+            if let Ok(v) = Self::try_connect().await {
+                return v;
+            }
+            tokio::time::sleep(retry_delay).await;
+        }
+    }
+
+    pub async fn try_connect() -> Result<Self> {
+        let poll_delay = Duration::from_millis(250);
+        let shared_memory = SharedMemory::connect(b"$pcars2$\0", poll_delay).await;
+        let (sequence_number, page) = loop {
+            if let Some(v) = Self::read_page(&shared_memory) {
+                break v;
+            }
+            tokio::time::sleep(POLL_DELAY).await;
+        };
+        if page.version < SHARED_MEMORY_VERSION {
+            bail!(
+                "Expected shared memory version {} or newer, got {}",
+                SHARED_MEMORY_VERSION,
+                page.version,
+            );
+        }
+        if GameState::from(page.game_state) == GameState::Exited {
+            bail!("Game is not running");
+        }
+        Ok(Self {
+            shared_memory,
+            last_sequence_number: sequence_number,
+        })
+    }
+
+    /// Wait for the game to finish writing a new update, and return it.
+    ///
+    /// Returns `None` once the game exits.
+    pub async fn next_sim_state(&mut self) -> Option<SimState> {
+        loop {
+            if self.sequence_number() != self.last_sequence_number {
+                // A page still being written is read again on the next poll
+                if let Some((sequence_number, page)) = Self::read_page(&self.shared_memory) {
+                    self.last_sequence_number = sequence_number;
+                    if GameState::from(page.game_state) == GameState::Exited {
+                        return None;
+                    }
+                    return Some(page.as_ref().into());
+                }
+            }
+            tokio::time::sleep(POLL_DELAY).await;
+        }
+    }
+
+    fn sequence_number(&self) -> u32 {
+        Self::read_sequence_number(&self.shared_memory)
+    }
+
+    fn read_sequence_number(shared_memory: &SharedMemory) -> u32 {
+        unsafe {
+            let page = shared_memory.get() as *const PageSharedMemory;
+            std::ptr::addr_of!((*page).sequence_number).read_volatile()
+        }
+    }
+
+    /// Copy the page once the game isn't writing to it.
+    ///
+    /// The sequence number is odd while the game is writing, and gets incremented again once
+    /// it's done, so a copy is valid if the number was even and unchanged during the copy.
+    /// Gives up after `READ_ATTEMPTS`, leaving the caller to retry after a sleep.
+    fn read_page(shared_memory: &SharedMemory) -> Option<(u32, Box<PageSharedMemory>)> {
+        for _ in 0..READ_ATTEMPTS {
+            let sequence_number_1 = Self::read_sequence_number(shared_memory);
+            if sequence_number_1 & 1 != 0 {
+                std::hint::spin_loop();
+                continue;
+            }
+            let page = Box::new(unsafe { shared_memory.copy_as::<PageSharedMemory>() });
+            let sequence_number_2 = Self::read_sequence_number(shared_memory);
+            if sequence_number_1 == sequence_number_2 {
+                return Some((sequence_number_1, page));
+            }
+        }
+        None
+    }
+}
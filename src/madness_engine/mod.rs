@@ -0,0 +1,439 @@
+//! Support for sims built on the Madness engine, like Project CARS 2 and Automobilista 2.
+//!
+//! Data is read either from the shared memory with [`Client`], which has to be enabled in the
+//! game settings as "Project CARS 2" shared memory, or from the UDP telemetry with
+//! [`udp::Client`], which works on remote machines too and has to be enabled as
+//! "Project CARS 2" UDP protocol version in the game settings.
+//! Both produce the same [`SimState`].
+
+mod client;
+mod shared_memory_data;
+pub mod udp;
+
+use crate::{Moment, Pedals, RacingFlags, Session, SessionPhase, SessionType, Simetry};
+pub use client::Client;
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use uom::si::angular_velocity::revolution_per_minute;
+use uom::si::f64::{AngularVelocity, Time, Velocity};
+use uom::si::time::second;
+use uom::si::velocity::meter_per_second;
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub enum GameState {
+    #[default]
+    Exited,
+    FrontEnd,
+    InGamePlaying,
+    InGamePaused,
+    InGameInMenuTimeTicking,
+    InGameRestarting,
+    InGameReplay,
+    FrontEndReplay,
+}
+
+impl From<u32> for GameState {
+    fn from(v: u32) -> Self {
+        match v {
+            1 => Self::FrontEnd,
+            2 => Self::InGamePlaying,
+            3 => Self::InGamePaused,
+            4 => Self::InGameInMenuTimeTicking,
+            5 => Self::InGameRestarting,
+            6 => Self::InGameReplay,
+            7 => Self::FrontEndReplay,
+            _ => Self::Exited,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub enum SessionState {
+    #[default]
+    Invalid,
+    Practice,
+    Test,
+    Qualify,
+    FormationLap,
+    Race,
+    TimeAttack,
+}
+
+impl From<u32> for SessionState {
+    fn from(v: u32) -> Self {
+        match v {
+            1 => Self::Practice,
+            2 => Self::Test,
+            3 => Self::Qualify,
+            4 => Self::FormationLap,
+            5 => Self::Race,
+            6 => Self::TimeAttack,
+            _ => Self::Invalid,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub enum RaceState {
+    #[default]
+    Invalid,
+    NotStarted,
+    Racing,
+    Finished,
+    Disqualified,
+    Retired,
+    Dnf,
+}
+
+impl From<u32> for RaceState {
+    fn from(v: u32) -> Self {
+        match v {
+            1 => Self::NotStarted,
+            2 => Self::Racing,
+            3 => Self::Finished,
+            4 => Self::Disqualified,
+            5 => Self::Retired,
+            6 => Self::Dnf,
+            _ => Self::Invalid,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub enum FlagColour {
+    #[default]
+    None,
+    Green,
+    Blue,
+    WhiteSlowCar,
+    WhiteFinalLap,
+    Red,
+    Yellow,
+    DoubleYellow,
+    BlackAndWhite,
+    BlackOrangeCircle,
+    Black,
+    Chequered,
+}
+
+impl From<u32> for FlagColour {
+    fn from(v: u32) -> Self {
+        match v {
+            1 => Self::Green,
+            2 => Self::Blue,
+            3 => Self::WhiteSlowCar,
+            4 => Self::WhiteFinalLap,
+            5 => Self::Red,
+            6 => Self::Yellow,
+            7 => Self::DoubleYellow,
+            8 => Self::BlackAndWhite,
+            9 => Self::BlackOrangeCircle,
+            10 => Self::Black,
+            11 => Self::Chequered,
+            _ => Self::None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub enum PitMode {
+    #[default]
+    None,
+    DrivingIntoPits,
+    InPit,
+    DrivingOutOfPits,
+    InGarage,
+    DrivingOutOfGarage,
+}
+
+impl From<u32> for PitMode {
+    fn from(v: u32) -> Self {
+        match v {
+            1 => Self::DrivingIntoPits,
+            2 => Self::InPit,
+            3 => Self::DrivingOutOfPits,
+            4 => Self::InGarage,
+            5 => Self::DrivingOutOfGarage,
+            _ => Self::None,
+        }
+    }
+}
+
+/// Bits of [`SimState::car_flags`].
+pub mod car_flags {
+    pub const HEADLIGHT: u32 = 0x01;
+    pub const ENGINE_ACTIVE: u32 = 0x02;
+    pub const ENGINE_WARNING: u32 = 0x04;
+    pub const SPEED_LIMITER: u32 = 0x08;
+    pub const ABS: u32 = 0x10;
+    pub const HANDBRAKE: u32 = 0x20;
+}
+
+/// Data of the viewed car and the session, with standings of all participants.
+///
+/// Wheel arrays are in front left, front right, rear left, rear right order.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct SimState {
+    pub game_state: GameState,
+    pub session_state: SessionState,
+    /// Race state of the viewed participant
+    pub race_state: RaceState,
+    /// Index of the viewed participant in [`SimState::participants`], -1 if none
+    pub viewed_participant_index: i32,
+    pub participants: Vec<Participant>,
+    /// Throttle input before any filtering, in 0-1 range
+    pub unfiltered_throttle: f32,
+    /// Brake input before any filtering, in 0-1 range
+    pub unfiltered_brake: f32,
+    /// Steering input before any filtering, in -1 to 1 range
+    pub unfiltered_steering: f32,
+    /// Clutch input before any filtering, in 0-1 range
+    pub unfiltered_clutch: f32,
+    pub car_name: String,
+    pub car_class_name: String,
+    /// Number of laps in the event, 0 for timed events
+    pub laps_in_event: u32,
+    pub track_location: String,
+    pub track_variation: String,
+    /// Track length in meters
+    pub track_length: f32,
+    /// Best lap time of the viewed participant in seconds, -1 if not set
+    pub best_lap_time: f32,
+    /// Last lap time of the viewed participant in seconds, -1 if not set
+    pub last_lap_time: f32,
+    /// Current lap time of the viewed participant in seconds, -1 if not set
+    pub current_time: f32,
+    /// Gap to the car ahead in seconds
+    pub split_time_ahead: f32,
+    /// Gap to the car behind in seconds
+    pub split_time_behind: f32,
+    /// Time left in timed events in seconds, -1 if not set
+    pub event_time_remaining: f32,
+    pub highest_flag_colour: FlagColour,
+    pub highest_flag_reason: u32,
+    pub pit_mode: PitMode,
+    pub pit_schedule: u32,
+    /// See [`car_flags`]
+    pub car_flags: u32,
+    pub oil_temp_celsius: f32,
+    pub water_temp_celsius: f32,
+    /// Fuel level in 0-1 range
+    pub fuel_level: f32,
+    /// Fuel capacity in liters
+    pub fuel_capacity: f32,
+    /// Speed in meters per second
+    pub speed: f32,
+    pub rpm: f32,
+    pub max_rpm: f32,
+    /// Brake in 0-1 range
+    pub brake: f32,
+    /// Throttle in 0-1 range
+    pub throttle: f32,
+    /// Clutch in 0-1 range
+    pub clutch: f32,
+    /// Steering in -1 to 1 range
+    pub steering: f32,
+    /// -1 = reverse, 0 = neutral, 1 and up forward gears
+    pub gear: i32,
+    pub num_gears: i32,
+    pub odometer_km: f32,
+    pub boost_amount: f32,
+    /// Orientation in radians
+    pub orientation: [f32; 3],
+    /// Velocity in car local space in meters per second
+    pub local_velocity: [f32; 3],
+    /// Velocity in world space in meters per second
+    pub world_velocity: [f32; 3],
+    /// Angular velocity in radians per second
+    pub angular_velocity: [f32; 3],
+    /// Acceleration in car local space in meters per second squared
+    pub local_acceleration: [f32; 3],
+    /// Acceleration in world space in meters per second squared
+    pub world_acceleration: [f32; 3],
+    /// Tyre temperatures in degrees Celsius
+    pub tyre_temp: [f32; 4],
+    /// Tyre wear in 0-1 range
+    pub tyre_wear: [f32; 4],
+    pub brake_temp_celsius: [f32; 4],
+    pub ambient_temperature: f32,
+    pub track_temperature: f32,
+    /// Rain density in 0-1 range
+    pub rain_density: f32,
+}
+
+impl SimState {
+    pub fn viewed_participant(&self) -> Option<&Participant> {
+        self.participants
+            .get(usize::try_from(self.viewed_participant_index).ok()?)
+    }
+
+    /// Active participants ordered by their race position.
+    pub fn standings(&self) -> Vec<&Participant> {
+        let mut standings = self
+            .participants
+            .iter()
+            .filter(|v| v.is_active)
+            .collect::<Vec<_>>();
+        standings.sort_by_key(|v| v.race_position);
+        standings
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Participant {
+    pub is_active: bool,
+    pub name: String,
+    /// Position in world space in meters
+    pub world_position: [f32; 3],
+    /// Distance driven in the current lap in meters
+    pub current_lap_distance: f32,
+    pub race_position: u32,
+    pub laps_completed: u32,
+    pub current_lap: u32,
+    pub current_sector: i32,
+    pub race_state: RaceState,
+    pub pit_mode: PitMode,
+    pub highest_flag_colour: FlagColour,
+    pub lap_invalidated: bool,
+    pub car_name: String,
+    pub car_class_name: String,
+    /// Fastest lap time in seconds, -1 if not set
+    pub fastest_lap_time: f32,
+    /// Last lap time in seconds, -1 if not set
+    pub last_lap_time: f32,
+}
+
+#[async_trait::async_trait]
+impl Simetry for Client {
+    fn name(&self) -> &str {
+        "MadnessEngine"
+    }
+
+    async fn next_moment(&mut self) -> Option<Box<dyn Moment + Send + Sync + 'static>> {
+        Some(Box::new(self.next_sim_state().await?))
+    }
+}
+
+impl Moment for SimState {
+    fn vehicle_gear(&self) -> Option<i8> {
+        Some(self.gear as i8)
+    }
+
+    fn vehicle_velocity(&self) -> Option<Velocity> {
+        Some(Velocity::new::<meter_per_second>(self.speed as f64))
+    }
+
+    fn vehicle_engine_rotation_speed(&self) -> Option<AngularVelocity> {
+        Some(AngularVelocity::new::<revolution_per_minute>(
+            self.rpm as f64,
+        ))
+    }
+
+    fn vehicle_max_engine_rotation_speed(&self) -> Option<AngularVelocity> {
+        Some(AngularVelocity::new::<revolution_per_minute>(
+            self.max_rpm as f64,
+        ))
+    }
+
+    fn is_pit_limiter_engaged(&self) -> Option<bool> {
+        Some(self.car_flags & car_flags::SPEED_LIMITER != 0)
+    }
+
+    fn is_vehicle_in_pit_lane(&self) -> Option<bool> {
+        Some(matches!(
+            self.pit_mode,
+            PitMode::DrivingIntoPits | PitMode::InPit | PitMode::DrivingOutOfPits
+        ))
+    }
+
+    fn flags(&self) -> Option<RacingFlags> {
+        let mut flags = RacingFlags::default();
+        match self.highest_flag_colour {
+            FlagColour::None => {}
+            FlagColour::Green => flags.green = true,
+            FlagColour::Blue => flags.blue = true,
+            FlagColour::WhiteSlowCar | FlagColour::WhiteFinalLap => flags.white = true,
+            FlagColour::Red => flags.red = true,
+            FlagColour::Yellow | FlagColour::DoubleYellow => flags.yellow = true,
+            FlagColour::BlackAndWhite => flags.black_and_white = true,
+            FlagColour::BlackOrangeCircle => flags.meatball = true,
+            FlagColour::Black => flags.black = true,
+            FlagColour::Chequered => flags.checkered = true,
+        }
+        Some(flags)
+    }
+
+    fn vehicle_model_id(&self) -> Option<Cow<str>> {
+        Some(self.car_name.as_str().into()).filter(|v: &Cow<str>| !v.is_empty())
+    }
+
+    fn vehicle_unique_id(&self) -> Option<Cow<str>> {
+        // No brand is sent, but car names already include it.
+        self.vehicle_model_id()
+    }
+
+    fn is_ignition_on(&self) -> Option<bool> {
+        Some(self.car_flags & car_flags::ENGINE_ACTIVE != 0)
+    }
+
+    fn pedals(&self) -> Option<Pedals> {
+        Some(Pedals {
+            throttle: self.throttle as f64,
+            brake: self.brake as f64,
+            clutch: self.clutch as f64,
+        })
+    }
+
+    fn pedals_raw(&self) -> Option<Pedals> {
+        Some(Pedals {
+            throttle: self.unfiltered_throttle as f64,
+            brake: self.unfiltered_brake as f64,
+            clutch: self.unfiltered_clutch as f64,
+        })
+    }
+
+    fn session(&self) -> Option<Session> {
+        let session_type = match self.session_state {
+            SessionState::Invalid => None,
+            SessionState::Practice | SessionState::Test => Some(SessionType::Practice),
+            SessionState::Qualify => Some(SessionType::Qualifying),
+            SessionState::FormationLap | SessionState::Race => Some(SessionType::Race),
+            SessionState::TimeAttack => Some(SessionType::Hotlap),
+        };
+        let phase = match (self.game_state, self.session_state, self.race_state) {
+            (GameState::InGamePaused, _, _) => Some(SessionPhase::Stopped),
+            (_, SessionState::FormationLap, _) => Some(SessionPhase::Formation),
+            (_, _, RaceState::Invalid) => None,
+            (_, _, RaceState::NotStarted) => Some(SessionPhase::Waiting),
+            (_, _, RaceState::Racing) => match self.highest_flag_colour {
+                FlagColour::Chequered => Some(SessionPhase::Checkered),
+                _ => Some(SessionPhase::Green),
+            },
+            (_, _, _) => Some(SessionPhase::Finished),
+        };
+        let time_remaining = Some(self.event_time_remaining)
+            .filter(|v| *v >= 0.0)
+            .map(|v| Time::new::<second>(v as f64));
+        let total_laps = Some(self.laps_in_event).filter(|v| *v > 0);
+        let laps_completed = self.viewed_participant().map(|v| v.laps_completed);
+        let track_id = if self.track_variation.is_empty() {
+            self.track_location.clone()
+        } else {
+            format!("{}|{}", self.track_location, self.track_variation)
+        };
+        Some(Session {
+            session_type,
+            phase,
+            time_remaining,
+            laps_remaining: total_laps
+                .zip(laps_completed)
+                .map(|(total, completed)| total.saturating_sub(completed)),
+            total_laps,
+            track_id: Some(track_id),
+            is_replay: Some(matches!(
+                self.game_state,
+                GameState::InGameReplay | GameState::FrontEndReplay
+            )),
+        })
+    }
+}
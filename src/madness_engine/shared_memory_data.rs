@@ -0,0 +1,300 @@
+//! Layout of the `$pcars2$` shared memory page, as described by `SharedMemory.h` of the
+//! Project CARS 2 shared memory API, version 9.
+//!
+//! C `bool` fields are stored as `u8`, and C strings as byte arrays.
+
+use crate::madness_engine::{Participant, SimState};
+
+pub const SHARED_MEMORY_VERSION: u32 = 9;
+pub const STORED_PARTICIPANTS_MAX: usize = 64;
+pub const STRING_LENGTH_MAX: usize = 64;
+pub const TYRE_COMPOUND_NAME_LENGTH_MAX: usize = 40;
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug)]
+pub struct ParticipantInfo {
+    pub is_active: u8,
+    pub name: [u8; STRING_LENGTH_MAX],
+    pub world_position: [f32; 3],
+    pub current_lap_distance: f32,
+    pub race_position: u32,
+    pub laps_completed: u32,
+    pub current_lap: u32,
+    pub current_sector: i32,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug)]
+pub struct PageSharedMemory {
+    // Version number for additional safety
+    pub version: u32,
+    pub build_version_number: u32,
+
+    // Game states
+    pub game_state: u32,
+    pub session_state: u32,
+    pub race_state: u32,
+
+    // Participant info
+    pub viewed_participant_index: i32,
+    pub num_participants: i32,
+    pub participant_info: [ParticipantInfo; STORED_PARTICIPANTS_MAX],
+
+    // Unfiltered input
+    pub unfiltered_throttle: f32,
+    pub unfiltered_brake: f32,
+    pub unfiltered_steering: f32,
+    pub unfiltered_clutch: f32,
+
+    // Vehicle information
+    pub car_name: [u8; STRING_LENGTH_MAX],
+    pub car_class_name: [u8; STRING_LENGTH_MAX],
+
+    // Event information
+    pub laps_in_event: u32,
+    pub track_location: [u8; STRING_LENGTH_MAX],
+    pub track_variation: [u8; STRING_LENGTH_MAX],
+    pub track_length: f32,
+
+    // Timings
+    pub num_sectors: i32,
+    pub lap_invalidated: u8,
+    pub best_lap_time: f32,
+    pub last_lap_time: f32,
+    pub current_time: f32,
+    pub split_time_ahead: f32,
+    pub split_time_behind: f32,
+    pub split_time: f32,
+    pub event_time_remaining: f32,
+    pub personal_fastest_lap_time: f32,
+    pub world_fastest_lap_time: f32,
+    pub current_sector_1_time: f32,
+    pub current_sector_2_time: f32,
+    pub current_sector_3_time: f32,
+    pub fastest_sector_1_time: f32,
+    pub fastest_sector_2_time: f32,
+    pub fastest_sector_3_time: f32,
+    pub personal_fastest_sector_1_time: f32,
+    pub personal_fastest_sector_2_time: f32,
+    pub personal_fastest_sector_3_time: f32,
+    pub world_fastest_sector_1_time: f32,
+    pub world_fastest_sector_2_time: f32,
+    pub world_fastest_sector_3_time: f32,
+
+    // Flags
+    pub highest_flag_colour: u32,
+    pub highest_flag_reason: u32,
+
+    // Pit information
+    pub pit_mode: u32,
+    pub pit_schedule: u32,
+
+    // Car state
+    pub car_flags: u32,
+    pub oil_temp_celsius: f32,
+    pub oil_pressure_kpa: f32,
+    pub water_temp_celsius: f32,
+    pub water_pressure_kpa: f32,
+    pub fuel_pressure_kpa: f32,
+    pub fuel_level: f32,
+    pub fuel_capacity: f32,
+    pub speed: f32,
+    pub rpm: f32,
+    pub max_rpm: f32,
+    pub brake: f32,
+    pub throttle: f32,
+    pub clutch: f32,
+    pub steering: f32,
+    pub gear: i32,
+    pub num_gears: i32,
+    pub odometer_km: f32,
+    pub anti_lock_active: u8,
+    pub last_opponent_collision_index: i32,
+    pub last_opponent_collision_magnitude: f32,
+    pub boost_active: u8,
+    pub boost_amount: f32,
+
+    // Motion and device related
+    pub orientation: [f32; 3],
+    pub local_velocity: [f32; 3],
+    pub world_velocity: [f32; 3],
+    pub angular_velocity: [f32; 3],
+    pub local_acceleration: [f32; 3],
+    pub world_acceleration: [f32; 3],
+    pub extents_centre: [f32; 3],
+
+    // Wheels and tyres
+    pub tyre_flags: [u32; 4],
+    pub terrain: [u32; 4],
+    pub tyre_y: [f32; 4],
+    pub tyre_rps: [f32; 4],
+    /// Obsolete, kept by the game for backward compatibility
+    pub tyre_slip_speed: [f32; 4],
+    pub tyre_temp: [f32; 4],
+    /// Obsolete, kept by the game for backward compatibility
+    pub tyre_grip: [f32; 4],
+    pub tyre_height_above_ground: [f32; 4],
+    /// Obsolete, kept by the game for backward compatibility
+    pub tyre_lateral_stiffness: [f32; 4],
+    pub tyre_wear: [f32; 4],
+    pub brake_damage: [f32; 4],
+    pub suspension_damage: [f32; 4],
+    pub brake_temp_celsius: [f32; 4],
+    pub tyre_tread_temp: [f32; 4],
+    pub tyre_layer_temp: [f32; 4],
+    pub tyre_carcass_temp: [f32; 4],
+    pub tyre_rim_temp: [f32; 4],
+    pub tyre_internal_air_temp: [f32; 4],
+
+    // Car damage
+    pub crash_state: u32,
+    pub aero_damage: f32,
+    pub engine_damage: f32,
+
+    // Weather
+    pub ambient_temperature: f32,
+    pub track_temperature: f32,
+    pub rain_density: f32,
+    pub wind_speed: f32,
+    pub wind_direction_x: f32,
+    pub wind_direction_y: f32,
+    pub cloud_brightness: f32,
+
+    // Odd while the game is writing the page, even once it's done
+    pub sequence_number: u32,
+
+    // Additional car variables
+    pub wheel_local_position_y: [f32; 4],
+    pub suspension_travel: [f32; 4],
+    pub suspension_velocity: [f32; 4],
+    pub air_pressure: [f32; 4],
+    pub engine_speed: f32,
+    pub engine_torque: f32,
+    pub wings: [f32; 2],
+    pub hand_brake: f32,
+
+    // Additional race variables, per participant
+    pub current_sector_1_times: [f32; STORED_PARTICIPANTS_MAX],
+    pub current_sector_2_times: [f32; STORED_PARTICIPANTS_MAX],
+    pub current_sector_3_times: [f32; STORED_PARTICIPANTS_MAX],
+    pub fastest_sector_1_times: [f32; STORED_PARTICIPANTS_MAX],
+    pub fastest_sector_2_times: [f32; STORED_PARTICIPANTS_MAX],
+    pub fastest_sector_3_times: [f32; STORED_PARTICIPANTS_MAX],
+    pub fastest_lap_times: [f32; STORED_PARTICIPANTS_MAX],
+    pub last_lap_times: [f32; STORED_PARTICIPANTS_MAX],
+    pub laps_invalidated: [u8; STORED_PARTICIPANTS_MAX],
+    pub race_states: [u32; STORED_PARTICIPANTS_MAX],
+    pub pit_modes: [u32; STORED_PARTICIPANTS_MAX],
+    pub orientations: [[f32; 3]; STORED_PARTICIPANTS_MAX],
+    pub speeds: [f32; STORED_PARTICIPANTS_MAX],
+    pub car_names: [[u8; STRING_LENGTH_MAX]; STORED_PARTICIPANTS_MAX],
+    pub car_class_names: [[u8; STRING_LENGTH_MAX]; STORED_PARTICIPANTS_MAX],
+
+    // Additional race variables
+    pub enforced_pit_stop_lap: i32,
+    pub translated_track_location: [u8; STRING_LENGTH_MAX],
+    pub translated_track_variation: [u8; STRING_LENGTH_MAX],
+    pub brake_bias: f32,
+    pub turbo_boost_pressure: f32,
+    pub tyre_compound: [[u8; TYRE_COMPOUND_NAME_LENGTH_MAX]; 4],
+    pub pit_schedules: [u32; STORED_PARTICIPANTS_MAX],
+    pub highest_flag_colours: [u32; STORED_PARTICIPANTS_MAX],
+    pub highest_flag_reasons: [u32; STORED_PARTICIPANTS_MAX],
+    pub nationalities: [u32; STORED_PARTICIPANTS_MAX],
+    pub snow_density: f32,
+}
+
+// Sizes of the structs in `SharedMemory.h`, to catch fields missing from the copies above.
+const _: () = assert!(std::mem::size_of::<ParticipantInfo>() == 100);
+const _: () = assert!(std::mem::size_of::<PageSharedMemory>() == 20_576);
+
+pub fn extract_string(data: &[u8]) -> String {
+    let length = data.iter().position(|v| *v == 0).unwrap_or(data.len());
+    String::from_utf8_lossy(&data[0..length]).into_owned()
+}
+
+impl From<&PageSharedMemory> for SimState {
+    fn from(v: &PageSharedMemory) -> Self {
+        let participant_count = usize::try_from(v.num_participants)
+            .unwrap_or_default()
+            .min(STORED_PARTICIPANTS_MAX);
+        let participants = (0..participant_count)
+            .map(|i| {
+                let info = &v.participant_info[i];
+                Participant {
+                    is_active: info.is_active != 0,
+                    name: extract_string(&info.name),
+                    world_position: info.world_position,
+                    current_lap_distance: info.current_lap_distance,
+                    race_position: info.race_position,
+                    laps_completed: info.laps_completed,
+                    current_lap: info.current_lap,
+                    current_sector: info.current_sector,
+                    race_state: v.race_states[i].into(),
+                    pit_mode: v.pit_modes[i].into(),
+                    highest_flag_colour: v.highest_flag_colours[i].into(),
+                    lap_invalidated: v.laps_invalidated[i] != 0,
+                    car_name: extract_string(&v.car_names[i]),
+                    car_class_name: extract_string(&v.car_class_names[i]),
+                    fastest_lap_time: v.fastest_lap_times[i],
+                    last_lap_time: v.last_lap_times[i],
+                }
+            })
+            .collect();
+        Self {
+            game_state: v.game_state.into(),
+            session_state: v.session_state.into(),
+            race_state: v.race_state.into(),
+            viewed_participant_index: v.viewed_participant_index,
+            participants,
+            unfiltered_throttle: v.unfiltered_throttle,
+            unfiltered_brake: v.unfiltered_brake,
+            unfiltered_steering: v.unfiltered_steering,
+            unfiltered_clutch: v.unfiltered_clutch,
+            car_name: extract_string(&v.car_name),
+            car_class_name: extract_string(&v.car_class_name),
+            laps_in_event: v.laps_in_event,
+            track_location: extract_string(&v.track_location),
+            track_variation: extract_string(&v.track_variation),
+            track_length: v.track_length,
+            best_lap_time: v.best_lap_time,
+            last_lap_time: v.last_lap_time,
+            current_time: v.current_time,
+            split_time_ahead: v.split_time_ahead,
+            split_time_behind: v.split_time_behind,
+            event_time_remaining: v.event_time_remaining,
+            highest_flag_colour: v.highest_flag_colour.into(),
+            highest_flag_reason: v.highest_flag_reason,
+            pit_mode: v.pit_mode.into(),
+            pit_schedule: v.pit_schedule,
+            car_flags: v.car_flags,
+            oil_temp_celsius: v.oil_temp_celsius,
+            water_temp_celsius: v.water_temp_celsius,
+            fuel_level: v.fuel_level,
+            fuel_capacity: v.fuel_capacity,
+            speed: v.speed,
+            rpm: v.rpm,
+            max_rpm: v.max_rpm,
+            brake: v.brake,
+            throttle: v.throttle,
+            clutch: v.clutch,
+            steering: v.steering,
+            gear: v.gear,
+            num_gears: v.num_gears,
+            odometer_km: v.odometer_km,
+            boost_amount: v.boost_amount,
+            orientation: v.orientation,
+            local_velocity: v.local_velocity,
+            world_velocity: v.world_velocity,
+            angular_velocity: v.angular_velocity,
+            local_acceleration: v.local_acceleration,
+            world_acceleration: v.world_acceleration,
+            tyre_temp: v.tyre_temp,
+            tyre_wear: v.tyre_wear,
+            brake_temp_celsius: v.brake_temp_celsius,
+            ambient_temperature: v.ambient_temperature,
+            track_temperature: v.track_temperature,
+            rain_density: v.rain_density,
+        }
+    }
+}
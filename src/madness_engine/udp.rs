@@ -0,0 +1,869 @@
+//! UDP telemetry of Project CARS 2 and Automobilista 2, version 2 of the protocol as of patch 3.
+//!
+//! The game broadcasts on port 5606 once "UDP Frequency" is set and "UDP Protocol Version" is
+//! set to "Project CARS 2" in the game settings.
+//! Each packet carries only a part of the state, so [`State`] keeps the latest ones to produce
+//! the same [`SimState`] that is read from the shared memory.
+
+use crate::madness_engine::shared_memory_data::extract_string;
+use crate::madness_engine::{Participant, SimState};
+use crate::{Moment, Simetry};
+use anyhow::{bail, Result};
+use byteorder::{LittleEndian, ReadBytesExt};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::Read;
+use std::time::Duration;
+use tokio::net::UdpSocket;
+
+/// Size of the [`PacketBase`] in bytes.
+pub const BASE_SIZE: usize = 12;
+/// Number of participants sent in timings and time stats packets.
+pub const UDP_STREAMER_PARTICIPANTS_SUPPORTED: usize = 32;
+/// Number of participants sent in a single participants packet.
+pub const PARTICIPANTS_PER_PACKET: usize = 16;
+/// Number of vehicles sent in a single vehicle names packet.
+pub const VEHICLES_PER_PACKET: usize = 16;
+/// Number of classes sent in a single class names packet.
+pub const CLASSES_PER_PACKET: usize = 60;
+
+pub const TELEMETRY_SIZE: usize = 559;
+pub const RACE_DEFINITION_SIZE: usize = 307;
+pub const PARTICIPANTS_SIZE: usize = 1136;
+pub const TIMINGS_SIZE: usize = 1063;
+pub const GAME_STATE_SIZE: usize = 22;
+pub const TIME_STATS_SIZE: usize = 1040;
+pub const VEHICLE_NAMES_SIZE: usize = 1164;
+pub const CLASS_NAMES_SIZE: usize = 1452;
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct PacketBase {
+    /// Counter across all packets
+    pub packet_number: u32,
+    /// Counter across packets of the same type
+    pub category_packet_number: u32,
+    /// Index of the part if the data is split into multiple packets, starting from 1
+    pub partial_packet_index: u8,
+    /// Number of parts the data is split into
+    pub partial_packet_number: u8,
+    pub packet_type: u8,
+    pub packet_version: u8,
+}
+
+/// Any packet sent by the game.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Packet {
+    Telemetry(Box<TelemetryPacket>),
+    RaceDefinition(RaceDefinitionPacket),
+    Participants(ParticipantsPacket),
+    Timings(TimingsPacket),
+    GameState(GameStatePacket),
+    TimeStats(TimeStatsPacket),
+    VehicleNames(VehicleNamesPacket),
+    ClassNames(ClassNamesPacket),
+    /// Packet types that are not parsed, like weather or participant vehicle details
+    Other(PacketBase),
+}
+
+impl Packet {
+    /// Parse a whole datagram sent by the game.
+    ///
+    /// Fails if the datagram is too short for its packet type.
+    pub fn parse(datagram: &[u8]) -> Result<Self> {
+        if datagram.len() < BASE_SIZE {
+            bail!(
+                "Datagram of {} bytes is too short for a packet base",
+                datagram.len()
+            );
+        }
+        let mut data = datagram;
+        let base = read_base(&mut data)?;
+        let expected_size = match base.packet_type {
+            0 => TELEMETRY_SIZE,
+            1 => RACE_DEFINITION_SIZE,
+            2 => PARTICIPANTS_SIZE,
+            3 => TIMINGS_SIZE,
+            4 => GAME_STATE_SIZE,
+            7 => TIME_STATS_SIZE,
+            // Both names packets share the type, and are told apart by their size
+            8 if datagram.len() >= CLASS_NAMES_SIZE => CLASS_NAMES_SIZE,
+            8 => VEHICLE_NAMES_SIZE,
+            _ => return Ok(Self::Other(base)),
+        };
+        // Packets may be padded at the end
+        if datagram.len() < expected_size {
+            bail!(
+                "Packet {} should have at least {} bytes, got {}",
+                base.packet_type,
+                expected_size,
+                datagram.len(),
+            );
+        }
+        let data = &mut data;
+        Ok(match (base.packet_type, expected_size) {
+            (0, _) => Self::Telemetry(Box::new(read_telemetry(base, data)?)),
+            (1, _) => Self::RaceDefinition(read_race_definition(base, data)?),
+            (2, _) => Self::Participants(ParticipantsPacket {
+                changed_timestamp: data.read_u32::<LittleEndian>()?,
+                names: (0..PARTICIPANTS_PER_PACKET)
+                    .map(|_| read_string::<64>(data))
+                    .collect::<Result<_>>()?,
+                nationalities: read_u32s(data)?,
+                indices: read_u16s(data)?,
+                base,
+            }),
+            (3, _) => Self::Timings(read_timings(base, data)?),
+            (4, _) => Self::GameState(read_game_state(base, data)?),
+            (7, _) => Self::TimeStats(TimeStatsPacket {
+                changed_timestamp: data.read_u32::<LittleEndian>()?,
+                stats: (0..UDP_STREAMER_PARTICIPANTS_SUPPORTED)
+                    .map(|_| read_participant_stats(data))
+                    .collect::<Result<_>>()?,
+                base,
+            }),
+            (_, CLASS_NAMES_SIZE) => Self::ClassNames(ClassNamesPacket {
+                classes: (0..CLASSES_PER_PACKET)
+                    .map(|_| {
+                        Ok(ClassInfo {
+                            class_index: data.read_u32::<LittleEndian>()?,
+                            name: read_string::<20>(data)?,
+                        })
+                    })
+                    .collect::<Result<_>>()?,
+                base,
+            }),
+            (_, _) => Self::VehicleNames(VehicleNamesPacket {
+                vehicles: (0..VEHICLES_PER_PACKET)
+                    .map(|_| read_vehicle_info(data))
+                    .collect::<Result<_>>()?,
+                base,
+            }),
+        })
+    }
+
+    pub fn base(&self) -> &PacketBase {
+        match self {
+            Self::Telemetry(v) => &v.base,
+            Self::RaceDefinition(v) => &v.base,
+            Self::Participants(v) => &v.base,
+            Self::Timings(v) => &v.base,
+            Self::GameState(v) => &v.base,
+            Self::TimeStats(v) => &v.base,
+            Self::VehicleNames(v) => &v.base,
+            Self::ClassNames(v) => &v.base,
+            Self::Other(v) => v,
+        }
+    }
+}
+
+/// Telemetry of the viewed car.
+///
+/// Wheel arrays are in front left, front right, rear left, rear right order.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TelemetryPacket {
+    pub base: PacketBase,
+    pub viewed_participant_index: i8,
+    /// Unfiltered throttle input in 0-255 range
+    pub unfiltered_throttle: u8,
+    /// Unfiltered brake input in 0-255 range
+    pub unfiltered_brake: u8,
+    /// Unfiltered steering input in -127 to 127 range
+    pub unfiltered_steering: i8,
+    /// Unfiltered clutch input in 0-255 range
+    pub unfiltered_clutch: u8,
+    /// See [`crate::madness_engine::car_flags`]
+    pub car_flags: u8,
+    pub oil_temp_celsius: i16,
+    pub oil_pressure_kpa: u16,
+    pub water_temp_celsius: i16,
+    pub water_pressure_kpa: u16,
+    pub fuel_pressure_kpa: u16,
+    /// Fuel capacity in liters
+    pub fuel_capacity: u8,
+    /// Brake in 0-255 range
+    pub brake: u8,
+    /// Throttle in 0-255 range
+    pub throttle: u8,
+    /// Clutch in 0-255 range
+    pub clutch: u8,
+    /// Fuel level in 0-1 range
+    pub fuel_level: f32,
+    /// Speed in meters per second
+    pub speed: f32,
+    pub rpm: u16,
+    pub max_rpm: u16,
+    /// Steering in -127 to 127 range
+    pub steering: i8,
+    /// Gear in the low 4 bits, where 15 is reverse, and number of gears in the high 4 bits
+    pub gear_num_gears: u8,
+    pub boost_amount: u8,
+    pub crash_state: u8,
+    pub odometer_km: f32,
+    pub orientation: [f32; 3],
+    pub local_velocity: [f32; 3],
+    pub world_velocity: [f32; 3],
+    pub angular_velocity: [f32; 3],
+    pub local_acceleration: [f32; 3],
+    pub world_acceleration: [f32; 3],
+    pub extents_centre: [f32; 3],
+    pub tyre_flags: [u8; 4],
+    pub terrain: [u8; 4],
+    pub tyre_y: [f32; 4],
+    pub tyre_rps: [f32; 4],
+    pub tyre_temp: [u8; 4],
+    pub tyre_height_above_ground: [f32; 4],
+    /// Tyre wear in 0-255 range
+    pub tyre_wear: [u8; 4],
+    pub brake_damage: [u8; 4],
+    pub suspension_damage: [u8; 4],
+    pub brake_temp_celsius: [i16; 4],
+    pub tyre_tread_temp: [u16; 4],
+    pub tyre_layer_temp: [u16; 4],
+    pub tyre_carcass_temp: [u16; 4],
+    pub tyre_rim_temp: [u16; 4],
+    pub tyre_internal_air_temp: [u16; 4],
+    pub tyre_temp_left: [u16; 4],
+    pub tyre_temp_center: [u16; 4],
+    pub tyre_temp_right: [u16; 4],
+    pub wheel_local_position_y: [f32; 4],
+    pub ride_height: [f32; 4],
+    pub suspension_travel: [f32; 4],
+    pub suspension_velocity: [f32; 4],
+    pub suspension_ride_height: [u16; 4],
+    pub air_pressure: [u16; 4],
+    pub engine_speed: f32,
+    pub engine_torque: f32,
+    pub wings: [u8; 2],
+    pub hand_brake: u8,
+    pub aero_damage: u8,
+    pub engine_damage: u8,
+    pub joy_pad: u32,
+    pub d_pad: u8,
+    pub tyre_compound: [String; 4],
+    pub turbo_boost_pressure: f32,
+    pub full_position: [f32; 3],
+    pub brake_bias: u8,
+    pub tick_count: u32,
+}
+
+impl TelemetryPacket {
+    /// -1 = reverse, 0 = neutral, 1 and up forward gears
+    pub fn gear(&self) -> i8 {
+        match self.gear_num_gears & 0x0f {
+            15 => -1,
+            v => v as i8,
+        }
+    }
+
+    pub fn num_gears(&self) -> u8 {
+        self.gear_num_gears >> 4
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RaceDefinitionPacket {
+    pub base: PacketBase,
+    pub world_fastest_lap_time: f32,
+    pub personal_fastest_lap_time: f32,
+    pub personal_fastest_sector_times: [f32; 3],
+    pub world_fastest_sector_times: [f32; 3],
+    /// Track length in meters
+    pub track_length: f32,
+    pub track_location: String,
+    pub track_variation: String,
+    pub translated_track_location: String,
+    pub translated_track_variation: String,
+    /// Number of laps, or the duration in 5 minute units if the top bit is set
+    pub laps_time_in_event: u16,
+    pub enforced_pit_stop_lap: i8,
+}
+
+impl RaceDefinitionPacket {
+    pub fn is_timed(&self) -> bool {
+        self.laps_time_in_event & 0x8000 != 0
+    }
+
+    /// Number of laps in the event, `None` for timed events.
+    pub fn laps_in_event(&self) -> Option<u16> {
+        Some(self.laps_time_in_event).filter(|_| !self.is_timed())
+    }
+}
+
+/// Names of up to 16 participants, starting from participant
+/// `(partial_packet_index - 1) * 16`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ParticipantsPacket {
+    pub base: PacketBase,
+    pub changed_timestamp: u32,
+    pub names: Vec<String>,
+    pub nationalities: [u32; PARTICIPANTS_PER_PACKET],
+    pub indices: [u16; PARTICIPANTS_PER_PACKET],
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TimingsPacket {
+    pub base: PacketBase,
+    pub num_participants: i8,
+    pub changed_timestamp: u32,
+    pub event_time_remaining: f32,
+    pub split_time_ahead: f32,
+    pub split_time_behind: f32,
+    pub split_time: f32,
+    pub participants: Vec<ParticipantTimings>,
+    pub local_participant_index: u16,
+    pub tick_count: u32,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ParticipantTimings {
+    /// Whole meters of the world position
+    pub world_position: [i16; 3],
+    pub orientation: [i16; 3],
+    pub current_lap_distance: u16,
+    /// Race position, with the top bit set if the participant is active
+    pub race_position: u8,
+    /// Sector in the low 3 bits, the rest are fractions of the world position
+    pub sector: u8,
+    /// Flag colour in the low 4 bits, flag reason in the high 4 bits
+    pub highest_flag: u8,
+    /// Pit mode in the low 4 bits, pit schedule in the high 4 bits
+    pub pit_mode_schedule: u8,
+    /// Vehicle index, with the top bit set if the participant is human
+    pub car_index: u16,
+    /// Race state in the low 3 bits, with bit 3 set if the lap is invalidated
+    pub race_state: u8,
+    pub current_lap: u8,
+    pub current_time: f32,
+    pub current_sector_time: f32,
+    pub mp_participant_index: u16,
+}
+
+impl ParticipantTimings {
+    pub fn is_active(&self) -> bool {
+        self.race_position & 0x80 != 0
+    }
+
+    pub fn is_human(&self) -> bool {
+        self.car_index & 0x8000 != 0
+    }
+
+    pub fn vehicle_index(&self) -> u16 {
+        self.car_index & 0x7fff
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct GameStatePacket {
+    pub base: PacketBase,
+    pub build_version_number: u16,
+    /// Game state in the low 3 bits, session state in the high 4 bits
+    pub game_state: u8,
+    pub ambient_temperature: i8,
+    pub track_temperature: i8,
+    pub rain_density: u8,
+    pub snow_density: u8,
+    pub wind_speed: i8,
+    pub wind_direction_x: i8,
+    pub wind_direction_y: i8,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TimeStatsPacket {
+    pub base: PacketBase,
+    pub changed_timestamp: u32,
+    pub stats: Vec<ParticipantStats>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ParticipantStats {
+    pub fastest_lap_time: f32,
+    pub last_lap_time: f32,
+    pub last_sector_time: f32,
+    pub fastest_sector_times: [f32; 3],
+    pub online_rep: u32,
+    pub mp_participant_index: u16,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct VehicleNamesPacket {
+    pub base: PacketBase,
+    pub vehicles: Vec<VehicleInfo>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct VehicleInfo {
+    pub index: u16,
+    pub class: u32,
+    pub name: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ClassNamesPacket {
+    pub base: PacketBase,
+    pub classes: Vec<ClassInfo>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ClassInfo {
+    pub class_index: u32,
+    pub name: String,
+}
+
+/// Latest packets received from the game.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct State {
+    pub telemetry: Option<TelemetryPacket>,
+    pub race_definition: Option<RaceDefinitionPacket>,
+    pub timings: Option<TimingsPacket>,
+    pub game_state: Option<GameStatePacket>,
+    pub time_stats: Option<TimeStatsPacket>,
+    /// Participant names, merged from all parts of the participants packets
+    pub participant_names: Vec<String>,
+    /// Vehicle names and classes by vehicle index
+    pub vehicles: HashMap<u16, VehicleInfo>,
+    /// Class names by class index
+    pub classes: HashMap<u32, String>,
+}
+
+impl State {
+    pub fn update(&mut self, packet: Packet) {
+        match packet {
+            Packet::Telemetry(v) => self.telemetry = Some(*v),
+            Packet::RaceDefinition(v) => self.race_definition = Some(v),
+            Packet::Participants(v) => {
+                let offset =
+                    (v.base.partial_packet_index.max(1) as usize - 1) * PARTICIPANTS_PER_PACKET;
+                if self.participant_names.len() < offset + v.names.len() {
+                    self.participant_names
+                        .resize(offset + v.names.len(), String::new());
+                }
+                for (i, name) in v.names.into_iter().enumerate() {
+                    self.participant_names[offset + i] = name;
+                }
+            }
+            Packet::Timings(v) => self.timings = Some(v),
+            Packet::GameState(v) => self.game_state = Some(v),
+            Packet::TimeStats(v) => self.time_stats = Some(v),
+            Packet::VehicleNames(v) => {
+                for vehicle in v.vehicles {
+                    self.vehicles.insert(vehicle.index, vehicle);
+                }
+            }
+            Packet::ClassNames(v) => {
+                for class in v.classes {
+                    self.classes.insert(class.class_index, class.name);
+                }
+            }
+            Packet::Other(_) => {}
+        }
+    }
+
+    fn vehicle_names(&self, vehicle_index: u16) -> (String, String) {
+        let Some(vehicle) = self.vehicles.get(&vehicle_index) else {
+            return Default::default();
+        };
+        let class = self.classes.get(&vehicle.class).cloned();
+        (vehicle.name.clone(), class.unwrap_or_default())
+    }
+
+    fn participants(&self) -> Vec<Participant> {
+        let Some(timings) = &self.timings else {
+            return vec![];
+        };
+        let count = usize::try_from(timings.num_participants).unwrap_or_default();
+        timings
+            .participants
+            .iter()
+            .take(count)
+            .enumerate()
+            .map(|(i, v)| {
+                let (car_name, car_class_name) = self.vehicle_names(v.vehicle_index());
+                let stats = self
+                    .time_stats
+                    .as_ref()
+                    .and_then(|stats| stats.stats.get(i));
+                Participant {
+                    is_active: v.is_active(),
+                    name: self.participant_names.get(i).cloned().unwrap_or_default(),
+                    world_position: v.world_position.map(|v| v as f32),
+                    current_lap_distance: v.current_lap_distance as f32,
+                    race_position: (v.race_position & 0x7f) as u32,
+                    laps_completed: (v.current_lap as u32).saturating_sub(1),
+                    current_lap: v.current_lap as u32,
+                    current_sector: (v.sector & 0x07) as i32,
+                    race_state: ((v.race_state & 0x07) as u32).into(),
+                    pit_mode: ((v.pit_mode_schedule & 0x0f) as u32).into(),
+                    highest_flag_colour: ((v.highest_flag & 0x0f) as u32).into(),
+                    lap_invalidated: v.race_state & 0x08 != 0,
+                    car_name,
+                    car_class_name,
+                    fastest_lap_time: stats.map_or(-1.0, |v| v.fastest_lap_time),
+                    last_lap_time: stats.map_or(-1.0, |v| v.last_lap_time),
+                }
+            })
+            .collect()
+    }
+
+    /// Combine the latest packets into the state of the viewed car.
+    ///
+    /// Fields of packets that were not received yet are left at their defaults.
+    pub fn sim_state(&self) -> SimState {
+        let mut state = SimState {
+            participants: self.participants(),
+            viewed_participant_index: -1,
+            best_lap_time: -1.0,
+            last_lap_time: -1.0,
+            current_time: -1.0,
+            event_time_remaining: -1.0,
+            ..SimState::default()
+        };
+        if let Some(v) = &self.game_state {
+            state.game_state = ((v.game_state & 0x07) as u32).into();
+            state.session_state = ((v.game_state >> 4) as u32).into();
+            state.ambient_temperature = v.ambient_temperature as f32;
+            state.track_temperature = v.track_temperature as f32;
+            state.rain_density = v.rain_density as f32 / 255.0;
+        }
+        if let Some(v) = &self.race_definition {
+            state.laps_in_event = v.laps_in_event().unwrap_or_default() as u32;
+            state.track_location = v.track_location.clone();
+            state.track_variation = v.track_variation.clone();
+            state.track_length = v.track_length;
+        }
+        if let Some(v) = &self.timings {
+            state.split_time_ahead = v.split_time_ahead;
+            state.split_time_behind = v.split_time_behind;
+            state.event_time_remaining = v.event_time_remaining;
+        }
+        if let Some(v) = &self.telemetry {
+            state.viewed_participant_index = v.viewed_participant_index as i32;
+            state.unfiltered_throttle = v.unfiltered_throttle as f32 / 255.0;
+            state.unfiltered_brake = v.unfiltered_brake as f32 / 255.0;
+            state.unfiltered_steering = v.unfiltered_steering as f32 / 127.0;
+            state.unfiltered_clutch = v.unfiltered_clutch as f32 / 255.0;
+            state.car_flags = v.car_flags as u32;
+            state.oil_temp_celsius = v.oil_temp_celsius as f32;
+            state.water_temp_celsius = v.water_temp_celsius as f32;
+            state.fuel_level = v.fuel_level;
+            state.fuel_capacity = v.fuel_capacity as f32;
+            state.speed = v.speed;
+            state.rpm = v.rpm as f32;
+            state.max_rpm = v.max_rpm as f32;
+            state.brake = v.brake as f32 / 255.0;
+            state.throttle = v.throttle as f32 / 255.0;
+            state.clutch = v.clutch as f32 / 255.0;
+            state.steering = v.steering as f32 / 127.0;
+            state.gear = v.gear() as i32;
+            state.num_gears = v.num_gears() as i32;
+            state.odometer_km = v.odometer_km;
+            state.boost_amount = v.boost_amount as f32;
+            state.orientation = v.orientation;
+            state.local_velocity = v.local_velocity;
+            state.world_velocity = v.world_velocity;
+            state.angular_velocity = v.angular_velocity;
+            state.local_acceleration = v.local_acceleration;
+            state.world_acceleration = v.world_acceleration;
+            state.tyre_temp = v.tyre_temp.map(|v| v as f32);
+            state.tyre_wear = v.tyre_wear.map(|v| v as f32 / 255.0);
+            state.brake_temp_celsius = v.brake_temp_celsius.map(|v| v as f32);
+        }
+        if let Some(viewed) = state.viewed_participant().cloned() {
+            let timings = self
+                .timings
+                .as_ref()
+                .and_then(|v| v.participants.get(state.viewed_participant_index as usize));
+            state.race_state = viewed.race_state;
+            state.highest_flag_colour = viewed.highest_flag_colour;
+            state.pit_mode = viewed.pit_mode;
+            state.car_name = viewed.car_name;
+            state.car_class_name = viewed.car_class_name;
+            state.best_lap_time = viewed.fastest_lap_time;
+            state.last_lap_time = viewed.last_lap_time;
+            if let Some(timings) = timings {
+                state.highest_flag_reason = (timings.highest_flag >> 4) as u32;
+                state.pit_schedule = (timings.pit_mode_schedule >> 4) as u32;
+                state.current_time = timings.current_time;
+            }
+        }
+        state
+    }
+}
+
+#[derive(Debug)]
+pub struct Client {
+    socket: UdpSocket,
+    state: State,
+}
+
+impl Client {
+    /// Listen on all interfaces, since the game broadcasts to the whole network.
+    pub const DEFAULT_URI: &'static str = "0.0.0.0:5606";
+
+    pub async fn connect(uri: &str, retry_delay: Duration) -> Self {
+        loop {
+            if let Ok(client) = Self::try_connect(uri).await {
+                return client;
+            }
+            tokio::time::sleep(retry_delay).await;
+        }
+    }
+
+    pub async fn try_connect(uri: &str) -> Result<Self> {
+        let mut slf = Self {
+            socket: UdpSocket::bind(uri).await?,
+            state: State::default(),
+        };
+        slf.next_sim_state().await?;
+        Ok(slf)
+    }
+
+    /// Wait for the next valid packet, and return the state with that packet merged into it.
+    ///
+    /// Datagrams that fail to parse are logged and skipped.
+    pub async fn next_sim_state(&mut self) -> Result<SimState> {
+        let mut buffer = [0u8; PACKET_BUFFER_SIZE];
+        loop {
+            let (size, _origin) = self.socket.recv_from(&mut buffer).await?;
+            match Packet::parse(&buffer[..size]) {
+                Ok(packet) => {
+                    self.state.update(packet);
+                    return Ok(self.state.sim_state());
+                }
+                Err(err) => log::warn!("Skipping Madness engine datagram: {err}"),
+            }
+        }
+    }
+
+    pub fn state(&self) -> &State {
+        &self.state
+    }
+}
+
+const PACKET_BUFFER_SIZE: usize = 2048;
+
+#[async_trait::async_trait]
+impl Simetry for Client {
+    fn name(&self) -> &str {
+        "MadnessEngine"
+    }
+
+    async fn next_moment(&mut self) -> Option<Box<dyn Moment + Send + Sync + 'static>> {
+        Some(Box::new(self.next_sim_state().await.ok()?))
+    }
+}
+
+fn read_base(data: &mut &[u8]) -> Result<PacketBase> {
+    Ok(PacketBase {
+        packet_number: data.read_u32::<LittleEndian>()?,
+        category_packet_number: data.read_u32::<LittleEndian>()?,
+        partial_packet_index: data.read_u8()?,
+        partial_packet_number: data.read_u8()?,
+        packet_type: data.read_u8()?,
+        packet_version: data.read_u8()?,
+    })
+}
+
+fn read_bytes<const N: usize>(data: &mut &[u8]) -> Result<[u8; N]> {
+    let mut out = [0; N];
+    data.read_exact(&mut out)?;
+    Ok(out)
+}
+
+fn read_string<const N: usize>(data: &mut &[u8]) -> Result<String> {
+    Ok(extract_string(&read_bytes::<N>(data)?))
+}
+
+fn read_f32s<const N: usize>(data: &mut &[u8]) -> Result<[f32; N]> {
+    let mut out = [0.0; N];
+    data.read_f32_into::<LittleEndian>(&mut out)?;
+    Ok(out)
+}
+
+fn read_i16s<const N: usize>(data: &mut &[u8]) -> Result<[i16; N]> {
+    let mut out = [0; N];
+    data.read_i16_into::<LittleEndian>(&mut out)?;
+    Ok(out)
+}
+
+fn read_u16s<const N: usize>(data: &mut &[u8]) -> Result<[u16; N]> {
+    let mut out = [0; N];
+    data.read_u16_into::<LittleEndian>(&mut out)?;
+    Ok(out)
+}
+
+fn read_u32s<const N: usize>(data: &mut &[u8]) -> Result<[u32; N]> {
+    let mut out = [0; N];
+    data.read_u32_into::<LittleEndian>(&mut out)?;
+    Ok(out)
+}
+
+fn read_telemetry(base: PacketBase, data: &mut &[u8]) -> Result<TelemetryPacket> {
+    Ok(TelemetryPacket {
+        base,
+        viewed_participant_index: data.read_i8()?,
+        unfiltered_throttle: data.read_u8()?,
+        unfiltered_brake: data.read_u8()?,
+        unfiltered_steering: data.read_i8()?,
+        unfiltered_clutch: data.read_u8()?,
+        car_flags: data.read_u8()?,
+        oil_temp_celsius: data.read_i16::<LittleEndian>()?,
+        oil_pressure_kpa: data.read_u16::<LittleEndian>()?,
+        water_temp_celsius: data.read_i16::<LittleEndian>()?,
+        water_pressure_kpa: data.read_u16::<LittleEndian>()?,
+        fuel_pressure_kpa: data.read_u16::<LittleEndian>()?,
+        fuel_capacity: data.read_u8()?,
+        brake: data.read_u8()?,
+        throttle: data.read_u8()?,
+        clutch: data.read_u8()?,
+        fuel_level: data.read_f32::<LittleEndian>()?,
+        speed: data.read_f32::<LittleEndian>()?,
+        rpm: data.read_u16::<LittleEndian>()?,
+        max_rpm: data.read_u16::<LittleEndian>()?,
+        steering: data.read_i8()?,
+        gear_num_gears: data.read_u8()?,
+        boost_amount: data.read_u8()?,
+        crash_state: data.read_u8()?,
+        odometer_km: data.read_f32::<LittleEndian>()?,
+        orientation: read_f32s(data)?,
+        local_velocity: read_f32s(data)?,
+        world_velocity: read_f32s(data)?,
+        angular_velocity: read_f32s(data)?,
+        local_acceleration: read_f32s(data)?,
+        world_acceleration: read_f32s(data)?,
+        extents_centre: read_f32s(data)?,
+        tyre_flags: read_bytes(data)?,
+        terrain: read_bytes(data)?,
+        tyre_y: read_f32s(data)?,
+        tyre_rps: read_f32s(data)?,
+        tyre_temp: read_bytes(data)?,
+        tyre_height_above_ground: read_f32s(data)?,
+        tyre_wear: read_bytes(data)?,
+        brake_damage: read_bytes(data)?,
+        suspension_damage: read_bytes(data)?,
+        brake_temp_celsius: read_i16s(data)?,
+        tyre_tread_temp: read_u16s(data)?,
+        tyre_layer_temp: read_u16s(data)?,
+        tyre_carcass_temp: read_u16s(data)?,
+        tyre_rim_temp: read_u16s(data)?,
+        tyre_internal_air_temp: read_u16s(data)?,
+        tyre_temp_left: read_u16s(data)?,
+        tyre_temp_center: read_u16s(data)?,
+        tyre_temp_right: read_u16s(data)?,
+        wheel_local_position_y: read_f32s(data)?,
+        ride_height: read_f32s(data)?,
+        suspension_travel: read_f32s(data)?,
+        suspension_velocity: read_f32s(data)?,
+        suspension_ride_height: read_u16s(data)?,
+        air_pressure: read_u16s(data)?,
+        engine_speed: data.read_f32::<LittleEndian>()?,
+        engine_torque: data.read_f32::<LittleEndian>()?,
+        wings: read_bytes(data)?,
+        hand_brake: data.read_u8()?,
+        aero_damage: data.read_u8()?,
+        engine_damage: data.read_u8()?,
+        joy_pad: data.read_u32::<LittleEndian>()?,
+        d_pad: data.read_u8()?,
+        tyre_compound: [
+            read_string::<40>(data)?,
+            read_string::<40>(data)?,
+            read_string::<40>(data)?,
+            read_string::<40>(data)?,
+        ],
+        turbo_boost_pressure: data.read_f32::<LittleEndian>()?,
+        full_position: read_f32s(data)?,
+        brake_bias: data.read_u8()?,
+        tick_count: data.read_u32::<LittleEndian>()?,
+    })
+}
+
+fn read_race_definition(base: PacketBase, data: &mut &[u8]) -> Result<RaceDefinitionPacket> {
+    Ok(RaceDefinitionPacket {
+        base,
+        world_fastest_lap_time: data.read_f32::<LittleEndian>()?,
+        personal_fastest_lap_time: data.read_f32::<LittleEndian>()?,
+        personal_fastest_sector_times: read_f32s(data)?,
+        world_fastest_sector_times: read_f32s(data)?,
+        track_length: data.read_f32::<LittleEndian>()?,
+        track_location: read_string::<64>(data)?,
+        track_variation: read_string::<64>(data)?,
+        translated_track_location: read_string::<64>(data)?,
+        translated_track_variation: read_string::<64>(data)?,
+        laps_time_in_event: data.read_u16::<LittleEndian>()?,
+        enforced_pit_stop_lap: data.read_i8()?,
+    })
+}
+
+fn read_timings(base: PacketBase, data: &mut &[u8]) -> Result<TimingsPacket> {
+    Ok(TimingsPacket {
+        base,
+        num_participants: data.read_i8()?,
+        changed_timestamp: data.read_u32::<LittleEndian>()?,
+        event_time_remaining: data.read_f32::<LittleEndian>()?,
+        split_time_ahead: data.read_f32::<LittleEndian>()?,
+        split_time_behind: data.read_f32::<LittleEndian>()?,
+        split_time: data.read_f32::<LittleEndian>()?,
+        participants: (0..UDP_STREAMER_PARTICIPANTS_SUPPORTED)
+            .map(|_| read_participant_timings(data))
+            .collect::<Result<_>>()?,
+        local_participant_index: data.read_u16::<LittleEndian>()?,
+        tick_count: data.read_u32::<LittleEndian>()?,
+    })
+}
+
+fn read_participant_timings(data: &mut &[u8]) -> Result<ParticipantTimings> {
+    Ok(ParticipantTimings {
+        world_position: read_i16s(data)?,
+        orientation: read_i16s(data)?,
+        current_lap_distance: data.read_u16::<LittleEndian>()?,
+        race_position: data.read_u8()?,
+        sector: data.read_u8()?,
+        highest_flag: data.read_u8()?,
+        pit_mode_schedule: data.read_u8()?,
+        car_index: data.read_u16::<LittleEndian>()?,
+        race_state: data.read_u8()?,
+        current_lap: data.read_u8()?,
+        current_time: data.read_f32::<LittleEndian>()?,
+        current_sector_time: data.read_f32::<LittleEndian>()?,
+        mp_participant_index: data.read_u16::<LittleEndian>()?,
+    })
+}
+
+fn read_game_state(base: PacketBase, data: &mut &[u8]) -> Result<GameStatePacket> {
+    Ok(GameStatePacket {
+        base,
+        build_version_number: data.read_u16::<LittleEndian>()?,
+        game_state: data.read_u8()?,
+        ambient_temperature: data.read_i8()?,
+        track_temperature: data.read_i8()?,
+        rain_density: data.read_u8()?,
+        snow_density: data.read_u8()?,
+        wind_speed: data.read_i8()?,
+        wind_direction_x: data.read_i8()?,
+        wind_direction_y: data.read_i8()?,
+    })
+}
+
+fn read_participant_stats(data: &mut &[u8]) -> Result<ParticipantStats> {
+    let stats = ParticipantStats {
+        fastest_lap_time: data.read_f32::<LittleEndian>()?,
+        last_lap_time: data.read_f32::<LittleEndian>()?,
+        last_sector_time: data.read_f32::<LittleEndian>()?,
+        fastest_sector_times: read_f32s(data)?,
+        online_rep: data.read_u32::<LittleEndian>()?,
+        mp_participant_index: data.read_u16::<LittleEndian>()?,
+    };
+    // Padding
+    read_bytes::<2>(data)?;
+    Ok(stats)
+}
+
+fn read_vehicle_info(data: &mut &[u8]) -> Result<VehicleInfo> {
+    let index = data.read_u16::<LittleEndian>()?;
+    // Padding
+    read_bytes::<2>(data)?;
+    Ok(VehicleInfo {
+        index,
+        class: data.read_u32::<LittleEndian>()?,
+        name: read_string::<64>(data)?,
+    })
+}
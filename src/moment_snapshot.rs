@@ -0,0 +1,169 @@
+use crate::{Moment, Pedals, RacingFlags, Session};
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use uom::si::f64::{AngularVelocity, Velocity};
+
+/// Owned copy of every value provided by a [`Moment`].
+///
+/// Can be captured from any moment, serialized, sent across threads, and replayed later,
+/// since it implements [`Moment`] itself.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct MomentSnapshot {
+    #[serde(default)]
+    pub vehicle_left: Option<bool>,
+    #[serde(default)]
+    pub vehicle_right: Option<bool>,
+    #[serde(default)]
+    pub gear: Option<i8>,
+    #[serde(default)]
+    pub speed: Option<Velocity>,
+    #[serde(default)]
+    pub engine_rotation_speed: Option<AngularVelocity>,
+    #[serde(default)]
+    pub max_engine_rotation_speed: Option<AngularVelocity>,
+    #[serde(default)]
+    pub pit_limiter_engaged: Option<bool>,
+    #[serde(default)]
+    pub in_pit_lane: Option<bool>,
+    #[serde(default)]
+    pub shift_point: Option<AngularVelocity>,
+    #[serde(default)]
+    pub flags: Option<RacingFlags>,
+    #[serde(default)]
+    pub vehicle_brand_id: Option<String>,
+    #[serde(default)]
+    pub vehicle_model_id: Option<String>,
+    #[serde(default)]
+    pub vehicle_unique_id: Option<String>,
+    #[serde(default)]
+    pub left_turn_indicator_on: Option<bool>,
+    #[serde(default)]
+    pub right_turn_indicator_on: Option<bool>,
+    #[serde(default)]
+    pub hazard_indicator_on: Option<bool>,
+    #[serde(default)]
+    pub ignition_on: Option<bool>,
+    #[serde(default)]
+    pub starter_on: Option<bool>,
+    #[serde(default)]
+    pub pedals: Option<Pedals>,
+    #[serde(default)]
+    pub pedals_raw: Option<Pedals>,
+    #[serde(default)]
+    pub session: Option<Session>,
+}
+
+impl MomentSnapshot {
+    /// Read every value of the moment into an owned snapshot.
+    pub fn capture<M: Moment + ?Sized>(moment: &M) -> Self {
+        Self {
+            vehicle_left: moment.is_vehicle_left(),
+            vehicle_right: moment.is_vehicle_right(),
+            gear: moment.vehicle_gear(),
+            speed: moment.vehicle_velocity(),
+            engine_rotation_speed: moment.vehicle_engine_rotation_speed(),
+            max_engine_rotation_speed: moment.vehicle_max_engine_rotation_speed(),
+            pit_limiter_engaged: moment.is_pit_limiter_engaged(),
+            in_pit_lane: moment.is_vehicle_in_pit_lane(),
+            shift_point: moment.shift_point(),
+            flags: moment.flags(),
+            vehicle_brand_id: moment.vehicle_brand_id().map(Cow::into_owned),
+            vehicle_model_id: moment.vehicle_model_id().map(Cow::into_owned),
+            vehicle_unique_id: moment.vehicle_unique_id().map(Cow::into_owned),
+            left_turn_indicator_on: moment.is_left_turn_indicator_on(),
+            right_turn_indicator_on: moment.is_right_turn_indicator_on(),
+            hazard_indicator_on: moment.is_hazard_indicator_on(),
+            ignition_on: moment.is_ignition_on(),
+            starter_on: moment.is_starter_on(),
+            pedals: moment.pedals(),
+            pedals_raw: moment.pedals_raw(),
+            session: moment.session(),
+        }
+    }
+}
+
+impl Moment for MomentSnapshot {
+    fn vehicle_gear(&self) -> Option<i8> {
+        self.gear
+    }
+
+    fn vehicle_velocity(&self) -> Option<Velocity> {
+        self.speed
+    }
+
+    fn vehicle_engine_rotation_speed(&self) -> Option<AngularVelocity> {
+        self.engine_rotation_speed
+    }
+
+    fn vehicle_max_engine_rotation_speed(&self) -> Option<AngularVelocity> {
+        self.max_engine_rotation_speed
+    }
+
+    fn is_pit_limiter_engaged(&self) -> Option<bool> {
+        self.pit_limiter_engaged
+    }
+
+    fn is_vehicle_in_pit_lane(&self) -> Option<bool> {
+        self.in_pit_lane
+    }
+
+    fn is_vehicle_left(&self) -> Option<bool> {
+        self.vehicle_left
+    }
+
+    fn is_vehicle_right(&self) -> Option<bool> {
+        self.vehicle_right
+    }
+
+    fn shift_point(&self) -> Option<AngularVelocity> {
+        self.shift_point
+    }
+
+    fn flags(&self) -> Option<RacingFlags> {
+        self.flags.clone()
+    }
+
+    fn vehicle_brand_id(&self) -> Option<Cow<str>> {
+        Some(self.vehicle_brand_id.as_ref()?.into())
+    }
+
+    fn vehicle_model_id(&self) -> Option<Cow<str>> {
+        Some(self.vehicle_model_id.as_ref()?.into())
+    }
+
+    fn vehicle_unique_id(&self) -> Option<Cow<str>> {
+        Some(self.vehicle_unique_id.as_ref()?.into())
+    }
+
+    fn is_left_turn_indicator_on(&self) -> Option<bool> {
+        self.left_turn_indicator_on
+    }
+
+    fn is_right_turn_indicator_on(&self) -> Option<bool> {
+        self.right_turn_indicator_on
+    }
+
+    fn is_hazard_indicator_on(&self) -> Option<bool> {
+        self.hazard_indicator_on
+    }
+
+    fn is_ignition_on(&self) -> Option<bool> {
+        self.ignition_on
+    }
+
+    fn is_starter_on(&self) -> Option<bool> {
+        self.starter_on
+    }
+
+    fn pedals(&self) -> Option<Pedals> {
+        self.pedals.clone()
+    }
+
+    fn pedals_raw(&self) -> Option<Pedals> {
+        self.pedals_raw.clone()
+    }
+
+    fn session(&self) -> Option<Session> {
+        self.session.clone()
+    }
+}
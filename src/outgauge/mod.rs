@@ -0,0 +1,452 @@
+//! Support for the OutGauge and OutSim UDP protocols of Live for Speed.
+//!
+//! The same protocols are also sent by BeamNG.drive and several other titles.
+//! Both have to be enabled in the game settings, and sent to the port the [`Client`] is bound to.
+//! Since the datagrams of both protocols have different sizes, they can share the same port.
+
+use crate::{Moment, Pedals, Simetry};
+use anyhow::{bail, Result};
+use byteorder::{LittleEndian, ReadBytesExt};
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::io::Read;
+use std::time::Duration;
+use tokio::net::UdpSocket;
+use uom::si::angular_velocity::revolution_per_minute;
+use uom::si::f64::{AngularVelocity, Velocity};
+use uom::si::velocity::meter_per_second;
+
+#[derive(Debug)]
+pub struct Client {
+    socket: UdpSocket,
+    state: SimState,
+}
+
+impl Client {
+    /// Port used by BeamNG.drive by default. Live for Speed has no default port.
+    pub const DEFAULT_URI: &'static str = "127.0.0.1:4444";
+
+    pub async fn connect(uri: &str, retry_delay: Duration) -> Self {
+        loop {
+            if let Ok(client) = Self::try_connect(uri).await {
+                return client;
+            }
+            tokio::time::sleep(retry_delay).await;
+        }
+    }
+
+    pub async fn try_connect(uri: &str) -> Result<Self> {
+        let mut slf = Self {
+            socket: UdpSocket::bind(uri).await?,
+            state: SimState::default(),
+        };
+        slf.next_sim_state().await?;
+        Ok(slf)
+    }
+
+    /// Wait for the next valid datagram, and return the state with it merged into it.
+    ///
+    /// Datagrams that match neither of the protocols are logged and skipped.
+    pub async fn next_sim_state(&mut self) -> Result<SimState> {
+        let mut buffer = [0u8; PACKET_BUFFER_SIZE];
+        loop {
+            let (size, _origin) = self.socket.recv_from(&mut buffer).await?;
+            match self.state.update(&buffer[..size]) {
+                Ok(()) => return Ok(self.state.clone()),
+                Err(err) => log::warn!("Skipping OutGauge datagram: {err}"),
+            }
+        }
+    }
+}
+
+const PACKET_BUFFER_SIZE: usize = 256;
+
+#[async_trait::async_trait]
+impl Simetry for Client {
+    fn name(&self) -> &str {
+        "OutGauge"
+    }
+
+    async fn next_moment(&mut self) -> Option<Box<dyn Moment + Send + Sync + 'static>> {
+        Some(Box::new(self.next_sim_state().await.ok()?))
+    }
+}
+
+/// Bits of [`OutGauge::flags`].
+pub mod flags {
+    /// Key
+    pub const SHIFT: u16 = 1 << 0;
+    /// Key
+    pub const CTRL: u16 = 1 << 1;
+    /// Show turbo gauge
+    pub const TURBO: u16 = 1 << 13;
+    /// Prefer kilometers over miles
+    pub const KM: u16 = 1 << 14;
+    /// Prefer bar over psi
+    pub const BAR: u16 = 1 << 15;
+}
+
+/// Bits of [`OutGauge::dash_lights`] and [`OutGauge::show_lights`].
+pub mod dash_lights {
+    /// Shift light
+    pub const SHIFT: u32 = 1 << 0;
+    /// Full beam
+    pub const FULL_BEAM: u32 = 1 << 1;
+    pub const HANDBRAKE: u32 = 1 << 2;
+    /// Pit speed limiter
+    pub const PIT_SPEED: u32 = 1 << 3;
+    /// Traction control active or switched off
+    pub const TC: u32 = 1 << 4;
+    /// Left turn signal
+    pub const SIGNAL_L: u32 = 1 << 5;
+    /// Right turn signal
+    pub const SIGNAL_R: u32 = 1 << 6;
+    /// Shared turn signal
+    pub const SIGNAL_ANY: u32 = 1 << 7;
+    /// Oil pressure warning
+    pub const OIL_WARN: u32 = 1 << 8;
+    /// Battery warning
+    pub const BATTERY: u32 = 1 << 9;
+    /// ABS active or switched off
+    pub const ABS: u32 = 1 << 10;
+    pub const SPARE: u32 = 1 << 11;
+}
+
+/// Latest datagram of each protocol.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct SimState {
+    pub outgauge: Option<OutGauge>,
+    pub outsim: Option<OutSim>,
+}
+
+impl SimState {
+    /// Parse a datagram of either protocol, and replace the previous one of the same protocol.
+    pub fn update(&mut self, datagram: &[u8]) -> Result<()> {
+        match datagram.len() {
+            OutGauge::SIZE | OutGauge::SIZE_WITH_ID => {
+                self.outgauge = Some(OutGauge::parse(datagram)?)
+            }
+            OutSim::SIZE | OutSim::SIZE_WITH_ID => self.outsim = Some(OutSim::parse(datagram)?),
+            size => bail!("Datagram of {size} bytes matches neither OutGauge nor OutSim"),
+        }
+        Ok(())
+    }
+
+    fn light(&self, light: u32) -> Option<bool> {
+        self.outgauge.as_ref()?.light(light)
+    }
+}
+
+/// Dashboard data of the viewed car.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct OutGauge {
+    /// Time in milliseconds, for checking the order
+    pub time_ms: u32,
+    /// Short car name
+    pub car: String,
+    /// See [`flags`]
+    pub flags: u16,
+    /// 0 = reverse, 1 = neutral, 2 = first gear...
+    pub gear: u8,
+    /// Unique ID of the viewed player, 0 if none
+    pub player_id: u8,
+    /// Speed in meters per second
+    pub speed: f32,
+    pub rpm: f32,
+    /// Turbo pressure in bar
+    pub turbo: f32,
+    /// Engine temperature in degrees Celsius
+    pub engine_temp: f32,
+    /// Fuel in 0-1 range
+    pub fuel: f32,
+    /// Oil pressure in bar
+    pub oil_pressure: f32,
+    /// Oil temperature in degrees Celsius
+    pub oil_temp: f32,
+    /// Lights available in the car, see [`dash_lights`]
+    pub dash_lights: u32,
+    /// Lights currently switched on, see [`dash_lights`]
+    pub show_lights: u32,
+    /// Throttle in 0-1 range
+    pub throttle: f32,
+    /// Brake in 0-1 range
+    pub brake: f32,
+    /// Clutch in 0-1 range
+    pub clutch: f32,
+    /// Usually fuel
+    pub display_1: String,
+    /// Usually settings
+    pub display_2: String,
+    /// Set only if an OutGauge ID is specified in the game settings
+    pub id: Option<i32>,
+}
+
+impl OutGauge {
+    pub const SIZE: usize = 92;
+    pub const SIZE_WITH_ID: usize = 96;
+
+    pub fn parse(datagram: &[u8]) -> Result<Self> {
+        if datagram.len() != Self::SIZE && datagram.len() != Self::SIZE_WITH_ID {
+            bail!(
+                "OutGauge datagram should have {} or {} bytes, got {}",
+                Self::SIZE,
+                Self::SIZE_WITH_ID,
+                datagram.len(),
+            );
+        }
+        let data = &mut &datagram[..];
+        Ok(Self {
+            time_ms: data.read_u32::<LittleEndian>()?,
+            car: read_string::<4>(data)?,
+            flags: data.read_u16::<LittleEndian>()?,
+            gear: data.read_u8()?,
+            player_id: data.read_u8()?,
+            speed: data.read_f32::<LittleEndian>()?,
+            rpm: data.read_f32::<LittleEndian>()?,
+            turbo: data.read_f32::<LittleEndian>()?,
+            engine_temp: data.read_f32::<LittleEndian>()?,
+            fuel: data.read_f32::<LittleEndian>()?,
+            oil_pressure: data.read_f32::<LittleEndian>()?,
+            oil_temp: data.read_f32::<LittleEndian>()?,
+            dash_lights: data.read_u32::<LittleEndian>()?,
+            show_lights: data.read_u32::<LittleEndian>()?,
+            throttle: data.read_f32::<LittleEndian>()?,
+            brake: data.read_f32::<LittleEndian>()?,
+            clutch: data.read_f32::<LittleEndian>()?,
+            display_1: read_string::<16>(data)?,
+            display_2: read_string::<16>(data)?,
+            id: match data.is_empty() {
+                true => None,
+                false => Some(data.read_i32::<LittleEndian>()?),
+            },
+        })
+    }
+
+    /// Whether the light is switched on, `None` if the car doesn't have it.
+    pub fn light(&self, light: u32) -> Option<bool> {
+        if self.dash_lights & light == 0 {
+            return None;
+        }
+        Some(self.show_lights & light != 0)
+    }
+
+    /// Encode the datagram, the inverse of [`OutGauge::parse`].
+    pub fn encode(&self) -> Vec<u8> {
+        let mut datagram = Vec::with_capacity(Self::SIZE_WITH_ID);
+        let data = &mut datagram;
+        data.extend(self.time_ms.to_le_bytes());
+        write_string::<4>(data, &self.car);
+        data.extend(self.flags.to_le_bytes());
+        data.extend([self.gear, self.player_id]);
+        for value in [
+            self.speed,
+            self.rpm,
+            self.turbo,
+            self.engine_temp,
+            self.fuel,
+            self.oil_pressure,
+            self.oil_temp,
+        ] {
+            data.extend(value.to_le_bytes());
+        }
+        data.extend(self.dash_lights.to_le_bytes());
+        data.extend(self.show_lights.to_le_bytes());
+        for value in [self.throttle, self.brake, self.clutch] {
+            data.extend(value.to_le_bytes());
+        }
+        write_string::<16>(data, &self.display_1);
+        write_string::<16>(data, &self.display_2);
+        if let Some(id) = self.id {
+            data.extend(id.to_le_bytes());
+        }
+        datagram
+    }
+
+    /// Fill the fields that [`Moment`] exposes, for sending data of other sims to dashboards
+    /// that understand this protocol. Other fields are zero, and `time_ms` is left to the caller.
+    pub fn from_moment(moment: &dyn Moment) -> Self {
+        let pedals = moment.pedals();
+        let rpm = moment.vehicle_engine_rotation_speed();
+        let mut dash_lights = 0;
+        let mut show_lights = 0;
+        let mut light = |light: u32, on: Option<bool>| {
+            if let Some(on) = on {
+                dash_lights |= light;
+                if on {
+                    show_lights |= light;
+                }
+            }
+        };
+        light(dash_lights::PIT_SPEED, moment.is_pit_limiter_engaged());
+        light(dash_lights::SIGNAL_L, moment.is_left_turn_indicator_on());
+        light(dash_lights::SIGNAL_R, moment.is_right_turn_indicator_on());
+        light(
+            dash_lights::SHIFT,
+            moment
+                .shift_point()
+                .zip(rpm)
+                .map(|(shift, rpm)| rpm >= shift),
+        );
+        Self {
+            time_ms: 0,
+            car: moment
+                .vehicle_model_id()
+                .map(|v| v.into_owned())
+                .unwrap_or_default(),
+            flags: 0,
+            gear: moment.vehicle_gear().unwrap_or(0).saturating_add(1).max(0) as u8,
+            player_id: 0,
+            speed: moment
+                .vehicle_velocity()
+                .map_or(0.0, |v| v.get::<meter_per_second>() as f32),
+            rpm: rpm.map_or(0.0, |v| v.get::<revolution_per_minute>() as f32),
+            turbo: 0.0,
+            engine_temp: 0.0,
+            fuel: 0.0,
+            oil_pressure: 0.0,
+            oil_temp: 0.0,
+            dash_lights,
+            show_lights,
+            throttle: pedals.as_ref().map_or(0.0, |v| v.throttle as f32),
+            brake: pedals.as_ref().map_or(0.0, |v| v.brake as f32),
+            clutch: pedals.as_ref().map_or(0.0, |v| v.clutch as f32),
+            display_1: String::new(),
+            display_2: String::new(),
+            id: None,
+        }
+    }
+}
+
+/// Motion data of the viewed car.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct OutSim {
+    /// Time in milliseconds, for checking the order
+    pub time_ms: u32,
+    /// Angular velocity in radians per second
+    pub angular_velocity: [f32; 3],
+    /// Heading in radians, anticlockwise from above
+    pub heading: f32,
+    /// Pitch in radians, anticlockwise from the right
+    pub pitch: f32,
+    /// Roll in radians, anticlockwise from the front
+    pub roll: f32,
+    /// Acceleration in meters per second squared
+    pub acceleration: [f32; 3],
+    /// Velocity in meters per second
+    pub velocity: [f32; 3],
+    /// Position in 1/65536 meters
+    pub position: [i32; 3],
+    /// Set only if an OutSim ID is specified in the game settings
+    pub id: Option<i32>,
+}
+
+impl OutSim {
+    pub const SIZE: usize = 64;
+    pub const SIZE_WITH_ID: usize = 68;
+
+    pub fn parse(datagram: &[u8]) -> Result<Self> {
+        if datagram.len() != Self::SIZE && datagram.len() != Self::SIZE_WITH_ID {
+            bail!(
+                "OutSim datagram should have {} or {} bytes, got {}",
+                Self::SIZE,
+                Self::SIZE_WITH_ID,
+                datagram.len(),
+            );
+        }
+        let data = &mut &datagram[..];
+        Ok(Self {
+            time_ms: data.read_u32::<LittleEndian>()?,
+            angular_velocity: read_vector(data)?,
+            heading: data.read_f32::<LittleEndian>()?,
+            pitch: data.read_f32::<LittleEndian>()?,
+            roll: data.read_f32::<LittleEndian>()?,
+            acceleration: read_vector(data)?,
+            velocity: read_vector(data)?,
+            position: [
+                data.read_i32::<LittleEndian>()?,
+                data.read_i32::<LittleEndian>()?,
+                data.read_i32::<LittleEndian>()?,
+            ],
+            id: match data.is_empty() {
+                true => None,
+                false => Some(data.read_i32::<LittleEndian>()?),
+            },
+        })
+    }
+
+    /// Position in meters.
+    pub fn position_meters(&self) -> [f64; 3] {
+        self.position.map(|v| v as f64 / 65536.0)
+    }
+}
+
+impl Moment for SimState {
+    fn vehicle_gear(&self) -> Option<i8> {
+        Some(self.outgauge.as_ref()?.gear.wrapping_sub(1) as i8)
+    }
+
+    fn vehicle_velocity(&self) -> Option<Velocity> {
+        Some(Velocity::new::<meter_per_second>(
+            self.outgauge.as_ref()?.speed as f64,
+        ))
+    }
+
+    fn vehicle_engine_rotation_speed(&self) -> Option<AngularVelocity> {
+        Some(AngularVelocity::new::<revolution_per_minute>(
+            self.outgauge.as_ref()?.rpm as f64,
+        ))
+    }
+
+    fn is_pit_limiter_engaged(&self) -> Option<bool> {
+        self.light(dash_lights::PIT_SPEED)
+    }
+
+    fn vehicle_model_id(&self) -> Option<Cow<str>> {
+        Some(self.outgauge.as_ref()?.car.as_str().into())
+    }
+
+    fn vehicle_unique_id(&self) -> Option<Cow<str>> {
+        self.vehicle_model_id()
+    }
+
+    fn is_left_turn_indicator_on(&self) -> Option<bool> {
+        self.light(dash_lights::SIGNAL_L)
+    }
+
+    fn is_right_turn_indicator_on(&self) -> Option<bool> {
+        self.light(dash_lights::SIGNAL_R)
+    }
+
+    fn pedals(&self) -> Option<Pedals> {
+        let outgauge = self.outgauge.as_ref()?;
+        Some(Pedals {
+            throttle: outgauge.throttle as f64,
+            brake: outgauge.brake as f64,
+            clutch: outgauge.clutch as f64,
+        })
+    }
+}
+
+fn read_string<const N: usize>(data: &mut &[u8]) -> Result<String> {
+    let mut bytes = [0u8; N];
+    data.read_exact(&mut bytes)?;
+    let length = bytes.iter().position(|v| *v == 0).unwrap_or(N);
+    Ok(String::from_utf8_lossy(&bytes[..length]).into_owned())
+}
+
+/// Write the string into `N` bytes, cutting it at a character boundary if it doesn't fit.
+fn write_string<const N: usize>(data: &mut Vec<u8>, value: &str) {
+    let mut length = value.len().min(N);
+    while !value.is_char_boundary(length) {
+        length -= 1;
+    }
+    let mut bytes = [0u8; N];
+    bytes[..length].copy_from_slice(&value.as_bytes()[..length]);
+    data.extend(bytes);
+}
+
+fn read_vector(data: &mut &[u8]) -> std::io::Result<[f32; 3]> {
+    let mut out = [0.0; 3];
+    data.read_f32_into::<LittleEndian>(&mut out)?;
+    Ok(out)
+}
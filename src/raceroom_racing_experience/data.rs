@@ -0,0 +1,1097 @@
+//! Owned copies of the R3E shared memory structures, with Rust naming and strings decoded.
+//!
+//! Field names and units match the C header provided by R3E.
+
+use crate::raceroom_racing_experience::bindings;
+use serde::{Deserialize, Serialize};
+
+pub(super) fn extract_string(data: &[u8]) -> String {
+    let length = data.iter().position(|v| *v == 0).unwrap_or(data.len());
+    String::from_utf8_lossy(&data[..length]).into_owned()
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Vec3<T> {
+    pub x: T,
+    pub y: T,
+    pub z: T,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Orientation {
+    pub pitch: f32,
+    pub yaw: f32,
+    pub roll: f32,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SectorStarts {
+    pub sector1: f32,
+    pub sector2: f32,
+    pub sector3: f32,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PlayerData {
+    /// Virtual physics time
+    /// Unit: Ticks (1 tick = 1/400th of a second)
+    pub game_simulation_ticks: i32,
+    /// Virtual physics time
+    /// Unit: Seconds
+    pub game_simulation_time: f64,
+    /// Car world-space position
+    pub position: Vec3<f64>,
+    /// Car world-space velocity
+    /// Unit: Meter per second (m/s)
+    pub velocity: Vec3<f64>,
+    /// Car local-space velocity
+    /// Unit: Meter per second (m/s)
+    pub local_velocity: Vec3<f64>,
+    /// Car world-space acceleration
+    /// Unit: Meter per second squared (m/s^2)
+    pub acceleration: Vec3<f64>,
+    /// Car local-space acceleration
+    /// Unit: Meter per second squared (m/s^2)
+    pub local_acceleration: Vec3<f64>,
+    /// Car body orientation
+    /// Unit: Euler angles
+    pub orientation: Vec3<f64>,
+    /// Car body rotation
+    pub rotation: Vec3<f64>,
+    /// Car body angular acceleration (torque divided by inertia)
+    pub angular_acceleration: Vec3<f64>,
+    /// Car world-space angular velocity
+    /// Unit: Radians per second
+    pub angular_velocity: Vec3<f64>,
+    /// Car local-space angular velocity
+    /// Unit: Radians per second
+    pub local_angular_velocity: Vec3<f64>,
+    /// Driver g-force local to car
+    pub local_g_force: Vec3<f64>,
+    /// Total steering force coming through steering bars
+    pub steering_force: f64,
+    pub steering_force_percentage: f64,
+    /// Current engine torque
+    pub engine_torque: f64,
+    /// Current downforce
+    /// Unit: Newtons (N)
+    pub current_downforce: f64,
+    /// Currently unused
+    pub voltage: f64,
+    pub ers_level: f64,
+    pub power_mgu_h: f64,
+    pub power_mgu_k: f64,
+    pub torque_mgu_k: f64,
+    /// Car setup (radians, meters, meters per second)
+    pub suspension_deflection: [f64; 4],
+    pub suspension_velocity: [f64; 4],
+    pub camber: [f64; 4],
+    pub ride_height: [f64; 4],
+    pub front_wing_height: f64,
+    pub front_roll_angle: f64,
+    pub rear_roll_angle: f64,
+    pub third_spring_suspension_deflection_front: f64,
+    pub third_spring_suspension_velocity_front: f64,
+    pub third_spring_suspension_deflection_rear: f64,
+    pub third_spring_suspension_velocity_rear: f64,
+    /// Reserved data
+    pub unused1: f64,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Flags {
+    /// Whether yellow flag is currently active
+    /// -1 = no data
+    /// 0 = not active
+    /// 1 = active
+    pub yellow: i32,
+    /// Whether yellow flag was caused by current slot
+    /// -1 = no data
+    /// 0 = didn't cause it
+    /// 1 = caused it
+    pub yellow_caused_it: i32,
+    /// Whether overtake of car in front by current slot is allowed under yellow flag
+    /// -1 = no data
+    /// 0 = not allowed
+    /// 1 = allowed
+    pub yellow_overtake: i32,
+    /// Whether you have gained positions illegaly under yellow flag to give back
+    /// -1 = no data
+    /// 0 = no positions gained
+    /// n = number of positions gained
+    pub yellow_positions_gained: i32,
+    /// Yellow flag for each sector; -1 = no data, 0 = not active, 1 = active
+    pub sector_yellow: [i32; 3],
+    /// Distance into track for closest yellow, -1.0 if no yellow flag exists
+    /// Unit: Meters (m)
+    pub closest_yellow_distance_into_track: f32,
+    /// Whether blue flag is currently active
+    /// -1 = no data
+    /// 0 = not active
+    /// 1 = active
+    pub blue: i32,
+    /// Whether black flag is currently active
+    /// -1 = no data
+    /// 0 = not active
+    /// 1 = active
+    pub black: i32,
+    /// Whether green flag is currently active
+    /// -1 = no data
+    /// 0 = not active
+    /// 1 = active
+    pub green: i32,
+    /// Whether checkered flag is currently active
+    /// -1 = no data
+    /// 0 = not active
+    /// 1 = active
+    pub checkered: i32,
+    /// Whether white flag is currently active
+    /// -1 = no data
+    /// 0 = not active
+    /// 1 = active
+    pub white: i32,
+    /// Whether black and white flag is currently active and reason
+    /// -1 = no data
+    /// 0 = not active
+    /// 1 = blue flag 1st warning
+    /// 2 = blue flag 2nd warning
+    /// 3 = wrong way
+    /// 4 = cutting track
+    pub black_and_white: i32,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CarDamage {
+    /// Range: 0.0 - 1.0
+    /// Note: -1.0 = N/A
+    pub engine: f32,
+    /// Range: 0.0 - 1.0
+    /// Note: -1.0 = N/A
+    pub transmission: f32,
+    /// Range: 0.0 - 1.0
+    /// Note: A bit arbitrary at the moment. 0.0 doesn't necessarily mean completely destroyed.
+    /// Note: -1.0 = N/A
+    pub aerodynamics: f32,
+    /// Range: 0.0 - 1.0
+    /// Note: -1.0 = N/A
+    pub suspension: f32,
+    /// Reserved data
+    pub unused1: f32,
+    pub unused2: f32,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CutTrackPenalties {
+    pub drive_through: i32,
+    pub stop_and_go: i32,
+    pub pit_stop: i32,
+    pub time_deduction: i32,
+    pub slow_down: i32,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Drs {
+    /// If DRS is equipped and allowed
+    /// 0 = No, 1 = Yes, -1 = N/A
+    pub equipped: i32,
+    /// Got DRS activation left
+    /// 0 = No, 1 = Yes, -1 = N/A
+    pub available: i32,
+    /// Number of DRS activations left this lap
+    /// Note: In sessions with 'endless' amount of drs activations per lap this value starts at int32::max
+    /// -1 = N/A
+    pub num_activations_left: i32,
+    /// DRS engaged
+    /// 0 = No, 1 = Yes, -1 = N/A
+    pub engaged: i32,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PushToPass {
+    pub available: i32,
+    pub engaged: i32,
+    pub amount_left: i32,
+    pub engaged_time_left: f32,
+    pub wait_time_left: f32,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TireTemp {
+    pub current_temp: [f32; 3],
+    pub optimal_temp: f32,
+    pub cold_temp: f32,
+    pub hot_temp: f32,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct BrakeTemp {
+    pub current_temp: f32,
+    pub optimal_temp: f32,
+    pub cold_temp: f32,
+    pub hot_temp: f32,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct AidSettings {
+    /// ABS; -1 = N/A, 0 = off, 1 = on, 5 = currently active
+    pub abs: i32,
+    /// TC; -1 = N/A, 0 = off, 1 = on, 5 = currently active
+    pub tc: i32,
+    /// ESP; -1 = N/A, 0 = off, 1 = on low, 2 = on medium, 3 = on high, 5 = currently active
+    pub esp: i32,
+    /// Countersteer; -1 = N/A, 0 = off, 1 = on, 5 = currently active
+    pub countersteer: i32,
+    /// Cornering; -1 = N/A, 0 = off, 1 = on, 5 = currently active
+    pub cornering: i32,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DriverInfo {
+    pub name: String,
+    pub car_number: i32,
+    pub class_id: i32,
+    pub model_id: i32,
+    pub team_id: i32,
+    pub livery_id: i32,
+    pub manufacturer_id: i32,
+    pub user_id: i32,
+    pub slot_id: i32,
+    pub class_performance_index: i32,
+    pub engine_type: i32,
+    pub car_width: f32,
+    pub car_length: f32,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DriverData {
+    pub driver_info: DriverInfo,
+    pub finish_status: i32,
+    pub place: i32,
+    pub place_class: i32,
+    pub lap_distance: f32,
+    pub position: Vec3<f32>,
+    pub track_sector: i32,
+    pub completed_laps: i32,
+    pub current_lap_valid: i32,
+    pub lap_time_current_self: f32,
+    pub sector_time_current_self: [f32; 3],
+    pub sector_time_previous_self: [f32; 3],
+    pub sector_time_best_self: [f32; 3],
+    pub time_delta_front: f32,
+    pub time_delta_behind: f32,
+    pub pitstop_status: i32,
+    pub in_pitlane: i32,
+    pub num_pitstops: i32,
+    pub penalties: CutTrackPenalties,
+    pub car_speed: f32,
+    pub tire_type_front: i32,
+    pub tire_type_rear: i32,
+    pub tire_subtype_front: i32,
+    pub tire_subtype_rear: i32,
+    pub base_penalty_weight: f32,
+    pub aid_penalty_weight: f32,
+    /// -1 unavailable, 0 = not engaged, 1 = engaged
+    pub drs_state: i32,
+    pub ptp_state: i32,
+    /// -1 unavailable, DriveThrough = 0, StopAndGo = 1, Pitstop = 2, Time = 3, Slowdown = 4, Disqualify = 5,
+    pub penalty_type: i32,
+    /// Based on the PenaltyType you can assume the reason is:
+    /// DriveThroughPenaltyInvalid = 0,
+    /// DriveThroughPenaltyCutTrack = 1,
+    /// DriveThroughPenaltyPitSpeeding = 2,
+    /// DriveThroughPenaltyFalseStart = 3,
+    /// DriveThroughPenaltyIgnoredBlue = 4,
+    /// DriveThroughPenaltyDrivingTooSlow = 5,
+    /// DriveThroughPenaltyIllegallyPassedBeforeGreen = 6,
+    /// DriveThroughPenaltyIllegallyPassedBeforeFinish = 7,
+    /// DriveThroughPenaltyIllegallyPassedBeforePitEntrance = 8,
+    /// DriveThroughPenaltyIgnoredSlowDown = 9,
+    /// DriveThroughPenaltyMax = 10
+    /// StopAndGoPenaltyInvalid = 0,
+    /// StopAndGoPenaltyCutTrack1st = 1,
+    /// StopAndGoPenaltyCutTrackMult = 2,
+    /// StopAndGoPenaltyYellowFlagOvertake = 3,
+    /// StopAndGoPenaltyMax = 4
+    /// PitstopPenaltyInvalid = 0,
+    /// PitstopPenaltyIgnoredPitstopWindow = 1,
+    /// PitstopPenaltyMax = 2
+    /// ServableTimePenaltyInvalid = 0,
+    /// ServableTimePenaltyServedMandatoryPitstopLate = 1,
+    /// ServableTimePenaltyIgnoredMinimumPitstopDuration = 2,
+    /// ServableTimePenaltyMax = 3
+    /// SlowDownPenaltyInvalid = 0,
+    /// SlowDownPenaltyCutTrack1st = 1,
+    /// SlowDownPenaltyCutTrackMult = 2,
+    /// SlowDownPenaltyMax = 3
+    /// DisqualifyPenaltyInvalid = -1,
+    /// DisqualifyPenaltyFalseStart = 0,
+    /// DisqualifyPenaltyPitlaneSpeeding = 1,
+    /// DisqualifyPenaltyWrongWay = 2,
+    /// DisqualifyPenaltyEnteringPitsUnderRed = 3,
+    /// DisqualifyPenaltyExitingPitsUnderRed = 4,
+    /// DisqualifyPenaltyFailedDriverChange = 5,
+    /// DisqualifyPenaltyThreeDriveThroughsInLap = 6,
+    /// DisqualifyPenaltyLappedFieldMultipleTimes = 7,
+    /// DisqualifyPenaltyIgnoredDriveThroughPenalty = 8,
+    /// DisqualifyPenaltyIgnoredStopAndGoPenalty = 9,
+    /// DisqualifyPenaltyIgnoredPitStopPenalty = 10,
+    /// DisqualifyPenaltyIgnoredTimePenalty = 11,
+    /// DisqualifyPenaltyExcessiveCutting = 12,
+    /// DisqualifyPenaltyIgnoredBlueFlag = 13,
+    /// DisqualifyPenaltyMax = 14
+    pub penalty_reason: i32,
+    /// -1 unavailable, 0 = ignition off, 1 = ignition on but not running, 2 = ignition on and running
+    pub engine_state: i32,
+    /// Orientation in Euler coordinates
+    pub orientation: Vec3<f32>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Shared {
+    /// ////////////////////////////////////////////////////////////////////////
+    /// Version
+    /// ////////////////////////////////////////////////////////////////////////
+    pub version_major: i32,
+    pub version_minor: i32,
+    /// Offset to num_cars
+    pub all_drivers_offset: i32,
+    /// size of the driver data struct
+    pub driver_data_size: i32,
+    /// ////////////////////////////////////////////////////////////////////////
+    /// Game State
+    /// ////////////////////////////////////////////////////////////////////////
+    pub game_paused: i32,
+    pub game_in_menus: i32,
+    pub game_in_replay: i32,
+    pub game_using_vr: i32,
+    /// Reserved data
+    pub game_unused1: i32,
+    /// ////////////////////////////////////////////////////////////////////////
+    /// High detail
+    /// ////////////////////////////////////////////////////////////////////////
+    /// High detail player vehicle data
+    pub player: PlayerData,
+    /// ////////////////////////////////////////////////////////////////////////
+    /// Event and session
+    /// ////////////////////////////////////////////////////////////////////////
+    pub track_name: String,
+    pub layout_name: String,
+    pub track_id: i32,
+    pub layout_id: i32,
+    pub layout_length: f32,
+    pub sector_start_factors: SectorStarts,
+    /// Race session durations
+    /// Note: Index 0-2 = race 1-3
+    /// Note: Value -1 = N/A
+    /// Note: If both laps and minutes are more than 0, race session starts with minutes then adds laps
+    pub race_session_laps: [i32; 3],
+    pub race_session_minutes: [i32; 3],
+    /// The current race event index, for championships with multiple events
+    /// Note: 0-indexed, -1 = N/A
+    pub event_index: i32,
+    /// Which session the player is in (practice, qualifying, race, etc.)
+    /// Note: See the r3e_session enum
+    pub session_type: i32,
+    /// The current iteration of the current type of session
+    /// Note: 1 = first, 2 = second etc, -1 = N/A
+    pub session_iteration: i32,
+    /// If the session is time based, lap based or time based with an extra lap at the end
+    pub session_length_format: i32,
+    /// Unit: Meter per second (m/s)
+    pub session_pit_speed_limit: f32,
+    /// Which phase the current session is in (gridwalk, countdown, green flag, etc.)
+    /// Note: See the r3e_session_phase enum
+    pub session_phase: i32,
+    /// Which phase start lights are in; -1 = unavailable, 0 = off, 1-5 = redlight on and counting down, 6 = greenlight on
+    /// Note: See the r3e_session_phase enum
+    pub start_lights: i32,
+    /// If tire wear is active (-1 = N/A, 0 = Off, 1 = 1x, 2 = 2x, 3 = 3x, 4 = 4x)
+    pub tire_wear_active: i32,
+    /// If fuel usage is active (-1 = N/A, 0 = Off, 1 = 1x, 2 = 2x, 3 = 3x, 4 = 4x)
+    pub fuel_use_active: i32,
+    /// Total number of laps in the race, or -1 if player is not in race mode (practice, test mode, etc.)
+    pub number_of_laps: i32,
+    /// Amount of time and time remaining for the current session
+    /// Note: Only available in time-based sessions, -1.0 = N/A
+    /// Units: Seconds
+    pub session_time_duration: f32,
+    pub session_time_remaining: f32,
+    /// Server max incident points, -1 = N/A
+    pub max_incident_points: i32,
+    /// Reserved data
+    pub event_unused2: f32,
+    /// ////////////////////////////////////////////////////////////////////////
+    /// Pit
+    /// ////////////////////////////////////////////////////////////////////////
+    /// Current status of the pit stop
+    /// Note: See the r3e_pit_window enum
+    pub pit_window_status: i32,
+    /// The minute/lap from which you're obligated to pit (-1 = N/A)
+    /// Unit: Minutes in time-based sessions, otherwise lap
+    pub pit_window_start: i32,
+    /// The minute/lap into which you need to have pitted (-1 = N/A)
+    /// Unit: Minutes in time-based sessions, otherwise lap
+    pub pit_window_end: i32,
+    /// If current vehicle is in pitlane (-1 = N/A)
+    pub in_pitlane: i32,
+    /// What is currently selected in pit menu, and array of states (preset/buttons: -1 = not selectable, 1 = selectable) (actions: -1 = N/A, 0 = unmarked for fix, 1 = marked for fix)
+    pub pit_menu_selection: i32,
+    pub pit_menu_state: [i32; 11],
+    /// Current vehicle pit state (-1 = N/A, 0 = None, 1 = Requested stop, 2 = Entered pitlane heading for pitspot, 3 = Stopped at pitspot, 4 = Exiting pitspot heading for pit exit)
+    pub pit_state: i32,
+    /// Current vehicle pitstop actions duration
+    pub pit_total_duration: f32,
+    pub pit_elapsed_time: f32,
+    /// Current vehicle pit action (-1 = N/A, 0 = None, 1 = Preparing, (combination of 2 = Penalty serve, 4 = Driver change, 8 = Refueling, 16 = Front tires, 32 = Rear tires, 64 = Body, 128 = Front wing, 256 = Rear wing, 512 = Suspension))
+    pub pit_action: i32,
+    /// Number of pitstops the current vehicle has performed (-1 = N/A)
+    pub num_pitstops: i32,
+    /// Pitstop with min duration (-1.0 = N/A, else seconds)
+    pub pit_min_duration_total: f32,
+    pub pit_min_duration_left: f32,
+    /// ////////////////////////////////////////////////////////////////////////
+    /// Scoring & Timings
+    /// ////////////////////////////////////////////////////////////////////////
+    /// The current state of each type of flag
+    pub flags: Flags,
+    /// Current position (1 = first place)
+    pub position: i32,
+    pub position_class: i32,
+    pub finish_status: i32,
+    /// Total number of cut track warnings (-1 = N/A)
+    pub cut_track_warnings: i32,
+    /// The number of penalties the car currently has pending of each type (-1 = N/A)
+    pub penalties: CutTrackPenalties,
+    /// Total number of penalties pending for the car
+    /// Note: See the 'penalties' field
+    pub num_penalties: i32,
+    /// How many laps the car has completed. If this value is 6, the car is on it's 7th lap. -1 = n/a
+    pub completed_laps: i32,
+    pub current_lap_valid: i32,
+    pub track_sector: i32,
+    pub lap_distance: f32,
+    /// fraction of lap completed, 0.0-1.0, -1.0 = N/A
+    pub lap_distance_fraction: f32,
+    /// The current best lap time for the leader of the session
+    /// Unit: Seconds (-1.0 = N/A)
+    pub lap_time_best_leader: f32,
+    /// The current best lap time for the leader of the current/viewed vehicle's class in the current session
+    /// Unit: Seconds (-1.0 = N/A)
+    pub lap_time_best_leader_class: f32,
+    /// Sector times of fastest lap by anyone in session
+    /// Unit: Seconds (-1.0 = N/A)
+    pub session_best_lap_sector_times: [f32; 3],
+    /// Best lap time
+    /// Unit: Seconds (-1.0 = N/A)
+    pub lap_time_best_self: f32,
+    pub sector_time_best_self: [f32; 3],
+    /// Previous lap
+    /// Unit: Seconds (-1.0 = N/A)
+    pub lap_time_previous_self: f32,
+    pub sector_time_previous_self: [f32; 3],
+    /// Current lap time
+    /// Unit: Seconds (-1.0 = N/A)
+    pub lap_time_current_self: f32,
+    pub sector_time_current_self: [f32; 3],
+    /// The time delta between this car's time and the leader
+    /// Unit: Seconds (-1.0 = N/A)
+    pub lap_time_delta_leader: f32,
+    /// The time delta between this car's time and the leader of the car's class
+    /// Unit: Seconds (-1.0 = N/A)
+    pub lap_time_delta_leader_class: f32,
+    /// Time delta between this car and the car placed in front
+    /// Unit: Seconds (-1.0 = N/A)
+    pub time_delta_front: f32,
+    /// Time delta between this car and the car placed behind
+    /// Unit: Seconds (-1.0 = N/A)
+    pub time_delta_behind: f32,
+    /// Time delta between this car's current laptime and this car's best laptime
+    /// Unit: Seconds (-1000.0 = N/A)
+    pub time_delta_best_self: f32,
+    /// Best time for each individual sector no matter lap
+    /// Unit: Seconds (-1.0 = N/A)
+    pub best_individual_sector_time_self: [f32; 3],
+    pub best_individual_sector_time_leader: [f32; 3],
+    pub best_individual_sector_time_leader_class: [f32; 3],
+    /// Incident points (-1 = N/A)
+    pub incident_points: i32,
+    /// -1 = N/A, 0 = this and next lap valid, 1 = this lap invalid, 2 = this and next lap invalid
+    pub lap_valid_state: i32,
+    /// Reserved data
+    pub score_unused1: f32,
+    pub score_unused2: f32,
+    /// ////////////////////////////////////////////////////////////////////////
+    /// Vehicle information
+    /// ////////////////////////////////////////////////////////////////////////
+    pub vehicle_info: DriverInfo,
+    pub player_name: String,
+    /// ////////////////////////////////////////////////////////////////////////
+    /// Vehicle state
+    /// ////////////////////////////////////////////////////////////////////////
+    /// Which controller is currently controlling the vehicle (AI, player, remote, etc.)
+    /// Note: See the r3e_control enum
+    pub control_type: i32,
+    /// Unit: Meter per second (m/s)
+    pub car_speed: f32,
+    /// Unit: Radians per second (rad/s)
+    pub engine_rps: f32,
+    pub max_engine_rps: f32,
+    pub upshift_rps: f32,
+    /// -2 = N/A, -1 = reverse, 0 = neutral, 1 = first gear, ... (for electric cars, gear is set to 2 if regenerative braking is enabled)
+    pub gear: i32,
+    /// -1 = N/A
+    pub num_gears: i32,
+    /// Physical location of car's center of gravity in world space (X, Y, Z) (Y = up)
+    pub car_cg_location: Vec3<f32>,
+    /// Pitch, yaw, roll
+    /// Unit: Radians (rad)
+    pub car_orientation: Orientation,
+    /// Acceleration in three axes (X, Y, Z) of car body in local-space.
+    /// From car center, +X=left, +Y=up, +Z=back.
+    /// Unit: Meter per second squared (m/s^2)
+    pub local_acceleration: Vec3<f32>,
+    /// Unit: Kilograms (kg)
+    /// Note: Car + penalty weight + fuel
+    pub total_mass: f32,
+    /// Unit: Liters (l)
+    /// Note: Fuel per lap show estimation when not enough data, then max recorded fuel per lap
+    /// Note: Not valid for remote players
+    pub fuel_left: f32,
+    pub fuel_capacity: f32,
+    pub fuel_per_lap: f32,
+    /// Unit: Celsius (C)
+    /// Note: Not valid for AI or remote players
+    pub engine_water_temp: f32,
+    pub engine_oil_temp: f32,
+    /// Unit: Kilopascals (KPa)
+    /// Note: Not valid for AI or remote players
+    pub fuel_pressure: f32,
+    pub engine_oil_pressure: f32,
+    /// Unit: (Bar)
+    /// Note: Not valid for AI or remote players (-1.0 = N/A)
+    pub turbo_pressure: f32,
+    /// How pressed the throttle pedal is
+    /// Range: 0.0 - 1.0 (-1.0 = N/A)
+    /// Note: Not valid for AI or remote players
+    pub throttle: f32,
+    pub throttle_raw: f32,
+    /// How pressed the brake pedal is
+    /// Range: 0.0 - 1.0 (-1.0 = N/A)
+    /// Note: Not valid for AI or remote players
+    pub brake: f32,
+    pub brake_raw: f32,
+    /// How pressed the clutch pedal is
+    /// Range: 0.0 - 1.0 (-1.0 = N/A)
+    /// Note: Not valid for AI or remote players
+    pub clutch: f32,
+    pub clutch_raw: f32,
+    /// How much the steering wheel is turned
+    /// Range: -1.0 - 1.0
+    /// Note: Not valid for AI or remote players
+    pub steer_input_raw: f32,
+    /// How many degrees in steer lock (center to full lock)
+    /// Note: Not valid for AI or remote players
+    pub steer_lock_degrees: i32,
+    /// How many degrees in wheel range (degrees full left to rull right)
+    /// Note: Not valid for AI or remote players
+    pub steer_wheel_range_degrees: i32,
+    /// Aid settings
+    pub aid_settings: AidSettings,
+    /// DRS data
+    pub drs: Drs,
+    /// Pit limiter (-1 = N/A, 0 = inactive, 1 = active)
+    pub pit_limiter: i32,
+    /// Push to pass data
+    pub push_to_pass: PushToPass,
+    /// How much the vehicle's brakes are biased towards the back wheels (0.3 = 30%, etc.) (-1.0 = N/A)
+    /// Note: Not valid for AI or remote players
+    pub brake_bias: f32,
+    /// DRS activations available in total (-1 = N/A or endless), placed outside of drs struct to keep backwards compatibility
+    pub drs_num_activations_total: i32,
+    /// PTP activations available in total (-1 = N/A, or there's no restriction per lap, or endless), placed outside of ptp struct to keep backwards compatibility
+    pub ptp_num_activations_total: i32,
+    /// Battery state of charge
+    /// Range: 0.0 - 100.0 (-1.0 = N/A)
+    pub battery_soc: f32,
+    /// Brake water tank (-1.0 = N/A)
+    /// Unit: Liters (l)
+    pub water_left: f32,
+    /// Reserved data
+    pub vehicle_unused1: Orientation,
+    /// ////////////////////////////////////////////////////////////////////////
+    /// Tires
+    /// ////////////////////////////////////////////////////////////////////////
+    /// Which type of tires the car has (option, prime, etc.)
+    /// Note: See the r3e_tire_type enum, deprecated - use the values further down instead
+    pub tire_type: i32,
+    /// Rotation speed
+    /// Uint: Radians per second
+    pub tire_rps: [f32; 4],
+    /// Wheel speed
+    /// Uint: Meters per second
+    pub tire_speed: [f32; 4],
+    /// Range: 0.0 - 1.0 (-1.0 = N/A)
+    pub tire_grip: [f32; 4],
+    /// Range: 0.0 - 1.0 (-1.0 = N/A)
+    pub tire_wear: [f32; 4],
+    /// (-1 = N/A, 0 = false, 1 = true)
+    pub tire_flatspot: [i32; 4],
+    /// Unit: Kilopascals (KPa) (-1.0 = N/A)
+    /// Note: Not valid for AI or remote players
+    pub tire_pressure: [f32; 4],
+    /// Percentage of dirt on tire (-1.0 = N/A)
+    /// Range: 0.0 - 1.0
+    pub tire_dirt: [f32; 4],
+    /// Current temperature of three points across the tread of the tire (-1.0 = N/A)
+    /// Optimum temperature
+    /// Cold temperature
+    /// Hot temperature
+    /// Unit: Celsius (C)
+    /// Note: Not valid for AI or remote players
+    pub tire_temp: [TireTemp; 4],
+    /// Which type of tires the car has (option, prime, etc.)
+    /// Note: See the r3e_tire_type enum
+    pub tire_type_front: i32,
+    pub tire_type_rear: i32,
+    /// Which subtype of tires the car has
+    /// Note: See the r3e_tire_subtype enum
+    pub tire_subtype_front: i32,
+    pub tire_subtype_rear: i32,
+    /// Current brake temperature (-1.0 = N/A)
+    /// Optimum temperature
+    /// Cold temperature
+    /// Hot temperature
+    /// Unit: Celsius (C)
+    /// Note: Not valid for AI or remote players
+    pub brake_temp: [BrakeTemp; 4],
+    /// Brake pressure (-1.0 = N/A)
+    /// Unit: Kilo Newtons (kN)
+    /// Note: Not valid for AI or remote players
+    pub brake_pressure: [f32; 4],
+    /// ////////////////////////////////////////////////////////////////////////
+    /// Electronics
+    /// ////////////////////////////////////////////////////////////////////////
+    /// -1 = N/A
+    pub traction_control_setting: i32,
+    pub engine_map_setting: i32,
+    pub engine_brake_setting: i32,
+    /// -1.0 = N/A, 0.0 -> 100.0 percent
+    pub traction_control_percent: f32,
+    /// Material under player car tires, see the r3e_mtrl_type enum
+    pub tire_on_mtrl: [i32; 4],
+    /// Tire load (N)
+    /// -1.0 = N/A
+    pub tire_load: [f32; 4],
+    /// ////////////////////////////////////////////////////////////////////////
+    /// Damage
+    /// ////////////////////////////////////////////////////////////////////////
+    /// The current state of various parts of the car
+    /// Note: Not valid for AI or remote players
+    pub car_damage: CarDamage,
+    /// ////////////////////////////////////////////////////////////////////////
+    /// Driver info
+    /// ////////////////////////////////////////////////////////////////////////
+    /// Number of cars (including the player) in the race
+    pub num_cars: i32,
+    /// Contains name and basic vehicle info for all drivers in place order
+    pub all_drivers_data_1: Vec<DriverData>,
+}
+
+impl From<bindings::r3e_vec3_f32> for Vec3<f32> {
+    fn from(v: bindings::r3e_vec3_f32) -> Self {
+        Self {
+            x: v.x,
+            y: v.y,
+            z: v.z,
+        }
+    }
+}
+
+impl From<bindings::r3e_vec3_f64> for Vec3<f64> {
+    fn from(v: bindings::r3e_vec3_f64) -> Self {
+        Self {
+            x: v.x,
+            y: v.y,
+            z: v.z,
+        }
+    }
+}
+
+impl From<bindings::r3e_ori_f32> for Orientation {
+    fn from(v: bindings::r3e_ori_f32) -> Self {
+        Self {
+            pitch: v.pitch,
+            yaw: v.yaw,
+            roll: v.roll,
+        }
+    }
+}
+
+impl From<bindings::r3e_sectorStarts> for SectorStarts {
+    fn from(v: bindings::r3e_sectorStarts) -> Self {
+        Self {
+            sector1: v.sector1,
+            sector2: v.sector2,
+            sector3: v.sector3,
+        }
+    }
+}
+
+impl From<bindings::r3e_playerdata> for PlayerData {
+    fn from(v: bindings::r3e_playerdata) -> Self {
+        Self {
+            game_simulation_ticks: v.game_simulation_ticks,
+            game_simulation_time: v.game_simulation_time,
+            position: v.position.into(),
+            velocity: v.velocity.into(),
+            local_velocity: v.local_velocity.into(),
+            acceleration: v.acceleration.into(),
+            local_acceleration: v.local_acceleration.into(),
+            orientation: v.orientation.into(),
+            rotation: v.rotation.into(),
+            angular_acceleration: v.angular_acceleration.into(),
+            angular_velocity: v.angular_velocity.into(),
+            local_angular_velocity: v.local_angular_velocity.into(),
+            local_g_force: v.local_g_force.into(),
+            steering_force: v.steering_force,
+            steering_force_percentage: v.steering_force_percentage,
+            engine_torque: v.engine_torque,
+            current_downforce: v.current_downforce,
+            voltage: v.voltage,
+            ers_level: v.ers_level,
+            power_mgu_h: v.power_mgu_h,
+            power_mgu_k: v.power_mgu_k,
+            torque_mgu_k: v.torque_mgu_k,
+            suspension_deflection: { v.suspension_deflection },
+            suspension_velocity: { v.suspension_velocity },
+            camber: { v.camber },
+            ride_height: { v.ride_height },
+            front_wing_height: v.front_wing_height,
+            front_roll_angle: v.front_roll_angle,
+            rear_roll_angle: v.rear_roll_angle,
+            third_spring_suspension_deflection_front: v.third_spring_suspension_deflection_front,
+            third_spring_suspension_velocity_front: v.third_spring_suspension_velocity_front,
+            third_spring_suspension_deflection_rear: v.third_spring_suspension_deflection_rear,
+            third_spring_suspension_velocity_rear: v.third_spring_suspension_velocity_rear,
+            unused1: v.unused1,
+        }
+    }
+}
+
+impl From<bindings::r3e_flags> for Flags {
+    fn from(v: bindings::r3e_flags) -> Self {
+        Self {
+            yellow: v.yellow,
+            yellow_caused_it: v.yellowCausedIt,
+            yellow_overtake: v.yellowOvertake,
+            yellow_positions_gained: v.yellowPositionsGained,
+            sector_yellow: { v.sector_yellow },
+            closest_yellow_distance_into_track: v.closest_yellow_distance_into_track,
+            blue: v.blue,
+            black: v.black,
+            green: v.green,
+            checkered: v.checkered,
+            white: v.white,
+            black_and_white: v.black_and_white,
+        }
+    }
+}
+
+impl From<bindings::r3e_car_damage> for CarDamage {
+    fn from(v: bindings::r3e_car_damage) -> Self {
+        Self {
+            engine: v.engine,
+            transmission: v.transmission,
+            aerodynamics: v.aerodynamics,
+            suspension: v.suspension,
+            unused1: v.unused1,
+            unused2: v.unused2,
+        }
+    }
+}
+
+impl From<bindings::r3e_cut_track_penalties> for CutTrackPenalties {
+    fn from(v: bindings::r3e_cut_track_penalties) -> Self {
+        Self {
+            drive_through: v.drive_through,
+            stop_and_go: v.stop_and_go,
+            pit_stop: v.pit_stop,
+            time_deduction: v.time_deduction,
+            slow_down: v.slow_down,
+        }
+    }
+}
+
+impl From<bindings::r3e_drs> for Drs {
+    fn from(v: bindings::r3e_drs) -> Self {
+        Self {
+            equipped: v.equipped,
+            available: v.available,
+            num_activations_left: v.numActivationsLeft,
+            engaged: v.engaged,
+        }
+    }
+}
+
+impl From<bindings::r3e_push_to_pass> for PushToPass {
+    fn from(v: bindings::r3e_push_to_pass) -> Self {
+        Self {
+            available: v.available,
+            engaged: v.engaged,
+            amount_left: v.amount_left,
+            engaged_time_left: v.engaged_time_left,
+            wait_time_left: v.wait_time_left,
+        }
+    }
+}
+
+impl From<bindings::r3e_tire_temp> for TireTemp {
+    fn from(v: bindings::r3e_tire_temp) -> Self {
+        Self {
+            current_temp: { v.current_temp },
+            optimal_temp: v.optimal_temp,
+            cold_temp: v.cold_temp,
+            hot_temp: v.hot_temp,
+        }
+    }
+}
+
+impl From<bindings::r3e_brake_temp> for BrakeTemp {
+    fn from(v: bindings::r3e_brake_temp) -> Self {
+        Self {
+            current_temp: v.current_temp,
+            optimal_temp: v.optimal_temp,
+            cold_temp: v.cold_temp,
+            hot_temp: v.hot_temp,
+        }
+    }
+}
+
+impl From<bindings::r3e_aid_settings> for AidSettings {
+    fn from(v: bindings::r3e_aid_settings) -> Self {
+        Self {
+            abs: v.abs,
+            tc: v.tc,
+            esp: v.esp,
+            countersteer: v.countersteer,
+            cornering: v.cornering,
+        }
+    }
+}
+
+impl From<bindings::r3e_driver_info> for DriverInfo {
+    fn from(v: bindings::r3e_driver_info) -> Self {
+        Self {
+            name: extract_string(&{ v.name }),
+            car_number: v.car_number,
+            class_id: v.class_id,
+            model_id: v.model_id,
+            team_id: v.team_id,
+            livery_id: v.livery_id,
+            manufacturer_id: v.manufacturer_id,
+            user_id: v.user_id,
+            slot_id: v.slot_id,
+            class_performance_index: v.class_performance_index,
+            engine_type: v.engine_type,
+            car_width: v.car_width,
+            car_length: v.car_length,
+        }
+    }
+}
+
+// Enum types are signed or unsigned depending on the platform bindings were generated on.
+#[allow(clippy::unnecessary_cast)]
+impl From<bindings::r3e_driver_data> for DriverData {
+    fn from(v: bindings::r3e_driver_data) -> Self {
+        Self {
+            driver_info: v.driver_info.into(),
+            finish_status: v.finish_status as i32,
+            place: v.place,
+            place_class: v.place_class,
+            lap_distance: v.lap_distance,
+            position: v.position.into(),
+            track_sector: v.track_sector,
+            completed_laps: v.completed_laps,
+            current_lap_valid: v.current_lap_valid,
+            lap_time_current_self: v.lap_time_current_self,
+            sector_time_current_self: { v.sector_time_current_self },
+            sector_time_previous_self: { v.sector_time_previous_self },
+            sector_time_best_self: { v.sector_time_best_self },
+            time_delta_front: v.time_delta_front,
+            time_delta_behind: v.time_delta_behind,
+            pitstop_status: v.pitstop_status as i32,
+            in_pitlane: v.in_pitlane,
+            num_pitstops: v.num_pitstops,
+            penalties: v.penalties.into(),
+            car_speed: v.car_speed,
+            tire_type_front: v.tire_type_front,
+            tire_type_rear: v.tire_type_rear,
+            tire_subtype_front: v.tire_subtype_front,
+            tire_subtype_rear: v.tire_subtype_rear,
+            base_penalty_weight: v.base_penalty_weight,
+            aid_penalty_weight: v.aid_penalty_weight,
+            drs_state: v.drs_state,
+            ptp_state: v.ptp_state,
+            penalty_type: v.penaltyType,
+            penalty_reason: v.penaltyReason,
+            engine_state: v.engineState,
+            orientation: v.orientation.into(),
+        }
+    }
+}
+
+// Enum types are signed or unsigned depending on the platform bindings were generated on.
+#[allow(clippy::unnecessary_cast)]
+impl From<bindings::r3e_shared> for Shared {
+    fn from(v: bindings::r3e_shared) -> Self {
+        Self {
+            version_major: v.version_major,
+            version_minor: v.version_minor,
+            all_drivers_offset: v.all_drivers_offset,
+            driver_data_size: v.driver_data_size,
+            game_paused: v.game_paused,
+            game_in_menus: v.game_in_menus,
+            game_in_replay: v.game_in_replay,
+            game_using_vr: v.game_using_vr,
+            game_unused1: v.game_unused1,
+            player: v.player.into(),
+            track_name: extract_string(&{ v.track_name }),
+            layout_name: extract_string(&{ v.layout_name }),
+            track_id: v.track_id,
+            layout_id: v.layout_id,
+            layout_length: v.layout_length,
+            sector_start_factors: v.sector_start_factors.into(),
+            race_session_laps: { v.race_session_laps },
+            race_session_minutes: { v.race_session_minutes },
+            event_index: v.event_index,
+            session_type: v.session_type,
+            session_iteration: v.session_iteration,
+            session_length_format: v.session_length_format as i32,
+            session_pit_speed_limit: v.session_pit_speed_limit,
+            session_phase: v.session_phase,
+            start_lights: v.start_lights,
+            tire_wear_active: v.tire_wear_active,
+            fuel_use_active: v.fuel_use_active,
+            number_of_laps: v.number_of_laps,
+            session_time_duration: v.session_time_duration,
+            session_time_remaining: v.session_time_remaining,
+            max_incident_points: v.max_incident_points,
+            event_unused2: v.event_unused2,
+            pit_window_status: v.pit_window_status,
+            pit_window_start: v.pit_window_start,
+            pit_window_end: v.pit_window_end,
+            in_pitlane: v.in_pitlane,
+            pit_menu_selection: v.pit_menu_selection as i32,
+            pit_menu_state: { v.pit_menu_state },
+            pit_state: v.pit_state,
+            pit_total_duration: v.pit_total_duration,
+            pit_elapsed_time: v.pit_elapsed_time,
+            pit_action: v.pit_action,
+            num_pitstops: v.num_pitstops,
+            pit_min_duration_total: v.pit_min_duration_total,
+            pit_min_duration_left: v.pit_min_duration_left,
+            flags: v.flags.into(),
+            position: v.position,
+            position_class: v.position_class,
+            finish_status: v.finish_status as i32,
+            cut_track_warnings: v.cut_track_warnings,
+            penalties: v.penalties.into(),
+            num_penalties: v.num_penalties,
+            completed_laps: v.completed_laps,
+            current_lap_valid: v.current_lap_valid,
+            track_sector: v.track_sector,
+            lap_distance: v.lap_distance,
+            lap_distance_fraction: v.lap_distance_fraction,
+            lap_time_best_leader: v.lap_time_best_leader,
+            lap_time_best_leader_class: v.lap_time_best_leader_class,
+            session_best_lap_sector_times: { v.session_best_lap_sector_times },
+            lap_time_best_self: v.lap_time_best_self,
+            sector_time_best_self: { v.sector_time_best_self },
+            lap_time_previous_self: v.lap_time_previous_self,
+            sector_time_previous_self: { v.sector_time_previous_self },
+            lap_time_current_self: v.lap_time_current_self,
+            sector_time_current_self: { v.sector_time_current_self },
+            lap_time_delta_leader: v.lap_time_delta_leader,
+            lap_time_delta_leader_class: v.lap_time_delta_leader_class,
+            time_delta_front: v.time_delta_front,
+            time_delta_behind: v.time_delta_behind,
+            time_delta_best_self: v.time_delta_best_self,
+            best_individual_sector_time_self: { v.best_individual_sector_time_self },
+            best_individual_sector_time_leader: { v.best_individual_sector_time_leader },
+            best_individual_sector_time_leader_class: {
+                v.best_individual_sector_time_leader_class
+            },
+            incident_points: v.incident_points,
+            lap_valid_state: v.lap_valid_state,
+            score_unused1: v.score_unused1,
+            score_unused2: v.score_unused2,
+            vehicle_info: v.vehicle_info.into(),
+            player_name: extract_string(&{ v.player_name }),
+            control_type: v.control_type,
+            car_speed: v.car_speed,
+            engine_rps: v.engine_rps,
+            max_engine_rps: v.max_engine_rps,
+            upshift_rps: v.upshift_rps,
+            gear: v.gear,
+            num_gears: v.num_gears,
+            car_cg_location: v.car_cg_location.into(),
+            car_orientation: v.car_orientation.into(),
+            local_acceleration: v.local_acceleration.into(),
+            total_mass: v.total_mass,
+            fuel_left: v.fuel_left,
+            fuel_capacity: v.fuel_capacity,
+            fuel_per_lap: v.fuel_per_lap,
+            engine_water_temp: v.engine_water_temp,
+            engine_oil_temp: v.engine_oil_temp,
+            fuel_pressure: v.fuel_pressure,
+            engine_oil_pressure: v.engine_oil_pressure,
+            turbo_pressure: v.turbo_pressure,
+            throttle: v.throttle,
+            throttle_raw: v.throttle_raw,
+            brake: v.brake,
+            brake_raw: v.brake_raw,
+            clutch: v.clutch,
+            clutch_raw: v.clutch_raw,
+            steer_input_raw: v.steer_input_raw,
+            steer_lock_degrees: v.steer_lock_degrees,
+            steer_wheel_range_degrees: v.steer_wheel_range_degrees,
+            aid_settings: v.aid_settings.into(),
+            drs: v.drs.into(),
+            pit_limiter: v.pit_limiter,
+            push_to_pass: v.push_to_pass.into(),
+            brake_bias: v.brake_bias,
+            drs_num_activations_total: v.drs_numActivationsTotal,
+            ptp_num_activations_total: v.ptp_numActivationsTotal,
+            battery_soc: v.battery_soc,
+            water_left: v.water_left,
+            vehicle_unused1: v.vehicle_unused1.into(),
+            tire_type: v.tire_type,
+            tire_rps: { v.tire_rps },
+            tire_speed: { v.tire_speed },
+            tire_grip: { v.tire_grip },
+            tire_wear: { v.tire_wear },
+            tire_flatspot: { v.tire_flatspot },
+            tire_pressure: { v.tire_pressure },
+            tire_dirt: { v.tire_dirt },
+            tire_temp: { v.tire_temp }.map(Into::into),
+            tire_type_front: v.tire_type_front,
+            tire_type_rear: v.tire_type_rear,
+            tire_subtype_front: v.tire_subtype_front,
+            tire_subtype_rear: v.tire_subtype_rear,
+            brake_temp: { v.brake_temp }.map(Into::into),
+            brake_pressure: { v.brake_pressure },
+            traction_control_setting: v.traction_control_setting,
+            engine_map_setting: v.engine_map_setting,
+            engine_brake_setting: v.engine_brake_setting,
+            traction_control_percent: v.traction_control_percent,
+            tire_on_mtrl: { v.tire_on_mtrl },
+            tire_load: { v.tire_load },
+            car_damage: v.car_damage.into(),
+            num_cars: v.num_cars,
+            all_drivers_data_1: { v.all_drivers_data_1 }
+                .into_iter()
+                .map(Into::into)
+                .collect(),
+        }
+    }
+}
@@ -0,0 +1,103 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Names of the manufacturers, cars, classes, tracks and layouts by their IDs, which are the
+/// only thing the shared memory provides.
+///
+/// Loaded from `r3e-data.json`, which the game ships in `Game/GameData/General` of its
+/// installation directory.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct GameData {
+    pub manufacturers: HashMap<i32, String>,
+    pub cars: HashMap<i32, String>,
+    pub classes: HashMap<i32, String>,
+    pub tracks: HashMap<i32, String>,
+    pub layouts: HashMap<i32, String>,
+}
+
+impl GameData {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let json = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        Self::from_json(&json)
+    }
+
+    pub fn from_json(json: &str) -> Result<Self> {
+        let file: DataFile = serde_json::from_str(json)?;
+        let names = |entries: HashMap<String, Entry>| {
+            entries
+                .into_values()
+                .map(|v| (v.id, v.name))
+                .collect::<HashMap<_, _>>()
+        };
+        let mut layouts = names(file.layouts);
+        layouts.extend(
+            file.tracks
+                .values()
+                .flat_map(|v| &v.layouts)
+                .map(|v| (v.id, v.name.clone())),
+        );
+        Ok(Self {
+            manufacturers: names(file.manufacturers),
+            cars: names(file.cars),
+            classes: names(file.classes),
+            tracks: file.tracks.into_values().map(|v| (v.id, v.name)).collect(),
+            layouts,
+        })
+    }
+
+    pub fn manufacturer_name(&self, id: i32) -> Option<&str> {
+        self.manufacturers.get(&id).map(String::as_str)
+    }
+
+    /// Name of the car, which is the model ID in the shared memory.
+    pub fn car_name(&self, id: i32) -> Option<&str> {
+        self.cars.get(&id).map(String::as_str)
+    }
+
+    pub fn class_name(&self, id: i32) -> Option<&str> {
+        self.classes.get(&id).map(String::as_str)
+    }
+
+    pub fn track_name(&self, id: i32) -> Option<&str> {
+        self.tracks.get(&id).map(String::as_str)
+    }
+
+    pub fn layout_name(&self, id: i32) -> Option<&str> {
+        self.layouts.get(&id).map(String::as_str)
+    }
+}
+
+/// Parts of `r3e-data.json` with names, whose entries are keyed by their IDs.
+#[derive(Deserialize)]
+struct DataFile {
+    #[serde(default)]
+    manufacturers: HashMap<String, Entry>,
+    #[serde(default)]
+    cars: HashMap<String, Entry>,
+    #[serde(default)]
+    classes: HashMap<String, Entry>,
+    #[serde(default)]
+    tracks: HashMap<String, Track>,
+    #[serde(default)]
+    layouts: HashMap<String, Entry>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct Entry {
+    id: i32,
+    name: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct Track {
+    id: i32,
+    name: String,
+    #[serde(default, rename = "layouts")]
+    layouts: Vec<Entry>,
+}
@@ -1,17 +1,27 @@
 use crate::windows_util::SharedMemory;
-use crate::{Moment, RacingFlags, Simetry};
+use crate::{Moment, Pedals, RacingFlags, Session, Simetry};
 use anyhow::{bail, Result};
+use serde::{Serialize, Serializer};
 use std::borrow::Cow;
+use std::sync::Arc;
 use std::time::Duration;
-use uom::si::angular_velocity::radian_per_second;
 use uom::si::f64::{AngularVelocity, Velocity};
-use uom::si::velocity::meter_per_second;
 
 pub mod bindings;
+pub mod data;
+mod game_data;
+mod view;
+
+pub use game_data::GameData;
+pub use view::{
+    ControlType, Driver, EngineState, FinishStatus, PitState, PitWindow, SessionLengthFormat,
+    SessionPhase, SessionType,
+};
 
 pub struct Client {
     shared_memory: SharedMemory,
     last_ticks: i32,
+    game_data: Option<Arc<GameData>>,
 }
 
 impl Client {
@@ -31,9 +41,16 @@ impl Client {
         Ok(Self {
             shared_memory,
             last_ticks: 0,
+            game_data: None,
         })
     }
 
+    /// Resolve the IDs of the shared memory to names with the data loaded from `r3e-data.json`.
+    pub fn with_game_data(mut self, game_data: GameData) -> Self {
+        self.game_data = Some(Arc::new(game_data));
+        self
+    }
+
     pub async fn next_sim_state(&mut self) -> Result<SimState> {
         loop {
             let r3e_shared = unsafe { self.shared_memory.copy_as::<bindings::r3e_shared>() };
@@ -59,7 +76,10 @@ impl Client {
                 continue;
             }
             self.last_ticks = r3e_shared.player.game_simulation_ticks;
-            return Ok(SimState { r3e_shared });
+            return Ok(SimState {
+                r3e_shared,
+                game_data: self.game_data.clone(),
+            });
         }
     }
 }
@@ -67,18 +87,43 @@ impl Client {
 #[derive(Debug)]
 pub struct SimState {
     pub r3e_shared: bindings::r3e_shared,
+    /// Names the IDs resolve to, `None` unless provided to the [`Client`].
+    pub game_data: Option<Arc<GameData>>,
+}
+
+/// Serializes as the owned [`data::Shared`] view.
+impl Serialize for SimState {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.data().serialize(serializer)
+    }
 }
 
 impl SimState {
+    /// Owned copy of the shared memory, with Rust naming and decoded strings.
+    pub fn data(&self) -> data::Shared {
+        self.r3e_shared.into()
+    }
+
+    /// Manufacturer name of the viewed vehicle, if the game data is provided.
+    pub fn manufacturer_name(&self) -> Option<&str> {
+        let id = self.r3e_shared.vehicle_info.manufacturer_id;
+        self.game_data.as_ref()?.manufacturer_name(id)
+    }
+
+    /// Car name of the viewed vehicle, if the game data is provided.
+    pub fn car_name(&self) -> Option<&str> {
+        let id = self.r3e_shared.vehicle_info.model_id;
+        self.game_data.as_ref()?.car_name(id)
+    }
+
+    /// Class name of the viewed vehicle, if the game data is provided.
+    pub fn class_name(&self) -> Option<&str> {
+        let id = self.r3e_shared.vehicle_info.class_id;
+        self.game_data.as_ref()?.class_name(id)
+    }
+
     pub fn current_driver_data(&self) -> Option<&bindings::r3e_driver_data> {
-        let slot_id = self.r3e_shared.vehicle_info.slot_id;
-        if slot_id < 0 {
-            return None;
-        }
-        self.r3e_shared
-            .all_drivers_data_1
-            .iter()
-            .find(|v| v.driver_info.slot_id == slot_id)
+        Some(self.player_driver()?.data)
     }
 }
 
@@ -95,59 +140,46 @@ impl Simetry for Client {
 
 impl Moment for SimState {
     fn vehicle_gear(&self) -> Option<i8> {
-        let gear = self.r3e_shared.gear as i8;
-        if gear == -2 {
-            return None;
-        }
-        Some(gear)
+        self.gear()
     }
 
     fn vehicle_velocity(&self) -> Option<Velocity> {
-        Some(Velocity::new::<meter_per_second>(
-            self.r3e_shared.car_speed as f64,
-        ))
+        Some(self.speed())
     }
 
     fn vehicle_engine_rotation_speed(&self) -> Option<AngularVelocity> {
-        Some(AngularVelocity::new::<radian_per_second>(
-            self.r3e_shared.engine_rps as f64,
-        ))
+        Some(self.engine_speed())
     }
 
     fn vehicle_max_engine_rotation_speed(&self) -> Option<AngularVelocity> {
-        let value = self.r3e_shared.max_engine_rps;
-        if value < 0.0 {
-            return None;
-        }
-        Some(AngularVelocity::new::<radian_per_second>(value as f64))
+        self.max_engine_speed()
     }
 
     fn is_pit_limiter_engaged(&self) -> Option<bool> {
-        let pit_limiter = self.r3e_shared.pit_limiter;
-        if pit_limiter == -1 {
-            return None;
-        }
-        Some(pit_limiter != 0)
+        self.is_pit_limiter_active()
     }
 
     fn is_vehicle_in_pit_lane(&self) -> Option<bool> {
-        let in_pitlane = self.r3e_shared.in_pitlane;
-        if in_pitlane == -1 {
-            return None;
-        }
-        Some(in_pitlane != 0)
+        self.is_in_pit_lane()
+    }
+
+    fn is_vehicle_left(&self) -> Option<bool> {
+        Some(self.cars_alongside()?.0)
+    }
+
+    fn is_vehicle_right(&self) -> Option<bool> {
+        Some(self.cars_alongside()?.1)
     }
 
     fn shift_point(&self) -> Option<AngularVelocity> {
-        let value = self.r3e_shared.upshift_rps;
-        if value < 0.0 {
-            return None;
-        }
-        Some(AngularVelocity::new::<radian_per_second>(value as f64))
+        self.upshift_speed()
     }
 
     fn flags(&self) -> Option<RacingFlags> {
         let flags = self.r3e_shared.flags;
+        let start_lights = self
+            .start_lights()
+            .filter(|_| self.session_phase() == Some(SessionPhase::Countdown));
         Some(RacingFlags {
             green: flags.green > 0,
             yellow: flags.yellow > 0,
@@ -158,23 +190,79 @@ impl Moment for SimState {
             checkered: flags.checkered > 0,
             meatball: false,
             black_and_white: flags.black_and_white > 0,
-            start_ready: false,
-            start_set: false,
-            start_go: false,
+            start_ready: start_lights == Some(0),
+            start_set: start_lights.is_some_and(|v| (1..=5).contains(&v)),
+            start_go: start_lights.is_some_and(|v| v >= 6),
         })
     }
 
-    fn vehicle_brand_id(&self) -> Option<Cow<str>> {
+    fn vehicle_brand_id(&self) -> Option<Cow<'_, str>> {
+        if let Some(name) = self.manufacturer_name() {
+            return Some(name.into());
+        }
         let value = self.r3e_shared.vehicle_info.manufacturer_id;
-        Some(value.to_string().into())
+        Some(value)
+            .filter(|v| *v >= 0)
+            .map(|v| v.to_string().into())
     }
 
-    fn vehicle_model_id(&self) -> Option<Cow<str>> {
+    fn vehicle_model_id(&self) -> Option<Cow<'_, str>> {
+        if let Some(name) = self.car_name() {
+            return Some(name.into());
+        }
         let value = self.r3e_shared.vehicle_info.model_id;
-        Some(value.to_string().into())
+        Some(value)
+            .filter(|v| *v >= 0)
+            .map(|v| v.to_string().into())
     }
 
     fn is_ignition_on(&self) -> Option<bool> {
-        Some(self.current_driver_data()?.engineState > 0)
+        Some(self.player_driver()?.engine_state()? != EngineState::Off)
+    }
+
+    fn pedals(&self) -> Option<Pedals> {
+        Some(Pedals {
+            throttle: self.throttle()?,
+            brake: self.brake()?,
+            clutch: self.clutch()?,
+        })
+    }
+
+    fn pedals_raw(&self) -> Option<Pedals> {
+        Some(Pedals {
+            throttle: self.throttle_raw()?,
+            brake: self.brake_raw()?,
+            clutch: self.clutch_raw()?,
+        })
+    }
+
+    fn session(&self) -> Option<Session> {
+        let phase = if self.is_game_paused() {
+            Some(crate::SessionPhase::Stopped)
+        } else {
+            self.session_phase().map(Into::into)
+        };
+        let total_laps = self.number_of_laps();
+        let completed_laps = self.completed_laps().unwrap_or_default();
+        let track_id = self.r3e_shared.track_id;
+        let layout_id = self.r3e_shared.layout_id;
+        let track_id = self
+            .game_data
+            .as_deref()
+            .and_then(|v| {
+                let track = v.track_name(track_id)?;
+                let layout = v.layout_name(layout_id)?;
+                Some(format!("{track} - {layout}"))
+            })
+            .unwrap_or_else(|| layout_id.to_string());
+        Some(Session {
+            session_type: self.session_type().map(Into::into),
+            phase,
+            time_remaining: self.session_time_remaining(),
+            laps_remaining: total_laps.map(|v| v.saturating_sub(completed_laps)),
+            total_laps,
+            track_id: Some(track_id),
+            is_replay: Some(self.is_game_in_replay()),
+        })
     }
 }
@@ -0,0 +1,597 @@
+//! Accessors over the raw shared memory with units, enums, and unavailable values as `None`.
+
+use crate::raceroom_racing_experience::data::{extract_string, Vec3};
+use crate::raceroom_racing_experience::{bindings, SimState};
+use serde::{Deserialize, Serialize};
+use uom::si::angular_velocity::radian_per_second;
+use uom::si::f64::{AngularVelocity, Length, ThermodynamicTemperature, Time, Velocity, Volume};
+use uom::si::length::meter;
+use uom::si::thermodynamic_temperature::degree_celsius;
+use uom::si::time::second;
+use uom::si::velocity::meter_per_second;
+use uom::si::volume::liter;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum SessionType {
+    Practice,
+    Qualify,
+    Race,
+    Warmup,
+}
+
+impl SessionType {
+    fn from_raw(value: i32) -> Option<Self> {
+        match value {
+            0 => Some(Self::Practice),
+            1 => Some(Self::Qualify),
+            2 => Some(Self::Race),
+            3 => Some(Self::Warmup),
+            _ => None,
+        }
+    }
+}
+
+impl From<SessionType> for crate::SessionType {
+    fn from(value: SessionType) -> Self {
+        match value {
+            SessionType::Practice => Self::Practice,
+            SessionType::Qualify => Self::Qualifying,
+            SessionType::Race => Self::Race,
+            SessionType::Warmup => Self::Warmup,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum SessionPhase {
+    /// Cars are in the garage.
+    Garage,
+    /// Cars are on the grid before the formation lap.
+    Gridwalk,
+    /// Formation lap, rolling start, etc.
+    Formation,
+    /// Start lights are counting down.
+    Countdown,
+    /// Session is running.
+    Green,
+    /// Checkered flag has been shown.
+    Checkered,
+}
+
+impl SessionPhase {
+    fn from_raw(value: i32) -> Option<Self> {
+        match value {
+            1 => Some(Self::Garage),
+            2 => Some(Self::Gridwalk),
+            3 => Some(Self::Formation),
+            4 => Some(Self::Countdown),
+            5 => Some(Self::Green),
+            6 => Some(Self::Checkered),
+            _ => None,
+        }
+    }
+}
+
+impl From<SessionPhase> for crate::SessionPhase {
+    fn from(value: SessionPhase) -> Self {
+        match value {
+            SessionPhase::Garage | SessionPhase::Gridwalk => Self::Waiting,
+            SessionPhase::Formation => Self::Formation,
+            SessionPhase::Countdown => Self::Countdown,
+            SessionPhase::Green => Self::Green,
+            SessionPhase::Checkered => Self::Checkered,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum SessionLengthFormat {
+    TimeBased,
+    LapBased,
+    /// Time based, with an extra lap after the time runs out.
+    TimeAndLapBased,
+}
+
+impl SessionLengthFormat {
+    fn from_raw(value: i32) -> Option<Self> {
+        match value {
+            0 => Some(Self::TimeBased),
+            1 => Some(Self::LapBased),
+            2 => Some(Self::TimeAndLapBased),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum PitState {
+    None,
+    /// Pit stop is requested.
+    Requested,
+    /// In the pit lane, heading for the pit spot.
+    Entered,
+    /// Stopped at the pit spot.
+    Stopped,
+    /// Leaving the pit spot, heading for the pit exit.
+    Exiting,
+}
+
+impl PitState {
+    fn from_raw(value: i32) -> Option<Self> {
+        match value {
+            0 => Some(Self::None),
+            1 => Some(Self::Requested),
+            2 => Some(Self::Entered),
+            3 => Some(Self::Stopped),
+            4 => Some(Self::Exiting),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum PitWindow {
+    /// The session has no mandatory pit stop.
+    Disabled,
+    Closed,
+    Open,
+    /// Stopped in the pit spot during the window.
+    Stopped,
+    /// The mandatory pit stop is done.
+    Completed,
+}
+
+impl PitWindow {
+    fn from_raw(value: i32) -> Option<Self> {
+        match value {
+            0 => Some(Self::Disabled),
+            1 => Some(Self::Closed),
+            2 => Some(Self::Open),
+            3 => Some(Self::Stopped),
+            4 => Some(Self::Completed),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum FinishStatus {
+    /// Still running.
+    None,
+    Finished,
+    /// Did not finish.
+    Dnf,
+    /// Did not qualify.
+    Dnq,
+    /// Did not start.
+    Dns,
+    /// Disqualified.
+    Dq,
+}
+
+impl FinishStatus {
+    fn from_raw(value: i32) -> Option<Self> {
+        match value {
+            0 => Some(Self::None),
+            1 => Some(Self::Finished),
+            2 => Some(Self::Dnf),
+            3 => Some(Self::Dnq),
+            4 => Some(Self::Dns),
+            5 => Some(Self::Dq),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum ControlType {
+    Player,
+    Ai,
+    Remote,
+    Replay,
+}
+
+impl ControlType {
+    fn from_raw(value: i32) -> Option<Self> {
+        match value {
+            0 => Some(Self::Player),
+            1 => Some(Self::Ai),
+            2 => Some(Self::Remote),
+            3 => Some(Self::Replay),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum EngineState {
+    Off,
+    /// Ignition is on, but the engine isn't running.
+    IgnitionOn,
+    Running,
+}
+
+impl EngineState {
+    fn from_raw(value: i32) -> Option<Self> {
+        match value {
+            0 => Some(Self::Off),
+            1 => Some(Self::IgnitionOn),
+            2 => Some(Self::Running),
+            _ => None,
+        }
+    }
+}
+
+/// Map the -1 of unavailable counts and indices to `None`.
+fn non_negative(value: i32) -> Option<u32> {
+    u32::try_from(value).ok()
+}
+
+/// Map the -1 of unavailable flags to `None`.
+fn flag(value: i32) -> Option<bool> {
+    (value >= 0).then_some(value > 0)
+}
+
+/// Map the -1.0 of unavailable values to `None`.
+fn available(value: f32) -> Option<f64> {
+    (value >= 0.0).then_some(value as f64)
+}
+
+fn seconds(value: f32) -> Option<Time> {
+    available(value).map(Time::new::<second>)
+}
+
+impl SimState {
+    pub fn is_game_paused(&self) -> bool {
+        self.r3e_shared.game_paused > 0
+    }
+
+    pub fn is_game_in_menus(&self) -> bool {
+        self.r3e_shared.game_in_menus > 0
+    }
+
+    pub fn is_game_in_replay(&self) -> bool {
+        self.r3e_shared.game_in_replay > 0
+    }
+
+    pub fn track_name(&self) -> String {
+        extract_string(&self.r3e_shared.track_name)
+    }
+
+    pub fn layout_name(&self) -> String {
+        extract_string(&self.r3e_shared.layout_name)
+    }
+
+    /// Length of the track layout.
+    pub fn layout_length(&self) -> Option<Length> {
+        available(self.r3e_shared.layout_length).map(Length::new::<meter>)
+    }
+
+    pub fn session_type(&self) -> Option<SessionType> {
+        SessionType::from_raw(self.r3e_shared.session_type)
+    }
+
+    pub fn session_phase(&self) -> Option<SessionPhase> {
+        SessionPhase::from_raw(self.r3e_shared.session_phase)
+    }
+
+    pub fn session_length_format(&self) -> Option<SessionLengthFormat> {
+        SessionLengthFormat::from_raw(self.r3e_shared.session_length_format)
+    }
+
+    /// Remaining time, only available in time based sessions.
+    pub fn session_time_remaining(&self) -> Option<Time> {
+        seconds(self.r3e_shared.session_time_remaining)
+    }
+
+    /// Total number of laps in the race, not available outside of races.
+    pub fn number_of_laps(&self) -> Option<u32> {
+        non_negative(self.r3e_shared.number_of_laps).filter(|v| *v > 0)
+    }
+
+    /// Frame of the start lights, 0 while they're off, 1-5 for the red lights counting down and
+    /// 6 for the green light.
+    pub fn start_lights(&self) -> Option<u32> {
+        non_negative(self.r3e_shared.start_lights)
+    }
+
+    pub fn pit_speed_limit(&self) -> Option<Velocity> {
+        available(self.r3e_shared.session_pit_speed_limit).map(Velocity::new::<meter_per_second>)
+    }
+
+    pub fn pit_state(&self) -> Option<PitState> {
+        PitState::from_raw(self.r3e_shared.pit_state)
+    }
+
+    pub fn pit_window(&self) -> Option<PitWindow> {
+        PitWindow::from_raw(self.r3e_shared.pit_window_status)
+    }
+
+    pub fn is_in_pit_lane(&self) -> Option<bool> {
+        flag(self.r3e_shared.in_pitlane)
+    }
+
+    pub fn is_pit_limiter_active(&self) -> Option<bool> {
+        flag(self.r3e_shared.pit_limiter)
+    }
+
+    /// Who is in control of the viewed vehicle.
+    pub fn control_type(&self) -> Option<ControlType> {
+        ControlType::from_raw(self.r3e_shared.control_type)
+    }
+
+    /// 1-based position in the session.
+    pub fn position(&self) -> Option<u32> {
+        non_negative(self.r3e_shared.position).filter(|v| *v > 0)
+    }
+
+    pub fn finish_status(&self) -> Option<FinishStatus> {
+        FinishStatus::from_raw(self.r3e_shared.finish_status)
+    }
+
+    pub fn completed_laps(&self) -> Option<u32> {
+        non_negative(self.r3e_shared.completed_laps)
+    }
+
+    pub fn lap_time_best(&self) -> Option<Time> {
+        seconds(self.r3e_shared.lap_time_best_self)
+    }
+
+    pub fn lap_time_previous(&self) -> Option<Time> {
+        seconds(self.r3e_shared.lap_time_previous_self)
+    }
+
+    pub fn lap_time_current(&self) -> Option<Time> {
+        seconds(self.r3e_shared.lap_time_current_self)
+    }
+
+    pub fn speed(&self) -> Velocity {
+        Velocity::new::<meter_per_second>(self.r3e_shared.car_speed as f64)
+    }
+
+    pub fn engine_speed(&self) -> AngularVelocity {
+        AngularVelocity::new::<radian_per_second>(self.r3e_shared.engine_rps as f64)
+    }
+
+    pub fn max_engine_speed(&self) -> Option<AngularVelocity> {
+        available(self.r3e_shared.max_engine_rps).map(AngularVelocity::new::<radian_per_second>)
+    }
+
+    /// Engine speed at which the shift light turns on.
+    pub fn upshift_speed(&self) -> Option<AngularVelocity> {
+        available(self.r3e_shared.upshift_rps).map(AngularVelocity::new::<radian_per_second>)
+    }
+
+    /// Current gear, -1 for reverse and 0 for neutral.
+    pub fn gear(&self) -> Option<i8> {
+        let gear = self.r3e_shared.gear;
+        (gear >= -1).then_some(gear as i8)
+    }
+
+    pub fn num_gears(&self) -> Option<u32> {
+        non_negative(self.r3e_shared.num_gears)
+    }
+
+    pub fn fuel_left(&self) -> Option<Volume> {
+        available(self.r3e_shared.fuel_left).map(Volume::new::<liter>)
+    }
+
+    pub fn fuel_capacity(&self) -> Option<Volume> {
+        available(self.r3e_shared.fuel_capacity).map(Volume::new::<liter>)
+    }
+
+    pub fn fuel_per_lap(&self) -> Option<Volume> {
+        available(self.r3e_shared.fuel_per_lap).map(Volume::new::<liter>)
+    }
+
+    pub fn engine_water_temp(&self) -> Option<ThermodynamicTemperature> {
+        available(self.r3e_shared.engine_water_temp)
+            .map(ThermodynamicTemperature::new::<degree_celsius>)
+    }
+
+    pub fn engine_oil_temp(&self) -> Option<ThermodynamicTemperature> {
+        available(self.r3e_shared.engine_oil_temp)
+            .map(ThermodynamicTemperature::new::<degree_celsius>)
+    }
+
+    /// Throttle (0.0-1.0) after assists.
+    pub fn throttle(&self) -> Option<f64> {
+        available(self.r3e_shared.throttle)
+    }
+
+    /// Throttle (0.0-1.0) as read from the input device.
+    pub fn throttle_raw(&self) -> Option<f64> {
+        available(self.r3e_shared.throttle_raw)
+    }
+
+    /// Brake (0.0-1.0) after assists.
+    pub fn brake(&self) -> Option<f64> {
+        available(self.r3e_shared.brake)
+    }
+
+    /// Brake (0.0-1.0) as read from the input device.
+    pub fn brake_raw(&self) -> Option<f64> {
+        available(self.r3e_shared.brake_raw)
+    }
+
+    /// Clutch (0.0-1.0) after assists.
+    pub fn clutch(&self) -> Option<f64> {
+        available(self.r3e_shared.clutch)
+    }
+
+    /// Clutch (0.0-1.0) as read from the input device.
+    pub fn clutch_raw(&self) -> Option<f64> {
+        available(self.r3e_shared.clutch_raw)
+    }
+
+    /// Steering input (-1.0 to 1.0) as read from the input device.
+    pub fn steering_raw(&self) -> f64 {
+        self.r3e_shared.steer_input_raw as f64
+    }
+
+    /// Data of all drivers in the session, in order of position.
+    pub fn drivers(&self) -> impl Iterator<Item = Driver<'_>> {
+        let num_cars = usize::try_from(self.r3e_shared.num_cars).unwrap_or_default();
+        self.r3e_shared
+            .all_drivers_data_1
+            .iter()
+            .take(num_cars)
+            .map(|data| Driver { data })
+    }
+
+    /// Data of the driver of the viewed vehicle.
+    pub fn player_driver(&self) -> Option<Driver<'_>> {
+        let slot_id = self.r3e_shared.vehicle_info.slot_id;
+        if slot_id < 0 {
+            return None;
+        }
+        self.drivers().find(|v| v.slot_id() == Some(slot_id as u32))
+    }
+
+    /// Check for cars alongside the viewed vehicle, as `(left, right)`.
+    ///
+    /// The game doesn't provide spotter data, so this compares the positions of the cars with
+    /// the heading of the viewed vehicle.
+    pub(super) fn cars_alongside(&self) -> Option<(bool, bool)> {
+        let player = self.player_driver()?;
+        let length = player.car_length()?.get::<meter>();
+        let width = player.car_width()?.get::<meter>();
+        let origin = player.position();
+        let yaw = self.r3e_shared.car_orientation.yaw as f64;
+        let (sin, cos) = yaw.sin_cos();
+        let mut alongside = (false, false);
+        for driver in self.drivers() {
+            if driver.slot_id() == player.slot_id() || driver.is_in_pit_lane() == Some(true) {
+                continue;
+            }
+            let position = driver.position();
+            let x = (position.x - origin.x).get::<meter>();
+            let z = (position.z - origin.z).get::<meter>();
+            // Yaw turns the car around the vertical axis, from facing +z towards +x.
+            let forward = x * sin + z * cos;
+            let right = x * cos - z * sin;
+            if forward.abs() >= length || right.abs() >= width * 2.0 {
+                continue;
+            }
+            if right < 0.0 {
+                alongside.0 = true;
+            } else {
+                alongside.1 = true;
+            }
+        }
+        Some(alongside)
+    }
+}
+
+/// Data of one driver in the session.
+#[derive(Clone, Copy, Debug)]
+pub struct Driver<'a> {
+    pub data: &'a bindings::r3e_driver_data,
+}
+
+impl Driver<'_> {
+    pub fn name(&self) -> String {
+        extract_string(&self.data.driver_info.name)
+    }
+
+    pub fn car_number(&self) -> Option<u32> {
+        non_negative(self.data.driver_info.car_number)
+    }
+
+    pub fn slot_id(&self) -> Option<u32> {
+        non_negative(self.data.driver_info.slot_id)
+    }
+
+    pub fn class_id(&self) -> Option<u32> {
+        non_negative(self.data.driver_info.class_id)
+    }
+
+    pub fn model_id(&self) -> Option<u32> {
+        non_negative(self.data.driver_info.model_id)
+    }
+
+    pub fn manufacturer_id(&self) -> Option<u32> {
+        non_negative(self.data.driver_info.manufacturer_id)
+    }
+
+    pub fn user_id(&self) -> Option<u32> {
+        non_negative(self.data.driver_info.user_id)
+    }
+
+    pub fn car_width(&self) -> Option<Length> {
+        available(self.data.driver_info.car_width)
+            .filter(|v| *v > 0.0)
+            .map(Length::new::<meter>)
+    }
+
+    pub fn car_length(&self) -> Option<Length> {
+        available(self.data.driver_info.car_length)
+            .filter(|v| *v > 0.0)
+            .map(Length::new::<meter>)
+    }
+
+    pub fn finish_status(&self) -> Option<FinishStatus> {
+        FinishStatus::from_raw(self.data.finish_status)
+    }
+
+    /// 1-based position in the session.
+    pub fn place(&self) -> Option<u32> {
+        non_negative(self.data.place).filter(|v| *v > 0)
+    }
+
+    /// 1-based position in the class.
+    pub fn place_class(&self) -> Option<u32> {
+        non_negative(self.data.place_class).filter(|v| *v > 0)
+    }
+
+    /// Distance travelled around the track in the current lap.
+    pub fn lap_distance(&self) -> Option<Length> {
+        available(self.data.lap_distance).map(Length::new::<meter>)
+    }
+
+    /// World-space position of the car.
+    pub fn position(&self) -> Vec3<Length> {
+        let position = self.data.position;
+        Vec3 {
+            x: Length::new::<meter>(position.x as f64),
+            y: Length::new::<meter>(position.y as f64),
+            z: Length::new::<meter>(position.z as f64),
+        }
+    }
+
+    pub fn completed_laps(&self) -> Option<u32> {
+        non_negative(self.data.completed_laps)
+    }
+
+    pub fn is_current_lap_valid(&self) -> Option<bool> {
+        flag(self.data.current_lap_valid)
+    }
+
+    pub fn lap_time_current(&self) -> Option<Time> {
+        seconds(self.data.lap_time_current_self)
+    }
+
+    pub fn time_delta_front(&self) -> Option<Time> {
+        seconds(self.data.time_delta_front)
+    }
+
+    pub fn time_delta_behind(&self) -> Option<Time> {
+        seconds(self.data.time_delta_behind)
+    }
+
+    pub fn is_in_pit_lane(&self) -> Option<bool> {
+        flag(self.data.in_pitlane)
+    }
+
+    pub fn num_pitstops(&self) -> Option<u32> {
+        non_negative(self.data.num_pitstops)
+    }
+
+    pub fn speed(&self) -> Velocity {
+        Velocity::new::<meter_per_second>(self.data.car_speed as f64)
+    }
+
+    pub fn engine_state(&self) -> Option<EngineState> {
+        EngineState::from_raw(self.data.engineState)
+    }
+}
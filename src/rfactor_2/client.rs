@@ -4,67 +4,105 @@ use crate::rfactor_2::shared_memory_data::{
     PageScoring, PageTelemetry, PageWeather,
 };
 use crate::rfactor_2::{
-    Extended, ForceFeedback, MultiRules, PitInfo, Rules, Scoring, SimState, Telemetry, Weather,
+    Extended, ForceFeedback, Game, MultiRules, PitInfo, Rules, Scoring, SimState, Telemetry,
+    Weather,
 };
-use crate::windows_util::SharedMemory;
+use crate::windows_util::{running_processes, SharedMemory};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
 use std::sync::Arc;
-use std::time::Duration;
-
-// TODO: handle input files as well.
+use std::time::{Duration, Instant};
 
 pub struct Client {
     sim_state_cache: SimState,
+    subscribed_buffers: BTreeSet<Buffer>,
+    telemetry_rate: UpdateRate,
+    scoring_rate: UpdateRate,
 
     /// Mapped view of rF2Telemetry structure
     telemetry: SharedMemory,
     /// Mapped view of rF2Scoring structure
     scoring: SharedMemory,
     /// Mapped view of rF2Rules structure
-    rules: SharedMemory,
+    rules: Option<SharedMemory>,
     /// Mapped view of rF2MultiRules structure
-    multi_rules: SharedMemory,
+    multi_rules: Option<SharedMemory>,
     /// Mapped view of rF2ForceFeedback structure
-    force_feedback: SharedMemory,
+    force_feedback: Option<SharedMemory>,
     /// Mapped view of rF2PitInfo structure
-    pit_info: SharedMemory,
+    pit_info: Option<SharedMemory>,
     /// Mapped view of rF2Weather structure
-    weather: SharedMemory,
+    weather: Option<SharedMemory>,
     /// Mapped view of rF2Extended structure
-    extended: SharedMemory,
+    extended: Option<SharedMemory>,
 }
 
+/// Number of polls per expected update of the fastest subscribed buffer.
+const POLLS_PER_UPDATE: u32 = 4;
+
+/// Number of attempts at copying a buffer the game is writing at the same time,
+/// before giving up until the next poll.
+const READ_ATTEMPTS: usize = 64;
+
+/// Number of polls for the first copy of a buffer while connecting, before giving up on it.
+const CONNECT_READ_ATTEMPTS: usize = 100;
+
 impl Client {
+    /// Shortest delay between polls of the buffers.
+    pub const MIN_POLL_DELAY: Duration = Duration::from_millis(1);
+    /// Longest delay between polls of the buffers, used while the game isn't updating them,
+    /// like in menus or while paused.
+    pub const MAX_POLL_DELAY: Duration = Duration::from_millis(50);
+
     pub async fn connect() -> Self {
-        Self::connect_with_config(&Config {
-            dedicated_server_pid: None,
-            dedicated_server_global: false,
-        })
-        .await
+        Self::connect_with_config(&Config::default()).await
     }
 
+    /// Connect to the plugin, mapping its buffers again if the telemetry or scoring buffer can't
+    /// be read, which happens if a buffer keeps failing its version checks.
     pub async fn connect_with_config(config: &Config) -> Self {
         let poll_delay = Duration::from_millis(250);
+        loop {
+            if let Some(client) = Self::try_connect_with_config(config, poll_delay).await {
+                return client;
+            }
+            log::warn!("Couldn't read the rFactor 2 buffers, connecting again");
+            tokio::time::sleep(poll_delay).await;
+        }
+    }
+
+    async fn try_connect_with_config(config: &Config, poll_delay: Duration) -> Option<Self> {
         let telemetry = open_file("Telemetry", config, poll_delay).await;
         let scoring = open_file("Scoring", config, poll_delay).await;
-        let rules = open_file("Rules", config, poll_delay).await;
-        let multi_rules = open_file("MultiRules", config, poll_delay).await;
-        let force_feedback = open_file("ForceFeedback", config, poll_delay).await;
-        let pit_info = open_file("PitInfo", config, poll_delay).await;
-        let weather = open_file("Weather", config, poll_delay).await;
-        let extended = open_file("Extended", config, poll_delay).await;
-        Self {
+        let rules = open_buffer(Buffer::Rules, config, poll_delay).await;
+        let multi_rules = open_buffer(Buffer::MultiRules, config, poll_delay).await;
+        let force_feedback = open_buffer(Buffer::ForceFeedback, config, poll_delay).await;
+        let pit_info = open_buffer(Buffer::PitInfo, config, poll_delay).await;
+        let weather = open_buffer(Buffer::Weather, config, poll_delay).await;
+        let extended = open_buffer(Buffer::Extended, config, poll_delay).await;
+        Some(Self {
             sim_state_cache: SimState {
-                telemetry: read_when_ready::<PageTelemetry, Telemetry>(&telemetry),
-                scoring: read_when_ready::<PageScoring, Scoring>(&scoring),
-                rules: read_when_ready::<PageRules, Rules>(&rules),
-                multi_rules: read_when_ready::<PageMultiRules, MultiRules>(&multi_rules),
-                force_feedback: read_when_ready::<PageForceFeedback, ForceFeedback>(
-                    &force_feedback,
-                ),
-                pit_info: read_when_ready::<PagePitInfo, PitInfo>(&pit_info),
-                weather: read_when_ready::<PageWeather, Weather>(&weather),
-                extended: read_when_ready::<PageExtended, Extended>(&extended),
+                game: detect_game(config),
+                telemetry: read_at_connect::<PageTelemetry, Telemetry>(&telemetry).await?,
+                scoring: read_at_connect::<PageScoring, Scoring>(&scoring).await?,
+                rules: read_optional::<PageRules, Rules>(&rules).await,
+                multi_rules: read_optional::<PageMultiRules, MultiRules>(&multi_rules).await,
+                force_feedback: read_optional::<PageForceFeedback, ForceFeedback>(&force_feedback)
+                    .await,
+                pit_info: read_optional::<PagePitInfo, PitInfo>(&pit_info).await,
+                weather: read_optional::<PageWeather, Weather>(&weather).await,
+                extended: read_optional::<PageExtended, Extended>(&extended).await,
+                changed: Buffer::ALL
+                    .into_iter()
+                    .filter(|v| config.is_buffer_mapped(*v))
+                    .collect(),
             },
+            subscribed_buffers: Buffer::ALL
+                .into_iter()
+                .filter(|v| config.is_buffer_subscribed(*v))
+                .collect(),
+            telemetry_rate: UpdateRate::default(),
+            scoring_rate: UpdateRate::default(),
             telemetry,
             scoring,
             rules,
@@ -73,63 +111,189 @@ impl Client {
             pit_info,
             weather,
             extended,
-        }
+        })
+    }
+
+    /// Title the plugin is running in.
+    pub fn game(&self) -> Game {
+        self.sim_state_cache.game
     }
 
-    pub fn force_feedback(&self) -> Arc<ForceFeedback> {
-        read_when_ready::<PageForceFeedback, ForceFeedback>(&self.force_feedback)
+    /// Latest force feedback value, `None` if the buffer is skipped in the [`Config`].
+    pub fn force_feedback(&self) -> Option<Arc<ForceFeedback>> {
+        let memory = self.force_feedback.as_ref()?;
+        read_when_ready::<PageForceFeedback, ForceFeedback>(memory)
+            .or_else(|| self.sim_state_cache.force_feedback.clone())
     }
 
+    /// Wait until any of the subscribed buffers changes, and return the state with all mapped
+    /// buffers that changed since the previous state read.
+    ///
+    /// The buffers are polled at a rate adapted to how often the game updates the subscribed
+    /// buffers, so the client sleeps most of the time between updates.
     pub async fn next_sim_state(&mut self) -> Option<SimState> {
         loop {
-            let mut changed = false;
-
-            if has_update_pending(self.sim_state_cache.telemetry.packet_id, &self.telemetry) {
-                self.sim_state_cache.telemetry =
-                    read_when_ready::<PageTelemetry, Telemetry>(&self.telemetry);
-                changed = true;
+            if self.has_subscribed_update() {
+                self.update_cache();
+                // Reads of buffers in the middle of a write are retried on the next poll.
+                if let Some(state) = self
+                    .sim_state_cache
+                    .take_if_changed(&self.subscribed_buffers)
+                {
+                    return Some(state);
+                }
             }
-            if has_update_pending(self.sim_state_cache.scoring.packet_id, &self.scoring) {
-                self.sim_state_cache.scoring =
-                    read_when_ready::<PageScoring, Scoring>(&self.scoring);
-                changed = true;
+            tokio::time::sleep(self.poll_delay()).await;
+        }
+    }
+
+    fn has_subscribed_update(&self) -> bool {
+        let cache = &self.sim_state_cache;
+        self.subscribed_buffers.iter().any(|buffer| match buffer {
+            Buffer::Telemetry => has_update_pending(cache.telemetry.packet_id, &self.telemetry),
+            Buffer::Scoring => has_update_pending(cache.scoring.packet_id, &self.scoring),
+            Buffer::Rules => has_optional_update(&cache.rules, &self.rules, |v| v.packet_id),
+            Buffer::MultiRules => {
+                has_optional_update(&cache.multi_rules, &self.multi_rules, |v| v.packet_id)
             }
-            if has_update_pending(self.sim_state_cache.rules.packet_id, &self.rules) {
-                self.sim_state_cache.rules = read_when_ready::<PageRules, Rules>(&self.rules);
-                changed = true;
+            Buffer::ForceFeedback => {
+                self.force_feedback().as_deref().map(|v| v.force_value)
+                    != cache.force_feedback.as_deref().map(|v| v.force_value)
             }
-            if has_update_pending(
-                self.sim_state_cache.multi_rules.packet_id,
-                &self.multi_rules,
-            ) {
-                self.sim_state_cache.multi_rules =
-                    read_when_ready::<PageMultiRules, MultiRules>(&self.multi_rules);
-                changed = true;
+            Buffer::PitInfo => {
+                has_optional_update(&cache.pit_info, &self.pit_info, |v| v.packet_id)
             }
-            if has_update_pending(self.sim_state_cache.pit_info.packet_id, &self.pit_info) {
-                self.sim_state_cache.pit_info =
-                    read_when_ready::<PagePitInfo, PitInfo>(&self.pit_info);
-                changed = true;
+            Buffer::Weather => has_optional_update(&cache.weather, &self.weather, |v| v.packet_id),
+            Buffer::Extended => {
+                has_optional_update(&cache.extended, &self.extended, |v| v.packet_id)
             }
-            if has_update_pending(self.sim_state_cache.weather.packet_id, &self.weather) {
-                self.sim_state_cache.weather =
-                    read_when_ready::<PageWeather, Weather>(&self.weather);
-                changed = true;
+        })
+    }
+
+    /// Read every mapped buffer that changed, including the ones that aren't subscribed,
+    /// so the state stays consistent.
+    ///
+    /// The changes add up in the cache until a state is returned.
+    fn update_cache(&mut self) {
+        let now = Instant::now();
+        let cache = &mut self.sim_state_cache;
+
+        if has_update_pending(cache.telemetry.packet_id, &self.telemetry) {
+            if let Some(data) = read_when_ready::<PageTelemetry, Telemetry>(&self.telemetry) {
+                cache.telemetry = data;
+                cache.changed.insert(Buffer::Telemetry);
+                self.telemetry_rate.record_update(now);
             }
-            if has_update_pending(self.sim_state_cache.extended.packet_id, &self.extended) {
-                self.sim_state_cache.extended =
-                    read_when_ready::<PageExtended, Extended>(&self.extended);
-                changed = true;
+        }
+        if has_update_pending(cache.scoring.packet_id, &self.scoring) {
+            if let Some(data) = read_when_ready::<PageScoring, Scoring>(&self.scoring) {
+                cache.scoring = data;
+                cache.changed.insert(Buffer::Scoring);
+                self.scoring_rate.record_update(now);
             }
+        }
+        if update_optional::<PageRules, Rules>(&mut cache.rules, &self.rules, |v| v.packet_id) {
+            cache.changed.insert(Buffer::Rules);
+        }
+        if update_optional::<PageMultiRules, MultiRules>(
+            &mut cache.multi_rules,
+            &self.multi_rules,
+            |v| v.packet_id,
+        ) {
+            cache.changed.insert(Buffer::MultiRules);
+        }
+        if update_optional::<PagePitInfo, PitInfo>(&mut cache.pit_info, &self.pit_info, |v| {
+            v.packet_id
+        }) {
+            cache.changed.insert(Buffer::PitInfo);
+        }
+        if update_optional::<PageWeather, Weather>(&mut cache.weather, &self.weather, |v| {
+            v.packet_id
+        }) {
+            cache.changed.insert(Buffer::Weather);
+        }
+        if update_optional::<PageExtended, Extended>(&mut cache.extended, &self.extended, |v| {
+            v.packet_id
+        }) {
+            cache.changed.insert(Buffer::Extended);
+        }
 
-            if changed {
-                self.sim_state_cache.force_feedback =
-                    read_when_ready::<PageForceFeedback, ForceFeedback>(&self.force_feedback);
-                return Some(self.sim_state_cache.clone());
-            }
-            tokio::time::sleep(Duration::from_millis(1)).await;
+        let force_feedback = self.force_feedback();
+        let cache = &mut self.sim_state_cache;
+        if force_feedback.as_deref().map(|v| v.force_value)
+            != cache.force_feedback.as_deref().map(|v| v.force_value)
+        {
+            cache.force_feedback = force_feedback;
+            cache.changed.insert(Buffer::ForceFeedback);
         }
     }
+
+    /// Delay until the next poll, based on how often the subscribed buffers with a steady
+    /// update rate were updated recently.
+    fn poll_delay(&self) -> Duration {
+        let now = Instant::now();
+        let rates = [
+            (Buffer::Telemetry, &self.telemetry_rate),
+            (Buffer::Scoring, &self.scoring_rate),
+        ];
+        Self::poll_delay_for(
+            rates
+                .into_iter()
+                .filter(|(buffer, _)| self.subscribed_buffers.contains(buffer))
+                .filter_map(|(_, rate)| rate.expected_interval(now))
+                .min(),
+        )
+    }
+
+    /// Delay between polls for the expected update interval of the fastest subscribed buffer,
+    /// polling a few times per update, or [`Self::MAX_POLL_DELAY`] if none is being updated.
+    pub fn poll_delay_for(expected_interval: Option<Duration>) -> Duration {
+        expected_interval.map_or(Self::MAX_POLL_DELAY, |v| {
+            (v / POLLS_PER_UPDATE).clamp(Self::MIN_POLL_DELAY, Self::MAX_POLL_DELAY)
+        })
+    }
+}
+
+/// Estimate of how often the game updates a buffer, which the client adapts its poll rate to.
+#[derive(Clone, Debug, Default)]
+pub struct UpdateRate {
+    last_update: Option<Instant>,
+    /// Moving average of the time between updates.
+    interval: Option<Duration>,
+}
+
+impl UpdateRate {
+    pub fn record_update(&mut self, now: Instant) {
+        if let Some(last_update) = self.last_update {
+            let interval = now - last_update;
+            self.interval = Some(match self.interval {
+                Some(average) => (average * 7 + interval) / 8,
+                None => interval,
+            });
+        }
+        self.last_update = Some(now);
+    }
+
+    /// Moving average of the time between updates, `None` until the second update.
+    pub fn average_interval(&self) -> Option<Duration> {
+        self.interval
+    }
+
+    /// Expected time between updates, or `None` if the buffer isn't being updated,
+    /// like while the game is paused.
+    pub fn expected_interval(&self, now: Instant) -> Option<Duration> {
+        let interval = self.interval?;
+        let last_update = self.last_update?;
+        // Missing a few updates in a row means the game stopped updating the buffer.
+        (now - last_update < interval * 4).then_some(interval)
+    }
+}
+
+/// Detect the title from the executable of the running game, or of the dedicated server
+/// set in the config. The buffers don't tell the titles apart, and the title can't change
+/// while they're mapped, so this is done once per connection.
+fn detect_game(config: &Config) -> Game {
+    Game::from_processes(running_processes(), config.dedicated_server_pid).unwrap_or(Game::RFactor2)
 }
 
 fn has_update_pending(old_id: PacketId, memory: &SharedMemory) -> bool {
@@ -142,28 +306,214 @@ fn has_update_pending(old_id: PacketId, memory: &SharedMemory) -> bool {
         })
 }
 
-fn read_when_ready<Page: Copy, Data: TryFrom<Box<Page>>>(memory: &SharedMemory) -> Arc<Data> {
-    loop {
+fn has_optional_update<Data>(
+    cache: &Option<Arc<Data>>,
+    memory: &Option<SharedMemory>,
+    packet_id: fn(&Data) -> PacketId,
+) -> bool {
+    match (cache, memory) {
+        (Some(data), Some(memory)) => has_update_pending(packet_id(data), memory),
+        _ => false,
+    }
+}
+
+/// Read an optional buffer into the cache if it is mapped and has changed.
+fn update_optional<Page: Copy, Data: TryFrom<Box<Page>>>(
+    cache: &mut Option<Arc<Data>>,
+    memory: &Option<SharedMemory>,
+    packet_id: fn(&Data) -> PacketId,
+) -> bool {
+    let Some(memory) = memory else {
+        return false;
+    };
+    if let Some(data) = cache {
+        if !has_update_pending(packet_id(data), memory) {
+            return false;
+        }
+    }
+    let Some(data) = read_when_ready::<Page, Data>(memory) else {
+        return false;
+    };
+    *cache = Some(data);
+    true
+}
+
+/// Read the buffer, retrying while the game is writing it at the same time.
+/// Gives up after `READ_ATTEMPTS`, leaving it to be read again on the next poll.
+fn read_when_ready<Page: Copy, Data: TryFrom<Box<Page>>>(
+    memory: &SharedMemory,
+) -> Option<Arc<Data>> {
+    for _ in 0..READ_ATTEMPTS {
         if let Ok(data) = Box::new(unsafe { *memory.get_as::<Page>() }).try_into() {
-            return Arc::new(data);
+            return Some(Arc::new(data));
+        }
+        std::hint::spin_loop();
+    }
+    None
+}
+
+/// Read the buffer for the first time, polling for up to `CONNECT_READ_ATTEMPTS`.
+async fn read_at_connect<Page: Copy, Data: TryFrom<Box<Page>>>(
+    memory: &SharedMemory,
+) -> Option<Arc<Data>> {
+    for _ in 0..CONNECT_READ_ATTEMPTS {
+        if let Some(data) = read_when_ready::<Page, Data>(memory) {
+            return Some(data);
+        }
+        tokio::time::sleep(Client::MIN_POLL_DELAY).await;
+    }
+    None
+}
+
+/// Read an optional buffer for the first time, leaving it to the polls if it can't be read yet.
+async fn read_optional<Page: Copy, Data: TryFrom<Box<Page>>>(
+    memory: &Option<SharedMemory>,
+) -> Option<Arc<Data>> {
+    read_at_connect::<Page, Data>(memory.as_ref()?).await
+}
+
+/// Buffers of the plugin the client reads.
+///
+/// Telemetry and scoring are always mapped, since they're needed for the [`Moment`](crate::Moment)
+/// data, but the others can be left unmapped.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize, Deserialize)]
+pub enum Buffer {
+    Telemetry,
+    Scoring,
+    Rules,
+    MultiRules,
+    ForceFeedback,
+    PitInfo,
+    Weather,
+    Extended,
+}
+
+impl Buffer {
+    pub const ALL: [Self; 8] = [
+        Self::Telemetry,
+        Self::Scoring,
+        Self::Rules,
+        Self::MultiRules,
+        Self::ForceFeedback,
+        Self::PitInfo,
+        Self::Weather,
+        Self::Extended,
+    ];
+
+    /// Name of the buffer in the name of its shared memory.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Telemetry => "Telemetry",
+            Self::Scoring => "Scoring",
+            Self::Rules => "Rules",
+            Self::MultiRules => "MultiRules",
+            Self::ForceFeedback => "ForceFeedback",
+            Self::PitInfo => "PitInfo",
+            Self::Weather => "Weather",
+            Self::Extended => "Extended",
         }
     }
 }
 
+/// Which plugin instance to connect to, and which of its buffers to map.
+///
+/// The default connects to the game running in the same session, and maps every buffer.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
 pub struct Config {
-    dedicated_server_pid: Option<String>,
+    dedicated_server_pid: Option<u32>,
     dedicated_server_global: bool,
+    skipped_buffers: BTreeSet<Buffer>,
+    /// `None` subscribes to every mapped buffer.
+    subscribed_buffers: Option<BTreeSet<Buffer>>,
+}
+
+impl Config {
+    /// Connect to the dedicated server with the process ID, whose buffers the plugin names
+    /// with the ID as a suffix.
+    pub fn dedicated_server_pid(mut self, pid: u32) -> Self {
+        self.dedicated_server_pid = Some(pid);
+        self
+    }
+
+    /// Look for the buffers in the `Global` namespace, where the plugin creates them if the
+    /// dedicated server is set to map them globally, like when running as a service.
+    pub fn dedicated_server_global(mut self, global: bool) -> Self {
+        self.dedicated_server_global = global;
+        self
+    }
+
+    /// Leave the buffer unmapped, so it's never read and is `None` in the [`SimState`].
+    ///
+    /// Telemetry and scoring can't be skipped, and stay mapped.
+    pub fn skip_buffer(mut self, buffer: Buffer) -> Self {
+        self.skipped_buffers.insert(buffer);
+        self
+    }
+
+    /// Only return a new [`SimState`] from [`Client::next_sim_state`] when this buffer, or
+    /// another subscribed one, changes.
+    ///
+    /// Every mapped buffer is subscribed until the first call. Buffers that aren't subscribed
+    /// are still read along with the subscribed ones, and flagged in [`SimState::changed`].
+    pub fn subscribe_buffer(mut self, buffer: Buffer) -> Self {
+        self.subscribed_buffers
+            .get_or_insert_with(BTreeSet::new)
+            .insert(buffer);
+        self
+    }
+
+    pub fn is_buffer_mapped(&self, buffer: Buffer) -> bool {
+        matches!(buffer, Buffer::Telemetry | Buffer::Scoring)
+            || !self.skipped_buffers.contains(&buffer)
+    }
+
+    pub fn is_buffer_subscribed(&self, buffer: Buffer) -> bool {
+        self.is_buffer_mapped(buffer)
+            && match &self.subscribed_buffers {
+                Some(subscribed_buffers) => subscribed_buffers.contains(&buffer),
+                None => true,
+            }
+    }
+}
+
+async fn open_buffer(
+    buffer: Buffer,
+    config: &Config,
+    poll_delay: Duration,
+) -> Option<SharedMemory> {
+    if !config.is_buffer_mapped(buffer) {
+        return None;
+    }
+    Some(open_file(buffer.name(), config, poll_delay).await)
+}
+
+pub(super) async fn open_file(
+    buffer_type: &str,
+    config: &Config,
+    poll_delay: Duration,
+) -> SharedMemory {
+    SharedMemory::connect(buffer_name(buffer_type, config).as_bytes(), poll_delay).await
+}
+
+/// Open one of the buffers the plugin reads input from.
+pub(super) async fn open_input_file(
+    buffer_type: &str,
+    config: &Config,
+    poll_delay: Duration,
+) -> SharedMemory {
+    SharedMemory::connect_writable(buffer_name(buffer_type, config).as_bytes(), poll_delay).await
 }
 
-async fn open_file(buffer_type: &str, config: &Config, poll_delay: Duration) -> SharedMemory {
-    let name = format!(
+fn buffer_name(buffer_type: &str, config: &Config) -> String {
+    format!(
         "{global}$rFactor2SMMP_{buffer_type}${pid}\0",
         global = if config.dedicated_server_global {
-            "Global\\\\"
+            "Global\\"
         } else {
             ""
         },
-        pid = config.dedicated_server_pid.as_deref().unwrap_or(""),
-    );
-    SharedMemory::connect(name.as_bytes(), poll_delay).await
+        pid = config
+            .dedicated_server_pid
+            .map_or(String::new(), |v| v.to_string()),
+    )
 }
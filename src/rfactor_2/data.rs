@@ -5,13 +5,16 @@ use crate::rfactor_2::shared_memory_data::{
     PageVehScoringCapture, PageVehicleScoring, PageVehicleTelemetry, PageWeather,
     PageWheelTelemetry, MAX_MAPPED_IDS, MAX_MAPPED_VEHICLES,
 };
-use crate::windows_util::cp1252_to_string;
+use crate::windows_util::{cp1252_to_string, string_to_cp1252};
 use anyhow::{bail, Error, Result};
+use serde::{Deserialize, Serialize};
 
-#[derive(Copy, Clone, Debug, Hash, Ord, PartialOrd, Eq, PartialEq)]
+#[derive(
+    Copy, Clone, Debug, Default, Hash, Ord, PartialOrd, Eq, PartialEq, Serialize, Deserialize,
+)]
 pub struct PacketId(pub u32);
 
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct Vec3 {
     pub x: f64,
     pub y: f64,
@@ -24,19 +27,19 @@ impl Vec3 {
     }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct ForceFeedback {
     /// Current FFB value
     pub force_value: f64,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct Telemetry {
     pub packet_id: PacketId,
     pub vehicles: Vec<VehicleTelemetry>,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct VehicleTelemetry {
     // Time
     /// slot ID (note that it can be re-used in multiplayer after someone leaves)    
@@ -191,12 +194,25 @@ pub struct VehicleTelemetry {
     /// the *physical* steering wheel range    
     pub physical_steering_wheel_range: f32,
 
+    // Hybrid
+    /// 0.0-1.0
+    pub battery_charge_fraction: f64,
+    /// current torque of boost motor (can be negative when in regenerating mode)
+    pub electric_boost_motor_torque: f64,
+    pub electric_boost_motor_rpm: f64,
+    /// Celsius
+    pub electric_boost_motor_temperature: f64,
+    /// Celsius
+    pub electric_boost_water_temperature: f64,
+    /// 0=unavailable 1=inactive, 2=propulsion, 3=regeneration
+    pub electric_boost_motor_state: u8,
+
     // keeping this at the end of the structure to make it easier to replace in future versions
     /// wheel info (front left, front right, rear left, rear right)    
     pub wheels: [WheelTelemetry; 4],
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct WheelTelemetry {
     /// meters
     pub suspension_deflection: f64,
@@ -260,7 +276,7 @@ pub struct WheelTelemetry {
     pub tire_inner_layer_temperature: [f64; 3],
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct Scoring {
     pub packet_id: PacketId,
 
@@ -269,7 +285,7 @@ pub struct Scoring {
     pub vehicles: Vec<VehicleScoring>,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct ScoringInfo {
     /// current track name
     pub track_name: String,
@@ -339,7 +355,7 @@ pub struct ScoringInfo {
     pub avg_path_wetness: f64,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct VehicleScoring {
     /// slot ID (note that it can be re-used in multiplayer after someone leaves)
     pub id: i32,
@@ -461,7 +477,7 @@ pub struct VehicleScoring {
     pub best_lap_sector2: f32,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct Rules {
     pub packet_id: PacketId,
 
@@ -470,7 +486,7 @@ pub struct Rules {
     pub participants: Vec<TrackRulesParticipant>,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct TrackRulesAction {
     // input only
     /// recommended action
@@ -481,7 +497,7 @@ pub struct TrackRulesAction {
     pub elapsed_time: f64,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct TrackRulesParticipant {
     // input only
     /// slot ID
@@ -514,7 +530,7 @@ pub struct TrackRulesParticipant {
     pub message: String,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct TrackRules {
     // input only
     /// current time
@@ -576,24 +592,24 @@ pub struct TrackRules {
 }
 
 /// Not supported yet.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct MultiRules {
     pub packet_id: PacketId,
 }
 
 /// Not supported yet.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct PitInfo {
     pub packet_id: PacketId,
 }
 
 /// Not supported yet.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Weather {
     pub packet_id: PacketId,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Extended {
     pub packet_id: PacketId,
 
@@ -608,6 +624,7 @@ pub struct Extended {
     /// Damage tracking for each vehicle
     ///
     /// Indexed by mID % MappedBufferHeader::MAX_MAPPED_IDS.
+    #[serde(with = "big_array")]
     pub tracked_damages: [TrackedDamage; MAX_MAPPED_IDS],
 
     // Function call based flags:
@@ -664,9 +681,21 @@ pub struct Extended {
     /// Ticks when last FCY rules message was updated.  Currently, only SCR plugin sets that.
     pub ticks_lsi_rules_instruction_message_updated: i64,
     pub lsi_rules_instruction_message: String,
+
+    /// Currently active UnsubscribedBuffersMask value.
+    pub unsubscribed_buffers_mask: i32,
+
+    /// HWControl input buffer is enabled.
+    pub hw_control_input_enabled: u8,
+    /// Weather Control input buffer is enabled.
+    pub weather_control_input_enabled: u8,
+    /// Rules Control input buffer is enabled.
+    pub rules_control_input_enabled: u8,
+    /// Plugin Control input buffer is enabled.
+    pub plugin_control_input_enabled: u8,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct PhysicsOptions {
     /// 0 (off) - 3 (high)
     pub traction_control: u8,
@@ -725,7 +754,7 @@ pub struct PhysicsOptions {
     pub steer_ratio_speed: f32,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct TrackedDamage {
     /// Max impact magnitude
     ///
@@ -737,7 +766,7 @@ pub struct TrackedDamage {
     pub accumulated_impact_magnitude: f64,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct SessionTransitionCapture {
     pub game_phase: u8,
     pub session: i32,
@@ -745,7 +774,7 @@ pub struct SessionTransitionCapture {
     pub scoring_vehicles: Vec<VehScoringCapture>,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct VehScoringCapture {
     /// slot ID (note that it can be re-used in multiplayer after someone leaves)
     pub id: i32,
@@ -864,6 +893,12 @@ impl From<&PageVehicleTelemetry> for VehicleTelemetry {
             turbo_boost_pressure: value.turbo_boost_pressure,
             physics_to_graphics_offset: value.physics_to_graphics_offset,
             physical_steering_wheel_range: value.physical_steering_wheel_range,
+            battery_charge_fraction: value.battery_charge_fraction,
+            electric_boost_motor_torque: value.electric_boost_motor_torque,
+            electric_boost_motor_rpm: value.electric_boost_motor_rpm,
+            electric_boost_motor_temperature: value.electric_boost_motor_temperature,
+            electric_boost_water_temperature: value.electric_boost_water_temperature,
+            electric_boost_motor_state: value.electric_boost_motor_state,
             wheels: value.wheels.map(|v| (&v).into()),
         }
     }
@@ -1120,6 +1155,68 @@ impl From<&PageTrackRulesParticipant> for TrackRulesParticipant {
     }
 }
 
+impl From<&TrackRules> for PageTrackRules {
+    fn from(value: &TrackRules) -> Self {
+        // Plain data, for which zeroes are valid. Counts are set along with the arrays.
+        let mut page: Self = unsafe { std::mem::zeroed() };
+        page.current_et = value.current_et;
+        page.stage = value.stage;
+        page.pole_column = value.pole_column;
+        page.yellow_flag_detected = value.yellow_flag_detected;
+        page.yellow_flag_laps_was_overridden = value.yellow_flag_laps_was_overridden;
+        page.safety_car_exists = value.safety_car_exists;
+        page.safety_car_active = value.safety_car_active;
+        page.safety_car_laps = value.safety_car_laps;
+        page.safety_car_threshold = value.safety_car_threshold;
+        page.safety_car_lap_dist = value.safety_car_lap_dist;
+        page.safety_car_lap_dist_at_start = value.safety_car_lap_dist_at_start;
+        page.pit_lane_start_dist = value.pit_lane_start_dist;
+        page.teleport_lap_dist = value.teleport_lap_dist;
+        page.yellow_flag_state = value.yellow_flag_state;
+        page.yellow_flag_laps = value.yellow_flag_laps;
+        page.safety_car_instruction = value.safety_car_instruction;
+        page.safety_car_speed = value.safety_car_speed;
+        page.safety_car_minimum_spacing = value.safety_car_minimum_spacing;
+        page.safety_car_maximum_spacing = value.safety_car_maximum_spacing;
+        page.minimum_column_spacing = value.minimum_column_spacing;
+        page.maximum_column_spacing = value.maximum_column_spacing;
+        page.minimum_speed = value.minimum_speed;
+        page.maximum_speed = value.maximum_speed;
+        page.message = string_to_cp1252(&value.message);
+        page
+    }
+}
+
+impl From<&TrackRulesAction> for PageTrackRulesAction {
+    fn from(value: &TrackRulesAction) -> Self {
+        Self {
+            command: value.command,
+            id: value.id,
+            elapsed_time: value.elapsed_time,
+        }
+    }
+}
+
+impl From<&TrackRulesParticipant> for PageTrackRulesParticipant {
+    fn from(value: &TrackRulesParticipant) -> Self {
+        // Plain data, for which zeroes are valid.
+        let mut page: Self = unsafe { std::mem::zeroed() };
+        page.id = value.id;
+        page.frozen_order = value.frozen_order;
+        page.place = value.place;
+        page.yellow_severity = value.yellow_severity;
+        page.current_relative_distance = value.current_relative_distance;
+        page.relative_laps = value.relative_laps;
+        page.column_assignment = value.column_assignment;
+        page.position_assignment = value.position_assignment;
+        page.pits_open = value.pits_open;
+        page.up_to_speed = value.up_to_speed;
+        page.goal_relative_distance = value.goal_relative_distance;
+        page.message = string_to_cp1252(&value.message);
+        page
+    }
+}
+
 impl TryFrom<Box<PageMultiRules>> for MultiRules {
     type Error = Error;
 
@@ -1190,6 +1287,11 @@ impl TryFrom<Box<PageExtended>> for Extended {
                 .ticks_lsi_rules_instruction_message_updated,
             lsi_rules_instruction_message: cp1252_to_string(&value.lsi_rules_instruction_message)
                 .unwrap_or_default(),
+            unsubscribed_buffers_mask: value.unsubscribed_buffers_mask,
+            hw_control_input_enabled: value.hw_control_input_enabled,
+            weather_control_input_enabled: value.weather_control_input_enabled,
+            rules_control_input_enabled: value.rules_control_input_enabled,
+            plugin_control_input_enabled: value.plugin_control_input_enabled,
         })
     }
 }
@@ -1276,3 +1378,26 @@ impl From<PageVec3> for Vec3 {
         }
     }
 }
+
+/// Serde only supports arrays of up to 32 elements, so larger ones go through a sequence.
+mod big_array {
+    use serde::de::Error;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<S: Serializer, T: Serialize, const N: usize>(
+        value: &[T; N],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(value)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>, T: Deserialize<'de>, const N: usize>(
+        deserializer: D,
+    ) -> Result<[T; N], D::Error> {
+        let items = Vec::<T>::deserialize(deserializer)?;
+        let length = items.len();
+        items
+            .try_into()
+            .map_err(|_| D::Error::invalid_length(length, &format!("{N} items").as_str()))
+    }
+}
@@ -0,0 +1,230 @@
+use crate::rfactor_2::client::{open_file, open_input_file};
+use crate::rfactor_2::shared_memory_data::{
+    PageExtended, PageHeader, PageHwControl, PagePluginControl, PageRulesControl,
+    PageWeatherControl, MAX_MAPPED_VEHICLES,
+};
+use crate::rfactor_2::{Config, Rules};
+use crate::windows_util::{string_to_cp1252, SharedMemory};
+use anyhow::{bail, Result};
+use serde::{Deserialize, Serialize};
+use std::mem::size_of;
+use std::sync::atomic::{fence, Ordering};
+use std::time::Duration;
+
+/// Writer of the input buffers of the plugin.
+///
+/// Each buffer has to be enabled in the plugin configuration, or requested through
+/// [`Input::send_plugin_control`], before the plugin reads it.
+/// The plugin only reads the latest write of each buffer on its update, so writes of the same
+/// buffer in quick succession might get lost.
+pub struct Input {
+    extended: SharedMemory,
+    hw_control: SharedMemory,
+    weather_control: SharedMemory,
+    rules_control: SharedMemory,
+    plugin_control: SharedMemory,
+}
+
+impl Input {
+    /// Connect to the input buffers, waiting until the plugin creates them.
+    ///
+    /// Plugin versions without input buffers never create them, so this never returns there.
+    pub async fn connect(config: &Config) -> Self {
+        let poll_delay = Duration::from_millis(250);
+        Self {
+            extended: open_file("Extended", config, poll_delay).await,
+            hw_control: open_input_file("HWControl", config, poll_delay).await,
+            weather_control: open_input_file("WeatherControl", config, poll_delay).await,
+            rules_control: open_input_file("RulesControl", config, poll_delay).await,
+            plugin_control: open_input_file("PluginControl", config, poll_delay).await,
+        }
+    }
+
+    /// Set the value of a control of the player's vehicle, 1.0 for pressed and 0.0 for released.
+    pub fn send_hw_control(&self, control: &HwControl, value: f64) -> Result<()> {
+        self.ensure_enabled("HWControl", |v| v.hw_control_input_enabled)?;
+        write_page(&self.hw_control, &Self::hw_control_page(control, value)?);
+        Ok(())
+    }
+
+    /// Change the weather, which the game interpolates towards until [`WeatherControl::et`].
+    pub fn send_weather_control(&self, weather: &WeatherControl) -> Result<()> {
+        self.ensure_enabled("WeatherControl", |v| v.weather_control_input_enabled)?;
+        write_page(&self.weather_control, &Self::weather_control_page(weather));
+        Ok(())
+    }
+
+    /// Inject rules, usually the latest [`Rules`] read by the client with the input/output
+    /// fields changed.
+    pub fn send_rules_control(&self, rules: &Rules) -> Result<()> {
+        self.ensure_enabled("RulesControl", |v| v.rules_control_input_enabled)?;
+        write_page(&self.rules_control, &Self::rules_control_page(rules)?);
+        Ok(())
+    }
+
+    /// Request optional plugin features, which the plugin only allows if its configuration
+    /// enables plugin control input.
+    pub fn send_plugin_control(&self, control: &PluginControl) -> Result<()> {
+        self.ensure_enabled("PluginControl", |v| v.plugin_control_input_enabled)?;
+        write_page(&self.plugin_control, &Self::plugin_control_page(control));
+        Ok(())
+    }
+
+    /// Page [`Input::send_hw_control`] writes, with the header left for the write to fill.
+    pub fn hw_control_page(control: &HwControl, value: f64) -> Result<Vec<u8>> {
+        let name = control.name();
+        let mut page: PageHwControl = unsafe { std::mem::zeroed() };
+        // Leave room for the null terminator, every character takes one byte in CP1252
+        if name.chars().count() >= page.control_name.len() {
+            bail!("HWControl name {name:?} is too long");
+        }
+        page.layout_version = PageHwControl::LAYOUT_VERSION;
+        page.control_name = string_to_cp1252(name);
+        page.ret_val = value;
+        Ok(page_bytes(&page))
+    }
+
+    /// Page [`Input::send_weather_control`] writes, with the header left for the write to fill.
+    pub fn weather_control_page(weather: &WeatherControl) -> Vec<u8> {
+        let mut page: PageWeatherControl = unsafe { std::mem::zeroed() };
+        page.layout_version = PageWeatherControl::LAYOUT_VERSION;
+        page.weather_info.et = weather.et;
+        page.weather_info.raining = weather.raining;
+        page.weather_info.cloudiness = weather.cloudiness;
+        page.weather_info.ambient_temp_k = weather.ambient_temp_k;
+        page.weather_info.wind_max_speed = weather.wind_max_speed;
+        page.weather_info.apply_cloudiness_instantly = weather.apply_cloudiness_instantly as u8;
+        page_bytes(&page)
+    }
+
+    /// Page [`Input::send_rules_control`] writes, with the header left for the write to fill.
+    pub fn rules_control_page(rules: &Rules) -> Result<Vec<u8>> {
+        if rules.actions.len() > MAX_MAPPED_VEHICLES
+            || rules.participants.len() > MAX_MAPPED_VEHICLES
+        {
+            bail!("RulesControl supports at most {MAX_MAPPED_VEHICLES} actions and participants");
+        }
+        let mut page: Box<PageRulesControl> = Box::new(unsafe { std::mem::zeroed() });
+        page.layout_version = PageRulesControl::LAYOUT_VERSION;
+        page.track_rules = (&rules.track_rules).into();
+        page.track_rules.num_actions = rules.actions.len() as i32;
+        page.track_rules.num_participants = rules.participants.len() as i32;
+        for (page_action, action) in page.actions.iter_mut().zip(&rules.actions) {
+            *page_action = action.into();
+        }
+        for (page_participant, participant) in page.participants.iter_mut().zip(&rules.participants)
+        {
+            *page_participant = participant.into();
+        }
+        Ok(page_bytes(page.as_ref()))
+    }
+
+    /// Page [`Input::send_plugin_control`] writes, with the header left for the write to fill.
+    pub fn plugin_control_page(control: &PluginControl) -> Vec<u8> {
+        let mut page: PagePluginControl = unsafe { std::mem::zeroed() };
+        page.layout_version = PagePluginControl::LAYOUT_VERSION;
+        page.request_enable_buffers_mask = control.enable_buffers_mask;
+        page.request_hw_control_input = control.hw_control_input as u8;
+        page.request_weather_control_input = control.weather_control_input as u8;
+        page.request_rules_control_input = control.rules_control_input as u8;
+        page_bytes(&page)
+    }
+
+    /// Check the flags the plugin reports in the extended buffer before writing, since it
+    /// ignores buffers that aren't enabled.
+    fn ensure_enabled(&self, buffer: &str, enabled: fn(&PageExtended) -> u8) -> Result<()> {
+        if enabled(unsafe { self.extended.get_as::<PageExtended>() }) == 0 {
+            bail!("{buffer} input is not enabled in the plugin");
+        }
+        Ok(())
+    }
+}
+
+fn page_bytes<Page: Copy>(page: &Page) -> Vec<u8> {
+    unsafe { std::slice::from_raw_parts(page as *const Page as *const u8, size_of::<Page>()) }
+        .to_vec()
+}
+
+/// Write the page with the same versioning the plugin uses for its own buffers, so the plugin
+/// never reads a partially written page.
+fn write_page(memory: &SharedMemory, page: &[u8]) {
+    let header: PageHeader = unsafe { memory.copy_as() };
+    let version = header.version_update_begin.wrapping_add(1).to_ne_bytes();
+    let header_size = size_of::<PageHeader>();
+    unsafe {
+        // The header holds the begin version followed by the end version.
+        memory.write_bytes(0, &version);
+        fence(Ordering::SeqCst);
+        memory.write_bytes(header_size, &page[header_size..]);
+        fence(Ordering::SeqCst);
+        memory.write_bytes(header_size / 2, &version);
+    }
+}
+
+/// Control of the player's vehicle, named as in the game's controller configuration.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum HwControl {
+    PitRequest,
+    ToggleHeadlights,
+    ToggleMfdA,
+    ToggleMfdB,
+    ToggleMfdC,
+    /// Any other control, by its name.
+    Other(String),
+}
+
+impl HwControl {
+    pub fn name(&self) -> &str {
+        match self {
+            Self::PitRequest => "PitRequest",
+            Self::ToggleHeadlights => "ToggleHeadlights",
+            Self::ToggleMfdA => "ToggleMFDA",
+            Self::ToggleMfdB => "ToggleMFDB",
+            Self::ToggleMfdC => "ToggleMFDC",
+            Self::Other(name) => name,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct WeatherControl {
+    /// Session time when the weather should take effect, far enough in the future for clouds
+    /// to roll in before rain starts.
+    pub et: f64,
+    /// Rain (0.0-1.0) at the nodes around the track, with the origin at index 1, 1.
+    pub raining: [[f64; 3]; 3],
+    /// General cloudiness (0.0=clear to 1.0=dark)
+    pub cloudiness: f64,
+    /// Ambient temperature (Kelvin)
+    pub ambient_temp_k: f64,
+    /// Maximum speed of wind (ground speed, but it affects how fast the clouds move, too)
+    pub wind_max_speed: f64,
+    /// Change the clouds instantly instead of rolling them in.
+    pub apply_cloudiness_instantly: bool,
+}
+
+/// Requests for optional features of the plugin.
+///
+/// Direct memory access can only be enabled in the plugin configuration, and is reported in
+/// [`Extended::direct_memory_access_enabled`](crate::rfactor_2::Extended).
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct PluginControl {
+    /// Buffers to keep updating, see [`subscribed_buffers`].
+    pub enable_buffers_mask: i32,
+    pub hw_control_input: bool,
+    pub weather_control_input: bool,
+    pub rules_control_input: bool,
+}
+
+/// Bits of [`PluginControl::enable_buffers_mask`].
+pub mod subscribed_buffers {
+    pub const TELEMETRY: i32 = 1 << 0;
+    pub const SCORING: i32 = 1 << 1;
+    pub const RULES: i32 = 1 << 2;
+    pub const MULTI_RULES: i32 = 1 << 3;
+    pub const FORCE_FEEDBACK: i32 = 1 << 4;
+    pub const GRAPHICS: i32 = 1 << 5;
+    pub const PIT_INFO: i32 = 1 << 6;
+    pub const WEATHER: i32 = 1 << 7;
+    pub const ALL: i32 = 0xFF;
+}
@@ -1,36 +1,185 @@
-//! Support for rFactor 2.
+//! Support for rFactor 2 and Le Mans Ultimate, which is built on the same engine.
 //!
 //! Requires installing and enabling plugin from <https://github.com/TheIronWolfModding/rF2SharedMemoryMapPlugin>.
 
 mod client;
 mod data;
+mod input;
 mod shared_memory_data;
 
-use crate::{Moment, RacingFlags, Simetry};
-pub use client::{Client, Config};
-pub use data::{Extended, ForceFeedback, MultiRules, PitInfo, Rules, Scoring, Telemetry, Weather};
+use crate::{Moment, Pedals, RacingFlags, Session, SessionPhase, SessionType, Simetry};
+pub use client::{Buffer, Client, Config, UpdateRate};
+pub use data::{
+    Extended, ForceFeedback, MultiRules, PitInfo, Rules, Scoring, ScoringInfo, Telemetry,
+    TrackRules, TrackRulesAction, TrackRulesParticipant, VehicleScoring, VehicleTelemetry, Weather,
+    WheelTelemetry,
+};
+pub use input::{subscribed_buffers, HwControl, Input, PluginControl, WeatherControl};
+use serde::{Deserialize, Serialize};
 use std::borrow::Cow;
+use std::collections::BTreeSet;
 use std::sync::Arc;
 use uom::si::angular_velocity::revolution_per_minute;
-use uom::si::f64::{AngularVelocity, Velocity};
+use uom::si::f64::{AngularVelocity, Time, Velocity};
+use uom::si::time::second;
 use uom::si::velocity::meter_per_second;
 
-#[derive(Clone, Debug)]
+/// Title running on the rFactor 2 engine.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum Game {
+    RFactor2,
+    LeMansUltimate,
+}
+
+impl Game {
+    /// Detect the title from the file name of the executable mapping the buffers,
+    /// like `Le Mans Ultimate.exe` or `rFactor2 Dedicated.exe`.
+    ///
+    /// Returns `None` for executables of neither title.
+    pub fn from_executable_name(name: &str) -> Option<Self> {
+        let name = name.to_lowercase();
+        if name.starts_with("le mans ultimate") || name.starts_with("lmu") {
+            Some(Self::LeMansUltimate)
+        } else if name.starts_with("rfactor2") || name.starts_with("rfactor 2") {
+            Some(Self::RFactor2)
+        } else {
+            None
+        }
+    }
+
+    /// Detect the title from the running processes, as pairs of process ID and executable name.
+    ///
+    /// With a dedicated server's process ID, only that process is considered. Otherwise the
+    /// buffers belong to the game, so its executables are preferred over those of dedicated
+    /// servers running on the same machine.
+    pub fn from_processes(
+        processes: impl IntoIterator<Item = (u32, String)>,
+        dedicated_server_pid: Option<u32>,
+    ) -> Option<Self> {
+        processes
+            .into_iter()
+            .filter_map(|(pid, name)| {
+                let game = Self::from_executable_name(&name)?;
+                match dedicated_server_pid {
+                    Some(dedicated_server_pid) => {
+                        (dedicated_server_pid == pid).then_some((0, game))
+                    }
+                    None => {
+                        let is_dedicated_server = name.to_lowercase().contains("dedicated");
+                        Some((is_dedicated_server as u8, game))
+                    }
+                }
+            })
+            .min_by_key(|(rank, _)| *rank)
+            .map(|(_, game)| game)
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::RFactor2 => "rFactor2",
+            Self::LeMansUltimate => "LeMansUltimate",
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct SimState {
+    pub game: Game,
     pub telemetry: Arc<Telemetry>,
     pub scoring: Arc<Scoring>,
-    pub rules: Arc<Rules>,
-    pub multi_rules: Arc<MultiRules>,
-    pub force_feedback: Arc<ForceFeedback>,
-    pub pit_info: Arc<PitInfo>,
-    pub weather: Arc<Weather>,
-    pub extended: Arc<Extended>,
+    /// `None` for buffers skipped in the [`Config`].
+    pub rules: Option<Arc<Rules>>,
+    pub multi_rules: Option<Arc<MultiRules>>,
+    pub force_feedback: Option<Arc<ForceFeedback>>,
+    pub pit_info: Option<Arc<PitInfo>>,
+    pub weather: Option<Arc<Weather>>,
+    pub extended: Option<Arc<Extended>>,
+    /// Buffers that changed since the previous state returned by the client.
+    #[serde(default)]
+    pub changed: BTreeSet<Buffer>,
+}
+
+impl SimState {
+    /// The vehicle driven by the player, `None` while the player isn't in a vehicle.
+    pub fn player(&self) -> Option<Vehicle<'_>> {
+        self.vehicles().find(|v| v.scoring.is_player != 0)
+    }
+
+    /// All vehicles in the session, in the order of the scoring buffer.
+    pub fn vehicles(&self) -> impl Iterator<Item = Vehicle<'_>> {
+        self.scoring.vehicles.iter().map(|scoring| Vehicle {
+            scoring,
+            telemetry: self.telemetry.vehicles.iter().find(|v| v.id == scoring.id),
+        })
+    }
+
+    /// Take a copy of the state if any of the buffers changed, and clear the changes so
+    /// they're only reported once.
+    ///
+    /// Otherwise the changes are kept, to be reported with the next copy taken.
+    pub fn take_if_changed(&mut self, buffers: &BTreeSet<Buffer>) -> Option<SimState> {
+        if !self.changed.iter().any(|v| buffers.contains(v)) {
+            return None;
+        }
+        let state = self.clone();
+        self.changed.clear();
+        Some(state)
+    }
+}
+
+/// Scoring and telemetry of a vehicle, which the plugin provides in separate buffers
+/// joined by the slot ID.
+#[derive(Clone, Copy, Debug)]
+pub struct Vehicle<'a> {
+    pub scoring: &'a VehicleScoring,
+    /// `None` if the telemetry buffer doesn't contain the vehicle, which happens briefly
+    /// after it joins, since the buffers are updated at different rates.
+    pub telemetry: Option<&'a VehicleTelemetry>,
+}
+
+impl<'a> Vehicle<'a> {
+    pub fn id(&self) -> i32 {
+        self.scoring.id
+    }
+
+    pub fn wheels(&self) -> Option<&'a [WheelTelemetry; 4]> {
+        Some(&self.telemetry?.wheels)
+    }
+
+    /// Vehicle name without the race number, like `Oreca 07 Gibson` for `Oreca 07 Gibson #22`.
+    pub fn vehicle_name(&self) -> &'a str {
+        let name = self.scoring.vehicle_name.as_str();
+        name.split_once('#').map_or(name, |v| v.0).trim()
+    }
+
+    /// First word of the vehicle name, which is usually the brand.
+    pub fn brand(&self) -> &'a str {
+        let name = self.vehicle_name();
+        name.split_once(' ').map_or(name, |v| v.0)
+    }
+
+    /// Vehicle name after the brand, empty if the name is a single word.
+    pub fn model(&self) -> &'a str {
+        self.vehicle_name()
+            .split_once(' ')
+            .map_or("", |v| v.1.trim_start())
+    }
+
+    /// Steering input (-1.0 to 1.0) as read by the game.
+    pub fn steering(&self) -> Option<f64> {
+        Some(self.telemetry?.filtered_steering)
+    }
+
+    /// Amount of fuel (liters).
+    pub fn fuel(&self) -> Option<f64> {
+        Some(self.telemetry?.fuel)
+    }
 }
 
 #[async_trait::async_trait]
 impl Simetry for Client {
     fn name(&self) -> &str {
-        "rFactor2"
+        self.game().name()
     }
 
     async fn next_moment(&mut self) -> Option<Box<dyn Moment + Send + Sync + 'static>> {
@@ -38,19 +187,18 @@ impl Simetry for Client {
     }
 }
 
+/// Full-course yellow states of `ScoringInfo::yellow_flag_state`, from pending to resume.
+const FULL_COURSE_YELLOW: std::ops::RangeInclusive<i8> = 1..=6;
+/// Race halt state of `ScoringInfo::yellow_flag_state`.
+const RACE_HALT: i8 = 7;
+
 impl Moment for SimState {
     fn vehicle_gear(&self) -> Option<i8> {
-        let player_scoring = self.scoring.vehicles.iter().find(|v| v.is_player != 0)?;
-        let player_id = player_scoring.id;
-        let player_telemetry = self.telemetry.vehicles.iter().find(|v| v.id == player_id)?;
-        Some(player_telemetry.gear as i8)
+        Some(self.player()?.telemetry?.gear as i8)
     }
 
     fn vehicle_velocity(&self) -> Option<Velocity> {
-        let player_scoring = self.scoring.vehicles.iter().find(|v| v.is_player != 0)?;
-        let player_id = player_scoring.id;
-        let player_telemetry = self.telemetry.vehicles.iter().find(|v| v.id == player_id)?;
-        let speed_vec_ms = &player_telemetry.local_vel;
+        let speed_vec_ms = &self.player()?.telemetry?.local_vel;
         let speed_ms = (speed_vec_ms.x * speed_vec_ms.x
             + speed_vec_ms.y * speed_vec_ms.y
             + speed_vec_ms.z * speed_vec_ms.z)
@@ -59,63 +207,131 @@ impl Moment for SimState {
     }
 
     fn vehicle_engine_rotation_speed(&self) -> Option<AngularVelocity> {
-        let player_scoring = self.scoring.vehicles.iter().find(|v| v.is_player != 0)?;
-        let player_id = player_scoring.id;
-        let player_telemetry = self.telemetry.vehicles.iter().find(|v| v.id == player_id)?;
         Some(AngularVelocity::new::<revolution_per_minute>(
-            player_telemetry.engine_rpm,
+            self.player()?.telemetry?.engine_rpm,
         ))
     }
 
     fn vehicle_max_engine_rotation_speed(&self) -> Option<AngularVelocity> {
-        let player_scoring = self.scoring.vehicles.iter().find(|v| v.is_player != 0)?;
-        let player_id = player_scoring.id;
-        let player_telemetry = self.telemetry.vehicles.iter().find(|v| v.id == player_id)?;
         Some(AngularVelocity::new::<revolution_per_minute>(
-            player_telemetry.engine_max_rpm,
+            self.player()?.telemetry?.engine_max_rpm,
         ))
     }
 
     fn is_pit_limiter_engaged(&self) -> Option<bool> {
-        let player_scoring = self.scoring.vehicles.iter().find(|v| v.is_player != 0)?;
-        let player_id = player_scoring.id;
-        let player_telemetry = self.telemetry.vehicles.iter().find(|v| v.id == player_id)?;
-        Some(player_telemetry.speed_limiter != 0)
+        Some(self.player()?.telemetry?.speed_limiter != 0)
     }
 
     fn is_vehicle_in_pit_lane(&self) -> Option<bool> {
-        let player_scoring = self.scoring.vehicles.iter().find(|v| v.is_player != 0)?;
-        Some(player_scoring.in_pits != 0)
+        Some(self.player()?.scoring.in_pits != 0)
     }
 
     fn flags(&self) -> Option<RacingFlags> {
-        let player_scoring = self.scoring.vehicles.iter().find(|v| v.is_player != 0)?;
+        let player = self.player()?.scoring;
+        let info = &self.scoring.scoring_info;
+        // Sector 0 is the last sector, both for the vehicle and for the sector flags.
+        let sector_yellow = usize::try_from(player.sector)
+            .ok()
+            .and_then(|v| info.sector_flag.get(v))
+            .is_some_and(|v| *v != 0);
+        let full_course_yellow = FULL_COURSE_YELLOW.contains(&info.yellow_flag_state);
+        let yellow = full_course_yellow || sector_yellow || player.individual_phase == 10;
+        let red = info.yellow_flag_state == RACE_HALT || info.game_phase == 7;
+        let countdown = info.game_phase == 4;
+        let lights_out = info.start_light > info.num_red_lights;
         Some(RacingFlags {
-            green: player_scoring.flag == 0,
-            yellow: player_scoring.individual_phase == 10,
-            blue: player_scoring.flag == 6,
+            green: info.game_phase == 5 && player.flag == 0 && !yellow,
+            yellow,
+            blue: player.flag == 6,
             white: false,
-            red: false,
-            black: player_scoring.num_penalties > 0,
-            checkered: player_scoring.finish_status == 1,
+            red,
+            black: player.num_penalties > 0 || player.finish_status == 3,
+            checkered: player.finish_status == 1,
             meatball: false,
             black_and_white: false,
-            start_ready: false,
-            start_set: false,
-            start_go: false,
+            start_ready: countdown && info.start_light == 0,
+            start_set: countdown && info.start_light > 0 && !lights_out,
+            start_go: countdown && info.start_light > 0 && lights_out,
+        })
+    }
+
+    fn vehicle_brand_id(&self) -> Option<Cow<'_, str>> {
+        Some(self.player()?.brand().into())
+    }
+
+    fn vehicle_model_id(&self) -> Option<Cow<'_, str>> {
+        Some(self.player()?.model().into())
+    }
+
+    fn vehicle_unique_id(&self) -> Option<Cow<'_, str>> {
+        Some(self.player()?.vehicle_name().into())
+    }
+
+    fn is_ignition_on(&self) -> Option<bool> {
+        Some(self.player()?.telemetry?.ignition_starter > 0)
+    }
+
+    fn is_starter_on(&self) -> Option<bool> {
+        Some(self.player()?.telemetry?.ignition_starter == 2)
+    }
+
+    // The turn indicators are left as `None`, since the plugin doesn't expose them,
+    // and the headlights it does expose have no counterpart in `Moment`.
+
+    fn pedals(&self) -> Option<Pedals> {
+        let telemetry = self.player()?.telemetry?;
+        Some(Pedals {
+            throttle: telemetry.filtered_throttle,
+            brake: telemetry.filtered_brake,
+            clutch: telemetry.filtered_clutch,
+        })
+    }
+
+    fn pedals_raw(&self) -> Option<Pedals> {
+        let telemetry = self.player()?.telemetry?;
+        Some(Pedals {
+            throttle: telemetry.unfiltered_throttle,
+            brake: telemetry.unfiltered_brake,
+            clutch: telemetry.unfiltered_clutch,
         })
     }
 
-    fn vehicle_unique_id(&self) -> Option<Cow<str>> {
-        let player_scoring = self.scoring.vehicles.iter().find(|v| v.is_player != 0)?;
-        Some(
-            player_scoring
-                .vehicle_name
-                .split_once('#')
-                .unwrap_or(("?", ""))
-                .0
-                .trim()
-                .into(),
-        )
+    fn session(&self) -> Option<Session> {
+        let scoring_info = &self.scoring.scoring_info;
+        let session_type = match scoring_info.session {
+            0..=4 => Some(SessionType::Practice),
+            5..=8 => Some(SessionType::Qualifying),
+            9 => Some(SessionType::Warmup),
+            10..=13 => Some(SessionType::Race),
+            _ => None,
+        };
+        let phase = match scoring_info.game_phase {
+            0..=2 => Some(SessionPhase::Waiting),
+            3 => Some(SessionPhase::Formation),
+            4 => Some(SessionPhase::Countdown),
+            5 => Some(SessionPhase::Green),
+            6 => Some(SessionPhase::FullCourseYellow),
+            7 | 9 => Some(SessionPhase::Stopped),
+            8 => Some(SessionPhase::Finished),
+            _ => None,
+        };
+        let time_remaining = Some(scoring_info.end_et - scoring_info.current_et)
+            .filter(|_| scoring_info.end_et > 0.0)
+            .map(|v| Time::new::<second>(v.max(0.0)));
+        let total_laps = Some(scoring_info.max_laps)
+            .filter(|v| *v > 0 && *v < i32::MAX)
+            .and_then(|v| u32::try_from(v).ok());
+        let completed_laps = self
+            .player()
+            .map_or(0, |v| v.scoring.total_laps.max(0) as u32);
+        Some(Session {
+            session_type,
+            phase,
+            time_remaining,
+            laps_remaining: total_laps.map(|v| v.saturating_sub(completed_laps)),
+            total_laps,
+            track_id: Some(scoring_info.track_name.clone()),
+            is_replay: None,
+        })
     }
 }
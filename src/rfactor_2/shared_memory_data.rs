@@ -209,9 +209,22 @@ pub struct PageVehicleTelemetry {
     /// the *physical* steering wheel range    
     pub physical_steering_wheel_range: f32,
 
+    // Hybrid
+    /// 0.0-1.0
+    pub battery_charge_fraction: f64,
+    /// current torque of boost motor (can be negative when in regenerating mode)
+    pub electric_boost_motor_torque: f64,
+    pub electric_boost_motor_rpm: f64,
+    /// Celsius
+    pub electric_boost_motor_temperature: f64,
+    /// Celsius
+    pub electric_boost_water_temperature: f64,
+    /// 0=unavailable 1=inactive, 2=propulsion, 3=regeneration
+    pub electric_boost_motor_state: u8,
+
     // Future use
     /// for future use (note that the slot ID has been moved to mID above)    
-    expansion: [Garbage; 152],
+    expansion: [Garbage; 111],
 
     // keeping this at the end of the structure to make it easier to replace in future versions
     /// wheel info (front left, front right, rear left, rear right)    
@@ -748,6 +761,104 @@ pub struct PageExtended {
     /// Ticks when last FCY rules message was updated.  Currently, only SCR plugin sets that.
     pub ticks_lsi_rules_instruction_message_updated: i64,
     pub lsi_rules_instruction_message: String96,
+
+    /// Currently active UnsubscribedBuffersMask value.
+    pub unsubscribed_buffers_mask: i32,
+
+    /// HWControl input buffer is enabled.
+    pub hw_control_input_enabled: u8,
+    /// Weather Control input buffer is enabled.
+    pub weather_control_input_enabled: u8,
+    /// Rules Control input buffer is enabled.
+    pub rules_control_input_enabled: u8,
+    /// Plugin Control input buffer is enabled.
+    pub plugin_control_input_enabled: u8,
+}
+
+#[repr(C, packed(4))]
+#[derive(Copy, Clone, Debug)]
+pub struct PageHwControl {
+    pub header: PageHeader,
+    /// Layout version the writer was built against.
+    pub layout_version: i32,
+
+    /// Name of the control, as in the controller configuration.
+    pub control_name: String96,
+    /// 1.0 for pressed, 0.0 for released.
+    pub ret_val: f64,
+}
+
+impl PageHwControl {
+    pub const LAYOUT_VERSION: i32 = 1;
+}
+
+#[repr(C, packed(4))]
+#[derive(Copy, Clone, Debug)]
+pub struct PageWeatherControl {
+    pub header: PageHeader,
+    /// Layout version the writer was built against.
+    pub layout_version: i32,
+
+    pub weather_info: PageWeatherControlInfo,
+}
+
+impl PageWeatherControl {
+    pub const LAYOUT_VERSION: i32 = 1;
+}
+
+#[repr(C, packed(4))]
+#[derive(Copy, Clone, Debug)]
+pub struct PageWeatherControlInfo {
+    /// when you want this weather to take effect
+    pub et: f64,
+    /// rain (0.0-1.0) at different nodes, with the origin at index 1, 1
+    pub raining: [[f64; 3]; 3],
+    /// general cloudiness (0.0=clear to 1.0=dark)
+    pub cloudiness: f64,
+    /// ambient temperature (Kelvin)
+    pub ambient_temp_k: f64,
+    /// maximum speed of wind (ground speed, but it affects how fast the clouds move, too)
+    pub wind_max_speed: f64,
+    /// preferably we roll the new clouds in, but you can instantly change them now
+    pub apply_cloudiness_instantly: u8,
+
+    unused: [Garbage; 3],
+    /// future use (humidity, pressure, air density, etc.)
+    expansion: [Garbage; 508],
+}
+
+#[repr(C, packed(4))]
+#[derive(Copy, Clone, Debug)]
+pub struct PageRulesControl {
+    pub header: PageHeader,
+    /// Layout version the writer was built against.
+    pub layout_version: i32,
+
+    pub track_rules: PageTrackRules,
+    pub actions: [PageTrackRulesAction; MAX_MAPPED_VEHICLES],
+    pub participants: [PageTrackRulesParticipant; MAX_MAPPED_VEHICLES],
+}
+
+impl PageRulesControl {
+    pub const LAYOUT_VERSION: i32 = 1;
+}
+
+#[repr(C, packed(4))]
+#[derive(Copy, Clone, Debug)]
+pub struct PagePluginControl {
+    pub header: PageHeader,
+    /// Layout version the writer was built against.
+    pub layout_version: i32,
+
+    /// Mask of the buffers to update, see `SubscribedBuffer` in the plugin.
+    pub request_enable_buffers_mask: i32,
+    pub request_hw_control_input: u8,
+    pub request_weather_control_input: u8,
+    pub request_rules_control_input: u8,
+}
+
+impl PagePluginControl {
+    pub const LAYOUT_VERSION: i32 = 1;
 }
 
 #[repr(C, packed(4))]
@@ -0,0 +1,381 @@
+//! Support for the UDP telemetry of Richard Burns Rally with the NGP plugin.
+//!
+//! Telemetry has to be enabled in `RichardBurnsRally.ini` by setting `udpTelemetry=1` with
+//! `udpTelemetryAddress=127.0.0.1` and `udpTelemetryPort=6776`.
+
+use crate::{Moment, Pedals, Session, Simetry};
+use anyhow::{bail, Result};
+use byteorder::{LittleEndian, ReadBytesExt};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tokio::net::UdpSocket;
+use uom::si::angular_velocity::revolution_per_minute;
+use uom::si::f64::{AngularVelocity, Velocity};
+use uom::si::velocity::kilometer_per_hour;
+
+#[derive(Debug)]
+pub struct Client {
+    socket: UdpSocket,
+}
+
+impl Client {
+    pub const DEFAULT_URI: &'static str = "127.0.0.1:6776";
+
+    pub async fn connect(uri: &str, retry_delay: Duration) -> Self {
+        loop {
+            if let Ok(client) = Self::try_connect(uri).await {
+                return client;
+            }
+            tokio::time::sleep(retry_delay).await;
+        }
+    }
+
+    pub async fn try_connect(uri: &str) -> Result<Self> {
+        let slf = Self {
+            socket: UdpSocket::bind(uri).await?,
+        };
+        slf.next_sim_state().await?;
+        Ok(slf)
+    }
+
+    /// Wait for the next valid datagram.
+    ///
+    /// Datagrams of unexpected size are logged and skipped.
+    pub async fn next_sim_state(&self) -> Result<SimState> {
+        let mut buffer = [0u8; PACKET_BUFFER_SIZE];
+        loop {
+            let size = self.socket.recv(&mut buffer).await?;
+            match SimState::parse(&buffer[..size]) {
+                Ok(state) => return Ok(state),
+                Err(err) => log::warn!("Skipping Richard Burns Rally datagram: {err}"),
+            }
+        }
+    }
+}
+
+const PACKET_BUFFER_SIZE: usize = 1024;
+
+#[async_trait::async_trait]
+impl Simetry for Client {
+    fn name(&self) -> &str {
+        "RichardBurnsRally"
+    }
+
+    async fn next_moment(&mut self) -> Option<Box<dyn Moment + Send + Sync + 'static>> {
+        Some(Box::new(self.next_sim_state().await.ok()?))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SimState {
+    pub total_steps: u32,
+    pub stage: Stage,
+    pub control: Control,
+    pub car: Car,
+}
+
+impl SimState {
+    pub const SIZE: usize = 664;
+
+    pub fn parse(datagram: &[u8]) -> Result<Self> {
+        if datagram.len() != Self::SIZE {
+            bail!(
+                "Expected {} bytes, but got {} bytes",
+                Self::SIZE,
+                datagram.len(),
+            );
+        }
+        let data = &mut &datagram[..];
+        Ok(Self {
+            total_steps: data.read_u32::<LittleEndian>()?,
+            stage: Stage::read(data)?,
+            control: Control::read(data)?,
+            car: Car::read(data)?,
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Stage {
+    pub index: i32,
+    /// Distance driven on the stage in meters
+    pub progress: f32,
+    /// Time since the stage start in seconds
+    pub race_time: f32,
+    pub drive_line_location: f32,
+    /// Distance remaining to the finish in meters
+    pub distance_to_end: f32,
+}
+
+impl Stage {
+    fn read(data: &mut &[u8]) -> Result<Self> {
+        Ok(Self {
+            index: data.read_i32::<LittleEndian>()?,
+            progress: read_f32(data)?,
+            race_time: read_f32(data)?,
+            drive_line_location: read_f32(data)?,
+            distance_to_end: read_f32(data)?,
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Control {
+    pub steering: f32,
+    pub throttle: f32,
+    pub brake: f32,
+    pub handbrake: f32,
+    pub clutch: f32,
+    /// 0 = reverse, 1 = neutral, 2 and up forward gears
+    pub gear: i32,
+    pub footbrake_pressure: f32,
+    pub handbrake_pressure: f32,
+}
+
+impl Control {
+    fn read(data: &mut &[u8]) -> Result<Self> {
+        Ok(Self {
+            steering: read_f32(data)?,
+            throttle: read_f32(data)?,
+            brake: read_f32(data)?,
+            handbrake: read_f32(data)?,
+            clutch: read_f32(data)?,
+            gear: data.read_i32::<LittleEndian>()?,
+            footbrake_pressure: read_f32(data)?,
+            handbrake_pressure: read_f32(data)?,
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Car {
+    pub index: i32,
+    /// Speed in km/h
+    pub speed: f32,
+    pub position: [f32; 3],
+    pub roll: f32,
+    pub pitch: f32,
+    pub yaw: f32,
+    pub velocities: Motion,
+    pub accelerations: Motion,
+    pub engine: Engine,
+    pub suspension_lf: Suspension,
+    pub suspension_rf: Suspension,
+    pub suspension_lb: Suspension,
+    pub suspension_rb: Suspension,
+}
+
+impl Car {
+    fn read(data: &mut &[u8]) -> Result<Self> {
+        Ok(Self {
+            index: data.read_i32::<LittleEndian>()?,
+            speed: read_f32(data)?,
+            position: read_f32s(data)?,
+            roll: read_f32(data)?,
+            pitch: read_f32(data)?,
+            yaw: read_f32(data)?,
+            velocities: Motion::read(data)?,
+            accelerations: Motion::read(data)?,
+            engine: Engine::read(data)?,
+            suspension_lf: Suspension::read(data)?,
+            suspension_rf: Suspension::read(data)?,
+            suspension_lb: Suspension::read(data)?,
+            suspension_rb: Suspension::read(data)?,
+        })
+    }
+
+    /// Suspensions in order front left, front right, rear left, rear right.
+    pub fn suspensions(&self) -> [&Suspension; 4] {
+        [
+            &self.suspension_lf,
+            &self.suspension_rf,
+            &self.suspension_lb,
+            &self.suspension_rb,
+        ]
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Motion {
+    pub surge: f32,
+    pub sway: f32,
+    pub heave: f32,
+    pub roll: f32,
+    pub pitch: f32,
+    pub yaw: f32,
+}
+
+impl Motion {
+    fn read(data: &mut &[u8]) -> Result<Self> {
+        Ok(Self {
+            surge: read_f32(data)?,
+            sway: read_f32(data)?,
+            heave: read_f32(data)?,
+            roll: read_f32(data)?,
+            pitch: read_f32(data)?,
+            yaw: read_f32(data)?,
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Engine {
+    pub rpm: f32,
+    pub radiator_coolant_temperature: f32,
+    pub engine_coolant_temperature: f32,
+    pub engine_temperature: f32,
+}
+
+impl Engine {
+    fn read(data: &mut &[u8]) -> Result<Self> {
+        Ok(Self {
+            rpm: read_f32(data)?,
+            radiator_coolant_temperature: read_f32(data)?,
+            engine_coolant_temperature: read_f32(data)?,
+            engine_temperature: read_f32(data)?,
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Suspension {
+    pub spring_deflection: f32,
+    pub rollbar_force: f32,
+    pub spring_force: f32,
+    pub damper_force: f32,
+    pub strut_force: f32,
+    pub is_helper_spring_active: bool,
+    pub damper: Damper,
+    pub wheel: Wheel,
+}
+
+impl Suspension {
+    fn read(data: &mut &[u8]) -> Result<Self> {
+        Ok(Self {
+            spring_deflection: read_f32(data)?,
+            rollbar_force: read_f32(data)?,
+            spring_force: read_f32(data)?,
+            damper_force: read_f32(data)?,
+            strut_force: read_f32(data)?,
+            is_helper_spring_active: data.read_i32::<LittleEndian>()? != 0,
+            damper: Damper {
+                damage: read_f32(data)?,
+                piston_velocity: read_f32(data)?,
+            },
+            wheel: Wheel::read(data)?,
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Damper {
+    pub damage: f32,
+    pub piston_velocity: f32,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Wheel {
+    pub brake_disk: BrakeDisk,
+    pub tire: Tire,
+}
+
+impl Wheel {
+    fn read(data: &mut &[u8]) -> Result<Self> {
+        Ok(Self {
+            brake_disk: BrakeDisk {
+                layer_temperature: read_f32(data)?,
+                temperature: read_f32(data)?,
+                wear: read_f32(data)?,
+            },
+            tire: Tire::read(data)?,
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct BrakeDisk {
+    pub layer_temperature: f32,
+    pub temperature: f32,
+    pub wear: f32,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Tire {
+    pub pressure: f32,
+    pub temperature: f32,
+    pub carcass_temperature: f32,
+    pub tread_temperature: f32,
+    /// Index of the segment touching the ground
+    pub current_segment: u32,
+    pub segments: [TireSegment; 8],
+}
+
+impl Tire {
+    fn read(data: &mut &[u8]) -> Result<Self> {
+        let pressure = read_f32(data)?;
+        let temperature = read_f32(data)?;
+        let carcass_temperature = read_f32(data)?;
+        let tread_temperature = read_f32(data)?;
+        let current_segment = data.read_u32::<LittleEndian>()?;
+        let mut segments = [TireSegment::default(); 8];
+        for segment in &mut segments {
+            segment.temperature = read_f32(data)?;
+            segment.wear = read_f32(data)?;
+        }
+        Ok(Self {
+            pressure,
+            temperature,
+            carcass_temperature,
+            tread_temperature,
+            current_segment,
+            segments,
+        })
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct TireSegment {
+    pub temperature: f32,
+    pub wear: f32,
+}
+
+fn read_f32(data: &mut &[u8]) -> Result<f32> {
+    Ok(data.read_f32::<LittleEndian>()?)
+}
+
+fn read_f32s<const N: usize>(data: &mut &[u8]) -> Result<[f32; N]> {
+    let mut out = [0.0; N];
+    data.read_f32_into::<LittleEndian>(&mut out)?;
+    Ok(out)
+}
+
+impl Moment for SimState {
+    fn vehicle_gear(&self) -> Option<i8> {
+        Some((self.control.gear - 1) as i8)
+    }
+
+    fn vehicle_velocity(&self) -> Option<Velocity> {
+        Some(Velocity::new::<kilometer_per_hour>(self.car.speed as f64))
+    }
+
+    fn vehicle_engine_rotation_speed(&self) -> Option<AngularVelocity> {
+        Some(AngularVelocity::new::<revolution_per_minute>(
+            self.car.engine.rpm as f64,
+        ))
+    }
+
+    fn pedals(&self) -> Option<Pedals> {
+        Some(Pedals {
+            throttle: self.control.throttle as f64,
+            brake: self.control.brake as f64,
+            clutch: self.control.clutch as f64,
+        })
+    }
+
+    fn session(&self) -> Option<Session> {
+        Some(Session {
+            track_id: Some(self.stage.index.to_string()),
+            ..Session::default()
+        })
+    }
+}
@@ -0,0 +1,55 @@
+use serde::{Deserialize, Serialize};
+use uom::si::f64::Time;
+
+/// Information about the session that is currently running in the sim.
+///
+/// Every field is optional, since sims expose different subsets of this data.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Session {
+    pub session_type: Option<SessionType>,
+    pub phase: Option<SessionPhase>,
+    /// Time left until the session ends, if the session is time limited.
+    pub time_remaining: Option<Time>,
+    /// Laps left until the session ends, if the session is lap limited.
+    pub laps_remaining: Option<u32>,
+    /// Total number of laps in the session, if the session is lap limited.
+    pub total_laps: Option<u32>,
+    /// Name or ID of the track, consistent for the same track and layout in the specific sim.
+    pub track_id: Option<String>,
+    /// Check if the data comes from a replay instead of live driving.
+    pub is_replay: Option<bool>,
+}
+
+/// Type of the session, as defined by the sim.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum SessionType {
+    Practice,
+    Qualifying,
+    Warmup,
+    Race,
+    /// Single car sessions against the clock, like hotlaps, time attacks or stages.
+    Hotlap,
+    /// Any other session type the sim might have, like drifting or drag racing.
+    Other,
+}
+
+/// Phase of the current session.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub enum SessionPhase {
+    /// Session has not started yet, cars are in the garage or on the grid.
+    Waiting,
+    /// Formation or parade laps.
+    Formation,
+    /// Start lights are counting down.
+    Countdown,
+    /// Session is running under green flag conditions.
+    Green,
+    /// Session is running under full course yellow or safety car.
+    FullCourseYellow,
+    /// Session has been stopped or paused.
+    Stopped,
+    /// Checkered flag has been shown, but not all cars have finished yet.
+    Checkered,
+    /// Session is over.
+    Finished,
+}
@@ -0,0 +1,884 @@
+//! Owned copy of the SCS telemetry shared memory, with Rust naming and strings decoded.
+//!
+//! Structure follows the layout of `scs-telemetry-common.hpp`, leaving out the padding buffers.
+
+use crate::truck_simulator::bindings;
+use serde::{Deserialize, Serialize};
+use std::os::raw::c_char;
+
+fn extract_string(data: &[c_char]) -> String {
+    let bytes = data
+        .iter()
+        .map(|v| *v as u8)
+        .take_while(|v| *v != 0)
+        .collect::<Vec<_>>();
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TrailerConB {
+    pub wheel_steerable: [bool; 16],
+    pub wheel_simulated: [bool; 16],
+    pub wheel_powered: [bool; 16],
+    pub wheel_liftable: [bool; 16],
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TrailerComB {
+    pub wheel_on_ground: [bool; 16],
+    pub attached: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TrailerComUi {
+    pub wheel_substance: [u32; 16],
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TrailerConUi {
+    pub wheel_count: u32,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TrailerComF {
+    pub cargo_damage: f32,
+    pub wear_chassis: f32,
+    pub wear_wheels: f32,
+    pub wear_body: f32,
+    pub wheel_susp_deflection: [f32; 16],
+    pub wheel_velocity: [f32; 16],
+    pub wheel_steering: [f32; 16],
+    pub wheel_rotation: [f32; 16],
+    pub wheel_lift: [f32; 16],
+    pub wheel_lift_offset: [f32; 16],
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TrailerConF {
+    pub wheel_radius: [f32; 16],
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TrailerComFv {
+    pub linear_velocity_x: f32,
+    pub linear_velocity_y: f32,
+    pub linear_velocity_z: f32,
+    pub angular_velocity_x: f32,
+    pub angular_velocity_y: f32,
+    pub angular_velocity_z: f32,
+    pub linear_acceleration_x: f32,
+    pub linear_acceleration_y: f32,
+    pub linear_acceleration_z: f32,
+    pub angular_acceleration_x: f32,
+    pub angular_acceleration_y: f32,
+    pub angular_acceleration_z: f32,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TrailerConFv {
+    pub hook_position_x: f32,
+    pub hook_position_y: f32,
+    pub hook_position_z: f32,
+    pub wheel_position_x: [f32; 16],
+    pub wheel_position_y: [f32; 16],
+    pub wheel_position_z: [f32; 16],
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TrailerComDp {
+    pub world_x: f64,
+    pub world_y: f64,
+    pub world_z: f64,
+    pub rotation_x: f64,
+    pub rotation_y: f64,
+    pub rotation_z: f64,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TrailerConS {
+    pub id: String,
+    pub cargo_acessory_id: String,
+    pub body_type: String,
+    pub brand_id: String,
+    pub brand: String,
+    pub name: String,
+    pub chain_type: String,
+    pub license_plate: String,
+    pub license_plate_country: String,
+    pub license_plate_country_id: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Trailer {
+    pub con_b: TrailerConB,
+    pub com_b: TrailerComB,
+    pub com_ui: TrailerComUi,
+    pub con_ui: TrailerConUi,
+    pub com_f: TrailerComF,
+    pub con_f: TrailerConF,
+    pub com_fv: TrailerComFv,
+    pub con_fv: TrailerConFv,
+    pub com_dp: TrailerComDp,
+    pub con_s: TrailerConS,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ScsValues {
+    /// Telemetry Plugin Version
+    pub telemetry_plugin_revision: u32,
+    /// Game major version
+    pub version_major: u32,
+    /// Game minor version
+    pub version_minor: u32,
+    /// Game identifier
+    /// actually 0 for unknown,1 for ets2 and 2 for ats
+    pub game: u32,
+    /// Game telemetry version major
+    pub telemetry_version_game_major: u32,
+    /// Game telemetry version minor
+    pub telemetry_version_game_minor: u32,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CommonUi {
+    /// In game time in minutes
+    pub time_abs: u32,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ConfigUi {
+    pub gears: u32,
+    pub gears_reverse: u32,
+    pub retarder_step_count: u32,
+    pub truck_wheel_count: u32,
+    pub selector_count: u32,
+    pub time_abs_delivery: u32,
+    pub max_trailer_count: u32,
+    pub unit_count: u32,
+    pub planned_distance_km: u32,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TruckUi {
+    pub shifter_slot: u32,
+    pub retarder_brake: u32,
+    pub lights_aux_front: u32,
+    pub lights_aux_roof: u32,
+    pub truck_wheel_substance: [u32; 16],
+    pub hshifter_position: [u32; 32],
+    pub hshifter_bitmask: [u32; 32],
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct GameplayUi {
+    pub job_delivered_delivery_time: u32,
+    pub job_starting_time: u32,
+    pub job_finished_time: u32,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CommonI {
+    pub rest_stop: i32,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TruckI {
+    pub gear: i32,
+    pub gear_dashboard: i32,
+    pub hshifter_resulting: [i32; 32],
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct GameplayI {
+    pub job_delivered_earned_xp: i32,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CommonF {
+    pub scale: f32,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ConfigF {
+    pub fuel_capacity: f32,
+    pub fuel_warning_factor: f32,
+    pub adblue_capacity: f32,
+    pub adblue_warning_factor: f32,
+    pub air_pressure_warning: f32,
+    pub air_pressur_emergency: f32,
+    pub oil_pressure_warning: f32,
+    pub water_temperature_warning: f32,
+    pub battery_voltage_warning: f32,
+    pub engine_rpm_max: f32,
+    pub gear_differential: f32,
+    pub cargo_mass: f32,
+    pub truck_wheel_radius: [f32; 16],
+    pub gear_ratios_forward: [f32; 24],
+    pub gear_ratios_reverse: [f32; 8],
+    pub unit_mass: f32,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TruckF {
+    pub speed: f32,
+    pub engine_rpm: f32,
+    pub user_steer: f32,
+    pub user_throttle: f32,
+    pub user_brake: f32,
+    pub user_clutch: f32,
+    pub game_steer: f32,
+    pub game_throttle: f32,
+    pub game_brake: f32,
+    pub game_clutch: f32,
+    pub cruise_control_speed: f32,
+    pub air_pressure: f32,
+    pub brake_temperature: f32,
+    pub fuel: f32,
+    pub fuel_avg_consumption: f32,
+    pub fuel_range: f32,
+    pub adblue: f32,
+    pub oil_pressure: f32,
+    pub oil_temperature: f32,
+    pub water_temperature: f32,
+    pub battery_voltage: f32,
+    pub lights_dashboard: f32,
+    pub wear_engine: f32,
+    pub wear_transmission: f32,
+    pub wear_cabin: f32,
+    pub wear_chassis: f32,
+    pub wear_wheels: f32,
+    pub truck_odometer: f32,
+    pub route_distance: f32,
+    pub route_time: f32,
+    pub speed_limit: f32,
+    pub truck_wheel_susp_deflection: [f32; 16],
+    pub truck_wheel_velocity: [f32; 16],
+    pub truck_wheel_steering: [f32; 16],
+    pub truck_wheel_rotation: [f32; 16],
+    pub truck_wheel_lift: [f32; 16],
+    pub truck_wheel_lift_offset: [f32; 16],
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct GameplayF {
+    pub job_delivered_cargo_damage: f32,
+    pub job_delivered_distance_km: f32,
+    pub refuel_amount: f32,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct JobF {
+    pub cargo_damage: f32,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ConfigB {
+    pub truck_wheel_steerable: [bool; 16],
+    pub truck_wheel_simulated: [bool; 16],
+    pub truck_wheel_powered: [bool; 16],
+    pub truck_wheel_liftable: [bool; 16],
+    pub is_cargo_loaded: bool,
+    pub special_job: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TruckB {
+    pub park_brake: bool,
+    pub motor_brake: bool,
+    pub air_pressure_warning: bool,
+    pub air_pressure_emergency: bool,
+    pub fuel_warning: bool,
+    pub adblue_warning: bool,
+    pub oil_pressure_warning: bool,
+    pub water_temperature_warning: bool,
+    pub battery_voltage_warning: bool,
+    pub electric_enabled: bool,
+    pub engine_enabled: bool,
+    pub wipers: bool,
+    pub blinker_left_active: bool,
+    pub blinker_right_active: bool,
+    pub blinker_left_on: bool,
+    pub blinker_right_on: bool,
+    pub lights_parking: bool,
+    pub lights_beam_low: bool,
+    pub lights_beam_high: bool,
+    pub lights_beacon: bool,
+    pub lights_brake: bool,
+    pub lights_reverse: bool,
+    pub lights_hazard: bool,
+    /// special field not a sdk field
+    pub cruise_control: bool,
+    pub truck_wheel_on_ground: [bool; 16],
+    pub shifter_toggle: [bool; 2],
+    pub differential_lock: bool,
+    pub lift_axle: bool,
+    pub lift_axle_indicator: bool,
+    pub trailer_lift_axle: bool,
+    pub trailer_lift_axle_indicator: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct GameplayB {
+    pub job_delivered_autopark_used: bool,
+    pub job_delivered_autoload_used: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ConfigFv {
+    pub cabin_position_x: f32,
+    pub cabin_position_y: f32,
+    pub cabin_position_z: f32,
+    pub head_position_x: f32,
+    pub head_position_y: f32,
+    pub head_position_z: f32,
+    pub truck_hook_position_x: f32,
+    pub truck_hook_position_y: f32,
+    pub truck_hook_position_z: f32,
+    pub truck_wheel_position_x: [f32; 16],
+    pub truck_wheel_position_y: [f32; 16],
+    pub truck_wheel_position_z: [f32; 16],
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TruckFv {
+    pub lv_acceleration_x: f32,
+    pub lv_acceleration_y: f32,
+    pub lv_acceleration_z: f32,
+    pub av_acceleration_x: f32,
+    pub av_acceleration_y: f32,
+    pub av_acceleration_z: f32,
+    pub acceleration_x: f32,
+    pub acceleration_y: f32,
+    pub acceleration_z: f32,
+    pub aa_acceleration_x: f32,
+    pub aa_acceleration_y: f32,
+    pub aa_acceleration_z: f32,
+    pub cabin_avx: f32,
+    pub cabin_avy: f32,
+    pub cabin_avz: f32,
+    pub cabin_aax: f32,
+    pub cabin_aay: f32,
+    pub cabin_aaz: f32,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TruckFp {
+    pub cabin_offset_x: f32,
+    pub cabin_offset_y: f32,
+    pub cabin_offset_z: f32,
+    pub cabin_offsetrotation_x: f32,
+    pub cabin_offsetrotation_y: f32,
+    pub cabin_offsetrotation_z: f32,
+    pub head_offset_x: f32,
+    pub head_offset_y: f32,
+    pub head_offset_z: f32,
+    pub head_offsetrotation_x: f32,
+    pub head_offsetrotation_y: f32,
+    pub head_offsetrotation_z: f32,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TruckDp {
+    pub coordinate_x: f64,
+    pub coordinate_y: f64,
+    pub coordinate_z: f64,
+    pub rotation_x: f64,
+    pub rotation_y: f64,
+    pub rotation_z: f64,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ConfigS {
+    pub truck_brand_id: String,
+    pub truck_brand: String,
+    pub truck_id: String,
+    pub truck_name: String,
+    pub cargo_id: String,
+    pub cargo: String,
+    pub city_dst_id: String,
+    pub city_dst: String,
+    pub comp_dst_id: String,
+    pub comp_dst: String,
+    pub city_src_id: String,
+    pub city_src: String,
+    pub comp_src_id: String,
+    pub comp_src: String,
+    pub shifter_type: String,
+    pub truck_license_plate: String,
+    pub truck_license_plate_country_id: String,
+    pub truck_license_plate_country: String,
+    pub job_market: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct GameplayS {
+    pub fine_offence: String,
+    pub ferry_source_name: String,
+    pub ferry_target_name: String,
+    pub ferry_source_id: String,
+    pub ferry_target_id: String,
+    pub train_source_name: String,
+    pub train_target_name: String,
+    pub train_source_id: String,
+    pub train_target_id: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ConfigUll {
+    pub job_income: u64,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct GameplayLl {
+    pub job_cancelled_penalty: i64,
+    pub job_delivered_revenue: i64,
+    pub fine_amount: i64,
+    pub tollgate_pay_amount: i64,
+    pub ferry_pay_amount: i64,
+    pub train_pay_amount: i64,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SpecialB {
+    pub on_job: bool,
+    pub job_finished: bool,
+    pub job_cancelled: bool,
+    pub job_delivered: bool,
+    pub fined: bool,
+    pub tollgate: bool,
+    pub ferry: bool,
+    pub train: bool,
+    pub refuel: bool,
+    pub refuel_payed: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Substances {
+    pub substance: Vec<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Trailers {
+    pub trailer: Vec<Trailer>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TelemetryMap {
+    /// Check if the game and the SDK are running
+    pub sdk_active: bool,
+    /// Check if the game and the telemetry are paused
+    pub paused: bool,
+    /// Not the game time, only a timestamp used to detect updates of the shared memory
+    pub time: u64,
+    pub simulated_time: u64,
+    pub render_time: u64,
+    pub multiplayer_time_offset: i64,
+    /// Contains Game independent values and plugin version
+    pub scs_values: ScsValues,
+    /// Contains common unsigned integers
+    pub common_ui: CommonUi,
+    /// Contains config unsigned integers
+    pub config_ui: ConfigUi,
+    /// Contains trailer/truck channel unsigned integers
+    pub truck_ui: TruckUi,
+    pub gameplay_ui: GameplayUi,
+    pub common_i: CommonI,
+    pub truck_i: TruckI,
+    pub gameplay_i: GameplayI,
+    pub common_f: CommonF,
+    pub config_f: ConfigF,
+    pub truck_f: TruckF,
+    pub gameplay_f: GameplayF,
+    pub job_f: JobF,
+    pub config_b: ConfigB,
+    pub truck_b: TruckB,
+    pub gameplay_b: GameplayB,
+    pub config_fv: ConfigFv,
+    pub truck_fv: TruckFv,
+    pub truck_fp: TruckFp,
+    pub truck_dp: TruckDp,
+    pub config_s: ConfigS,
+    pub gameplay_s: GameplayS,
+    pub config_ull: ConfigUll,
+    pub gameplay_ll: GameplayLl,
+    pub special_b: SpecialB,
+    pub substances: Substances,
+    pub trailer: Trailers,
+}
+
+impl From<&bindings::scsTrailer_t> for Trailer {
+    fn from(v: &bindings::scsTrailer_t) -> Self {
+        Self {
+            con_b: TrailerConB {
+                wheel_steerable: v.con_b.wheelSteerable,
+                wheel_simulated: v.con_b.wheelSimulated,
+                wheel_powered: v.con_b.wheelPowered,
+                wheel_liftable: v.con_b.wheelLiftable,
+            },
+            com_b: TrailerComB {
+                wheel_on_ground: v.com_b.wheelOnGround,
+                attached: v.com_b.attached,
+            },
+            com_ui: TrailerComUi {
+                wheel_substance: v.com_ui.wheelSubstance,
+            },
+            con_ui: TrailerConUi {
+                wheel_count: v.con_ui.wheelCount,
+            },
+            com_f: TrailerComF {
+                cargo_damage: v.com_f.cargoDamage,
+                wear_chassis: v.com_f.wearChassis,
+                wear_wheels: v.com_f.wearWheels,
+                wear_body: v.com_f.wearBody,
+                wheel_susp_deflection: v.com_f.wheelSuspDeflection,
+                wheel_velocity: v.com_f.wheelVelocity,
+                wheel_steering: v.com_f.wheelSteering,
+                wheel_rotation: v.com_f.wheelRotation,
+                wheel_lift: v.com_f.wheelLift,
+                wheel_lift_offset: v.com_f.wheelLiftOffset,
+            },
+            con_f: TrailerConF {
+                wheel_radius: v.con_f.wheelRadius,
+            },
+            com_fv: TrailerComFv {
+                linear_velocity_x: v.com_fv.linearVelocityX,
+                linear_velocity_y: v.com_fv.linearVelocityY,
+                linear_velocity_z: v.com_fv.linearVelocityZ,
+                angular_velocity_x: v.com_fv.angularVelocityX,
+                angular_velocity_y: v.com_fv.angularVelocityY,
+                angular_velocity_z: v.com_fv.angularVelocityZ,
+                linear_acceleration_x: v.com_fv.linearAccelerationX,
+                linear_acceleration_y: v.com_fv.linearAccelerationY,
+                linear_acceleration_z: v.com_fv.linearAccelerationZ,
+                angular_acceleration_x: v.com_fv.angularAccelerationX,
+                angular_acceleration_y: v.com_fv.angularAccelerationY,
+                angular_acceleration_z: v.com_fv.angularAccelerationZ,
+            },
+            con_fv: TrailerConFv {
+                hook_position_x: v.con_fv.hookPositionX,
+                hook_position_y: v.con_fv.hookPositionY,
+                hook_position_z: v.con_fv.hookPositionZ,
+                wheel_position_x: v.con_fv.wheelPositionX,
+                wheel_position_y: v.con_fv.wheelPositionY,
+                wheel_position_z: v.con_fv.wheelPositionZ,
+            },
+            com_dp: TrailerComDp {
+                world_x: v.com_dp.worldX,
+                world_y: v.com_dp.worldY,
+                world_z: v.com_dp.worldZ,
+                rotation_x: v.com_dp.rotationX,
+                rotation_y: v.com_dp.rotationY,
+                rotation_z: v.com_dp.rotationZ,
+            },
+            con_s: TrailerConS {
+                id: extract_string(&v.con_s.id),
+                cargo_acessory_id: extract_string(&v.con_s.cargoAcessoryId),
+                body_type: extract_string(&v.con_s.bodyType),
+                brand_id: extract_string(&v.con_s.brandId),
+                brand: extract_string(&v.con_s.brand),
+                name: extract_string(&v.con_s.name),
+                chain_type: extract_string(&v.con_s.chainType),
+                license_plate: extract_string(&v.con_s.licensePlate),
+                license_plate_country: extract_string(&v.con_s.licensePlateCountry),
+                license_plate_country_id: extract_string(&v.con_s.licensePlateCountryId),
+            },
+        }
+    }
+}
+
+impl From<&bindings::scsTelemetryMap_t> for TelemetryMap {
+    fn from(v: &bindings::scsTelemetryMap_t) -> Self {
+        Self {
+            sdk_active: v.sdkActive,
+            paused: v.paused,
+            time: v.time,
+            simulated_time: v.simulatedTime,
+            render_time: v.renderTime,
+            multiplayer_time_offset: v.multiplayerTimeOffset,
+            scs_values: ScsValues {
+                telemetry_plugin_revision: v.scs_values.telemetry_plugin_revision,
+                version_major: v.scs_values.version_major,
+                version_minor: v.scs_values.version_minor,
+                game: v.scs_values.game,
+                telemetry_version_game_major: v.scs_values.telemetry_version_game_major,
+                telemetry_version_game_minor: v.scs_values.telemetry_version_game_minor,
+            },
+            common_ui: CommonUi {
+                time_abs: v.common_ui.time_abs,
+            },
+            config_ui: ConfigUi {
+                gears: v.config_ui.gears,
+                gears_reverse: v.config_ui.gears_reverse,
+                retarder_step_count: v.config_ui.retarderStepCount,
+                truck_wheel_count: v.config_ui.truckWheelCount,
+                selector_count: v.config_ui.selectorCount,
+                time_abs_delivery: v.config_ui.time_abs_delivery,
+                max_trailer_count: v.config_ui.maxTrailerCount,
+                unit_count: v.config_ui.unitCount,
+                planned_distance_km: v.config_ui.plannedDistanceKm,
+            },
+            truck_ui: TruckUi {
+                shifter_slot: v.truck_ui.shifterSlot,
+                retarder_brake: v.truck_ui.retarderBrake,
+                lights_aux_front: v.truck_ui.lightsAuxFront,
+                lights_aux_roof: v.truck_ui.lightsAuxRoof,
+                truck_wheel_substance: v.truck_ui.truck_wheelSubstance,
+                hshifter_position: v.truck_ui.hshifterPosition,
+                hshifter_bitmask: v.truck_ui.hshifterBitmask,
+            },
+            gameplay_ui: GameplayUi {
+                job_delivered_delivery_time: v.gameplay_ui.jobDeliveredDeliveryTime,
+                job_starting_time: v.gameplay_ui.jobStartingTime,
+                job_finished_time: v.gameplay_ui.jobFinishedTime,
+            },
+            common_i: CommonI {
+                rest_stop: v.common_i.restStop,
+            },
+            truck_i: TruckI {
+                gear: v.truck_i.gear,
+                gear_dashboard: v.truck_i.gearDashboard,
+                hshifter_resulting: v.truck_i.hshifterResulting,
+            },
+            gameplay_i: GameplayI {
+                job_delivered_earned_xp: v.gameplay_i.jobDeliveredEarnedXp,
+            },
+            common_f: CommonF {
+                scale: v.common_f.scale,
+            },
+            config_f: ConfigF {
+                fuel_capacity: v.config_f.fuelCapacity,
+                fuel_warning_factor: v.config_f.fuelWarningFactor,
+                adblue_capacity: v.config_f.adblueCapacity,
+                adblue_warning_factor: v.config_f.adblueWarningFactor,
+                air_pressure_warning: v.config_f.airPressureWarning,
+                air_pressur_emergency: v.config_f.airPressurEmergency,
+                oil_pressure_warning: v.config_f.oilPressureWarning,
+                water_temperature_warning: v.config_f.waterTemperatureWarning,
+                battery_voltage_warning: v.config_f.batteryVoltageWarning,
+                engine_rpm_max: v.config_f.engineRpmMax,
+                gear_differential: v.config_f.gearDifferential,
+                cargo_mass: v.config_f.cargoMass,
+                truck_wheel_radius: v.config_f.truckWheelRadius,
+                gear_ratios_forward: v.config_f.gearRatiosForward,
+                gear_ratios_reverse: v.config_f.gearRatiosReverse,
+                unit_mass: v.config_f.unitMass,
+            },
+            truck_f: TruckF {
+                speed: v.truck_f.speed,
+                engine_rpm: v.truck_f.engineRpm,
+                user_steer: v.truck_f.userSteer,
+                user_throttle: v.truck_f.userThrottle,
+                user_brake: v.truck_f.userBrake,
+                user_clutch: v.truck_f.userClutch,
+                game_steer: v.truck_f.gameSteer,
+                game_throttle: v.truck_f.gameThrottle,
+                game_brake: v.truck_f.gameBrake,
+                game_clutch: v.truck_f.gameClutch,
+                cruise_control_speed: v.truck_f.cruiseControlSpeed,
+                air_pressure: v.truck_f.airPressure,
+                brake_temperature: v.truck_f.brakeTemperature,
+                fuel: v.truck_f.fuel,
+                fuel_avg_consumption: v.truck_f.fuelAvgConsumption,
+                fuel_range: v.truck_f.fuelRange,
+                adblue: v.truck_f.adblue,
+                oil_pressure: v.truck_f.oilPressure,
+                oil_temperature: v.truck_f.oilTemperature,
+                water_temperature: v.truck_f.waterTemperature,
+                battery_voltage: v.truck_f.batteryVoltage,
+                lights_dashboard: v.truck_f.lightsDashboard,
+                wear_engine: v.truck_f.wearEngine,
+                wear_transmission: v.truck_f.wearTransmission,
+                wear_cabin: v.truck_f.wearCabin,
+                wear_chassis: v.truck_f.wearChassis,
+                wear_wheels: v.truck_f.wearWheels,
+                truck_odometer: v.truck_f.truckOdometer,
+                route_distance: v.truck_f.routeDistance,
+                route_time: v.truck_f.routeTime,
+                speed_limit: v.truck_f.speedLimit,
+                truck_wheel_susp_deflection: v.truck_f.truck_wheelSuspDeflection,
+                truck_wheel_velocity: v.truck_f.truck_wheelVelocity,
+                truck_wheel_steering: v.truck_f.truck_wheelSteering,
+                truck_wheel_rotation: v.truck_f.truck_wheelRotation,
+                truck_wheel_lift: v.truck_f.truck_wheelLift,
+                truck_wheel_lift_offset: v.truck_f.truck_wheelLiftOffset,
+            },
+            gameplay_f: GameplayF {
+                job_delivered_cargo_damage: v.gameplay_f.jobDeliveredCargoDamage,
+                job_delivered_distance_km: v.gameplay_f.jobDeliveredDistanceKm,
+                refuel_amount: v.gameplay_f.refuelAmount,
+            },
+            job_f: JobF {
+                cargo_damage: v.job_f.cargoDamage,
+            },
+            config_b: ConfigB {
+                truck_wheel_steerable: v.config_b.truckWheelSteerable,
+                truck_wheel_simulated: v.config_b.truckWheelSimulated,
+                truck_wheel_powered: v.config_b.truckWheelPowered,
+                truck_wheel_liftable: v.config_b.truckWheelLiftable,
+                is_cargo_loaded: v.config_b.isCargoLoaded,
+                special_job: v.config_b.specialJob,
+            },
+            truck_b: TruckB {
+                park_brake: v.truck_b.parkBrake,
+                motor_brake: v.truck_b.motorBrake,
+                air_pressure_warning: v.truck_b.airPressureWarning,
+                air_pressure_emergency: v.truck_b.airPressureEmergency,
+                fuel_warning: v.truck_b.fuelWarning,
+                adblue_warning: v.truck_b.adblueWarning,
+                oil_pressure_warning: v.truck_b.oilPressureWarning,
+                water_temperature_warning: v.truck_b.waterTemperatureWarning,
+                battery_voltage_warning: v.truck_b.batteryVoltageWarning,
+                electric_enabled: v.truck_b.electricEnabled,
+                engine_enabled: v.truck_b.engineEnabled,
+                wipers: v.truck_b.wipers,
+                blinker_left_active: v.truck_b.blinkerLeftActive,
+                blinker_right_active: v.truck_b.blinkerRightActive,
+                blinker_left_on: v.truck_b.blinkerLeftOn,
+                blinker_right_on: v.truck_b.blinkerRightOn,
+                lights_parking: v.truck_b.lightsParking,
+                lights_beam_low: v.truck_b.lightsBeamLow,
+                lights_beam_high: v.truck_b.lightsBeamHigh,
+                lights_beacon: v.truck_b.lightsBeacon,
+                lights_brake: v.truck_b.lightsBrake,
+                lights_reverse: v.truck_b.lightsReverse,
+                lights_hazard: v.truck_b.lightsHazard,
+                cruise_control: v.truck_b.cruiseControl,
+                truck_wheel_on_ground: v.truck_b.truck_wheelOnGround,
+                shifter_toggle: v.truck_b.shifterToggle,
+                differential_lock: v.truck_b.differentialLock,
+                lift_axle: v.truck_b.liftAxle,
+                lift_axle_indicator: v.truck_b.liftAxleIndicator,
+                trailer_lift_axle: v.truck_b.trailerLiftAxle,
+                trailer_lift_axle_indicator: v.truck_b.trailerLiftAxleIndicator,
+            },
+            gameplay_b: GameplayB {
+                job_delivered_autopark_used: v.gameplay_b.jobDeliveredAutoparkUsed,
+                job_delivered_autoload_used: v.gameplay_b.jobDeliveredAutoloadUsed,
+            },
+            config_fv: ConfigFv {
+                cabin_position_x: v.config_fv.cabinPositionX,
+                cabin_position_y: v.config_fv.cabinPositionY,
+                cabin_position_z: v.config_fv.cabinPositionZ,
+                head_position_x: v.config_fv.headPositionX,
+                head_position_y: v.config_fv.headPositionY,
+                head_position_z: v.config_fv.headPositionZ,
+                truck_hook_position_x: v.config_fv.truckHookPositionX,
+                truck_hook_position_y: v.config_fv.truckHookPositionY,
+                truck_hook_position_z: v.config_fv.truckHookPositionZ,
+                truck_wheel_position_x: v.config_fv.truckWheelPositionX,
+                truck_wheel_position_y: v.config_fv.truckWheelPositionY,
+                truck_wheel_position_z: v.config_fv.truckWheelPositionZ,
+            },
+            truck_fv: TruckFv {
+                lv_acceleration_x: v.truck_fv.lv_accelerationX,
+                lv_acceleration_y: v.truck_fv.lv_accelerationY,
+                lv_acceleration_z: v.truck_fv.lv_accelerationZ,
+                av_acceleration_x: v.truck_fv.av_accelerationX,
+                av_acceleration_y: v.truck_fv.av_accelerationY,
+                av_acceleration_z: v.truck_fv.av_accelerationZ,
+                acceleration_x: v.truck_fv.accelerationX,
+                acceleration_y: v.truck_fv.accelerationY,
+                acceleration_z: v.truck_fv.accelerationZ,
+                aa_acceleration_x: v.truck_fv.aa_accelerationX,
+                aa_acceleration_y: v.truck_fv.aa_accelerationY,
+                aa_acceleration_z: v.truck_fv.aa_accelerationZ,
+                cabin_avx: v.truck_fv.cabinAVX,
+                cabin_avy: v.truck_fv.cabinAVY,
+                cabin_avz: v.truck_fv.cabinAVZ,
+                cabin_aax: v.truck_fv.cabinAAX,
+                cabin_aay: v.truck_fv.cabinAAY,
+                cabin_aaz: v.truck_fv.cabinAAZ,
+            },
+            truck_fp: TruckFp {
+                cabin_offset_x: v.truck_fp.cabinOffsetX,
+                cabin_offset_y: v.truck_fp.cabinOffsetY,
+                cabin_offset_z: v.truck_fp.cabinOffsetZ,
+                cabin_offsetrotation_x: v.truck_fp.cabinOffsetrotationX,
+                cabin_offsetrotation_y: v.truck_fp.cabinOffsetrotationY,
+                cabin_offsetrotation_z: v.truck_fp.cabinOffsetrotationZ,
+                head_offset_x: v.truck_fp.headOffsetX,
+                head_offset_y: v.truck_fp.headOffsetY,
+                head_offset_z: v.truck_fp.headOffsetZ,
+                head_offsetrotation_x: v.truck_fp.headOffsetrotationX,
+                head_offsetrotation_y: v.truck_fp.headOffsetrotationY,
+                head_offsetrotation_z: v.truck_fp.headOffsetrotationZ,
+            },
+            truck_dp: TruckDp {
+                coordinate_x: v.truck_dp.coordinateX,
+                coordinate_y: v.truck_dp.coordinateY,
+                coordinate_z: v.truck_dp.coordinateZ,
+                rotation_x: v.truck_dp.rotationX,
+                rotation_y: v.truck_dp.rotationY,
+                rotation_z: v.truck_dp.rotationZ,
+            },
+            config_s: ConfigS {
+                truck_brand_id: extract_string(&v.config_s.truckBrandId),
+                truck_brand: extract_string(&v.config_s.truckBrand),
+                truck_id: extract_string(&v.config_s.truckId),
+                truck_name: extract_string(&v.config_s.truckName),
+                cargo_id: extract_string(&v.config_s.cargoId),
+                cargo: extract_string(&v.config_s.cargo),
+                city_dst_id: extract_string(&v.config_s.cityDstId),
+                city_dst: extract_string(&v.config_s.cityDst),
+                comp_dst_id: extract_string(&v.config_s.compDstId),
+                comp_dst: extract_string(&v.config_s.compDst),
+                city_src_id: extract_string(&v.config_s.citySrcId),
+                city_src: extract_string(&v.config_s.citySrc),
+                comp_src_id: extract_string(&v.config_s.compSrcId),
+                comp_src: extract_string(&v.config_s.compSrc),
+                shifter_type: extract_string(&v.config_s.shifterType),
+                truck_license_plate: extract_string(&v.config_s.truckLicensePlate),
+                truck_license_plate_country_id: extract_string(
+                    &v.config_s.truckLicensePlateCountryId,
+                ),
+                truck_license_plate_country: extract_string(&v.config_s.truckLicensePlateCountry),
+                job_market: extract_string(&v.config_s.jobMarket),
+            },
+            gameplay_s: GameplayS {
+                fine_offence: extract_string(&v.gameplay_s.fineOffence),
+                ferry_source_name: extract_string(&v.gameplay_s.ferrySourceName),
+                ferry_target_name: extract_string(&v.gameplay_s.ferryTargetName),
+                ferry_source_id: extract_string(&v.gameplay_s.ferrySourceId),
+                ferry_target_id: extract_string(&v.gameplay_s.ferryTargetId),
+                train_source_name: extract_string(&v.gameplay_s.trainSourceName),
+                train_target_name: extract_string(&v.gameplay_s.trainTargetName),
+                train_source_id: extract_string(&v.gameplay_s.trainSourceId),
+                train_target_id: extract_string(&v.gameplay_s.trainTargetId),
+            },
+            config_ull: ConfigUll {
+                job_income: v.config_ull.jobIncome,
+            },
+            gameplay_ll: GameplayLl {
+                job_cancelled_penalty: v.gameplay_ll.jobCancelledPenalty,
+                job_delivered_revenue: v.gameplay_ll.jobDeliveredRevenue,
+                fine_amount: v.gameplay_ll.fineAmount,
+                tollgate_pay_amount: v.gameplay_ll.tollgatePayAmount,
+                ferry_pay_amount: v.gameplay_ll.ferryPayAmount,
+                train_pay_amount: v.gameplay_ll.trainPayAmount,
+            },
+            special_b: SpecialB {
+                on_job: v.special_b.onJob,
+                job_finished: v.special_b.jobFinished,
+                job_cancelled: v.special_b.jobCancelled,
+                job_delivered: v.special_b.jobDelivered,
+                fined: v.special_b.fined,
+                tollgate: v.special_b.tollgate,
+                ferry: v.special_b.ferry,
+                train: v.special_b.train,
+                refuel: v.special_b.refuel,
+                refuel_payed: v.special_b.refuelPayed,
+            },
+            substances: Substances {
+                substance: v
+                    .substances
+                    .substance
+                    .iter()
+                    .map(|v| extract_string(v))
+                    .collect(),
+            },
+            trailer: Trailers {
+                trailer: v.trailer.trailer.iter().map(Trailer::from).collect(),
+            },
+        }
+    }
+}
@@ -1,6 +1,8 @@
 use crate::windows_util::SharedMemory;
 use crate::{Moment, Pedals, Simetry};
 use anyhow::{bail, Result};
+use serde::ser::SerializeStruct;
+use serde::{Deserialize, Serialize, Serializer};
 use std::borrow::Cow;
 use std::time::Duration;
 use uom::si::angular_velocity::revolution_per_minute;
@@ -8,6 +10,7 @@ use uom::si::f64::{AngularVelocity, Velocity};
 use uom::si::velocity::kilometer_per_hour;
 
 pub mod bindings;
+pub mod data;
 pub mod json_client;
 
 pub struct Client {
@@ -73,7 +76,7 @@ impl Client {
     }
 }
 
-#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub enum Game {
     Ets2,
     Ats,
@@ -95,7 +98,22 @@ pub struct SimState {
     pub shared: bindings::scsTelemetryMap_t,
 }
 
+/// Serializes the shared memory as the owned [`data::TelemetryMap`] view.
+impl Serialize for SimState {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("SimState", 2)?;
+        state.serialize_field("game", &self.game)?;
+        state.serialize_field("shared", &self.data())?;
+        state.end()
+    }
+}
+
 impl SimState {
+    /// Owned copy of the shared memory, with Rust naming and decoded strings.
+    pub fn data(&self) -> data::TelemetryMap {
+        (&self.shared).into()
+    }
+
     pub fn parse_string(data: &[i8]) -> String {
         String::from_utf8(
             data.iter()
@@ -0,0 +1,118 @@
+//! Relay for sharing a UDP telemetry port between several applications.
+//!
+//! Only one process can bind the port a game sends telemetry to. The relay binds it instead,
+//! and sends every datagram unchanged to a list of destinations, like a motion rig and a dash
+//! configured to listen on other ports. It also parses the datagrams, so it can be used as a
+//! [`Simetry`] client at the same time.
+
+use crate::{codemasters, dirt_rally_2, Moment, Simetry};
+use anyhow::Result;
+use std::net::SocketAddr;
+use tokio::net::UdpSocket;
+
+pub struct Relay<T> {
+    socket: UdpSocket,
+    destinations: Vec<SocketAddr>,
+    name: &'static str,
+    parse: fn(&[u8]) -> Result<T>,
+}
+
+impl Relay<dirt_rally_2::SimState> {
+    /// Relay DiRT Rally 2.0 telemetry received on `uri`.
+    pub async fn dirt_rally_2(uri: &str, destinations: Vec<SocketAddr>) -> Result<Self> {
+        Self::bind(uri, destinations, "DirtRally2", |datagram| {
+            Ok(dirt_rally_2::SimState::parse(datagram)?)
+        })
+        .await
+    }
+}
+
+impl Relay<codemasters::SimState> {
+    /// Relay telemetry of other Codemasters titles received on `uri`.
+    pub async fn codemasters(
+        uri: &str,
+        title: codemasters::Title,
+        destinations: Vec<SocketAddr>,
+    ) -> Result<Self> {
+        Self::bind(
+            uri,
+            destinations,
+            title.name(),
+            codemasters::SimState::parse,
+        )
+        .await
+    }
+}
+
+impl<T> Relay<T> {
+    /// Bind `uri`, parsing received datagrams with `parse`.
+    ///
+    /// Destinations must not include `uri` itself, or datagrams would loop forever.
+    pub async fn bind(
+        uri: &str,
+        destinations: Vec<SocketAddr>,
+        name: &'static str,
+        parse: fn(&[u8]) -> Result<T>,
+    ) -> Result<Self> {
+        Ok(Self {
+            socket: UdpSocket::bind(uri).await?,
+            destinations,
+            name,
+            parse,
+        })
+    }
+
+    pub fn local_addr(&self) -> Result<SocketAddr> {
+        Ok(self.socket.local_addr()?)
+    }
+
+    pub fn destinations(&self) -> &[SocketAddr] {
+        &self.destinations
+    }
+
+    /// Wait for the next datagram that parses, forwarding every datagram received meanwhile.
+    ///
+    /// Datagrams are forwarded even if they fail to parse, since destinations might
+    /// understand them. Failures to reach a destination are logged and don't stop the relay.
+    pub async fn next_sim_state(&mut self) -> Result<T> {
+        let mut buffer = [0u8; PACKET_BUFFER_SIZE];
+        loop {
+            let size = match self.socket.recv(&mut buffer).await {
+                Ok(size) => size,
+                // Windows reports destinations that aren't listening on the next receive
+                Err(err) if err.kind() == std::io::ErrorKind::ConnectionReset => continue,
+                Err(err) => return Err(err.into()),
+            };
+            let datagram = &buffer[..size];
+            for destination in &self.destinations {
+                if let Err(err) = self.socket.send_to(datagram, destination).await {
+                    log::warn!("Failed to relay datagram to {destination}: {err}");
+                }
+            }
+            match (self.parse)(datagram) {
+                Ok(state) => return Ok(state),
+                Err(err) => log::warn!("Skipping {} datagram: {err}", self.name),
+            }
+        }
+    }
+
+    /// Forward datagrams until the socket fails, without using the parsed data.
+    pub async fn run(mut self) -> Result<()> {
+        loop {
+            self.next_sim_state().await?;
+        }
+    }
+}
+
+const PACKET_BUFFER_SIZE: usize = 2048;
+
+#[async_trait::async_trait]
+impl<T: Moment + Send + Sync + 'static> Simetry for Relay<T> {
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    async fn next_moment(&mut self) -> Option<Box<dyn Moment + Send + Sync + 'static>> {
+        Some(Box::new(self.next_sim_state().await.ok()?))
+    }
+}
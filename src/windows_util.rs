@@ -3,8 +3,11 @@ use std::string::FromUtf16Error;
 use std::time::Duration;
 use windows::core::PCSTR;
 use windows::Win32::Foundation::{CloseHandle, HANDLE};
+use windows::Win32::System::Diagnostics::ToolHelp::{
+    CreateToolhelp32Snapshot, Process32FirstW, Process32NextW, PROCESSENTRY32W, TH32CS_SNAPPROCESS,
+};
 use windows::Win32::System::Memory::{
-    MapViewOfFile, OpenFileMappingA, UnmapViewOfFile, FILE_MAP_READ,
+    MapViewOfFile, OpenFileMappingA, UnmapViewOfFile, FILE_MAP, FILE_MAP_READ, FILE_MAP_WRITE,
 };
 
 #[derive(Debug)]
@@ -71,14 +74,23 @@ pub struct SharedMemory {
 
 impl SharedMemory {
     pub async fn connect(name: &[u8], poll_delay: Duration) -> Self {
+        Self::connect_with_access(name, FILE_MAP_READ, poll_delay).await
+    }
+
+    /// Connect to memory that other processes read input from.
+    pub async fn connect_writable(name: &[u8], poll_delay: Duration) -> Self {
+        let access = FILE_MAP(FILE_MAP_READ.0 | FILE_MAP_WRITE.0);
+        Self::connect_with_access(name, access, poll_delay).await
+    }
+
+    async fn connect_with_access(name: &[u8], access: FILE_MAP, poll_delay: Duration) -> Self {
         let handle;
         loop {
             {
-                let handle_opt = unsafe {
-                    OpenFileMappingA(FILE_MAP_READ.0, false, PCSTR::from_raw(name.as_ptr()))
-                }
-                .ok()
-                .and_then(SafeHandle::new);
+                let handle_opt =
+                    unsafe { OpenFileMappingA(access.0, false, PCSTR::from_raw(name.as_ptr())) }
+                        .ok()
+                        .and_then(SafeHandle::new);
                 if let Some(val) = handle_opt {
                     handle = val;
                     break;
@@ -90,9 +102,8 @@ impl SharedMemory {
         let file_view;
         loop {
             {
-                let file_view_opt = SafeFileView::new(unsafe {
-                    MapViewOfFile(handle.get(), FILE_MAP_READ, 0, 0, 0)
-                });
+                let file_view_opt =
+                    SafeFileView::new(unsafe { MapViewOfFile(handle.get(), access, 0, 0, 0) });
                 if let Some(val) = file_view_opt {
                     file_view = val;
                     break;
@@ -118,6 +129,41 @@ impl SharedMemory {
     pub unsafe fn copy_as<T: Copy>(&self) -> T {
         *(self.get() as *const T)
     }
+
+    /// Write the bytes at the offset, which requires the memory to be connected as writable.
+    pub unsafe fn write_bytes(&self, offset: usize, bytes: &[u8]) {
+        let destination = (self.get() as *mut u8).add(offset);
+        std::ptr::copy_nonoverlapping(bytes.as_ptr(), destination, bytes.len());
+    }
+}
+
+/// Process IDs of running processes, with the file names of their executables.
+pub fn running_processes() -> Vec<(u32, String)> {
+    let mut processes = Vec::new();
+    let Some(snapshot) = unsafe { CreateToolhelp32Snapshot(TH32CS_SNAPPROCESS, 0) }
+        .ok()
+        .and_then(SafeHandle::new)
+    else {
+        return processes;
+    };
+    let mut entry = PROCESSENTRY32W {
+        dwSize: std::mem::size_of::<PROCESSENTRY32W>() as u32,
+        ..Default::default()
+    };
+    let mut found = unsafe { Process32FirstW(snapshot.get(), &mut entry) }.as_bool();
+    while found {
+        let length = entry
+            .szExeFile
+            .iter()
+            .position(|&v| v == 0)
+            .unwrap_or(entry.szExeFile.len());
+        processes.push((
+            entry.th32ProcessID,
+            String::from_utf16_lossy(&entry.szExeFile[..length]),
+        ));
+        found = unsafe { Process32NextW(snapshot.get(), &mut entry) }.as_bool();
+    }
+    processes
 }
 
 /// Decoding table based on data from https://www.cp1252.com and ftp://ftp.unicode.org/Public/MAPPINGS/VENDORS/MICSFT/WINDOWS/CP1252.TXT
@@ -133,6 +179,21 @@ pub fn cp1252_to_string(data: &[u8]) -> Result<String, FromUtf16Error> {
     String::from_utf16(&codepoints)
 }
 
+/// Encode the string as a null terminated CP1252 string, replacing characters CP1252 lacks
+/// with `?`, and cutting the string if it doesn't fit.
+pub fn string_to_cp1252<const N: usize>(value: &str) -> [u8; N] {
+    let mut out = [0u8; N];
+    let mut utf16 = [0u16; 2];
+    for (byte, character) in out.iter_mut().take(N.saturating_sub(1)).zip(value.chars()) {
+        let codepoint = character.encode_utf16(&mut utf16)[0];
+        *byte = match MAPPING.iter().position(|v| *v == codepoint) {
+            Some(index) if character.len_utf16() == 1 && codepoint != 0 => index as u8,
+            _ => b'?',
+        };
+    }
+    out
+}
+
 const MAPPING: [u16; 256] = [
     0x0000, 0x0001, 0x0002, 0x0003, 0x0004, 0x0005, 0x0006, 0x0007, 0x0008, 0x0009, 0x000A, 0x000B,
     0x000C, 0x000D, 0x000E, 0x000F, 0x0010, 0x0011, 0x0012, 0x0013, 0x0014, 0x0015, 0x0016, 0x0017,
@@ -0,0 +1,320 @@
+use simetry::assetto_corsa_competizione::broadcasting::{
+    BroadcastingEventType, Camera, CarLocation, Client, DriverCategory, InboundMessage, LapType,
+    OutboundMessage, RaceSessionType, Registration, SessionPhase, SimState,
+};
+use simetry::{Moment, SessionType, Simetry};
+use std::time::Duration;
+use tokio::net::UdpSocket;
+use uom::si::time::second;
+use uom::si::velocity::kilometer_per_hour;
+
+fn string(data: &mut Vec<u8>, value: &str) {
+    data.extend((value.len() as u16).to_le_bytes());
+    data.extend(value.as_bytes());
+}
+
+fn registration_result(success: bool) -> Vec<u8> {
+    let mut data = vec![1];
+    data.extend(7i32.to_le_bytes());
+    data.extend([success as u8, 1]);
+    string(&mut data, if success { "" } else { "Wrong password" });
+    data
+}
+
+fn lap_info(data: &mut Vec<u8>, car_index: u16, splits: &[i32], is_outlap: bool) {
+    data.extend(95_123i32.to_le_bytes());
+    data.extend(car_index.to_le_bytes());
+    data.extend(0u16.to_le_bytes());
+    data.push(splits.len() as u8);
+    for split in splits {
+        data.extend(split.to_le_bytes());
+    }
+    data.extend([0, 1, is_outlap as u8, 0]);
+}
+
+fn realtime_update(focused_car_index: i32) -> Vec<u8> {
+    let mut data = vec![2];
+    data.extend(1u16.to_le_bytes());
+    data.extend(2u16.to_le_bytes());
+    data.extend([10, 5]);
+    data.extend(60_000f32.to_le_bytes());
+    data.extend(1_200_000f32.to_le_bytes());
+    data.extend(focused_car_index.to_le_bytes());
+    string(&mut data, "Onboard");
+    string(&mut data, "Onboard0");
+    string(&mut data, "Basic HUD");
+    data.push(0);
+    data.extend(50_400_000f32.to_le_bytes());
+    data.extend([21, 30, 5, 0, 2]);
+    lap_info(&mut data, 1, &[30_000, 35_000], false);
+    data
+}
+
+fn realtime_car_update(car_index: u16, location: u8) -> Vec<u8> {
+    let mut data = vec![3];
+    data.extend(car_index.to_le_bytes());
+    data.extend(0u16.to_le_bytes());
+    data.extend([1, 5]);
+    for value in [10.0f32, -20.0, 1.5] {
+        data.extend(value.to_le_bytes());
+    }
+    data.push(location);
+    for value in [187u16, 2, 1, 2] {
+        data.extend(value.to_le_bytes());
+    }
+    data.extend(0.25f32.to_le_bytes());
+    data.extend(4u16.to_le_bytes());
+    data.extend((-350i32).to_le_bytes());
+    lap_info(&mut data, car_index, &[30_000, 35_000, i32::MAX], false);
+    lap_info(&mut data, car_index, &[], false);
+    lap_info(&mut data, car_index, &[31_000], true);
+    data
+}
+
+fn entry_list(car_indices: &[u16]) -> Vec<u8> {
+    let mut data = vec![4];
+    data.extend(7i32.to_le_bytes());
+    data.extend((car_indices.len() as u16).to_le_bytes());
+    for car_index in car_indices {
+        data.extend(car_index.to_le_bytes());
+    }
+    data
+}
+
+fn track_data() -> Vec<u8> {
+    let mut data = vec![5];
+    data.extend(7i32.to_le_bytes());
+    string(&mut data, "monza");
+    data.extend(21i32.to_le_bytes());
+    data.extend(5793i32.to_le_bytes());
+    data.push(1);
+    string(&mut data, "Onboard");
+    data.push(2);
+    string(&mut data, "Onboard0");
+    string(&mut data, "Onboard1");
+    data.push(1);
+    string(&mut data, "Basic HUD");
+    data
+}
+
+fn entry_list_car(car_index: u16) -> Vec<u8> {
+    let mut data = vec![6];
+    data.extend(car_index.to_le_bytes());
+    data.push(20);
+    string(&mut data, "Team");
+    data.extend(88i32.to_le_bytes());
+    data.extend([0, 0]);
+    data.extend(3u16.to_le_bytes());
+    data.push(1);
+    string(&mut data, "First");
+    string(&mut data, "Last");
+    string(&mut data, "LAS");
+    data.push(3);
+    data.extend(3u16.to_le_bytes());
+    data
+}
+
+fn broadcasting_event() -> Vec<u8> {
+    let mut data = vec![7, 5];
+    string(&mut data, "Lap completed");
+    data.extend(61_000i32.to_le_bytes());
+    data.extend(1i32.to_le_bytes());
+    data
+}
+
+#[test]
+fn encodes_outbound_messages() {
+    let register = OutboundMessage::RegisterCommandApplication {
+        display_name: "a".to_string(),
+        connection_password: "pw".to_string(),
+        update_interval_ms: 250,
+        command_password: "".to_string(),
+    };
+    assert_eq!(
+        register.encode(),
+        [1, 4, 1, 0, b'a', 2, 0, b'p', b'w', 250, 0, 0, 0, 0, 0],
+    );
+    let focus = OutboundMessage::ChangeFocus {
+        connection_id: 7,
+        car_index: Some(3),
+        camera: None,
+    };
+    assert_eq!(focus.encode(), [50, 7, 0, 0, 0, 1, 3, 0, 0]);
+    let camera = OutboundMessage::ChangeFocus {
+        connection_id: 7,
+        car_index: None,
+        camera: Some(Camera {
+            camera_set: "A".to_string(),
+            camera: "B".to_string(),
+        }),
+    };
+    assert_eq!(
+        camera.encode(),
+        [50, 7, 0, 0, 0, 0, 1, 1, 0, b'A', 1, 0, b'B']
+    );
+}
+
+#[test]
+fn parses_inbound_messages() {
+    let InboundMessage::RealtimeUpdate(update) =
+        InboundMessage::parse(&realtime_update(1)).unwrap()
+    else {
+        panic!("Expected a realtime update");
+    };
+    assert_eq!(update.session_type, RaceSessionType::Race);
+    assert_eq!(update.phase, SessionPhase::Session);
+    assert_eq!(update.current_hud_page, "Basic HUD");
+    assert_eq!(update.replay_session_time, None);
+    assert_eq!(update.clouds, 0.5);
+    assert_eq!(update.wetness, 0.2);
+    assert_eq!(
+        update.best_session_lap.splits,
+        [Some(30_000), Some(35_000), None]
+    );
+
+    let InboundMessage::RealtimeCarUpdate(car) =
+        InboundMessage::parse(&realtime_car_update(1, 1)).unwrap()
+    else {
+        panic!("Expected a realtime car update");
+    };
+    assert_eq!(car.gear, 3);
+    assert_eq!(car.car_location, CarLocation::Track);
+    assert_eq!(car.kmh, 187);
+    assert_eq!(car.delta, -350);
+    assert_eq!(car.best_session_lap.splits[2], None);
+    assert_eq!(car.last_lap.splits, [None, None, None]);
+    assert_eq!(car.current_lap.lap_type, LapType::Outlap);
+
+    let InboundMessage::TrackData(track) = InboundMessage::parse(&track_data()).unwrap() else {
+        panic!("Expected track data");
+    };
+    assert_eq!(track.track_name, "monza");
+    assert_eq!(track.camera_sets["Onboard"], ["Onboard0", "Onboard1"]);
+    assert_eq!(track.hud_pages, ["Basic HUD"]);
+
+    let InboundMessage::EntryListCar(info) = InboundMessage::parse(&entry_list_car(1)).unwrap()
+    else {
+        panic!("Expected an entry list car");
+    };
+    assert_eq!(info.race_number, 88);
+    assert_eq!(info.drivers[0].short_name, "LAS");
+    assert_eq!(info.drivers[0].category, DriverCategory::Platinum);
+
+    let InboundMessage::BroadcastingEvent(event) =
+        InboundMessage::parse(&broadcasting_event()).unwrap()
+    else {
+        panic!("Expected a broadcasting event");
+    };
+    assert_eq!(event.event_type, BroadcastingEventType::LapCompleted);
+    assert_eq!(event.message, "Lap completed");
+
+    assert!(InboundMessage::parse(&[]).is_err());
+    assert!(InboundMessage::parse(&[99]).is_err());
+    assert!(InboundMessage::parse(&realtime_car_update(1, 1)[..40]).is_err());
+}
+
+#[test]
+fn tracks_entry_list_and_focused_car() {
+    let mut state = SimState::default();
+    for datagram in [
+        entry_list(&[1, 2]),
+        entry_list_car(1),
+        entry_list_car(2),
+        track_data(),
+        realtime_car_update(1, 2),
+        realtime_car_update(2, 1),
+        realtime_update(1),
+    ] {
+        state.update(InboundMessage::parse(&datagram).unwrap());
+    }
+    assert_eq!(state.vehicle_gear(), Some(3));
+    assert_eq!(
+        state
+            .vehicle_velocity()
+            .unwrap()
+            .get::<kilometer_per_hour>(),
+        187.0,
+    );
+    assert_eq!(state.is_vehicle_in_pit_lane(), Some(true));
+    assert_eq!(state.vehicle_unique_id().as_deref(), Some("20"));
+    let session = state.session().unwrap();
+    assert_eq!(session.session_type, Some(SessionType::Race));
+    assert_eq!(session.phase, Some(simetry::SessionPhase::Green));
+    assert_eq!(session.time_remaining.unwrap().get::<second>(), 1200.0);
+    assert_eq!(session.track_id.as_deref(), Some("monza"));
+    assert_eq!(session.is_replay, Some(false));
+    assert_eq!(state.standings().len(), 2);
+
+    state.update(InboundMessage::parse(&entry_list(&[2])).unwrap());
+    assert_eq!(state.entry_list.len(), 1);
+    assert!(state.focused_car().is_none());
+    assert_eq!(state.vehicle_gear(), None);
+}
+
+#[tokio::test]
+async fn client_registers_and_sends_commands() {
+    let game = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    let uri = game.local_addr().unwrap().to_string();
+    let registration = Registration {
+        connection_password: "asd".to_string(),
+        ..Default::default()
+    };
+    let stand_in = async {
+        let mut buffer = [0u8; 256];
+        let (size, client) = game.recv_from(&mut buffer).await.unwrap();
+        assert_eq!(buffer[..2], [1, 4]);
+        assert!(buffer[..size].windows(3).any(|v| v == b"asd"));
+        game.send_to(&registration_result(true), client)
+            .await
+            .unwrap();
+        let (size, _) = game.recv_from(&mut buffer).await.unwrap();
+        assert_eq!(buffer[..size], [10, 7, 0, 0, 0]);
+        let (size, _) = game.recv_from(&mut buffer).await.unwrap();
+        assert_eq!(buffer[..size], [11, 7, 0, 0, 0]);
+        game.send_to(&[0; 4], client).await.unwrap();
+        game.send_to(&track_data(), client).await.unwrap();
+        let (size, _) = game.recv_from(&mut buffer).await.unwrap();
+        assert_eq!(
+            buffer[..size],
+            [49, 7, 0, 0, 0, 7, 0, b'B', b'a', b's', b'i', b'c', b' ', b'H']
+        );
+        let (size, _) = game.recv_from(&mut buffer).await.unwrap();
+        assert_eq!(buffer[..size], [9, 7, 0, 0, 0]);
+    };
+    let client = async {
+        let mut client = Client::try_connect(&uri, &registration).await.unwrap();
+        assert_eq!(client.name(), "AssettoCorsaCompetizione");
+        let state = client.next_sim_state().await.unwrap();
+        assert_eq!(state.connection_id, 7);
+        assert!(!state.is_read_only);
+        assert_eq!(state.track_data.unwrap().track_id, 21);
+        client.change_hud_page("Basic H").await.unwrap();
+        client.unregister().await.unwrap();
+    };
+    tokio::join!(stand_in, client);
+}
+
+#[tokio::test]
+async fn client_fails_on_rejected_registration() {
+    let game = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    let uri = game.local_addr().unwrap().to_string();
+    let stand_in = async {
+        let mut buffer = [0u8; 256];
+        let (_, client) = game.recv_from(&mut buffer).await.unwrap();
+        game.send_to(&registration_result(false), client)
+            .await
+            .unwrap();
+    };
+    let client = async {
+        let registration = Registration {
+            update_interval: Duration::from_millis(100),
+            ..Default::default()
+        };
+        let err = Client::try_connect(&uri, &registration)
+            .await
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("Wrong password"));
+    };
+    tokio::join!(stand_in, client);
+}
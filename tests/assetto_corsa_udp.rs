@@ -0,0 +1,167 @@
+use simetry::assetto_corsa::udp::{CarInfo, Client, HandshakeResponse, Lap, Operation, Packet};
+use simetry::{Moment, Pedals, Simetry};
+use tokio::net::UdpSocket;
+use uom::si::angular_velocity::revolution_per_minute;
+use uom::si::velocity::kilometer_per_hour;
+
+fn string(value: &str) -> Vec<u8> {
+    let mut data = value
+        .encode_utf16()
+        .flat_map(|v| v.to_le_bytes())
+        .collect::<Vec<_>>();
+    data.resize(100, 0);
+    data
+}
+
+fn handshake_response() -> Vec<u8> {
+    // Older versions terminate strings with `%`
+    let data = [
+        string("ks_mazda_mx5_cup%"),
+        string("Driver"),
+        4i32.to_le_bytes().to_vec(),
+        1i32.to_le_bytes().to_vec(),
+        string("magione"),
+        string(""),
+    ]
+    .concat();
+    assert_eq!(data.len(), HandshakeResponse::SIZE);
+    data
+}
+
+fn car_info(gear: i32) -> Vec<u8> {
+    let mut data = vec![b'a', 0, 0, 0];
+    data.extend(328i32.to_le_bytes());
+    for speed in [120f32, 74.5, 33.3] {
+        data.extend(speed.to_le_bytes());
+    }
+    // ABS enabled, TC enabled and in pit
+    data.extend([1, 0, 0, 1, 1, 0, 0, 0]);
+    data.resize(40, 0);
+    for lap in [31_000i32, 95_500, 94_250, 3] {
+        data.extend(lap.to_le_bytes());
+    }
+    for value in [1f32, 0.5, 0.0, 6200.0, -0.1] {
+        data.extend(value.to_le_bytes());
+    }
+    data.extend(gear.to_le_bytes());
+    data.resize(CarInfo::SIZE, 0);
+    data
+}
+
+fn lap() -> Vec<u8> {
+    let data = [
+        2i32.to_le_bytes().to_vec(),
+        5i32.to_le_bytes().to_vec(),
+        string("Other Driver"),
+        string("ks_mazda_mx5_cup"),
+        96_000i32.to_le_bytes().to_vec(),
+    ]
+    .concat();
+    assert_eq!(data.len(), Lap::SIZE);
+    data
+}
+
+#[test]
+fn encodes_operations() {
+    assert_eq!(
+        Operation::SubscribeUpdate.encode(),
+        [1, 0, 0, 0, 1, 0, 0, 0, 1, 0, 0, 0],
+    );
+    assert_eq!(Operation::Dismiss.encode()[8], 3);
+}
+
+#[test]
+fn parses_packets() {
+    let Packet::HandshakeResponse(handshake) = Packet::parse(&handshake_response()).unwrap() else {
+        panic!("Expected a handshake response");
+    };
+    assert_eq!(handshake.car_name, "ks_mazda_mx5_cup");
+    assert_eq!(handshake.driver_name, "Driver");
+    assert_eq!(handshake.identifier, 4);
+    assert_eq!(handshake.track_name, "magione");
+    assert_eq!(handshake.track_config, "");
+
+    let Packet::CarInfo(info) = Packet::parse(&car_info(4)).unwrap() else {
+        panic!("Expected car info");
+    };
+    assert_eq!(info.identifier, b'a');
+    assert!(info.is_abs_enabled);
+    assert!(!info.is_abs_in_action);
+    assert!(info.is_tc_enabled);
+    assert!(info.is_in_pit);
+    assert_eq!(info.best_lap, 94_250);
+    assert_eq!(info.lap_count, 3);
+    assert_eq!(info.steer, -0.1);
+
+    let Packet::Lap(spot) = Packet::parse(&lap()).unwrap() else {
+        panic!("Expected a lap");
+    };
+    assert_eq!(spot.car_identifier_number, 2);
+    assert_eq!(spot.driver_name, "Other Driver");
+    assert_eq!(spot.time, 96_000);
+
+    assert!(Packet::parse(&car_info(4)[..100]).is_err());
+}
+
+#[tokio::test]
+async fn client_handshakes_and_subscribes() {
+    let game = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    let uri = game.local_addr().unwrap().to_string();
+    let stand_in = async {
+        let mut buffer = [0u8; 16];
+        let (size, client) = game.recv_from(&mut buffer).await.unwrap();
+        assert_eq!(&buffer[..size], Operation::Handshake.encode());
+        game.send_to(&handshake_response(), client).await.unwrap();
+        let (size, _) = game.recv_from(&mut buffer).await.unwrap();
+        assert_eq!(&buffer[..size], Operation::SubscribeUpdate.encode());
+        game.send_to(&lap(), client).await.unwrap();
+        game.send_to(&[0; 4], client).await.unwrap();
+        game.send_to(&car_info(0), client).await.unwrap();
+        let (size, _) = game.recv_from(&mut buffer).await.unwrap();
+        assert_eq!(&buffer[..size], Operation::Dismiss.encode());
+    };
+    let client = async {
+        let mut client = Client::try_connect(&uri).await.unwrap();
+        assert_eq!(client.name(), "AssettoCorsa");
+        assert_eq!(client.handshake().track_name, "magione");
+        let state = client.next_sim_state().await.unwrap();
+        assert_eq!(state.last_lap.as_ref().unwrap().lap, 5);
+
+        assert_eq!(state.vehicle_gear(), Some(-1));
+        let speed = state.vehicle_velocity().unwrap();
+        assert!((speed.get::<kilometer_per_hour>() - 120.0).abs() < 1e-9);
+        assert_eq!(
+            state
+                .vehicle_engine_rotation_speed()
+                .unwrap()
+                .get::<revolution_per_minute>(),
+            6200.0,
+        );
+        assert_eq!(state.is_vehicle_in_pit_lane(), Some(true));
+        assert_eq!(
+            state.vehicle_unique_id().as_deref(),
+            Some("ks_mazda_mx5_cup"),
+        );
+        assert_eq!(
+            state.pedals(),
+            Some(Pedals {
+                throttle: 1.0,
+                brake: 0.5,
+                clutch: 0.0,
+            }),
+        );
+        assert_eq!(
+            state.session().unwrap().track_id.as_deref(),
+            Some("magione"),
+        );
+        client.dismiss().await.unwrap();
+    };
+    tokio::join!(stand_in, client);
+}
+
+#[tokio::test]
+async fn client_fails_without_handshake_response() {
+    let game = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    let uri = game.local_addr().unwrap().to_string();
+    assert!(Client::try_connect(&uri).await.is_err());
+}
@@ -0,0 +1,111 @@
+mod common;
+
+use simetry::codemasters::{Client, ExtraData, SimState, Title};
+use simetry::{Moment, Pedals, Simetry};
+use uom::si::angular_velocity::revolution_per_minute;
+use uom::si::velocity::meter_per_second;
+
+/// Fields numbered by their index, with a few set to meaningful values.
+fn datagram(extra_data: ExtraData) -> Vec<u8> {
+    let mut fields = (0..66).map(|v| v as f32).collect::<Vec<_>>();
+    fields[7] = 30.0;
+    fields[29] = 1.0;
+    fields[31] = 0.5;
+    fields[32] = 0.0;
+    fields[33] = 10.0;
+    fields[37] = 650.0;
+    fields[47] = 1.0;
+    fields[59] = 2.0;
+    fields[60] = 5.0;
+    fields[61] = 4321.4;
+    fields[63] = 800.0;
+    fields.truncate(extra_data.field_count());
+    fields.iter().flat_map(|v| v.to_le_bytes()).collect()
+}
+
+#[test]
+fn picks_layout_by_length() {
+    let sizes = ExtraData::ALL.map(|v| datagram(v).len());
+    assert_eq!(sizes, [20, 152, 256, 264]);
+    for extra_data in ExtraData::ALL {
+        let state = SimState::parse(&datagram(extra_data)).unwrap();
+        assert_eq!(state.extra_data, extra_data);
+        assert_eq!(state.base.position_x, 4.0);
+        assert_eq!(state.motion.is_some(), extra_data >= ExtraData::Level1);
+        assert_eq!(state.race.is_some(), extra_data >= ExtraData::Level2);
+        assert_eq!(state.engine.is_some(), extra_data == ExtraData::Level3);
+    }
+}
+
+#[test]
+fn rejects_truncated_and_oversized() {
+    assert!(SimState::parse(&[]).is_err());
+    assert!(SimState::parse(&datagram(ExtraData::Level3)[..260]).is_err());
+    let mut data = datagram(ExtraData::Level3);
+    data.extend([0; 4]);
+    assert!(SimState::parse(&data).is_err());
+}
+
+#[test]
+fn exposes_extra_fields() {
+    let state = SimState::parse(&datagram(ExtraData::Level3)).unwrap();
+    let motion = state.motion.as_ref().unwrap();
+    assert_eq!(motion.wheel_speed, [25.0, 26.0, 27.0, 28.0]);
+    let race = state.race.as_ref().unwrap();
+    assert_eq!(race.race_position, 39.0);
+    assert_eq!(race.kers_level, 40.0);
+    assert_eq!(race.sector_1_time, 49.0);
+    assert_eq!(race.sector_2_time, 50.0);
+    assert_eq!(race.tyre_pressure, [55.0, 56.0, 57.0, 58.0]);
+    let engine = state.engine.as_ref().unwrap();
+    assert_eq!(engine.idle_rpm, 64.0);
+    assert_eq!(engine.max_gears, 65.0);
+}
+
+#[test]
+fn maps_moment() {
+    let state = SimState::parse(&datagram(ExtraData::Level3)).unwrap();
+    assert_eq!(state.vehicle_gear(), Some(-1));
+    assert_eq!(
+        state.vehicle_velocity().unwrap().get::<meter_per_second>(),
+        30.0,
+    );
+    let rpm = state.vehicle_engine_rotation_speed().unwrap();
+    assert!((rpm.get::<revolution_per_minute>() - 6500.0).abs() < 1e-9);
+    let max_rpm = state.vehicle_max_engine_rotation_speed().unwrap();
+    assert!((max_rpm.get::<revolution_per_minute>() - 8000.0).abs() < 1e-9);
+    assert_eq!(state.is_vehicle_in_pit_lane(), Some(true));
+    assert_eq!(
+        state.pedals(),
+        Some(Pedals {
+            throttle: 1.0,
+            brake: 0.5,
+            clutch: 0.0,
+        }),
+    );
+    let session = state.session().unwrap();
+    assert_eq!(session.total_laps, Some(5));
+    assert_eq!(session.laps_remaining, Some(3));
+    assert_eq!(session.track_id.as_deref(), Some("4321"));
+
+    let state = SimState::parse(&datagram(ExtraData::Level1)).unwrap();
+    assert!(state.vehicle_gear().is_some());
+    assert!(state.session().is_none());
+    let state = SimState::parse(&datagram(ExtraData::Level0)).unwrap();
+    assert!(state.vehicle_gear().is_none());
+}
+
+#[tokio::test]
+async fn client_reports_title() {
+    let (client, game) =
+        common::connect_to_stand_in(&datagram(ExtraData::Level3), |uri| async move {
+            Client::try_connect(&uri, Title::Dirt4).await.unwrap()
+        })
+        .await;
+    for data in [vec![0; 7], datagram(ExtraData::Level2)] {
+        game.send(&data).await.unwrap();
+    }
+    assert_eq!(client.name(), "Dirt4");
+    let state = client.next_sim_state().await.unwrap();
+    assert_eq!(state.extra_data, ExtraData::Level2);
+}
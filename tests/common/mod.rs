@@ -0,0 +1,36 @@
+use std::future::Future;
+use std::time::Duration;
+use tokio::net::UdpSocket;
+
+/// Connect a UDP client to a socket standing in for the game, and return both.
+///
+/// The client binds a free local port passed to `connect`, and is sent `datagram` until it's
+/// done connecting, since datagrams sent before it binds are lost. Once connected, the stand-in
+/// can send it more datagrams without waiting.
+pub async fn connect_to_stand_in<C, F>(
+    datagram: &[u8],
+    connect: impl FnOnce(String) -> F,
+) -> (C, UdpSocket)
+where
+    F: Future<Output = C>,
+{
+    let game = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    let uri = free_local_uri().await;
+    game.connect(&uri).await.unwrap();
+    let connect = connect(uri);
+    tokio::pin!(connect);
+    loop {
+        tokio::select! {
+            biased;
+            client = &mut connect => return (client, game),
+            _ = tokio::time::sleep(Duration::from_millis(10)) => {}
+        }
+        // Refused until the client binds.
+        let _ = game.send(datagram).await;
+    }
+}
+
+async fn free_local_uri() -> String {
+    let socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    socket.local_addr().unwrap().to_string()
+}
@@ -0,0 +1,94 @@
+mod common;
+
+use simetry::dirt_rally_2::{Client, ParseError, SimState};
+use simetry::{Moment, Simetry};
+
+fn datagram() -> Vec<u8> {
+    let mut fields = (0..66).map(|v| v as f32).collect::<Vec<_>>();
+    fields[0] = 100.0;
+    fields[1] = 30.5;
+    fields[33] = 10.0;
+    fields[47] = 1.0;
+    fields.iter().flat_map(|v| v.to_le_bytes()).collect()
+}
+
+/// Xorshift generator, to keep the fuzzing reproducible without extra dependencies.
+struct Random(u64);
+
+impl Random {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+}
+
+#[test]
+fn exposes_previously_unused_fields() {
+    let state = SimState::parse(&datagram()).unwrap();
+    assert_eq!(state.time_of_lap_start(), 69.5);
+    assert_eq!(state.race_position, 39.0);
+    assert_eq!(state.fuel_in_tank, 45.0);
+    assert_eq!(state.time_of_sector_1, 49.0);
+    assert_eq!(state.time_of_sector_2, 50.0);
+    assert_eq!(state.pressure_tyre_front_right, 58.0);
+    assert_eq!(state.time_of_last_lap, 62.0);
+    assert_eq!(state.maximum_gears, 65.0);
+    assert_eq!(state.vehicle_gear(), Some(-1));
+    assert_eq!(state.is_vehicle_in_pit_lane(), Some(true));
+}
+
+#[test]
+fn rejects_wrong_sizes() {
+    assert_eq!(
+        SimState::parse(&datagram()[..100]),
+        Err(ParseError::WrongSize {
+            expected: 264,
+            actual: 100,
+        }),
+    );
+    let mut data = datagram();
+    data.push(0);
+    assert!(SimState::parse(&data).is_err());
+}
+
+#[test]
+fn survives_random_datagrams() {
+    let mut random = Random(0x2545_f491_4f6c_dd1d);
+    for _ in 0..2000 {
+        // Favour the expected size, so the values get fuzzed as well
+        let size = match random.next() % 4 {
+            0 => SimState::SIZE,
+            _ => (random.next() % 600) as usize,
+        };
+        let data = (0..size).map(|_| random.next() as u8).collect::<Vec<_>>();
+        match SimState::parse(&data) {
+            Ok(state) => {
+                assert_eq!(size, SimState::SIZE);
+                assert_eq!(state.time.to_le_bytes(), data[..4]);
+                let _ = state.vehicle_gear();
+                let _ = state.session();
+            }
+            Err(ParseError::WrongSize { expected, actual }) => {
+                assert_eq!(expected, SimState::SIZE);
+                assert_eq!(actual, size);
+                assert_ne!(size, SimState::SIZE);
+            }
+        }
+    }
+}
+
+#[tokio::test]
+async fn client_skips_stray_datagrams() {
+    let (client, game) = common::connect_to_stand_in(&datagram(), |uri| async move {
+        Client::try_connect(&uri).await.unwrap()
+    })
+    .await;
+    for data in [vec![2; 300], datagram()] {
+        game.send(&data).await.unwrap();
+    }
+    assert_eq!(client.name(), "DirtRally2");
+    let state = client.next_sim_state().await.unwrap();
+    assert_eq!(state.time, 100.0);
+}
@@ -0,0 +1,201 @@
+mod common;
+
+use simetry::ea_wrc::{Client, Layout, Value};
+use simetry::{Moment, Pedals, Simetry};
+use std::sync::Arc;
+use uom::si::angular_velocity::revolution_per_minute;
+use uom::si::f64::AngularVelocity;
+use uom::si::velocity::meter_per_second;
+
+const CHANNELS: &str = r#"{
+    "versions": { "schema": 1, "data": 3 },
+    "channels": [
+        { "id": "packet_4cc", "type": "fourcc", "units": "", "description": "" },
+        { "id": "packet_uid", "type": "uint64" },
+        { "id": "stage_length", "type": "float64" },
+        { "id": "vehicle_speed", "type": "float32" },
+        { "id": "vehicle_engine_rpm_current", "type": "float32" },
+        { "id": "vehicle_engine_rpm_max", "type": "float32" },
+        { "id": "vehicle_gear_index", "type": "uint8" },
+        { "id": "vehicle_gear_index_neutral", "type": "uint8" },
+        { "id": "vehicle_gear_index_reverse", "type": "uint8" },
+        { "id": "vehicle_throttle", "type": "float32" },
+        { "id": "vehicle_brake", "type": "float32" },
+        { "id": "vehicle_clutch", "type": "float32" },
+        { "id": "shiftlights_rpm_end", "type": "float32" },
+        { "id": "shiftlights_rpm_valid", "type": "boolean" },
+        { "id": "stage_progress", "type": "float32" },
+        { "id": "vehicle_brake_temperature_bl", "type": "float32" },
+        { "id": "vehicle_brake_temperature_br", "type": "float32" },
+        { "id": "vehicle_brake_temperature_fl", "type": "float32" },
+        { "id": "vehicle_brake_temperature_fr", "type": "float32" }
+    ]
+}"#;
+
+// Customised to send the stage progress first and skip unneeded channels
+const STRUCTURE: &str = r#"{
+    "versions": { "schema": 1, "data": 3 },
+    "id": "custom",
+    "packets": [
+        {
+            "id": "session_start",
+            "header": { "channels": ["packet_4cc", "packet_uid"] },
+            "channels": ["stage_length"]
+        },
+        {
+            "id": "session_update",
+            "header": { "channels": ["packet_4cc", "packet_uid"] },
+            "channels": [
+                "stage_progress",
+                "vehicle_speed",
+                "vehicle_engine_rpm_current",
+                "vehicle_engine_rpm_max",
+                "vehicle_gear_index",
+                "vehicle_gear_index_neutral",
+                "vehicle_gear_index_reverse",
+                "vehicle_throttle",
+                "vehicle_brake",
+                "vehicle_clutch",
+                "shiftlights_rpm_end",
+                "shiftlights_rpm_valid",
+                "vehicle_brake_temperature_bl",
+                "vehicle_brake_temperature_br",
+                "vehicle_brake_temperature_fl",
+                "vehicle_brake_temperature_fr"
+            ]
+        },
+        {
+            "id": "session_pause",
+            "header": { "channels": ["packet_4cc", "packet_uid"] },
+            "channels": []
+        },
+        {
+            "id": "session_resume",
+            "header": { "channels": ["packet_4cc", "packet_uid"] },
+            "channels": []
+        }
+    ]
+}"#;
+
+fn assert_rpm(value: Option<AngularVelocity>, expected: f64) {
+    assert!((value.unwrap().get::<revolution_per_minute>() - expected).abs() < 1e-9);
+}
+
+fn layout() -> Layout {
+    Layout::from_json(CHANNELS, STRUCTURE).unwrap()
+}
+
+fn header(four_cc: &[u8; 4]) -> Vec<u8> {
+    let mut data = four_cc.to_vec();
+    data.extend(42u64.to_le_bytes());
+    data
+}
+
+fn session_start() -> Vec<u8> {
+    let mut data = header(b"sess");
+    data.extend(10_500f64.to_le_bytes());
+    data
+}
+
+fn session_update(gear: u8) -> Vec<u8> {
+    let mut data = header(b"sesu");
+    for value in [0.25f32, 25.0, 6000.0, 8000.0] {
+        data.extend(value.to_le_bytes());
+    }
+    // Neutral is 0 and reverse is 6
+    data.extend([gear, 0, 6]);
+    for value in [1f32, 0.5, 0.0, 7500.0] {
+        data.extend(value.to_le_bytes());
+    }
+    data.push(1);
+    for value in [300f32, 310.0, 400.0, 410.0] {
+        data.extend(value.to_le_bytes());
+    }
+    data
+}
+
+#[test]
+fn resolves_layout() {
+    let layout = layout();
+    assert_eq!(layout.structure_id, "custom");
+    assert_eq!(layout.packets[0].size(), 20);
+    assert_eq!(layout.packets[1].four_cc, Some("sesu"));
+    assert_eq!(layout.packets[1].channels[2].0, "stage_progress");
+    assert_eq!(layout.packets[1].size(), session_update(1).len());
+
+    let structure = STRUCTURE.replace("\"stage_progress\"", "\"missing_channel\"");
+    assert!(Layout::from_json(CHANNELS, &structure).is_err());
+}
+
+#[test]
+fn decodes_by_channel_name() {
+    let layout = layout();
+    let start = layout.decode(&session_start()).unwrap();
+    assert_eq!(start.id, "session_start");
+    assert_eq!(
+        start.get("packet_4cc"),
+        Some(&Value::FourCc("sess".to_string()))
+    );
+    assert_eq!(start.get("packet_uid"), Some(&Value::Unsigned(42)));
+    assert_eq!(start.get_f64("stage_length"), Some(10_500.0));
+
+    let update = layout.decode(&session_update(3)).unwrap();
+    assert_eq!(update.id, "session_update");
+    assert_eq!(update.get_f64("stage_progress"), Some(0.25));
+    assert_eq!(update.get_bool("shiftlights_rpm_valid"), Some(true));
+
+    // Pause and resume only differ by their four character code
+    assert_eq!(layout.decode(&header(b"sesp")).unwrap().id, "session_pause");
+    assert_eq!(
+        layout.decode(&header(b"sesr")).unwrap().id,
+        "session_resume"
+    );
+
+    assert!(layout.decode(&session_update(3)[..30]).is_err());
+    assert!(layout.decode(&[]).is_err());
+}
+
+#[tokio::test]
+async fn client_maps_standard_channels() {
+    let (mut client, game) = common::connect_to_stand_in(&session_start(), |uri| async move {
+        Client::try_connect(&uri, Arc::new(layout())).await.unwrap()
+    })
+    .await;
+    for datagram in [
+        vec![0; 3],
+        session_update(3),
+        header(b"sesp"),
+        session_update(6),
+    ] {
+        game.send(&datagram).await.unwrap();
+    }
+    assert_eq!(client.name(), "EaWrc");
+    let state = client.next_sim_state().await.unwrap();
+    assert_eq!(state.vehicle_gear(), Some(3));
+    assert_eq!(
+        state.vehicle_velocity().unwrap().get::<meter_per_second>(),
+        25.0,
+    );
+    assert_rpm(state.vehicle_engine_rotation_speed(), 6000.0);
+    assert_rpm(state.vehicle_max_engine_rotation_speed(), 8000.0);
+    assert_rpm(state.shift_point(), 7500.0);
+    assert_eq!(
+        state.pedals(),
+        Some(Pedals {
+            throttle: 1.0,
+            brake: 0.5,
+            clutch: 0.0,
+        }),
+    );
+    assert_eq!(state.stage_progress(), Some(0.25));
+    assert_eq!(
+        state.wheels("vehicle_brake_temperature"),
+        Some([400.0, 410.0, 300.0, 310.0]),
+    );
+    assert!(state.packets.contains_key("session_start"));
+
+    let state = client.next_sim_state().await.unwrap();
+    assert!(state.is_paused);
+    let state = client.next_sim_state().await.unwrap();
+    assert_eq!(state.vehicle_gear(), Some(-1));
+}
@@ -0,0 +1,208 @@
+use simetry::emitter::{Emitter, Protocol, Target};
+use simetry::outgauge::{self, dash_lights, OutGauge};
+use simetry::{dirt_rally_2, Moment, Pedals, Session, Simetry};
+use std::borrow::Cow;
+use std::time::Duration;
+use tokio::net::UdpSocket;
+use uom::si::angular_velocity::revolution_per_minute;
+use uom::si::f64::{AngularVelocity, Velocity};
+use uom::si::velocity::meter_per_second;
+
+/// Stand-in for a sim read through shared memory.
+#[derive(Clone)]
+struct SharedMemoryMoment {
+    gear: i8,
+}
+
+impl Moment for SharedMemoryMoment {
+    fn vehicle_gear(&self) -> Option<i8> {
+        Some(self.gear)
+    }
+
+    fn vehicle_velocity(&self) -> Option<Velocity> {
+        Some(Velocity::new::<meter_per_second>(42.5))
+    }
+
+    fn vehicle_engine_rotation_speed(&self) -> Option<AngularVelocity> {
+        Some(AngularVelocity::new::<revolution_per_minute>(7250.0))
+    }
+
+    fn vehicle_max_engine_rotation_speed(&self) -> Option<AngularVelocity> {
+        Some(AngularVelocity::new::<revolution_per_minute>(8000.0))
+    }
+
+    fn shift_point(&self) -> Option<AngularVelocity> {
+        Some(AngularVelocity::new::<revolution_per_minute>(7000.0))
+    }
+
+    fn is_pit_limiter_engaged(&self) -> Option<bool> {
+        Some(false)
+    }
+
+    fn is_vehicle_in_pit_lane(&self) -> Option<bool> {
+        Some(true)
+    }
+
+    fn vehicle_model_id(&self) -> Option<Cow<'_, str>> {
+        Some("FZ5".into())
+    }
+
+    fn pedals(&self) -> Option<Pedals> {
+        Some(Pedals {
+            throttle: 0.75,
+            brake: 0.25,
+            clutch: 0.0,
+        })
+    }
+
+    fn session(&self) -> Option<Session> {
+        Some(Session {
+            total_laps: Some(10),
+            laps_remaining: Some(4),
+            track_id: Some("5120".to_string()),
+            ..Session::default()
+        })
+    }
+}
+
+struct SharedMemoryClient {
+    moments: Vec<SharedMemoryMoment>,
+}
+
+#[async_trait::async_trait]
+impl Simetry for SharedMemoryClient {
+    fn name(&self) -> &str {
+        "SharedMemory"
+    }
+
+    async fn next_moment(&mut self) -> Option<Box<dyn Moment + Send + Sync + 'static>> {
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        Some(Box::new(self.moments.pop()?))
+    }
+}
+
+fn assert_rpm(actual: Option<AngularVelocity>, expected: f64) {
+    let actual = actual.unwrap().get::<revolution_per_minute>();
+    assert!((actual - expected).abs() < 1e-3, "{actual} != {expected}");
+}
+
+fn assert_same_moment(decoded: &dyn Moment, gear: i8) {
+    assert_eq!(decoded.vehicle_gear(), Some(gear));
+    assert_eq!(
+        decoded
+            .vehicle_velocity()
+            .unwrap()
+            .get::<meter_per_second>(),
+        42.5,
+    );
+    assert_rpm(decoded.vehicle_engine_rotation_speed(), 7250.0);
+    assert_eq!(
+        decoded.pedals(),
+        Some(Pedals {
+            throttle: 0.75,
+            brake: 0.25,
+            clutch: 0.0,
+        }),
+    );
+}
+
+#[test]
+fn dirt_rally_2_round_trip() {
+    for gear in [-1, 0, 4] {
+        let moment = SharedMemoryMoment { gear };
+        let datagram = Protocol::DirtRally2.encode(&moment, Duration::from_millis(1500));
+        let decoded = dirt_rally_2::SimState::parse(&datagram).unwrap();
+        assert_eq!(decoded.time, 1.5);
+        assert_same_moment(&decoded, gear);
+        assert_rpm(decoded.vehicle_max_engine_rotation_speed(), 8000.0);
+        assert_eq!(decoded.is_vehicle_in_pit_lane(), Some(true));
+        assert_eq!(decoded.session(), moment.session());
+    }
+}
+
+#[test]
+fn outgauge_round_trip() {
+    for gear in [-1, 0, 4] {
+        let moment = SharedMemoryMoment { gear };
+        let datagram = Protocol::OutGauge.encode(&moment, Duration::from_millis(1500));
+        assert_eq!(datagram.len(), OutGauge::SIZE);
+        let decoded = OutGauge::parse(&datagram).unwrap();
+        assert_eq!(decoded.time_ms, 1500);
+        assert_eq!(decoded.light(dash_lights::SHIFT), Some(true));
+        assert_eq!(decoded.light(dash_lights::PIT_SPEED), Some(false));
+        assert_eq!(decoded.light(dash_lights::SIGNAL_L), None);
+        assert_eq!(decoded.encode(), datagram);
+        let state = outgauge::SimState {
+            outgauge: Some(decoded),
+            outsim: None,
+        };
+        assert_same_moment(&state, gear);
+        assert_eq!(state.vehicle_model_id().as_deref(), Some("FZ5"));
+    }
+}
+
+#[test]
+fn outgauge_truncates_strings() {
+    let datagram = OutGauge {
+        car: "ÄÄÄ".to_string(),
+        display_1: "x".repeat(20),
+        id: Some(7),
+        ..OutGauge::from_moment(&SharedMemoryMoment { gear: 1 })
+    }
+    .encode();
+    assert_eq!(datagram.len(), OutGauge::SIZE_WITH_ID);
+    let decoded = OutGauge::parse(&datagram).unwrap();
+    assert_eq!(decoded.car, "ÄÄ");
+    assert_eq!(decoded.display_1, "x".repeat(16));
+    assert_eq!(decoded.id, Some(7));
+}
+
+#[tokio::test]
+async fn emits_to_every_target() {
+    let motion_rig = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    let dash = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    let emitter = Emitter::bind(
+        "127.0.0.1:0",
+        vec![
+            Target {
+                protocol: Protocol::DirtRally2,
+                destination: motion_rig.local_addr().unwrap(),
+            },
+            Target {
+                protocol: Protocol::OutGauge,
+                destination: dash.local_addr().unwrap(),
+            },
+        ],
+    )
+    .await
+    .unwrap();
+    let mut client = SharedMemoryClient {
+        moments: vec![
+            SharedMemoryMoment { gear: 3 },
+            SharedMemoryMoment { gear: 2 },
+        ],
+    };
+    let run = tokio::time::timeout(
+        Duration::from_secs(2),
+        emitter.run(&mut client, Duration::from_millis(5)),
+    );
+    run.await.expect("emitter should stop with the client");
+
+    let mut buffer = [0u8; 512];
+    let mut rig_gears = Vec::new();
+    while let Ok(size) = motion_rig.try_recv(&mut buffer) {
+        let state = dirt_rally_2::SimState::parse(&buffer[..size]).unwrap();
+        rig_gears.push(state.vehicle_gear().unwrap());
+    }
+    let mut dash_gears = Vec::new();
+    while let Ok(size) = dash.try_recv(&mut buffer) {
+        dash_gears.push(OutGauge::parse(&buffer[..size]).unwrap().gear);
+    }
+    // The latest moment is repeated until the next arrives
+    assert!(rig_gears.len() > 2, "{rig_gears:?}");
+    assert_eq!(rig_gears.first(), Some(&2));
+    assert_eq!(rig_gears.last(), Some(&3));
+    assert!(rig_gears.windows(2).all(|v| v[0] <= v[1]));
+    assert_eq!(dash_gears.first(), Some(&3));
+    assert_eq!(dash_gears.last(), Some(&4));
+}
@@ -0,0 +1,548 @@
+use simetry::f1::packets::{Event, Packet, HEADER_SIZE};
+use simetry::f1::SimState;
+use simetry::{Moment, Pedals, SessionPhase, SessionType};
+use uom::si::angular_velocity::revolution_per_minute;
+use uom::si::velocity::kilometer_per_hour;
+
+const SESSION_UID: u64 = 0x0123_4567_89ab_cdef;
+const PLAYER: u8 = 1;
+
+/// Datagram of the given size with a filled header and a zeroed body.
+fn datagram(format: u16, packet_id: u8, session_uid: u64, size: usize) -> Vec<u8> {
+    let mut data = vec![];
+    data.extend(format.to_le_bytes());
+    data.extend([(format % 100) as u8, 1, 5, 1, packet_id]);
+    data.extend(session_uid.to_le_bytes());
+    data.extend(12.5f32.to_le_bytes());
+    data.extend(100u32.to_le_bytes());
+    data.extend(100u32.to_le_bytes());
+    data.extend([PLAYER, 255]);
+    assert_eq!(data.len(), HEADER_SIZE);
+    data.resize(size, 0);
+    data
+}
+
+fn write(data: &mut [u8], offset: usize, bytes: &[u8]) {
+    data[offset..offset + bytes.len()].copy_from_slice(bytes);
+}
+
+fn car_telemetry(format: u16) -> Vec<u8> {
+    let mut data = datagram(format, 6, SESSION_UID, 1352);
+    let car = HEADER_SIZE + 60 * PLAYER as usize;
+    write(&mut data, car, &287u16.to_le_bytes());
+    write(&mut data, car + 2, &0.75f32.to_le_bytes());
+    write(&mut data, car + 6, &(-0.25f32).to_le_bytes());
+    write(&mut data, car + 10, &0.5f32.to_le_bytes());
+    write(&mut data, car + 14, &[40, 7]);
+    write(&mut data, car + 16, &11500u16.to_le_bytes());
+    write(
+        &mut data,
+        car + 22,
+        &[200u16.to_le_bytes(), 210u16.to_le_bytes()].concat(),
+    );
+    // MFD panel, secondary player MFD panel and suggested gear after the cars
+    write(&mut data, 1349, &[255, 255, 8]);
+    data
+}
+
+fn car_status(format: u16) -> Vec<u8> {
+    let mut data = datagram(format, 7, SESSION_UID, 1239);
+    let car = HEADER_SIZE + 55 * PLAYER as usize;
+    // Pit limiter engaged
+    write(&mut data, car + 4, &[1]);
+    write(&mut data, car + 17, &13000u16.to_le_bytes());
+    // Blue flag
+    write(&mut data, car + 28, &[2]);
+    data
+}
+
+fn session(format: u16, session_type: u8) -> Vec<u8> {
+    let size = match format {
+        2023 => 644,
+        _ => 753,
+    };
+    let mut data = datagram(format, 1, SESSION_UID, size);
+    write(&mut data, HEADER_SIZE + 3, &[57]);
+    write(&mut data, HEADER_SIZE + 6, &[session_type, 10]);
+    write(&mut data, HEADER_SIZE + 9, &1800u16.to_le_bytes());
+    // Number of marshal zones, followed by the first zone
+    write(&mut data, HEADER_SIZE + 18, &[1]);
+    write(&mut data, HEADER_SIZE + 19, &0.25f32.to_le_bytes());
+    write(&mut data, HEADER_SIZE + 23, &[3]);
+    // Full safety car after all the marshal zones
+    write(&mut data, HEADER_SIZE + 19 + 21 * 5, &[1]);
+    data
+}
+
+fn lap_data_2024() -> Vec<u8> {
+    let mut data = datagram(2024, 2, SESSION_UID, 1285);
+    let car = HEADER_SIZE + 57 * PLAYER as usize;
+    // Sector 1 time of 1:02.345
+    write(&mut data, car + 8, &2345u16.to_le_bytes());
+    write(&mut data, car + 10, &[1]);
+    // Current lap 12, in pit lane
+    write(&mut data, car + 33, &[12, 1]);
+    write(&mut data, car + 52, &321.5f32.to_le_bytes());
+    write(&mut data, car + 56, &[9]);
+    data
+}
+
+fn event(code: &[u8; 4], payload: &[u8]) -> Vec<u8> {
+    let mut data = datagram(2024, 3, SESSION_UID, 45);
+    write(&mut data, HEADER_SIZE, code);
+    write(&mut data, HEADER_SIZE + 4, payload);
+    data
+}
+
+fn state_from(datagrams: &[Vec<u8>]) -> SimState {
+    let mut state = SimState::default();
+    for data in datagrams {
+        state.update(Packet::parse(data).unwrap());
+    }
+    state
+}
+
+#[test]
+fn parses_packets_of_both_formats() {
+    for format in [2023, 2024] {
+        let Packet::CarTelemetry(packet) = Packet::parse(&car_telemetry(format)).unwrap() else {
+            panic!("Expected car telemetry packet");
+        };
+        assert_eq!(packet.header.packet_format, format);
+        assert_eq!(packet.header.session_uid, SESSION_UID);
+        assert_eq!(packet.car_telemetry.len(), 22);
+        let telemetry = &packet.car_telemetry[PLAYER as usize];
+        assert_eq!(telemetry.speed, 287);
+        assert_eq!(telemetry.steer, -0.25);
+        assert_eq!(telemetry.gear, 7);
+        assert_eq!(telemetry.brakes_temperature, [200, 210, 0, 0]);
+        assert_eq!(packet.suggested_gear, 8);
+
+        let Packet::Session(packet) = Packet::parse(&session(format, 5)).unwrap() else {
+            panic!("Expected session packet");
+        };
+        assert_eq!(packet.total_laps, 57);
+        assert_eq!(packet.marshal_zones.len(), 1);
+        assert_eq!(packet.marshal_zones[0].zone_flag, 3);
+        assert_eq!(packet.safety_car_status, 1);
+        assert_eq!(packet.settings.is_some(), format == 2024);
+        assert_eq!(packet.session_type(), Some(SessionType::Qualifying));
+    }
+}
+
+#[test]
+fn parses_format_specific_fields() {
+    let Packet::LapData(packet) = Packet::parse(&lap_data_2024()).unwrap() else {
+        panic!("Expected lap data packet");
+    };
+    let lap = &packet.lap_data[PLAYER as usize];
+    assert_eq!(lap.sector1_time_ms, 62_345);
+    assert_eq!(lap.current_lap_num, 12);
+    assert_eq!(lap.speed_trap_fastest_speed, Some(321.5));
+    assert_eq!(lap.speed_trap_fastest_lap, Some(9));
+
+    let session_type = |format, value| match Packet::parse(&session(format, value)).unwrap() {
+        Packet::Session(packet) => packet.session_type(),
+        _ => panic!("Expected session packet"),
+    };
+    assert_eq!(session_type(2023, 10), Some(SessionType::Race));
+    assert_eq!(session_type(2023, 13), Some(SessionType::Hotlap));
+    assert_eq!(session_type(2024, 12), Some(SessionType::Qualifying));
+    assert_eq!(session_type(2024, 15), Some(SessionType::Race));
+    assert_eq!(session_type(2024, 18), Some(SessionType::Hotlap));
+    assert_eq!(session_type(2024, 0), None);
+}
+
+#[test]
+fn parses_events() {
+    let parse = |data: Vec<u8>| match Packet::parse(&data).unwrap() {
+        Packet::Event(packet) => packet.event,
+        _ => panic!("Expected event packet"),
+    };
+    assert_eq!(parse(event(b"SSTA", &[])), Event::SessionStarted);
+    assert_eq!(
+        parse(event(
+            b"FTLP",
+            &[&[3], &81.25f32.to_le_bytes()[..]].concat()
+        )),
+        Event::FastestLap {
+            vehicle_index: 3,
+            lap_time: 81.25,
+        },
+    );
+    assert_eq!(
+        parse(event(b"OVTK", &[4, 7])),
+        Event::Overtake {
+            overtaking_vehicle_index: 4,
+            being_overtaken_vehicle_index: 7,
+        },
+    );
+    assert_eq!(
+        parse(event(b"RTMT", &[2, 5])),
+        Event::Retirement {
+            vehicle_index: 2,
+            reason: Some(5),
+        },
+    );
+    assert_eq!(
+        parse(event(b"ABCD", &[])),
+        Event::Other {
+            code: "ABCD".to_string(),
+        },
+    );
+}
+
+#[test]
+fn rejects_invalid_datagrams() {
+    let mut too_long = car_telemetry(2023);
+    too_long.push(0);
+    assert!(Packet::parse(&too_long).is_err());
+
+    let too_short = &car_telemetry(2023)[..1351];
+    assert!(Packet::parse(too_short).is_err());
+
+    // F1 23 session packet sent with the F1 24 format
+    let mut wrong_size = session(2023, 1);
+    write(&mut wrong_size, 0, &2024u16.to_le_bytes());
+    assert!(Packet::parse(&wrong_size).is_err());
+
+    assert!(Packet::parse(&car_telemetry(2022)).is_err());
+    assert!(Packet::parse(&[0; HEADER_SIZE - 1]).is_err());
+}
+
+#[test]
+fn keeps_unparsed_packet_types() {
+    let data = datagram(2024, 11, SESSION_UID, 1460);
+    let packet = Packet::parse(&data).unwrap();
+    assert!(matches!(packet, Packet::Other(_)));
+    assert_eq!(packet.header().packet_id, 11);
+}
+
+#[test]
+fn merges_packets_into_moment() {
+    let state = state_from(&[
+        session(2024, 15),
+        lap_data_2024(),
+        car_telemetry(2024),
+        car_status(2024),
+    ]);
+
+    assert_eq!(state.vehicle_gear(), Some(7));
+    assert_eq!(
+        state
+            .vehicle_velocity()
+            .unwrap()
+            .get::<kilometer_per_hour>(),
+        287.0,
+    );
+    assert_eq!(
+        state
+            .vehicle_engine_rotation_speed()
+            .unwrap()
+            .get::<revolution_per_minute>(),
+        11500.0,
+    );
+    assert_eq!(
+        state
+            .vehicle_max_engine_rotation_speed()
+            .unwrap()
+            .get::<revolution_per_minute>(),
+        13000.0,
+    );
+    assert_eq!(state.is_pit_limiter_engaged(), Some(true));
+    assert_eq!(state.is_vehicle_in_pit_lane(), Some(true));
+    let flags = state.flags().unwrap();
+    assert!(flags.blue);
+    assert!(!flags.yellow);
+    assert_eq!(
+        state.pedals(),
+        Some(Pedals {
+            throttle: 0.75,
+            brake: 0.5,
+            clutch: 0.4,
+        }),
+    );
+
+    let session = state.session().unwrap();
+    assert_eq!(session.session_type, Some(SessionType::Race));
+    assert_eq!(session.phase, Some(SessionPhase::FullCourseYellow));
+    assert_eq!(session.total_laps, Some(57));
+    assert_eq!(session.laps_remaining, Some(46));
+    assert_eq!(session.track_id, Some("10".to_string()));
+}
+
+#[test]
+fn new_session_drops_previous_data() {
+    let mut state = state_from(&[session(2023, 10), car_telemetry(2023)]);
+    assert!(state.vehicle_gear().is_some());
+
+    let mut status = car_status(2023);
+    write(&mut status, 7, &42u64.to_le_bytes());
+    state.update(Packet::parse(&status).unwrap());
+
+    assert_eq!(state.session_uid, 42);
+    assert!(state.vehicle_gear().is_none());
+    assert!(state.session().is_none());
+    assert_eq!(state.is_pit_limiter_engaged(), Some(true));
+}
+
+/// Parse one of the F1 24 reference packets in `tests/fixtures/f1`.
+fn fixture(name: &str) -> Packet {
+    let path = format!(
+        "{}/tests/fixtures/f1/{name}.bin",
+        env!("CARGO_MANIFEST_DIR")
+    );
+    let data = std::fs::read(&path).unwrap();
+    let packet = Packet::parse(&data).unwrap();
+    let header = packet.header();
+    assert_eq!(header.packet_format, 2024);
+    assert_eq!(header.game_year, 24);
+    assert_eq!(header.session_uid, 0x5eed_f124_0000_0042);
+    assert_eq!(header.player_car_index, 19);
+    assert_eq!(header.secondary_player_car_index, 255);
+    packet
+}
+
+#[test]
+fn parses_motion_fixture() {
+    let Packet::Motion(packet) = fixture("motion") else {
+        panic!("Expected motion packet");
+    };
+    let car = &packet.car_motion[21];
+    assert_eq!(car.world_position, [121.0, 2.5, -321.0]);
+    assert_eq!(car.world_velocity, [71.0, 0.1, -20.0]);
+    assert_eq!(car.world_forward_dir, [1021, 0, -32000]);
+    assert_eq!(car.world_right_dir, [32000, 0, 1021]);
+    assert_eq!(car.g_force_longitudinal, -1.25);
+    assert_eq!(car.roll, -0.02);
+}
+
+#[test]
+fn parses_session_fixture() {
+    let Packet::Session(packet) = fixture("session") else {
+        panic!("Expected session packet");
+    };
+    assert_eq!(packet.track_temperature, 34);
+    assert_eq!(packet.total_laps, 57);
+    assert_eq!(packet.track_length, 5412);
+    assert_eq!(packet.session_type(), Some(SessionType::Race));
+    assert_eq!(packet.track_id, 3);
+    assert_eq!(packet.session_duration, 7200);
+    assert_eq!(packet.spectator_car_index, 255);
+    assert_eq!(packet.marshal_zones.len(), 3);
+    assert_eq!(packet.marshal_zones[1].zone_start, 0.35);
+    assert_eq!(packet.marshal_zones[1].zone_flag, 3);
+    assert_eq!(packet.safety_car_status, 2);
+    assert_eq!(packet.weather_forecast_samples.len(), 2);
+    assert_eq!(packet.weather_forecast_samples[1].time_offset, 5);
+    assert_eq!(packet.weather_forecast_samples[1].rain_percentage, 10);
+    assert_eq!(packet.ai_difficulty, 90);
+    assert_eq!(packet.session_link_identifier, 33);
+    assert_eq!(packet.pit_stop_rejoin_position, 12);
+    assert_eq!(packet.gearbox_assist, 2);
+    assert_eq!(packet.game_mode, 3);
+    assert_eq!(packet.rule_set, 0);
+    assert_eq!(packet.time_of_day, 840);
+    assert_eq!(packet.num_safety_car_periods, 1);
+    let settings = packet.settings.unwrap();
+    assert_eq!(settings.equal_car_performance, 1);
+    assert_eq!(settings.num_sessions_in_weekend, 25);
+    assert_eq!(settings.weekend_structure[..4], [1, 5, 8, 10]);
+    assert_eq!(settings.sector2_lap_distance_start, 1800.5);
+    assert_eq!(settings.sector3_lap_distance_start, 3600.25);
+}
+
+#[test]
+fn parses_lap_data_fixture() {
+    let Packet::LapData(packet) = fixture("lap_data") else {
+        panic!("Expected lap data packet");
+    };
+    let lap = &packet.lap_data[19];
+    assert_eq!(lap.last_lap_time_ms, 91_253);
+    assert_eq!(lap.sector1_time_ms, 62_345);
+    assert_eq!(lap.sector2_time_ms, 10_987);
+    assert_eq!(lap.delta_to_car_in_front_ms, 531);
+    assert_eq!(lap.delta_to_race_leader_ms, 68_765);
+    assert_eq!(lap.lap_distance, 2519.5);
+    assert_eq!(lap.car_position, 20);
+    assert_eq!(lap.current_lap_num, 28);
+    assert_eq!(lap.grid_position, 20);
+    assert_eq!(lap.result_status, 2);
+    assert_eq!(lap.speed_trap_fastest_speed, Some(311.5));
+    assert_eq!(lap.speed_trap_fastest_lap, Some(3));
+    assert_eq!(packet.time_trial_pb_car_index, 255);
+}
+
+#[test]
+fn parses_event_fixture() {
+    let Packet::Event(packet) = fixture("event_overtake") else {
+        panic!("Expected event packet");
+    };
+    assert_eq!(
+        packet.event,
+        Event::Overtake {
+            overtaking_vehicle_index: 19,
+            being_overtaken_vehicle_index: 4,
+        },
+    );
+}
+
+#[test]
+fn parses_participants_fixture() {
+    let Packet::Participants(packet) = fixture("participants") else {
+        panic!("Expected participants packet");
+    };
+    assert_eq!(packet.num_active_cars, 22);
+    let player = &packet.participants[19];
+    assert_eq!(player.ai_controlled, 0);
+    assert_eq!(player.team_id, 9);
+    assert_eq!(player.race_number, 20);
+    assert_eq!(player.name, "Driver 19");
+    assert_eq!(player.tech_level, Some(1519));
+    assert_eq!(player.platform, 255);
+    assert_eq!(packet.participants[21].name, "Driver 21");
+}
+
+#[test]
+fn parses_car_setups_fixture() {
+    let Packet::CarSetups(packet) = fixture("car_setups") else {
+        panic!("Expected car setups packet");
+    };
+    let setup = &packet.car_setups[19];
+    assert_eq!(setup.front_wing, 30);
+    assert_eq!(setup.front_camber, -3.5);
+    assert_eq!(setup.brake_bias, 56);
+    assert_eq!(setup.engine_braking, Some(80));
+    assert_eq!(setup.tyre_pressures, [22.5, 22.5, 21.0, 21.0]);
+    assert_eq!(setup.fuel_load, 39.0);
+    assert_eq!(packet.next_front_wing_value, Some(31.0));
+}
+
+#[test]
+fn parses_car_telemetry_fixture() {
+    let Packet::CarTelemetry(packet) = fixture("car_telemetry") else {
+        panic!("Expected car telemetry packet");
+    };
+    let telemetry = &packet.car_telemetry[19];
+    assert_eq!(telemetry.speed, 299);
+    assert_eq!(telemetry.steer, -0.125);
+    assert_eq!(telemetry.gear, 7);
+    assert_eq!(telemetry.engine_rpm, 11800);
+    assert_eq!(telemetry.rev_lights_bit_value, 0x3fff);
+    assert_eq!(telemetry.brakes_temperature, [520, 530, 600, 610]);
+    assert_eq!(telemetry.tyres_inner_temperature, [100, 101, 98, 99]);
+    assert_eq!(telemetry.engine_temperature, 110);
+    assert_eq!(telemetry.tyres_pressure, [23.1, 23.2, 22.1, 22.2]);
+    assert_eq!(telemetry.surface_type, [0, 0, 0, 1]);
+    assert_eq!(packet.mfd_panel_index, 255);
+    assert_eq!(packet.suggested_gear, 8);
+}
+
+#[test]
+fn parses_car_status_fixture() {
+    let Packet::CarStatus(packet) = fixture("car_status") else {
+        panic!("Expected car status packet");
+    };
+    let status = &packet.car_status[19];
+    assert_eq!(status.pit_limiter_status, 1);
+    assert_eq!(status.fuel_in_tank, 80.5);
+    assert_eq!(status.max_rpm, 13000);
+    assert_eq!(status.max_gears, 8);
+    assert_eq!(status.actual_tyre_compound, 18);
+    assert_eq!(status.vehicle_fia_flags, 2);
+    assert_eq!(status.ers_store_energy, 2_500_000.0);
+    assert_eq!(status.ers_deployed_this_lap, 300.0);
+    assert_eq!(packet.car_status[18].vehicle_fia_flags, 0);
+}
+
+#[test]
+fn parses_final_classification_fixture() {
+    let Packet::FinalClassification(packet) = fixture("final_classification") else {
+        panic!("Expected final classification packet");
+    };
+    assert_eq!(packet.num_cars, 22);
+    let result = &packet.classification[19];
+    assert_eq!(result.position, 20);
+    assert_eq!(result.points, 6);
+    assert_eq!(result.result_status, 3);
+    assert_eq!(result.best_lap_time_ms, 83_475);
+    assert_eq!(result.total_race_time, 5451.125);
+    assert_eq!(result.num_tyre_stints, 2);
+    assert_eq!(result.tyre_stints_visual[..2], [16, 17]);
+    assert_eq!(result.tyre_stints_end_laps[..2], [20, 57]);
+}
+
+#[test]
+fn parses_car_damage_fixture() {
+    let Packet::CarDamage(packet) = fixture("car_damage") else {
+        panic!("Expected car damage packet");
+    };
+    let damage = &packet.car_damage[21];
+    assert_eq!(damage.tyres_wear, [10.5, 11.5, 8.25, 9.25]);
+    assert_eq!(damage.tyres_damage, [1, 2, 3, 4]);
+    assert_eq!(damage.brakes_damage, [0, 0, 0, 1]);
+    assert_eq!(damage.rear_wing_damage, 7);
+    assert_eq!(damage.gear_box_damage, 2);
+    assert_eq!(damage.engine_damage, 0);
+    assert_eq!(damage.engine_tc_wear, 17);
+    assert_eq!(damage.engine_seized, 0);
+}
+
+#[test]
+fn parses_tyre_sets_fixture() {
+    let Packet::TyreSets(packet) = fixture("tyre_sets") else {
+        panic!("Expected tyre sets packet");
+    };
+    assert_eq!(packet.car_index, 19);
+    assert_eq!(packet.tyre_sets.len(), 20);
+    let fitted = &packet.tyre_sets[packet.fitted_index as usize];
+    assert_eq!(fitted.fitted, 1);
+    assert_eq!(fitted.visual_tyre_compound, 18);
+    assert_eq!(fitted.wear, 2);
+    assert_eq!(fitted.life_span, 18);
+    assert_eq!(packet.tyre_sets[19].lap_delta_time, -1900);
+}
+
+/// Datagrams of the sessions recorded by the `f1_record_session` example.
+fn recorded_sessions() -> Vec<(std::path::PathBuf, Vec<Vec<u8>>)> {
+    let dir = format!("{}/tests/fixtures/f1/captures", env!("CARGO_MANIFEST_DIR"));
+    let mut sessions = vec![];
+    for entry in std::fs::read_dir(dir).unwrap() {
+        let path = entry.unwrap().path();
+        if path.extension().and_then(|v| v.to_str()) != Some("bin") {
+            continue;
+        }
+        let data = std::fs::read(&path).unwrap();
+        let mut rest = &data[..];
+        let mut datagrams = vec![];
+        while !rest.is_empty() {
+            let (size, tail) = rest.split_at(4);
+            let size = u32::from_le_bytes(size.try_into().unwrap()) as usize;
+            datagrams.push(tail[..size].to_vec());
+            rest = &tail[size..];
+        }
+        sessions.push((path, datagrams));
+    }
+    sessions
+}
+
+#[test]
+fn parses_recorded_sessions() {
+    let sessions = recorded_sessions();
+    if sessions.is_empty() {
+        eprintln!("No recorded F1 sessions in tests/fixtures/f1/captures");
+    }
+    for (path, datagrams) in sessions {
+        let mut state = SimState::default();
+        for (index, datagram) in datagrams.iter().enumerate() {
+            let packet = Packet::parse(datagram)
+                .unwrap_or_else(|err| panic!("{}: datagram {index}: {err}", path.display()));
+            state.update(packet);
+        }
+        assert!(state.car_telemetry.is_some(), "{}", path.display());
+        assert!(state.lap_data.is_some(), "{}", path.display());
+        assert!(state.participants.is_some(), "{}", path.display());
+        let gear = state.vehicle_gear().unwrap();
+        assert!((-1..=8).contains(&gear), "{}", path.display());
+    }
+}
@@ -0,0 +1,190 @@
+use simetry::forza::{CarClass, Format, SimState};
+use simetry::{Moment, Pedals};
+use uom::si::angular_velocity::revolution_per_minute;
+use uom::si::velocity::meter_per_second;
+
+const CAR_ORDINAL: i32 = 2352;
+
+fn sled() -> Vec<u8> {
+    let mut data = vec![];
+    data.extend(1i32.to_le_bytes());
+    data.extend(123_456u32.to_le_bytes());
+    for rpm in [8000f32, 900.0, 6500.0] {
+        data.extend(rpm.to_le_bytes());
+    }
+    // Acceleration, then velocity of 3-4-0 m/s in car local space
+    data.resize(32, 0);
+    for velocity in [3f32, 4.0, 0.0] {
+        data.extend(velocity.to_le_bytes());
+    }
+    data.resize(212, 0);
+    for value in [CAR_ORDINAL, 5, 812, 1, 8] {
+        data.extend(value.to_le_bytes());
+    }
+    assert_eq!(data.len(), Format::SLED_SIZE);
+    data
+}
+
+fn dash() -> Vec<u8> {
+    let mut data = vec![0; 12];
+    data.extend(55.5f32.to_le_bytes());
+    data.resize(68, 0);
+    data.extend(7u16.to_le_bytes());
+    // Race position, throttle, brake, clutch, handbrake, gear and steering
+    data.extend([3, 255, 51, 0, 0, 4, (-64i8) as u8]);
+    data.resize(79, 0);
+    data
+}
+
+fn dash_datagram() -> Vec<u8> {
+    let data = [sled(), dash()].concat();
+    assert_eq!(data.len(), Format::DASH_SIZE);
+    data
+}
+
+fn horizon_dash_datagram() -> Vec<u8> {
+    let data = [sled(), vec![0xff; 12], dash(), vec![0xff]].concat();
+    assert_eq!(data.len(), Format::HORIZON_DASH_SIZE);
+    data
+}
+
+fn motorsport_dash_datagram() -> Vec<u8> {
+    let mut data = [sled(), dash()].concat();
+    for wear in [0.1f32, 0.2, 0.3, 0.4] {
+        data.extend(wear.to_le_bytes());
+    }
+    data.extend(510i32.to_le_bytes());
+    assert_eq!(data.len(), Format::MOTORSPORT_DASH_SIZE);
+    data
+}
+
+fn assert_sled(state: &SimState) {
+    assert_eq!(state.sled.is_race_on, 1);
+    assert_eq!(state.sled.timestamp_ms, 123_456);
+    assert_eq!(state.sled.car_ordinal, CAR_ORDINAL);
+    assert_eq!(state.sled.car_class, 5);
+    assert_eq!(state.sled.car_performance_index, 812);
+    assert_eq!(state.sled.num_cylinders, 8);
+    assert_eq!(
+        state
+            .vehicle_engine_rotation_speed()
+            .unwrap()
+            .get::<revolution_per_minute>(),
+        6500.0,
+    );
+    assert_eq!(
+        state
+            .vehicle_max_engine_rotation_speed()
+            .unwrap()
+            .get::<revolution_per_minute>(),
+        8000.0,
+    );
+    assert_eq!(
+        state.vehicle_unique_id().as_deref(),
+        Some(CAR_ORDINAL.to_string().as_str()),
+    );
+}
+
+fn assert_dash(state: &SimState) {
+    assert_sled(state);
+    let dash = state.dash.as_ref().unwrap();
+    assert_eq!(dash.lap_number, 7);
+    assert_eq!(dash.race_position, 3);
+    assert_eq!(dash.steer, -64);
+    assert_eq!(state.vehicle_gear(), Some(4));
+    assert_eq!(
+        state.vehicle_velocity().unwrap().get::<meter_per_second>(),
+        55.5,
+    );
+    assert_eq!(
+        state.pedals(),
+        Some(Pedals {
+            throttle: 1.0,
+            brake: 0.2,
+            clutch: 0.0,
+        }),
+    );
+}
+
+#[test]
+fn parses_sled() {
+    let state = SimState::parse(&sled()).unwrap();
+    assert_eq!(state.format, Format::Sled);
+    assert_sled(&state);
+    assert!(state.dash.is_none());
+    assert!(state.vehicle_gear().is_none());
+    assert!(state.pedals().is_none());
+    assert_eq!(
+        state.vehicle_velocity().unwrap().get::<meter_per_second>(),
+        5.0,
+    );
+}
+
+#[test]
+fn parses_dash() {
+    let state = SimState::parse(&dash_datagram()).unwrap();
+    assert_eq!(state.format, Format::Dash);
+    assert_dash(&state);
+    assert!(state.motorsport_extras.is_none());
+}
+
+#[test]
+fn parses_horizon_dash() {
+    let state = SimState::parse(&horizon_dash_datagram()).unwrap();
+    assert_eq!(state.format, Format::HorizonDash);
+    assert_dash(&state);
+}
+
+#[test]
+fn parses_motorsport_dash() {
+    let state = SimState::parse(&motorsport_dash_datagram()).unwrap();
+    assert_eq!(state.format, Format::MotorsportDash);
+    assert_dash(&state);
+    let extras = state.motorsport_extras.unwrap();
+    assert_eq!(extras.tire_wear, [0.1, 0.2, 0.3, 0.4]);
+    assert_eq!(extras.track_ordinal, 510);
+}
+
+#[test]
+fn maps_reverse_gear() {
+    let mut data = dash_datagram();
+    data[Format::SLED_SIZE + 75] = 0;
+    let state = SimState::parse(&data).unwrap();
+    assert_eq!(state.vehicle_gear(), Some(-1));
+}
+
+#[test]
+fn maps_car_class_by_game() {
+    let state = SimState::parse(&dash_datagram()).unwrap();
+    assert_eq!(state.car_class(), Some(CarClass::R));
+    let state = SimState::parse(&horizon_dash_datagram()).unwrap();
+    assert_eq!(state.car_class(), Some(CarClass::S2));
+    let mut data = horizon_dash_datagram();
+    data[216] = 7;
+    assert_eq!(SimState::parse(&data).unwrap().car_class(), None);
+}
+
+#[test]
+fn reports_nothing_while_race_is_off() {
+    let mut data = dash_datagram();
+    data[..4].copy_from_slice(&0i32.to_le_bytes());
+    // Gear 0 would otherwise be reverse.
+    data[Format::SLED_SIZE + 75] = 0;
+    let state = SimState::parse(&data).unwrap();
+    assert!(!state.is_race_on());
+    assert!(state.vehicle_gear().is_none());
+    assert!(state.vehicle_velocity().is_none());
+    assert!(state.vehicle_engine_rotation_speed().is_none());
+    assert!(state.vehicle_max_engine_rotation_speed().is_none());
+    assert!(state.vehicle_unique_id().is_none());
+    assert!(state.pedals().is_none());
+}
+
+#[test]
+fn rejects_unknown_sizes() {
+    assert!(SimState::parse(&[]).is_err());
+    assert!(SimState::parse(&dash_datagram()[..300]).is_err());
+    let mut data = dash_datagram();
+    data.push(0);
+    assert!(SimState::parse(&data).is_err());
+}
@@ -0,0 +1,113 @@
+#![cfg(feature = "unstable_generic_http_client")]
+
+use simetry::generic_http::{
+    GenericHttpClient, GenericHttpServer, GenericStreamClient, StreamConfig,
+};
+use simetry::{Moment, MomentSnapshot, Pedals, Simetry};
+use std::collections::VecDeque;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use uom::si::angular_velocity::revolution_per_minute;
+use uom::si::f64::{AngularVelocity, Velocity};
+use uom::si::velocity::meter_per_second;
+
+struct FakeSim {
+    moments: mpsc::Receiver<MomentSnapshot>,
+}
+
+#[async_trait::async_trait]
+impl Simetry for FakeSim {
+    fn name(&self) -> &str {
+        "FakeSim"
+    }
+
+    async fn next_moment(&mut self) -> Option<Box<dyn Moment + Send + Sync + 'static>> {
+        Some(Box::new(self.moments.recv().await?))
+    }
+}
+
+fn snapshot(gear: i8) -> MomentSnapshot {
+    MomentSnapshot {
+        gear: Some(gear),
+        speed: Some(Velocity::new::<meter_per_second>(30.0)),
+        engine_rotation_speed: Some(AngularVelocity::new::<revolution_per_minute>(6000.0)),
+        vehicle_brand_id: Some("brand".to_string()),
+        pedals: Some(Pedals {
+            throttle: 1.0,
+            brake: 0.0,
+            clutch: 0.5,
+        }),
+        ..MomentSnapshot::default()
+    }
+}
+
+#[tokio::test]
+async fn client_reads_moments_served_by_server() {
+    let server = GenericHttpServer::bind("127.0.0.1:0").await.unwrap();
+    let uri = format!("http://{}/", server.local_addr());
+
+    assert!(GenericHttpClient::try_connect(&uri).await.is_err());
+
+    let (sender, moments) = mpsc::channel(1);
+    let mut expected = VecDeque::from([snapshot(3), snapshot(4)]);
+    let run = server.run(Box::new(FakeSim { moments }));
+    let check = async {
+        let mut client = GenericHttpClient::connect(&uri, Duration::from_millis(10)).await;
+        assert_eq!(client.name(), "FakeSim");
+        while let Some(snapshot) = expected.pop_front() {
+            sender.send(snapshot.clone()).await.unwrap();
+            loop {
+                let moment = client.next_moment().await.unwrap();
+                if moment.vehicle_gear() == snapshot.gear {
+                    assert_eq!(MomentSnapshot::capture(moment.as_ref()), snapshot);
+                    break;
+                }
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            }
+        }
+        drop(sender);
+    };
+    tokio::join!(run, check);
+
+    assert!(GenericHttpClient::try_connect(&uri).await.is_err());
+}
+
+#[tokio::test]
+async fn stream_client_receives_selected_fields() {
+    let server = GenericHttpServer::bind("127.0.0.1:0").await.unwrap();
+    let uri = format!("http://{}/", server.local_addr());
+    let config = StreamConfig::default()
+        .max_rate(1000)
+        .fields(["gear", "pedals"]);
+
+    assert!(GenericStreamClient::try_connect(&uri, config.clone())
+        .await
+        .is_err());
+
+    let (sender, moments) = mpsc::channel(1);
+    let run = server.run(Box::new(FakeSim { moments }));
+    let check = async {
+        let mut client =
+            GenericStreamClient::connect(&uri, config, Duration::from_millis(10)).await;
+        assert_eq!(client.name(), "FakeSim");
+        for gear in 1..=5 {
+            let snapshot = snapshot(gear);
+            sender.send(snapshot.clone()).await.unwrap();
+            loop {
+                let moment = client.next_moment().await.unwrap();
+                if moment.vehicle_gear() == snapshot.gear {
+                    let expected = MomentSnapshot {
+                        gear: snapshot.gear,
+                        pedals: snapshot.pedals.clone(),
+                        ..MomentSnapshot::default()
+                    };
+                    assert_eq!(MomentSnapshot::capture(moment.as_ref()), expected);
+                    break;
+                }
+            }
+        }
+        drop(sender);
+        assert!(client.next_moment().await.is_none());
+    };
+    tokio::join!(run, check);
+}
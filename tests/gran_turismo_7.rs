@@ -0,0 +1,157 @@
+use salsa20::cipher::{KeyIvInit, StreamCipher};
+use salsa20::Salsa20;
+use simetry::gran_turismo_7::{decrypt, flags, Client, SimState, MAGIC, PACKET_SIZE};
+use simetry::{Moment, Pedals, SessionPhase, Simetry};
+use tokio::net::UdpSocket;
+use uom::si::angular_velocity::revolution_per_minute;
+use uom::si::velocity::meter_per_second;
+
+fn write(data: &mut [u8], offset: usize, bytes: &[u8]) {
+    data[offset..offset + bytes.len()].copy_from_slice(bytes);
+}
+
+/// Decrypted packet built by hand at the offsets of the game's layout, not recorded from the game.
+fn packet(packet_id: i32, gear: u8) -> Vec<u8> {
+    let mut data = vec![0; PACKET_SIZE];
+    write(&mut data, 0x00, &MAGIC.to_le_bytes());
+    write(&mut data, 0x3c, &5200f32.to_le_bytes());
+    write(&mut data, 0x44, &42.5f32.to_le_bytes());
+    write(&mut data, 0x48, &100f32.to_le_bytes());
+    write(&mut data, 0x4c, &38f32.to_le_bytes());
+    write(
+        &mut data,
+        0x60,
+        &[&80f32.to_le_bytes()[..], &81f32.to_le_bytes()].concat(),
+    );
+    write(&mut data, 0x70, &packet_id.to_le_bytes());
+    write(&mut data, 0x74, &3i16.to_le_bytes());
+    write(&mut data, 0x76, &10i16.to_le_bytes());
+    write(&mut data, 0x78, &92_345i32.to_le_bytes());
+    write(&mut data, 0x84, &(-1i16).to_le_bytes());
+    write(&mut data, 0x88, &6800i16.to_le_bytes());
+    write(&mut data, 0x8a, &7500i16.to_le_bytes());
+    write(
+        &mut data,
+        0x8e,
+        &(flags::CAR_ON_TRACK | flags::TCS_ACTIVE).to_le_bytes(),
+    );
+    write(&mut data, 0x90, &[0x50 | gear, 255, 0]);
+    write(&mut data, 0xf4, &0.25f32.to_le_bytes());
+    write(&mut data, 0x104, &3.5f32.to_le_bytes());
+    write(&mut data, 0x124, &3344i32.to_le_bytes());
+    data
+}
+
+/// Encrypt the packet the same way the game does.
+fn encrypt(packet: &[u8], iv1: u32) -> Vec<u8> {
+    let iv2 = iv1 ^ 0xDEAD_BEAF;
+    let nonce = [iv2.to_le_bytes(), iv1.to_le_bytes()].concat();
+    let mut data = packet.to_vec();
+    Salsa20::new(b"Simulator Interface Packet GT7 v".into(), nonce[..].into())
+        .apply_keystream(&mut data);
+    write(&mut data, 0x40, &iv1.to_le_bytes());
+    data
+}
+
+#[test]
+fn decrypts_and_parses_packets() {
+    let encrypted = encrypt(&packet(17, 4), 0x1234_5678);
+    let state = SimState::parse(&decrypt(&encrypted).unwrap()).unwrap();
+
+    assert_eq!(state.packet_id, 17);
+    assert_eq!(state.gas_level, 42.5);
+    assert_eq!(state.tire_temperature, [80.0, 81.0, 0.0, 0.0]);
+    assert_eq!(state.best_lap_time_ms, 92_345);
+    assert_eq!(state.current_gear, 4);
+    assert_eq!(state.suggested_gear, 5);
+    assert_eq!(state.gear_ratios[0], 3.5);
+    assert_eq!(state.flags & flags::TCS_ACTIVE, flags::TCS_ACTIVE);
+
+    assert_eq!(state.vehicle_gear(), Some(4));
+    assert_eq!(
+        state.vehicle_velocity().unwrap().get::<meter_per_second>(),
+        38.0,
+    );
+    assert_eq!(
+        state
+            .vehicle_engine_rotation_speed()
+            .unwrap()
+            .get::<revolution_per_minute>(),
+        5200.0,
+    );
+    assert_eq!(
+        state
+            .vehicle_max_engine_rotation_speed()
+            .unwrap()
+            .get::<revolution_per_minute>(),
+        7500.0,
+    );
+    assert_eq!(
+        state.shift_point().unwrap().get::<revolution_per_minute>(),
+        6800.0,
+    );
+    assert_eq!(state.vehicle_unique_id().as_deref(), Some("3344"));
+    assert_eq!(
+        state.pedals(),
+        Some(Pedals {
+            throttle: 1.0,
+            brake: 0.0,
+            clutch: 0.25,
+        }),
+    );
+    let session = state.session().unwrap();
+    assert_eq!(session.phase, Some(SessionPhase::Green));
+    assert_eq!(session.total_laps, Some(10));
+    assert_eq!(session.laps_remaining, Some(8));
+}
+
+#[test]
+fn maps_special_gears() {
+    let reverse = SimState::parse(&packet(1, 0)).unwrap();
+    assert_eq!(reverse.vehicle_gear(), Some(-1));
+    let neutral = SimState::parse(&packet(1, 15)).unwrap();
+    assert_eq!(neutral.vehicle_gear(), Some(0));
+}
+
+#[test]
+fn rejects_invalid_packets() {
+    // Not encrypted at all
+    assert!(decrypt(&packet(1, 1)).is_err());
+
+    let mut tampered = encrypt(&packet(1, 1), 7);
+    write(&mut tampered, 0x40, &8u32.to_le_bytes());
+    assert!(decrypt(&tampered).is_err());
+
+    let encrypted = encrypt(&packet(1, 1), 7);
+    assert!(decrypt(&encrypted[..PACKET_SIZE - 1]).is_err());
+    assert!(SimState::parse(&packet(1, 1)[1..]).is_err());
+}
+
+#[tokio::test]
+async fn client_sends_heartbeat_and_receives_packets() {
+    let console = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    let console_uri = console.local_addr().unwrap().to_string();
+    let stand_in = async {
+        let mut buffer = [0u8; 16];
+        let (size, client) = console.recv_from(&mut buffer).await.unwrap();
+        assert_eq!(&buffer[..size], b"A");
+        for packet_id in 1..=3 {
+            let data = encrypt(&packet(packet_id, 2), packet_id as u32 * 1000);
+            console.send_to(&data, client).await.unwrap();
+        }
+        // Garbage from elsewhere is skipped
+        console.send_to(&[0; PACKET_SIZE], client).await.unwrap();
+        let data = encrypt(&packet(4, 2), 4000);
+        console.send_to(&data, client).await.unwrap();
+    };
+    let client = async {
+        let mut client = Client::try_connect("127.0.0.1:0", &console_uri)
+            .await
+            .unwrap();
+        assert_eq!(client.name(), "GranTurismo7");
+        for packet_id in 2..=4 {
+            assert_eq!(client.next_sim_state().await.unwrap().packet_id, packet_id);
+        }
+    };
+    tokio::join!(stand_in, client);
+}
@@ -0,0 +1,309 @@
+use simetry::madness_engine::udp::{
+    Client, Packet, State, CLASS_NAMES_SIZE, GAME_STATE_SIZE, PARTICIPANTS_SIZE,
+    RACE_DEFINITION_SIZE, TELEMETRY_SIZE, TIME_STATS_SIZE, TIMINGS_SIZE, VEHICLE_NAMES_SIZE,
+};
+use simetry::madness_engine::{car_flags, FlagColour, GameState, PitMode, RaceState, SessionState};
+use simetry::{Moment, Pedals, SessionPhase, SessionType, Simetry};
+use tokio::net::UdpSocket;
+use uom::si::angular_velocity::revolution_per_minute;
+use uom::si::time::second;
+use uom::si::velocity::meter_per_second;
+
+fn write(data: &mut [u8], offset: usize, bytes: &[u8]) {
+    data[offset..offset + bytes.len()].copy_from_slice(bytes);
+}
+
+fn packet(size: usize, packet_type: u8, partial_packet_index: u8) -> Vec<u8> {
+    let mut data = vec![0; size];
+    write(&mut data, 8, &[partial_packet_index, 2, packet_type, 1]);
+    data
+}
+
+fn telemetry(gear: u8) -> Vec<u8> {
+    let mut data = packet(TELEMETRY_SIZE, 0, 1);
+    // Viewed participant, unfiltered throttle, brake, steering and clutch, and car flags
+    write(
+        &mut data,
+        12,
+        &[
+            1,
+            255,
+            51,
+            0,
+            0,
+            (car_flags::ENGINE_ACTIVE | car_flags::SPEED_LIMITER) as u8,
+        ],
+    );
+    // Fuel capacity, brake, throttle, clutch
+    write(&mut data, 28, &[60, 0, 204, 0]);
+    write(&mut data, 36, &44.5f32.to_le_bytes());
+    write(&mut data, 40, &6100u16.to_le_bytes());
+    write(&mut data, 42, &8500u16.to_le_bytes());
+    write(&mut data, 45, &[0x60 | gear]);
+    write(&mut data, 176, &[85, 86, 87, 88]);
+    write(&mut data, 378, b"Soft");
+    write(&mut data, 555, &4321u32.to_le_bytes());
+    data
+}
+
+fn race_definition() -> Vec<u8> {
+    let mut data = packet(RACE_DEFINITION_SIZE, 1, 1);
+    write(&mut data, 44, &4_318f32.to_le_bytes());
+    write(&mut data, 48, b"Spa");
+    write(&mut data, 112, b"GP");
+    write(&mut data, 304, &12u16.to_le_bytes());
+    data
+}
+
+fn participants() -> Vec<u8> {
+    let mut data = packet(PARTICIPANTS_SIZE, 2, 1);
+    for (i, name) in ["Alice", "Bob", "Carol"].iter().enumerate() {
+        write(&mut data, 16 + i * 64, name.as_bytes());
+    }
+    data
+}
+
+fn timings() -> Vec<u8> {
+    let mut data = packet(TIMINGS_SIZE, 3, 1);
+    write(&mut data, 12, &[3]);
+    write(&mut data, 17, &600f32.to_le_bytes());
+    // Race position, flag, pit mode, vehicle and lap of each participant
+    for (i, (position, flag, pit_mode, vehicle, lap)) in [
+        (2u8, 0u8, 0u8, 10u16, 4u8),
+        (1, 6, 2, 11, 5),
+        (3, 0, 0, 10, 4),
+    ]
+    .into_iter()
+    .enumerate()
+    {
+        let offset = 33 + i * 32;
+        let active = if i == 2 { 0 } else { 0x80 };
+        write(
+            &mut data,
+            offset + 14,
+            &[position | active, 2, flag, pit_mode],
+        );
+        write(&mut data, offset + 18, &(0x8000 | vehicle).to_le_bytes());
+        write(&mut data, offset + 20, &[2 | 0x08, lap]);
+        write(&mut data, offset + 22, &31.5f32.to_le_bytes());
+    }
+    data
+}
+
+fn game_state() -> Vec<u8> {
+    let mut data = packet(GAME_STATE_SIZE, 4, 1);
+    // Playing a race
+    write(&mut data, 14, &[2 | (5 << 4), 21, 34, 128]);
+    data
+}
+
+fn time_stats() -> Vec<u8> {
+    let mut data = packet(TIME_STATS_SIZE, 7, 1);
+    for i in 0..3 {
+        let offset = 16 + i * 32;
+        write(&mut data, offset, &(120.0f32 + i as f32).to_le_bytes());
+        write(&mut data, offset + 4, &(125.0f32 + i as f32).to_le_bytes());
+    }
+    data
+}
+
+fn vehicle_names() -> Vec<u8> {
+    let mut data = packet(VEHICLE_NAMES_SIZE, 8, 1);
+    for (i, (index, class, name)) in [(10u16, 3u32, "Porsche 911 GT3 R"), (11, 4, "Ginetta G55")]
+        .into_iter()
+        .enumerate()
+    {
+        let offset = 12 + i * 72;
+        write(&mut data, offset, &index.to_le_bytes());
+        write(&mut data, offset + 4, &class.to_le_bytes());
+        write(&mut data, offset + 8, name.as_bytes());
+    }
+    data
+}
+
+fn class_names() -> Vec<u8> {
+    let mut data = packet(CLASS_NAMES_SIZE, 8, 1);
+    for (i, (index, name)) in [(3u32, "GT3"), (4, "GT4")].into_iter().enumerate() {
+        let offset = 12 + i * 24;
+        write(&mut data, offset, &index.to_le_bytes());
+        write(&mut data, offset + 4, name.as_bytes());
+    }
+    data
+}
+
+fn assert_pedals(pedals: Pedals, throttle: f64, brake: f64) {
+    assert!((pedals.throttle - throttle).abs() < 1e-6, "{pedals:?}");
+    assert!((pedals.brake - brake).abs() < 1e-6, "{pedals:?}");
+    assert_eq!(pedals.clutch, 0.0);
+}
+
+fn full_state() -> State {
+    let mut state = State::default();
+    for datagram in [
+        telemetry(3),
+        race_definition(),
+        participants(),
+        timings(),
+        game_state(),
+        time_stats(),
+        vehicle_names(),
+        class_names(),
+    ] {
+        state.update(Packet::parse(&datagram).unwrap());
+    }
+    state
+}
+
+#[test]
+fn parses_telemetry() {
+    let Packet::Telemetry(parsed) = Packet::parse(&telemetry(3)).unwrap() else {
+        panic!("Expected a telemetry packet");
+    };
+    assert_eq!(parsed.gear(), 3);
+    assert_eq!(parsed.num_gears(), 6);
+    assert_eq!(parsed.tyre_compound[0], "Soft");
+    assert_eq!(parsed.tick_count, 4321);
+
+    let Packet::Telemetry(reverse) = Packet::parse(&telemetry(15)).unwrap() else {
+        panic!("Expected a telemetry packet");
+    };
+    assert_eq!(reverse.gear(), -1);
+}
+
+#[test]
+fn tells_names_packets_apart() {
+    assert!(matches!(
+        Packet::parse(&vehicle_names()).unwrap(),
+        Packet::VehicleNames(_),
+    ));
+    assert!(matches!(
+        Packet::parse(&class_names()).unwrap(),
+        Packet::ClassNames(_),
+    ));
+}
+
+#[test]
+fn merges_packets_into_sim_state() {
+    let state = full_state().sim_state();
+
+    assert_eq!(state.game_state, GameState::InGamePlaying);
+    assert_eq!(state.session_state, SessionState::Race);
+    assert_eq!(state.race_state, RaceState::Racing);
+    assert_eq!(state.highest_flag_colour, FlagColour::Yellow);
+    assert_eq!(state.pit_mode, PitMode::InPit);
+    assert_eq!(state.car_name, "Ginetta G55");
+    assert_eq!(state.car_class_name, "GT4");
+    assert_eq!(state.best_lap_time, 121.0);
+    assert_eq!(state.last_lap_time, 126.0);
+    assert_eq!(state.current_time, 31.5);
+    assert_eq!(state.tyre_temp, [85.0, 86.0, 87.0, 88.0]);
+    assert_eq!(state.rain_density, 128.0 / 255.0);
+
+    assert_eq!(state.vehicle_gear(), Some(3));
+    assert_eq!(
+        state.vehicle_velocity().unwrap().get::<meter_per_second>(),
+        44.5,
+    );
+    assert_eq!(
+        state
+            .vehicle_engine_rotation_speed()
+            .unwrap()
+            .get::<revolution_per_minute>(),
+        6100.0,
+    );
+    assert_eq!(
+        state
+            .vehicle_max_engine_rotation_speed()
+            .unwrap()
+            .get::<revolution_per_minute>(),
+        8500.0,
+    );
+    assert_eq!(state.is_pit_limiter_engaged(), Some(true));
+    assert_eq!(state.is_vehicle_in_pit_lane(), Some(true));
+    assert_eq!(state.is_ignition_on(), Some(true));
+    assert!(state.flags().unwrap().yellow);
+    assert_eq!(state.vehicle_unique_id().as_deref(), Some("Ginetta G55"));
+    assert_pedals(state.pedals().unwrap(), 0.8, 0.0);
+    assert_pedals(state.pedals_raw().unwrap(), 1.0, 0.2);
+
+    let session = state.session().unwrap();
+    assert_eq!(session.session_type, Some(SessionType::Race));
+    assert_eq!(session.phase, Some(SessionPhase::Green));
+    assert_eq!(session.time_remaining.unwrap().get::<second>(), 600.0);
+    assert_eq!(session.total_laps, Some(12));
+    assert_eq!(session.laps_remaining, Some(8));
+    assert_eq!(session.track_id.as_deref(), Some("Spa|GP"));
+    assert_eq!(session.is_replay, Some(false));
+}
+
+#[test]
+fn orders_standings_by_race_position() {
+    let state = full_state().sim_state();
+    assert_eq!(state.participants.len(), 3);
+    let standings = state.standings();
+    // Carol is not active
+    assert_eq!(standings.len(), 2);
+    assert_eq!(standings[0].name, "Bob");
+    assert_eq!(standings[0].race_position, 1);
+    assert_eq!(standings[0].car_class_name, "GT4");
+    assert!(standings[0].lap_invalidated);
+    assert_eq!(standings[1].name, "Alice");
+    assert_eq!(standings[1].car_name, "Porsche 911 GT3 R");
+    assert_eq!(standings[1].laps_completed, 3);
+    assert_eq!(standings[1].fastest_lap_time, 120.0);
+}
+
+#[test]
+fn sim_state_without_packets_has_no_viewed_participant() {
+    let state = State::default().sim_state();
+    assert!(state.viewed_participant().is_none());
+    assert!(state.standings().is_empty());
+    assert_eq!(state.session().unwrap().session_type, None);
+}
+
+#[test]
+fn rejects_short_datagrams() {
+    assert!(Packet::parse(&[]).is_err());
+    assert!(Packet::parse(&telemetry(1)[..TELEMETRY_SIZE - 1]).is_err());
+    assert!(Packet::parse(&timings()[..100]).is_err());
+    assert!(matches!(
+        Packet::parse(&packet(16, 5, 1)).unwrap(),
+        Packet::Other(_),
+    ));
+}
+
+#[tokio::test]
+async fn client_receives_packets() {
+    let mut client = None;
+    let game = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    let receiver = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    let uri = receiver.local_addr().unwrap().to_string();
+    drop(receiver);
+    let connect = async {
+        client = Some(Client::try_connect(&uri).await.unwrap());
+    };
+    let send = async {
+        // Keep sending until the client is bound and has received a packet
+        for _ in 0..100 {
+            game.send_to(&game_state(), &uri).await.unwrap();
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+    };
+    tokio::select! {
+        _ = connect => {},
+        _ = send => panic!("Client did not receive any packets"),
+    }
+    let mut client = client.unwrap();
+    assert_eq!(client.name(), "MadnessEngine");
+    game.send_to(&[0; 4], &uri).await.unwrap();
+    game.send_to(&telemetry(2), &uri).await.unwrap();
+    // Game state packets sent while connecting may still be queued
+    let state = loop {
+        let state = client.next_sim_state().await.unwrap();
+        if client.state().telemetry.is_some() {
+            break state;
+        }
+    };
+    assert_eq!(state.gear, 2);
+    assert_eq!(state.game_state, GameState::InGamePlaying);
+}
@@ -0,0 +1,142 @@
+use simetry::outgauge::{dash_lights, flags, OutGauge, OutSim, SimState};
+use simetry::{Moment, Pedals};
+use uom::si::angular_velocity::revolution_per_minute;
+use uom::si::velocity::meter_per_second;
+
+fn outgauge(gear: u8, id: Option<i32>) -> Vec<u8> {
+    let mut data = vec![];
+    data.extend(1500u32.to_le_bytes());
+    data.extend(b"XRT\0");
+    data.extend((flags::KM | flags::BAR).to_le_bytes());
+    data.extend([gear, 3]);
+    for value in [27.5f32, 5400.0, 0.5, 90.0, 0.75, 4.0, 100.0] {
+        data.extend(value.to_le_bytes());
+    }
+    let available = dash_lights::SHIFT | dash_lights::PIT_SPEED | dash_lights::SIGNAL_L;
+    data.extend(available.to_le_bytes());
+    data.extend((dash_lights::PIT_SPEED | dash_lights::ABS).to_le_bytes());
+    for value in [1f32, 0.25, 0.0] {
+        data.extend(value.to_le_bytes());
+    }
+    data.extend(b"Fuel 75%\0\0\0\0\0\0\0\0");
+    data.extend([0; 16]);
+    if let Some(id) = id {
+        data.extend(id.to_le_bytes());
+    }
+    data
+}
+
+fn outsim(id: Option<i32>) -> Vec<u8> {
+    let mut data = vec![];
+    data.extend(1510u32.to_le_bytes());
+    for value in [
+        0.1f32, 0.2, 0.3, 1.5, 0.0, 0.0, 2.0, 0.0, 9.81, 27.5, 0.0, 0.0,
+    ] {
+        data.extend(value.to_le_bytes());
+    }
+    for value in [65536i32 * 10, -32768, 0] {
+        data.extend(value.to_le_bytes());
+    }
+    if let Some(id) = id {
+        data.extend(id.to_le_bytes());
+    }
+    data
+}
+
+#[test]
+fn parses_outgauge() {
+    let data = outgauge(3, None);
+    assert_eq!(data.len(), OutGauge::SIZE);
+    let outgauge = OutGauge::parse(&data).unwrap();
+    assert_eq!(outgauge.time_ms, 1500);
+    assert_eq!(outgauge.car, "XRT");
+    assert_eq!(outgauge.flags, flags::KM | flags::BAR);
+    assert_eq!(outgauge.player_id, 3);
+    assert_eq!(outgauge.fuel, 0.75);
+    assert_eq!(outgauge.display_1, "Fuel 75%");
+    assert_eq!(outgauge.display_2, "");
+    assert_eq!(outgauge.id, None);
+
+    assert_eq!(outgauge.light(dash_lights::PIT_SPEED), Some(true));
+    assert_eq!(outgauge.light(dash_lights::SIGNAL_L), Some(false));
+    // Switched on, but not available in the car
+    assert_eq!(outgauge.light(dash_lights::ABS), None);
+}
+
+#[test]
+fn parses_optional_ids() {
+    let outgauge = OutGauge::parse(&outgauge(3, Some(7))).unwrap();
+    assert_eq!(outgauge.id, Some(7));
+    let outsim = OutSim::parse(&outsim(Some(8))).unwrap();
+    assert_eq!(outsim.id, Some(8));
+}
+
+#[test]
+fn parses_outsim() {
+    let data = outsim(None);
+    assert_eq!(data.len(), OutSim::SIZE);
+    let outsim = OutSim::parse(&data).unwrap();
+    assert_eq!(outsim.time_ms, 1510);
+    assert_eq!(outsim.angular_velocity, [0.1, 0.2, 0.3]);
+    assert_eq!(outsim.heading, 1.5);
+    assert_eq!(outsim.acceleration, [2.0, 0.0, 9.81]);
+    assert_eq!(outsim.velocity, [27.5, 0.0, 0.0]);
+    assert_eq!(outsim.position_meters(), [10.0, -0.5, 0.0]);
+    assert_eq!(outsim.id, None);
+}
+
+#[test]
+fn merges_both_protocols() {
+    let mut state = SimState::default();
+    assert!(state.vehicle_gear().is_none());
+
+    state.update(&outsim(None)).unwrap();
+    assert!(state.outsim.is_some());
+    assert!(state.pedals().is_none());
+
+    state.update(&outgauge(3, None)).unwrap();
+    assert!(state.outsim.is_some());
+    assert_eq!(state.vehicle_gear(), Some(2));
+    assert_eq!(
+        state.vehicle_velocity().unwrap().get::<meter_per_second>(),
+        27.5,
+    );
+    assert_eq!(
+        state
+            .vehicle_engine_rotation_speed()
+            .unwrap()
+            .get::<revolution_per_minute>(),
+        5400.0,
+    );
+    assert_eq!(state.is_pit_limiter_engaged(), Some(true));
+    assert_eq!(state.is_left_turn_indicator_on(), Some(false));
+    assert_eq!(state.is_right_turn_indicator_on(), None);
+    assert_eq!(state.vehicle_unique_id().as_deref(), Some("XRT"));
+    assert_eq!(
+        state.pedals(),
+        Some(Pedals {
+            throttle: 1.0,
+            brake: 0.25,
+            clutch: 0.0,
+        }),
+    );
+}
+
+#[test]
+fn maps_special_gears() {
+    let mut state = SimState::default();
+    state.update(&outgauge(0, None)).unwrap();
+    assert_eq!(state.vehicle_gear(), Some(-1));
+    state.update(&outgauge(1, None)).unwrap();
+    assert_eq!(state.vehicle_gear(), Some(0));
+}
+
+#[test]
+fn rejects_unknown_sizes() {
+    let mut state = SimState::default();
+    assert!(state.update(&[]).is_err());
+    assert!(state.update(&outgauge(1, None)[..90]).is_err());
+    assert!(OutGauge::parse(&outsim(None)).is_err());
+    assert!(OutSim::parse(&outgauge(1, None)).is_err());
+    assert_eq!(state, SimState::default());
+}
@@ -0,0 +1,127 @@
+#![cfg(feature = "with_r3e")]
+
+use simetry::raceroom_racing_experience::bindings::r3e_shared;
+use simetry::raceroom_racing_experience::{
+    EngineState, GameData, PitState, SessionPhase, SimState,
+};
+use simetry::Moment;
+use std::sync::Arc;
+use uom::si::length::meter;
+
+const GAME_DATA: &str = r#"{
+    "manufacturers": {"4596": {"Id": 4596, "Name": "BMW", "Country": "Germany"}},
+    "cars": {"1234": {"Id": 1234, "Name": "BMW M3 E30 Gr.A", "Class": 1703}},
+    "classes": {"1703": {"Id": 1703, "Name": "DTM 1992"}},
+    "tracks": {
+        "1693": {
+            "Id": 1693,
+            "Name": "Hockenheimring",
+            "layouts": [{"Id": 1694, "Track": 1693, "Name": "Grand Prix"}]
+        }
+    }
+}"#;
+
+fn sim_state() -> SimState {
+    let mut r3e_shared: Box<r3e_shared> = Box::new(unsafe { std::mem::zeroed() });
+    r3e_shared.vehicle_info.slot_id = 4;
+    r3e_shared.num_cars = 2;
+    for (index, slot_id) in [(0, 4), (1, 9)] {
+        let driver = &mut r3e_shared.all_drivers_data_1[index];
+        driver.driver_info.slot_id = slot_id;
+        driver.driver_info.car_width = 2.0;
+        driver.driver_info.car_length = 4.5;
+        driver.engineState = 2;
+    }
+    SimState {
+        r3e_shared: *r3e_shared,
+        game_data: None,
+    }
+}
+
+#[test]
+fn unavailable_values_are_none() {
+    let mut state = sim_state();
+    state.r3e_shared.session_phase = -1;
+    state.r3e_shared.pit_state = -1;
+    state.r3e_shared.pit_limiter = -1;
+    state.r3e_shared.throttle = -1.0;
+    state.r3e_shared.fuel_left = -1.0;
+    state.r3e_shared.gear = -2;
+    assert_eq!(state.session_phase(), None);
+    assert_eq!(state.pit_state(), None);
+    assert_eq!(state.is_pit_limiter_engaged(), None);
+    assert_eq!(state.pedals(), None);
+    assert_eq!(state.fuel_left(), None);
+    assert_eq!(state.vehicle_gear(), None);
+
+    state.r3e_shared.session_phase = 4;
+    state.r3e_shared.pit_state = 3;
+    state.r3e_shared.gear = -1;
+    assert_eq!(state.session_phase(), Some(SessionPhase::Countdown));
+    assert_eq!(state.pit_state(), Some(PitState::Stopped));
+    assert_eq!(state.vehicle_gear(), Some(-1));
+}
+
+#[test]
+fn player_driver_and_pedals() {
+    let mut state = sim_state();
+    state.r3e_shared.throttle = 0.5;
+    state.r3e_shared.throttle_raw = 1.0;
+    let player = state.player_driver().unwrap();
+    assert_eq!(player.slot_id(), Some(4));
+    assert_eq!(player.engine_state(), Some(EngineState::Running));
+    assert_eq!(state.drivers().count(), 2);
+    assert_eq!(state.pedals().unwrap().throttle, 0.5);
+    assert_eq!(state.pedals_raw().unwrap().throttle, 1.0);
+    assert_eq!(state.is_ignition_on(), Some(true));
+}
+
+#[test]
+fn cars_alongside() {
+    let mut state = sim_state();
+    state.r3e_shared.all_drivers_data_1[1].position.x = -2.5;
+    assert_eq!(state.is_vehicle_left(), Some(true));
+    assert_eq!(state.is_vehicle_right(), Some(false));
+
+    state.r3e_shared.all_drivers_data_1[1].position.x = 0.0;
+    state.r3e_shared.all_drivers_data_1[1].position.z = 10.0;
+    assert_eq!(state.is_vehicle_left(), Some(false));
+    assert_eq!(state.is_vehicle_right(), Some(false));
+    let position = state.drivers().nth(1).unwrap().position();
+    assert_eq!(position.z.get::<meter>(), 10.0);
+}
+
+#[test]
+fn start_lights() {
+    let mut state = sim_state();
+    state.r3e_shared.session_phase = 4;
+    state.r3e_shared.start_lights = 3;
+    let flags = state.flags().unwrap();
+    assert!(flags.start_set && !flags.start_go);
+    state.r3e_shared.start_lights = 6;
+    assert!(state.flags().unwrap().start_go);
+}
+
+#[test]
+fn names_resolve_from_game_data() {
+    let mut state = sim_state();
+    state.r3e_shared.vehicle_info.manufacturer_id = 4596;
+    state.r3e_shared.vehicle_info.model_id = 1234;
+    state.r3e_shared.vehicle_info.class_id = 1703;
+    state.r3e_shared.track_id = 1693;
+    state.r3e_shared.layout_id = 1694;
+    assert_eq!(state.vehicle_brand_id().as_deref(), Some("4596"));
+    assert_eq!(state.session().unwrap().track_id.as_deref(), Some("1694"));
+
+    state.game_data = Some(Arc::new(GameData::from_json(GAME_DATA).unwrap()));
+    assert_eq!(state.vehicle_brand_id().as_deref(), Some("BMW"));
+    assert_eq!(state.vehicle_model_id().as_deref(), Some("BMW M3 E30 Gr.A"));
+    assert_eq!(state.class_name(), Some("DTM 1992"));
+    assert_eq!(
+        state.session().unwrap().track_id.as_deref(),
+        Some("Hockenheimring - Grand Prix"),
+    );
+
+    state.r3e_shared.vehicle_info.model_id = 1;
+    assert_eq!(state.vehicle_model_id().as_deref(), Some("1"));
+}
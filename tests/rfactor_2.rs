@@ -0,0 +1,366 @@
+use simetry::rfactor_2::{
+    Buffer, Client, Config, Game, HwControl, Input, PluginControl, Rules, Scoring, SimState,
+    Telemetry, TrackRules, UpdateRate, VehicleScoring, VehicleTelemetry, WeatherControl,
+};
+use simetry::{Moment, SimetryConnectionBuilder};
+use std::collections::BTreeSet;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+fn sim_state(scoring: Scoring, telemetry: Telemetry) -> SimState {
+    SimState {
+        game: Game::RFactor2,
+        telemetry: Arc::new(telemetry),
+        scoring: Arc::new(scoring),
+        rules: None,
+        multi_rules: None,
+        force_feedback: None,
+        pit_info: None,
+        weather: None,
+        extended: None,
+        changed: BTreeSet::new(),
+    }
+}
+
+fn session_with_player() -> (Scoring, Telemetry) {
+    let scoring = Scoring {
+        vehicles: vec![
+            VehicleScoring {
+                id: 3,
+                vehicle_name: "Ferrari 499P #50".to_string(),
+                ..Default::default()
+            },
+            VehicleScoring {
+                id: 7,
+                vehicle_name: "Oreca 07 Gibson #22".to_string(),
+                is_player: 1,
+                sector: 1,
+                ..Default::default()
+            },
+        ],
+        ..Default::default()
+    };
+    let telemetry = Telemetry {
+        vehicles: vec![VehicleTelemetry {
+            id: 7,
+            gear: 3,
+            ignition_starter: 2,
+            filtered_throttle: 0.5,
+            unfiltered_throttle: 1.0,
+            fuel: 42.0,
+            ..Default::default()
+        }],
+        ..Default::default()
+    };
+    (scoring, telemetry)
+}
+
+#[test]
+fn config_maps_every_buffer_by_default() {
+    let config = Config::default();
+    assert!(Buffer::ALL.iter().all(|v| config.is_buffer_mapped(*v)));
+}
+
+#[test]
+fn config_skips_selected_buffers() {
+    let config = Config::default()
+        .dedicated_server_pid(4242)
+        .dedicated_server_global(true)
+        .skip_buffer(Buffer::Weather)
+        .skip_buffer(Buffer::Extended);
+    let mapped = Buffer::ALL
+        .into_iter()
+        .filter(|v| config.is_buffer_mapped(*v))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        mapped,
+        [
+            Buffer::Telemetry,
+            Buffer::Scoring,
+            Buffer::Rules,
+            Buffer::MultiRules,
+            Buffer::ForceFeedback,
+            Buffer::PitInfo,
+        ],
+    );
+    assert_ne!(
+        SimetryConnectionBuilder::default().rfactor_2_config(config),
+        SimetryConnectionBuilder::default(),
+    );
+}
+
+#[test]
+fn config_keeps_telemetry_and_scoring_mapped() {
+    let config = Config::default()
+        .skip_buffer(Buffer::Telemetry)
+        .skip_buffer(Buffer::Scoring);
+    assert!(config.is_buffer_mapped(Buffer::Telemetry));
+    assert!(config.is_buffer_mapped(Buffer::Scoring));
+}
+
+#[test]
+fn config_subscribes_to_selected_buffers() {
+    assert!(Buffer::ALL
+        .iter()
+        .all(|v| Config::default().is_buffer_subscribed(*v)));
+    let config = Config::default()
+        .skip_buffer(Buffer::Weather)
+        .subscribe_buffer(Buffer::Scoring)
+        .subscribe_buffer(Buffer::Weather);
+    let subscribed = Buffer::ALL
+        .into_iter()
+        .filter(|v| config.is_buffer_subscribed(*v))
+        .collect::<Vec<_>>();
+    assert_eq!(subscribed, [Buffer::Scoring]);
+}
+
+#[test]
+fn changes_are_kept_until_a_subscribed_buffer_changes() {
+    let (scoring, telemetry) = session_with_player();
+    let mut state = sim_state(scoring, telemetry);
+    let subscribed = BTreeSet::from([Buffer::Telemetry]);
+    state.changed.insert(Buffer::Weather);
+    assert!(state.take_if_changed(&subscribed).is_none());
+    assert_eq!(state.changed, BTreeSet::from([Buffer::Weather]));
+
+    state.changed.insert(Buffer::Telemetry);
+    let taken = state.take_if_changed(&subscribed).unwrap();
+    assert_eq!(
+        taken.changed,
+        BTreeSet::from([Buffer::Telemetry, Buffer::Weather]),
+    );
+    assert!(state.changed.is_empty());
+    assert!(state.take_if_changed(&subscribed).is_none());
+}
+
+#[test]
+fn poll_delay_stays_within_bounds() {
+    assert_eq!(Client::poll_delay_for(None), Client::MAX_POLL_DELAY);
+    assert_eq!(
+        Client::poll_delay_for(Some(Duration::ZERO)),
+        Client::MIN_POLL_DELAY,
+    );
+    assert_eq!(
+        Client::poll_delay_for(Some(Duration::from_secs(10))),
+        Client::MAX_POLL_DELAY,
+    );
+    let delay = Client::poll_delay_for(Some(Duration::from_millis(20)));
+    assert!(delay > Client::MIN_POLL_DELAY && delay < Duration::from_millis(20));
+}
+
+#[test]
+fn update_rate_converges() {
+    let start = Instant::now();
+    let mut rate = UpdateRate::default();
+    rate.record_update(start);
+    assert_eq!(rate.average_interval(), None);
+    assert_eq!(rate.expected_interval(start), None);
+
+    let mut now = start + Duration::from_millis(100);
+    rate.record_update(now);
+    assert_eq!(rate.average_interval(), Some(Duration::from_millis(100)));
+    for _ in 0..50 {
+        now += Duration::from_millis(10);
+        rate.record_update(now);
+    }
+    let average = rate.average_interval().unwrap();
+    assert!(average.abs_diff(Duration::from_millis(10)) < Duration::from_millis(1));
+    assert_eq!(rate.expected_interval(now), Some(average));
+
+    // The game stopped updating the buffer, like while paused.
+    assert_eq!(rate.expected_interval(now + average * 4), None);
+}
+
+#[test]
+fn game_from_executable_name() {
+    for (name, game) in [
+        ("rFactor2.exe", Some(Game::RFactor2)),
+        ("rFactor2 Dedicated.exe", Some(Game::RFactor2)),
+        ("Le Mans Ultimate.exe", Some(Game::LeMansUltimate)),
+        ("LE MANS ULTIMATE DEDICATED.EXE", Some(Game::LeMansUltimate)),
+        ("LMU Dedicated.exe", Some(Game::LeMansUltimate)),
+        ("explorer.exe", None),
+        ("", None),
+    ] {
+        assert_eq!(Game::from_executable_name(name), game, "{name}");
+    }
+}
+
+#[test]
+fn game_from_processes_prefers_the_game_over_dedicated_servers() {
+    let processes = || {
+        [
+            (10, "explorer.exe"),
+            (20, "rFactor2 Dedicated.exe"),
+            (30, "Le Mans Ultimate.exe"),
+        ]
+        .map(|(pid, name)| (pid, name.to_string()))
+    };
+    assert_eq!(
+        Game::from_processes(processes(), None),
+        Some(Game::LeMansUltimate),
+    );
+    assert_eq!(
+        Game::from_processes(processes(), Some(20)),
+        Some(Game::RFactor2),
+    );
+    assert_eq!(Game::from_processes(processes(), Some(10)), None);
+    assert_eq!(
+        Game::from_processes(processes().into_iter().take(2), None),
+        Some(Game::RFactor2),
+    );
+}
+
+#[test]
+fn hw_control_names() {
+    assert_eq!(HwControl::PitRequest.name(), "PitRequest");
+    assert_eq!(HwControl::ToggleMfdA.name(), "ToggleMFDA");
+    assert_eq!(HwControl::Other("Horn".to_string()).name(), "Horn");
+}
+
+/// Layout version, which follows the page header of two versions.
+fn layout_version(page: &[u8]) -> i32 {
+    i32::from_ne_bytes(page[8..12].try_into().unwrap())
+}
+
+#[test]
+fn hw_control_page_encodes_name_in_cp1252() {
+    let page =
+        Input::hw_control_page(&HwControl::Other("Klaxon élan €✓".to_string()), 1.0).unwrap();
+    assert_eq!(page.len(), 116);
+    assert_eq!(&page[..8], [0; 8]);
+    assert_eq!(layout_version(&page), 1);
+    let name = &page[12..108];
+    assert_eq!(&name[..15], b"Klaxon \xe9lan \x80?\0");
+    assert!(name[15..].iter().all(|v| *v == 0));
+    assert_eq!(f64::from_ne_bytes(page[108..].try_into().unwrap()), 1.0);
+
+    // Every character takes one byte, leaving room for the null terminator
+    let longest = HwControl::Other("é".repeat(95));
+    let page = Input::hw_control_page(&longest, 0.0).unwrap();
+    assert_eq!(page[12..107], [0xe9; 95]);
+    assert_eq!(page[107], 0);
+    assert!(Input::hw_control_page(&HwControl::Other("é".repeat(96)), 0.0).is_err());
+}
+
+#[test]
+fn rules_control_page_truncates_message() {
+    let rules = Rules {
+        track_rules: TrackRules {
+            message: "Full course yellow ".repeat(10),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let page = Input::rules_control_page(&rules).unwrap();
+    assert_eq!(layout_version(&page), 1);
+    let start = page
+        .windows(19)
+        .position(|v| v == b"Full course yellow ")
+        .unwrap();
+    let message = &page[start..start + 96];
+    assert_eq!(&message[..95], &rules.track_rules.message.as_bytes()[..95]);
+    assert_eq!(message[95], 0);
+
+    let too_many = Rules {
+        actions: vec![Default::default(); 129],
+        ..Default::default()
+    };
+    assert!(Input::rules_control_page(&too_many).is_err());
+}
+
+#[test]
+fn control_pages_carry_layout_version_and_values() {
+    let page = Input::plugin_control_page(&PluginControl {
+        enable_buffers_mask: 0x42,
+        hw_control_input: true,
+        weather_control_input: false,
+        rules_control_input: true,
+    });
+    assert_eq!(layout_version(&page), 1);
+    assert_eq!(i32::from_ne_bytes(page[12..16].try_into().unwrap()), 0x42);
+    assert_eq!(page[16..19], [1, 0, 1]);
+
+    let page = Input::weather_control_page(&WeatherControl {
+        et: 600.0,
+        raining: [[0.5; 3]; 3],
+        cloudiness: 0.75,
+        ambient_temp_k: 290.0,
+        wind_max_speed: 3.0,
+        apply_cloudiness_instantly: true,
+    });
+    assert_eq!(layout_version(&page), 1);
+    let value = |offset: usize| f64::from_ne_bytes(page[offset..offset + 8].try_into().unwrap());
+    assert_eq!(value(12), 600.0);
+    assert_eq!(value(20), 0.5);
+    assert_eq!(value(92), 0.75);
+    assert_eq!(value(100), 290.0);
+    assert_eq!(value(108), 3.0);
+    assert_eq!(page[116], 1);
+}
+
+#[test]
+fn player_joins_scoring_and_telemetry() {
+    let (scoring, telemetry) = session_with_player();
+    let state = sim_state(scoring, telemetry);
+    let player = state.player().unwrap();
+    assert_eq!(player.id(), 7);
+    assert_eq!(player.brand(), "Oreca");
+    assert_eq!(player.model(), "07 Gibson");
+    assert_eq!(player.fuel(), Some(42.0));
+    let vehicles = state.vehicles().collect::<Vec<_>>();
+    assert_eq!(vehicles.len(), 2);
+    assert!(vehicles[0].telemetry.is_none());
+    assert_eq!(vehicles[0].vehicle_name(), "Ferrari 499P");
+}
+
+#[test]
+fn moment_reads_player_vehicle() {
+    let (scoring, telemetry) = session_with_player();
+    let state = sim_state(scoring, telemetry);
+    assert_eq!(state.vehicle_gear(), Some(3));
+    assert_eq!(state.is_ignition_on(), Some(true));
+    assert_eq!(state.is_starter_on(), Some(true));
+    assert_eq!(state.pedals().unwrap().throttle, 0.5);
+    assert_eq!(state.pedals_raw().unwrap().throttle, 1.0);
+    assert_eq!(state.vehicle_brand_id().as_deref(), Some("Oreca"));
+    assert_eq!(
+        state.vehicle_unique_id().as_deref(),
+        Some("Oreca 07 Gibson")
+    );
+    assert_eq!(state.is_left_turn_indicator_on(), None);
+    assert_eq!(state.is_hazard_indicator_on(), None);
+}
+
+#[test]
+fn flags_from_session_state() {
+    let (mut scoring, telemetry) = session_with_player();
+    scoring.scoring_info.game_phase = 5;
+    let state = sim_state(scoring.clone(), telemetry.clone());
+    assert!(state.flags().unwrap().green);
+
+    scoring.scoring_info.sector_flag = [0, 1, 0];
+    let flags = sim_state(scoring.clone(), telemetry.clone())
+        .flags()
+        .unwrap();
+    assert!(flags.yellow);
+    assert!(!flags.green);
+
+    scoring.scoring_info.sector_flag = [0; 3];
+    scoring.scoring_info.game_phase = 4;
+    scoring.scoring_info.num_red_lights = 5;
+    scoring.scoring_info.start_light = 3;
+    let flags = sim_state(scoring.clone(), telemetry.clone())
+        .flags()
+        .unwrap();
+    assert!(flags.start_set && !flags.start_go);
+
+    scoring.scoring_info.start_light = 6;
+    let flags = sim_state(scoring.clone(), telemetry.clone())
+        .flags()
+        .unwrap();
+    assert!(flags.start_go && !flags.start_set);
+
+    scoring.scoring_info.yellow_flag_state = 7;
+    assert!(sim_state(scoring, telemetry).flags().unwrap().red);
+}
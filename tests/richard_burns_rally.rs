@@ -0,0 +1,178 @@
+mod common;
+
+use simetry::richard_burns_rally::{Client, SimState};
+use simetry::{Moment, Pedals, Simetry};
+use uom::si::angular_velocity::revolution_per_minute;
+use uom::si::velocity::kilometer_per_hour;
+
+fn suspension(data: &mut Vec<u8>, wheel: f32) {
+    for value in [0.05f32, 10.0, 2000.0, 300.0, 2300.0] {
+        data.extend(value.to_le_bytes());
+    }
+    data.extend(1i32.to_le_bytes());
+    for value in [0.0f32, 0.2] {
+        data.extend(value.to_le_bytes());
+    }
+    // Brake disk temperatures differ per wheel
+    for value in [wheel + 10.0, wheel, 0.01] {
+        data.extend(value.to_le_bytes());
+    }
+    for value in [2.1f32, 60.0, 55.0, 65.0] {
+        data.extend(value.to_le_bytes());
+    }
+    data.extend(3u32.to_le_bytes());
+    for segment in 0..8 {
+        data.extend((60.0 + segment as f32).to_le_bytes());
+        data.extend(0.01f32.to_le_bytes());
+    }
+}
+
+fn telemetry(gear: i32) -> Vec<u8> {
+    let mut data = vec![];
+    data.extend(1234u32.to_le_bytes());
+    data.extend(41i32.to_le_bytes());
+    for value in [2500f32, 120.5, 2400.0, 7500.0] {
+        data.extend(value.to_le_bytes());
+    }
+    for value in [-0.1f32, 0.8, 0.25, 0.0, 0.0] {
+        data.extend(value.to_le_bytes());
+    }
+    data.extend(gear.to_le_bytes());
+    for value in [0.25f32, 0.0] {
+        data.extend(value.to_le_bytes());
+    }
+    data.extend(0i32.to_le_bytes());
+    data.extend(110f32.to_le_bytes());
+    data.extend([0u8; 4 * 18]);
+    for value in [6800f32, 85.0, 90.0, 95.0] {
+        data.extend(value.to_le_bytes());
+    }
+    for wheel in [300f32, 310.0, 200.0, 210.0] {
+        suspension(&mut data, wheel);
+    }
+    data
+}
+
+#[test]
+fn parses_telemetry() {
+    let data = telemetry(4);
+    assert_eq!(data.len(), SimState::SIZE);
+    let state = SimState::parse(&data).unwrap();
+    assert_eq!(state.total_steps, 1234);
+    assert_eq!(state.stage.index, 41);
+    assert_eq!(state.stage.distance_to_end, 7500.0);
+    assert_eq!(state.control.steering, -0.1);
+    assert_eq!(state.control.footbrake_pressure, 0.25);
+    assert_eq!(state.car.speed, 110.0);
+    assert_eq!(state.car.engine.engine_temperature, 95.0);
+    let temperatures = state
+        .car
+        .suspensions()
+        .map(|v| v.wheel.brake_disk.temperature);
+    assert_eq!(temperatures, [300.0, 310.0, 200.0, 210.0]);
+    let suspension = &state.car.suspension_rb;
+    assert!(suspension.is_helper_spring_active);
+    assert_eq!(suspension.damper.piston_velocity, 0.2);
+    assert_eq!(suspension.wheel.tire.current_segment, 3);
+    assert_eq!(suspension.wheel.tire.segments[7].temperature, 67.0);
+}
+
+#[test]
+fn parses_reference_datagram() {
+    let data = include_bytes!("fixtures/richard_burns_rally/telemetry.bin");
+    let state = SimState::parse(data).unwrap();
+    assert_eq!(state.total_steps, 18250);
+    assert_eq!(state.stage.index, 12);
+    assert_eq!(state.stage.progress, 3250.5);
+    assert_eq!(state.stage.race_time, 182.25);
+    assert_eq!(state.stage.distance_to_end, 6749.5);
+    assert_eq!(state.control.steering, -0.375);
+    assert_eq!(state.control.clutch, 0.125);
+    assert_eq!(state.control.gear, 5);
+    assert_eq!(state.car.index, 7);
+    assert_eq!(state.car.position, [1520.25, -88.5, 412.75]);
+    assert_eq!(state.car.yaw, 2.5);
+    assert_eq!(state.car.velocities.surge, 35.5);
+    assert_eq!(state.car.accelerations.yaw, 1.5);
+    assert_eq!(state.car.engine.rpm, 6250.0);
+    assert_eq!(state.car.engine.engine_temperature, 97.5);
+    let suspensions = state.car.suspensions();
+    assert_eq!(
+        suspensions.map(|v| v.wheel.brake_disk.temperature),
+        [412.0, 405.5, 288.0, 291.5],
+    );
+    assert_eq!(
+        suspensions.map(|v| v.is_helper_spring_active),
+        [false, false, true, true],
+    );
+    let suspension = &state.car.suspension_rb;
+    assert_eq!(suspension.damper_force, -313.5);
+    assert_eq!(suspension.damper.piston_velocity, 0.125);
+    assert_eq!(suspension.wheel.brake_disk.wear, 0.125);
+    let tire = &suspension.wheel.tire;
+    assert_eq!(tire.pressure, 1.75);
+    assert_eq!(tire.tread_temperature, 78.25);
+    assert_eq!(tire.current_segment, 5);
+    assert_eq!(tire.segments[7].temperature, 80.0);
+    assert_eq!(tire.segments[7].wear, 0.0625);
+
+    assert_eq!(state.vehicle_gear(), Some(4));
+    let speed = state.vehicle_velocity().unwrap();
+    assert!((speed.get::<kilometer_per_hour>() - 128.5).abs() < 1e-9);
+}
+
+#[test]
+fn rejects_wrong_sizes() {
+    assert!(SimState::parse(&[]).is_err());
+    assert!(SimState::parse(&telemetry(4)[..600]).is_err());
+    let mut data = telemetry(4);
+    data.push(0);
+    assert!(SimState::parse(&data).is_err());
+}
+
+#[test]
+fn maps_moment() {
+    let state = SimState::parse(&telemetry(4)).unwrap();
+    assert_eq!(state.vehicle_gear(), Some(3));
+    assert_eq!(
+        state
+            .vehicle_velocity()
+            .unwrap()
+            .get::<kilometer_per_hour>(),
+        110.0,
+    );
+    let rpm = state.vehicle_engine_rotation_speed().unwrap();
+    assert!((rpm.get::<revolution_per_minute>() - 6800.0).abs() < 1e-9);
+    assert_eq!(
+        state.pedals(),
+        Some(Pedals {
+            throttle: 0.8f32 as f64,
+            brake: 0.25,
+            clutch: 0.0,
+        }),
+    );
+    assert_eq!(state.session().unwrap().track_id.as_deref(), Some("41"));
+
+    assert_eq!(
+        SimState::parse(&telemetry(0)).unwrap().vehicle_gear(),
+        Some(-1)
+    );
+    assert_eq!(
+        SimState::parse(&telemetry(1)).unwrap().vehicle_gear(),
+        Some(0)
+    );
+}
+
+#[tokio::test]
+async fn client_skips_invalid_datagrams() {
+    let (mut client, game) = common::connect_to_stand_in(&telemetry(2), |uri| async move {
+        Client::try_connect(&uri).await.unwrap()
+    })
+    .await;
+    for datagram in [vec![0; 12], telemetry(5)] {
+        game.send(&datagram).await.unwrap();
+    }
+    assert_eq!(client.name(), "RichardBurnsRally");
+    let moment = client.next_moment().await.unwrap();
+    assert_eq!(moment.vehicle_gear(), Some(4));
+}
@@ -0,0 +1,71 @@
+use simetry::codemasters::Title;
+use simetry::udp_relay::Relay;
+use simetry::Simetry;
+use std::time::Duration;
+use tokio::net::UdpSocket;
+
+fn datagram(time: f32) -> Vec<u8> {
+    let mut fields = (0..66).map(|v| v as f32).collect::<Vec<_>>();
+    fields[0] = time;
+    fields[33] = 3.0;
+    fields.iter().flat_map(|v| v.to_le_bytes()).collect()
+}
+
+async fn receive(socket: &UdpSocket) -> Vec<u8> {
+    let mut buffer = [0u8; 2048];
+    let size = tokio::time::timeout(Duration::from_secs(2), socket.recv(&mut buffer))
+        .await
+        .expect("datagram not relayed")
+        .unwrap();
+    buffer[..size].to_vec()
+}
+
+#[tokio::test]
+async fn relays_datagrams_unchanged() {
+    let dash = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    let rig = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    let destinations = vec![dash.local_addr().unwrap(), rig.local_addr().unwrap()];
+    let mut relay = Relay::dirt_rally_2("127.0.0.1:0", destinations)
+        .await
+        .unwrap();
+    assert_eq!(relay.name(), "DirtRally2");
+    let uri = relay.local_addr().unwrap();
+
+    let game = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    let stray = vec![7; 12];
+    for data in [stray.clone(), datagram(1.0), datagram(2.0)] {
+        game.send_to(&data, uri).await.unwrap();
+    }
+
+    // The stray datagram is forwarded, but skipped by the relay itself
+    let state = relay.next_sim_state().await.unwrap();
+    assert_eq!(state.time, 1.0);
+    let moment = relay.next_moment().await.unwrap();
+    assert_eq!(moment.vehicle_gear(), Some(3));
+    for destination in [&dash, &rig] {
+        assert_eq!(receive(destination).await, stray);
+        assert_eq!(receive(destination).await, datagram(1.0));
+        assert_eq!(receive(destination).await, datagram(2.0));
+    }
+}
+
+#[tokio::test]
+async fn keeps_relaying_without_destinations_listening() {
+    let listener = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    let gone = listener.local_addr().unwrap();
+    drop(listener);
+    let logger = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    let destinations = vec![gone, logger.local_addr().unwrap()];
+    let relay = Relay::codemasters("127.0.0.1:0", Title::Dirt4, destinations)
+        .await
+        .unwrap();
+    assert_eq!(relay.name(), "Dirt4");
+    let uri = relay.local_addr().unwrap();
+    tokio::spawn(relay.run());
+
+    let game = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    for time in [1.0, 2.0] {
+        game.send_to(&datagram(time), uri).await.unwrap();
+        assert_eq!(receive(&logger).await, datagram(time));
+    }
+}